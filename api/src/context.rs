@@ -49,8 +49,8 @@ use aptos_types::{
 use futures::{channel::oneshot, SinkExt};
 use mini_moka::sync::Cache;
 use move_core_types::{
-    identifier::Identifier,
-    language_storage::{ModuleId, StructTag},
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag},
     move_resource::MoveResource,
 };
 use serde::Serialize;
@@ -79,6 +79,7 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub stream_active_connections: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for Context {
@@ -131,6 +132,7 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            stream_active_connections: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -204,6 +206,14 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
+    pub fn max_view_batch_size(&self) -> usize {
+        self.node_config.api.max_view_batch_size
+    }
+
+    pub fn max_gas_view_batch(&self) -> u64 {
+        self.node_config.api.max_gas_view_batch
+    }
+
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
@@ -230,6 +240,25 @@ impl Context {
             .map_err(|e| E::service_unavailable_with_code_no_info(e, AptosErrorCode::InternalError))
     }
 
+    /// Returns the oldest version the state can be read at. The state values are pruned
+    /// separately from the ledger, so this can be newer than the oldest ledger version.
+    pub fn get_oldest_state_version<E: ServiceUnavailableError>(
+        &self,
+        ledger_info: &LedgerInfo,
+    ) -> Result<Version, E> {
+        let first_state_value_version = self
+            .db
+            .get_first_state_value_version()
+            .context("Failed to retrieve oldest state value version")
+            .map_err(|e| {
+                E::service_unavailable_with_code_no_info(e, AptosErrorCode::InternalError)
+            })?;
+        Ok(std::cmp::max(
+            ledger_info.oldest_ledger_version.0,
+            first_state_value_version.unwrap_or_default(),
+        ))
+    }
+
     pub fn get_latest_storage_ledger_info<E: ServiceUnavailableError>(
         &self,
     ) -> Result<LedgerInfo, E> {
@@ -968,6 +997,63 @@ impl Context {
         }
     }
 
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        end_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or(anyhow!("Internal indexer reader doesn't exist"))?
+            .get_events_by_type(
+                type_tag,
+                start_version,
+                end_version,
+                limit as u64,
+                ledger_version,
+            )
+    }
+
+    pub fn get_transactions_by_function(
+        &self,
+        module_id: &ModuleId,
+        function: &IdentStr,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or(anyhow!("Internal indexer reader doesn't exist"))?
+            .get_transactions_by_function(
+                module_id,
+                function,
+                start_version,
+                limit as u64,
+                true,
+                ledger_version,
+            )?
+            .into_iter()
+            .map(|t| self.convert_into_transaction_on_chain_data(t))
+            .collect()
+    }
+
+    pub fn get_resources_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_address: Option<AccountAddress>,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<(Vec<(AccountAddress, StateValue)>, Option<AccountAddress>)> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or(anyhow!("Internal indexer reader doesn't exist"))?
+            .get_resources_by_type(struct_tag, start_address, limit, ledger_version)
+    }
+
     pub fn get_indexer_reader(&self) -> Option<&Arc<dyn IndexerReader>> {
         self.indexer_reader.as_ref()
     }
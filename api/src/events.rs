@@ -9,8 +9,8 @@ use crate::{
     failpoint::fail_point_poem,
     page::Page,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        version_pruned, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResultWith404, InternalError,
     },
     ApiTags,
 };
@@ -19,7 +19,8 @@ use aptos_api_types::{
     verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
     MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::EventWithVersion, event::EventKey};
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::{
     param::{Path, Query},
     OpenApi,
@@ -150,6 +151,61 @@ impl EventsApi {
         })
         .await
     }
+
+    /// Get events by type
+    ///
+    /// Retrieves the events of the given type emitted in versions `[start_version, end_version)`,
+    /// regardless of the account or module that emitted them, in ascending order. The events of
+    /// a transaction are never split across pages, so the next page starts at the version after
+    /// the last returned event.
+    ///
+    /// This requires the node to run the internal indexer with the event by type index enabled.
+    #[oai(
+        path = "/events/by_type/:event_type",
+        method = "get",
+        operation_id = "get_events_by_type",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_type(
+        &self,
+        accept_type: AcceptType,
+        /// Type of the events to retrieve e.g. `0x1::coin::CoinDeposit`
+        event_type: Path<MoveStructTag>,
+        /// Version to start retrieving events from.
+        ///
+        /// If unspecified, defaults to the oldest version not pruned
+        start_version: Query<Option<U64>>,
+        /// Version to stop retrieving events at, exclusive.
+        ///
+        /// If unspecified, retrieves events up to the latest version
+        end_version: Query<Option<U64>>,
+        /// Max number of events to retrieve.
+        ///
+        /// If unspecified, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        event_type
+            .0
+            .verify(0)
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_events_by_type")?;
+        self.context
+            .check_api_output_enabled("Get events by type", &accept_type)?;
+        let page = Page::new(
+            start_version.0.map(|v| v.0),
+            limit.0,
+            self.context.max_events_page_size(),
+        );
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.list_by_type(accept_type, page, event_type.0, end_version.0.map(|v| v.0))
+        })
+        .await
+    }
 }
 
 impl EventsApi {
@@ -179,6 +235,62 @@ impl EventsApi {
                 )
             })?;
 
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// List events of a type, across all event keys
+    fn list_by_type(
+        &self,
+        accept_type: AcceptType,
+        page: Page,
+        event_type: MoveStructTag,
+        end_version: Option<u64>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let tag: StructTag = event_type
+            .try_into()
+            .context("Failed to parse given event type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    &latest_ledger_info,
+                )
+            })?;
+
+        let oldest_version = latest_ledger_info.oldest_ledger_version.0;
+        let start_version = page.start_option().unwrap_or(oldest_version);
+        if start_version < oldest_version {
+            return Err(version_pruned(start_version, &latest_ledger_info));
+        }
+        let type_tag = TypeTag::Struct(Box::new(tag));
+        let events = self
+            .context
+            .get_events_by_type(
+                &type_tag,
+                start_version,
+                end_version.unwrap_or(u64::MAX),
+                page.limit(&latest_ledger_info)?,
+                latest_ledger_info.version(),
+            )
+            .context(format!("Failed to find events by type {}", type_tag))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    fn render(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        events: Vec<EventWithVersion>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
         match accept_type {
             AcceptType::Json => {
                 let events = self
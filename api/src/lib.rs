@@ -18,11 +18,13 @@ mod index;
 mod log;
 pub mod metrics;
 mod page;
+mod proof;
 mod response;
 mod runtime;
 mod set_failpoints;
 pub mod spec;
 mod state;
+mod stream;
 #[cfg(test)]
 pub mod tests;
 mod transactions;
@@ -45,6 +47,9 @@ pub enum ApiTags {
     /// General information
     General,
 
+    /// Proofs for verifying the ledger
+    Proofs,
+
     /// Access to tables
     Tables,
 
@@ -105,3 +105,20 @@ pub static WAIT_TRANSACTION_POLL_TIME: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static STREAM_ACTIVE_CONNECTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_stream_active_connections",
+        "Number of active server-sent event streams"
+    )
+    .unwrap()
+});
+
+pub static STREAM_ITEMS_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_stream_items_sent",
+        "Number of items pushed on server-sent event streams, grouped by stream type",
+        &["stream_type"]
+    )
+    .unwrap()
+});
@@ -0,0 +1,472 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accept_type::AcceptType,
+    context::{api_spawn_blocking, Context},
+    failpoint::fail_point_poem,
+    page::Page,
+    response::{
+        version_not_found, version_pruned, BadRequestError, BasicErrorWith404, BasicResponse,
+        BasicResponseStatus, BasicResultWith404, InternalError,
+    },
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    AptosErrorCode, EpochChangeProofResponse, LedgerInfo, RawStateValueRequest, StateProofResponse,
+    StateValueChunkWithProofBcs, StateValueProofResponse, StateValueRangeProofResponse,
+    StateValueWithProofBcs, TransactionInfoProofResponse, TransactionInfoWithProofBcs, U64,
+};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, proof::TransactionInfoWithProof,
+    state_store::state_key::StateKey, transaction::Version,
+};
+use poem_openapi::{
+    param::{Path, Query},
+    payload::Json,
+    types::ToJSON,
+    OpenApi,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// API for retrieving proofs, to verify the ledger without trusting the node
+///
+/// Apart from the state proof, the proofs are relative to the latest ledger info signed by
+/// the validators, which is included in the response. Clients verify it against a trusted
+/// validator set first, e.g., the one reached by following the state proof.
+#[derive(Clone)]
+pub struct ProofApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl ProofApi {
+    /// Get state proof
+    ///
+    /// Get the latest ledger info signed by the validators, along with the epoch change
+    /// ledger infos since the epoch of `known_version`. A light client trusting the
+    /// validator set at `known_version` can verify the epoch changes one by one, and then
+    /// the latest ledger info against the validator set of the latest epoch.
+    ///
+    /// If there are too many epoch changes to include, `more` is set, and the client
+    /// should request again starting from the last epoch change.
+    #[oai(
+        path = "/proofs/state",
+        method = "get",
+        operation_id = "get_state_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_state_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Ledger version the client already trusts
+        known_version: Query<U64>,
+    ) -> BasicResultWith404<StateProofResponse> {
+        fail_point_poem("endpoint_get_state_proof")?;
+        self.context
+            .check_api_output_enabled("Get state proof", &accept_type)?;
+
+        let api = self.clone();
+        api_spawn_blocking(move || api.state_proof(&accept_type, known_version.0 .0)).await
+    }
+
+    /// Get state value with proof
+    ///
+    /// Get the state value of a BCS-encoded state key, along with a sparse Merkle proof of
+    /// its existence, or of its absence if there is no such state key. The proof is against
+    /// the state checkpoint hash of the transaction info at `ledger_version`, which is in
+    /// turn proven against the latest signed ledger info by an accumulator proof.
+    ///
+    /// The `ledger_version` must be a state checkpoint, e.g., the version of a signed ledger
+    /// info. It defaults to the version of the latest signed ledger info.
+    #[oai(
+        path = "/proofs/state_value",
+        method = "post",
+        operation_id = "get_state_value_with_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_state_value_with_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Request that carries the BCS-encoded state key
+        request: Json<RawStateValueRequest>,
+        /// Ledger version to get the state at
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<StateValueProofResponse> {
+        fail_point_poem("endpoint_get_state_value_with_proof")?;
+        self.context
+            .check_api_output_enabled("Get state value with proof", &accept_type)?;
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.state_value_with_proof(&accept_type, request.0, ledger_version.0)
+        })
+        .await
+    }
+
+    /// Get state values with range proof
+    ///
+    /// Get a chunk of state values at `ledger_version`, ordered by the hashes of their keys,
+    /// along with a sparse Merkle range proof that there are no other state values in
+    /// between. Paging through the chunks yields the whole state at the version.
+    ///
+    /// The `ledger_version` must be a state checkpoint, e.g., the version of a signed ledger
+    /// info. It defaults to the version of the latest signed ledger info.
+    #[oai(
+        path = "/proofs/state_values",
+        method = "get",
+        operation_id = "get_state_values_with_range_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_state_values_with_range_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Ledger version to get the state at
+        ledger_version: Query<Option<U64>>,
+        /// Index of the first state value, among all state values ordered by key hash
+        start: Query<Option<U64>>,
+        /// Max number of state values to retrieve
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<StateValueRangeProofResponse> {
+        fail_point_poem("endpoint_get_state_values_with_range_proof")?;
+        self.context
+            .check_api_output_enabled("Get state values with range proof", &accept_type)?;
+
+        let page = Page::new(
+            start.0.map(|inner| inner.0),
+            limit.0,
+            self.context.max_account_resources_page_size(),
+        );
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.state_values_with_range_proof(&accept_type, ledger_version.0, page)
+        })
+        .await
+    }
+
+    /// Get transaction info with proof
+    ///
+    /// Get the transaction info at a version, along with the accumulator proof connecting it
+    /// to the latest signed ledger info. The transaction info commits to the transaction, its
+    /// events, its write set and, at state checkpoints, the root hash of the state.
+    #[oai(
+        path = "/proofs/transaction_info/:txn_version",
+        method = "get",
+        operation_id = "get_transaction_info_with_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_transaction_info_with_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Version of the transaction
+        txn_version: Path<U64>,
+    ) -> BasicResultWith404<TransactionInfoProofResponse> {
+        fail_point_poem("endpoint_get_transaction_info_with_proof")?;
+        self.context
+            .check_api_output_enabled("Get transaction info with proof", &accept_type)?;
+
+        let api = self.clone();
+        api_spawn_blocking(move || api.transaction_info_with_proof(&accept_type, txn_version.0 .0))
+            .await
+    }
+
+    /// Get epoch change proof
+    ///
+    /// Get the ledger infos ending the epochs in `[start_epoch, end_epoch)`, each signed by
+    /// the validators of the epoch it ends, and carrying the validator set of the next one.
+    /// `end_epoch` defaults to the latest epoch.
+    ///
+    /// If there are too many epochs in the range, `more` is set, and the client should
+    /// request again starting from the epoch after the last included ledger info.
+    #[oai(
+        path = "/proofs/epoch_change",
+        method = "get",
+        operation_id = "get_epoch_change_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_epoch_change_proof(
+        &self,
+        accept_type: AcceptType,
+        /// First epoch to get the ending ledger info of
+        start_epoch: Query<U64>,
+        /// Epoch to stop before
+        end_epoch: Query<Option<U64>>,
+    ) -> BasicResultWith404<EpochChangeProofResponse> {
+        fail_point_poem("endpoint_get_epoch_change_proof")?;
+        self.context
+            .check_api_output_enabled("Get epoch change proof", &accept_type)?;
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.epoch_change_proof(
+                &accept_type,
+                start_epoch.0 .0,
+                end_epoch.0.map(|inner| inner.0),
+            )
+        })
+        .await
+    }
+}
+
+impl ProofApi {
+    fn state_proof(
+        &self,
+        accept_type: &AcceptType,
+        known_version: Version,
+    ) -> BasicResultWith404<StateProofResponse> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        if known_version > ledger_info.version() {
+            return Err(version_not_found(known_version, &ledger_info));
+        }
+
+        let state_proof = self
+            .context
+            .db
+            .get_state_proof(known_version)
+            .context("Failed to get state proof")
+            .map_err(|err| internal_error(err, &ledger_info))?;
+
+        respond(accept_type, state_proof, &ledger_info)
+    }
+
+    fn state_value_with_proof(
+        &self,
+        accept_type: &AcceptType,
+        request: RawStateValueRequest,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<StateValueProofResponse> {
+        let (ledger_info, signed_ledger_info) = self.ledger_infos()?;
+        let state_key: StateKey = bcs::from_bytes(&request.key.0)
+            .context(format!("Failed deserializing state key: {}", request.key))
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                )
+            })?;
+        let (version, transaction_info_with_proof) =
+            self.state_checkpoint(ledger_version, &signed_ledger_info, &ledger_info)?;
+
+        let (state_value, proof) = self
+            .context
+            .db
+            .get_state_value_with_proof_by_version(&state_key, version)
+            .context(format!(
+                "Failed to get state value with proof. key: {}",
+                request.key
+            ))
+            .map_err(|err| internal_error(err, &ledger_info))?;
+
+        respond(
+            accept_type,
+            StateValueWithProofBcs {
+                ledger_info_with_signatures: signed_ledger_info,
+                version,
+                state_value,
+                proof,
+                transaction_info_with_proof,
+            },
+            &ledger_info,
+        )
+    }
+
+    fn state_values_with_range_proof(
+        &self,
+        accept_type: &AcceptType,
+        ledger_version: Option<U64>,
+        page: Page,
+    ) -> BasicResultWith404<StateValueRangeProofResponse> {
+        let (ledger_info, signed_ledger_info) = self.ledger_infos()?;
+        let (version, transaction_info_with_proof) =
+            self.state_checkpoint(ledger_version, &signed_ledger_info, &ledger_info)?;
+
+        let limit = page.limit(&ledger_info)?;
+        let start = page.start_option().unwrap_or_default();
+        let state_item_count =
+            self.context
+                .db
+                .get_state_item_count(version)
+                .context("Failed to get state item count")
+                .map_err(|err| internal_error(err, &ledger_info))? as u64;
+        if start >= state_item_count {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Given start index ({}) is out of range, there are {} state values at version {}",
+                    start, state_item_count, version
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let chunk = self
+            .context
+            .db
+            .get_state_value_chunk_with_proof(version, start as usize, limit as usize)
+            .context("Failed to get state value chunk with proof")
+            .map_err(|err| internal_error(err, &ledger_info))?;
+
+        respond(
+            accept_type,
+            StateValueChunkWithProofBcs {
+                ledger_info_with_signatures: signed_ledger_info,
+                version,
+                chunk,
+                transaction_info_with_proof,
+            },
+            &ledger_info,
+        )
+    }
+
+    fn transaction_info_with_proof(
+        &self,
+        accept_type: &AcceptType,
+        version: Version,
+    ) -> BasicResultWith404<TransactionInfoProofResponse> {
+        let (ledger_info, signed_ledger_info) = self.ledger_infos()?;
+        let transaction_info_with_proof =
+            self.transaction_info(version, &signed_ledger_info, &ledger_info)?;
+
+        respond(
+            accept_type,
+            TransactionInfoWithProofBcs {
+                ledger_info_with_signatures: signed_ledger_info,
+                version,
+                transaction_info_with_proof,
+            },
+            &ledger_info,
+        )
+    }
+
+    fn epoch_change_proof(
+        &self,
+        accept_type: &AcceptType,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BasicResultWith404<EpochChangeProofResponse> {
+        let (ledger_info, signed_ledger_info) = self.ledger_infos()?;
+        // The epoch of the latest ledger info is still open, unless it ends the epoch.
+        let latest_epoch = signed_ledger_info.ledger_info().next_block_epoch();
+        let end_epoch = end_epoch.unwrap_or(latest_epoch);
+        if start_epoch >= end_epoch || end_epoch > latest_epoch {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Invalid epoch range [{}, {}), the latest ended epoch is {}",
+                    start_epoch,
+                    end_epoch,
+                    latest_epoch - 1
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let epoch_change_proof = self
+            .context
+            .db
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+            .context("Failed to get epoch ending ledger infos")
+            .map_err(|err| internal_error(err, &ledger_info))?;
+
+        respond(accept_type, epoch_change_proof, &ledger_info)
+    }
+
+    /// Returns the latest ledger info for the response headers, along with the latest signed
+    /// ledger info, which the proofs are relative to. The latter can be slightly newer.
+    fn ledger_infos(&self) -> Result<(LedgerInfo, LedgerInfoWithSignatures), BasicErrorWith404> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let signed_ledger_info = self
+            .context
+            .get_latest_ledger_info_with_signatures()
+            .context("Failed to get latest signed ledger info")
+            .map_err(|err| internal_error(err, &ledger_info))?;
+        Ok((ledger_info, signed_ledger_info))
+    }
+
+    /// Returns the transaction info at `version` along with its proof against the signed
+    /// ledger info.
+    fn transaction_info(
+        &self,
+        version: Version,
+        signed_ledger_info: &LedgerInfoWithSignatures,
+        ledger_info: &LedgerInfo,
+    ) -> Result<TransactionInfoWithProof, BasicErrorWith404> {
+        let signed_version = signed_ledger_info.ledger_info().version();
+        if version > signed_version {
+            return Err(version_not_found(version, ledger_info));
+        }
+        if version < ledger_info.oldest_ledger_version.0 {
+            return Err(version_pruned(version, ledger_info));
+        }
+
+        let txn = self
+            .context
+            .db
+            .get_transaction_by_version(version, signed_version, false)
+            .context(format!("Failed to get transaction at version {}", version))
+            .map_err(|err| internal_error(err, ledger_info))?;
+        Ok(txn.proof)
+    }
+
+    /// Resolves the version to get the state at, which must be a state checkpoint, and returns
+    /// it along with the proof of its transaction info, which carries the state root hash.
+    fn state_checkpoint(
+        &self,
+        ledger_version: Option<U64>,
+        signed_ledger_info: &LedgerInfoWithSignatures,
+        ledger_info: &LedgerInfo,
+    ) -> Result<(Version, TransactionInfoWithProof), BasicErrorWith404> {
+        let version = ledger_version
+            .map(|inner| inner.0)
+            .unwrap_or_else(|| signed_ledger_info.ledger_info().version());
+        if version < self.context.get_oldest_state_version(ledger_info)? {
+            return Err(version_pruned(version, ledger_info));
+        }
+
+        let transaction_info_with_proof =
+            self.transaction_info(version, signed_ledger_info, ledger_info)?;
+        if transaction_info_with_proof
+            .transaction_info()
+            .state_checkpoint_hash()
+            .is_none()
+        {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Ledger version({}) is not a state checkpoint, e.g., the version of a signed ledger info",
+                    version
+                ),
+                AptosErrorCode::InvalidInput,
+                ledger_info,
+            ));
+        }
+        Ok((version, transaction_info_with_proof))
+    }
+}
+
+fn internal_error(err: impl std::fmt::Display, ledger_info: &LedgerInfo) -> BasicErrorWith404 {
+    BasicErrorWith404::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+}
+
+/// Responds with the value as is for BCS, or with its JSON representation.
+fn respond<B, T>(
+    accept_type: &AcceptType,
+    value: B,
+    ledger_info: &LedgerInfo,
+) -> BasicResultWith404<T>
+where
+    B: Serialize,
+    T: for<'a> TryFrom<&'a B, Error = bcs::Error> + ToJSON + Send + Sync,
+{
+    match accept_type {
+        AcceptType::Json => {
+            let response = T::try_from(&value).map_err(|err| internal_error(err, ledger_info))?;
+            BasicResponse::try_from_json((response, ledger_info, BasicResponseStatus::Ok))
+        },
+        AcceptType::Bcs => {
+            BasicResponse::try_from_bcs((value, ledger_info, BasicResponseStatus::Ok))
+        },
+    }
+}
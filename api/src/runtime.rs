@@ -12,9 +12,11 @@ use crate::{
     events::EventsApi,
     index::IndexApi,
     log::middleware_log,
+    proof::ProofApi,
     set_failpoints,
     spec::{spec_endpoint_json, spec_endpoint_yaml},
     state::StateApi,
+    stream,
     transactions::TransactionsApi,
     view_function::ViewFunctionApi,
 };
@@ -116,6 +118,7 @@ pub fn get_api_service(
         BlocksApi,
         EventsApi,
         IndexApi,
+        ProofApi,
         StateApi,
         TransactionsApi,
         ViewFunctionApi,
@@ -139,6 +142,9 @@ pub fn get_api_service(
         IndexApi {
             context: context.clone(),
         },
+        ProofApi {
+            context: context.clone(),
+        },
         StateApi {
             context: context.clone(),
         },
@@ -248,6 +254,19 @@ pub fn attach_poem_to_runtime(
                     .at(
                         "/set_failpoint",
                         poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+                    )
+                    // Server-sent event streams are also served outside of the OpenAPI spec.
+                    .at(
+                        "/stream/transactions",
+                        poem::get(stream::stream_transactions_poem).data(context.clone()),
+                    )
+                    .at(
+                        "/stream/events",
+                        poem::get(stream::stream_events_poem).data(context.clone()),
+                    )
+                    .at(
+                        "/stream/blocks",
+                        poem::get(stream::stream_blocks_poem).data(context.clone()),
                     ),
             )
             .with(cors)
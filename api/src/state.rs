@@ -5,21 +5,32 @@ use crate::{
     accept_type::AcceptType,
     context::api_spawn_blocking,
     failpoint::fail_point_poem,
+    page::determine_limit,
     response::{
         api_forbidden, build_not_found, module_not_found, resource_not_found, table_item_not_found,
         BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        InternalError, NotFoundError,
     },
     ApiTags, Context,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper,
-    MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawStateValueRequest,
-    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    verify_module_identifier, AccountMoveResource, Address, AptosErrorCode, AsConverter,
+    IdentifierWrapper, LedgerInfo, MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue,
+    RawStateValueRequest, RawTableItemRequest, StateKeyWrapper, TableItemRequest, VerifyInput,
+    VerifyInputWithRecursion, U64,
 };
-use aptos_types::state_store::{state_key::StateKey, table::TableHandle, TStateView};
-use move_core_types::language_storage::StructTag;
+use aptos_storage_interface::state_view::DbStateView;
+use aptos_types::{
+    account_address::AccountAddress,
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        table::TableHandle,
+        TStateView,
+    },
+};
+use bytes::Bytes;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::{
     param::{Path, Query},
     payload::Json,
@@ -83,6 +94,68 @@ impl StateApi {
         .await
     }
 
+    /// Get resources by type
+    ///
+    /// Retrieves the resources of the given type held by any account at a specific ledger
+    /// version, ordered by account address. If the ledger version is not specified in the
+    /// request, the latest ledger version is used.
+    ///
+    /// Resources within resource groups, e.g. `0x1::object::ObjectCore`, are not indexed and such
+    /// types are rejected with a 400.
+    ///
+    /// The index only tracks the latest indexed version: when querying an older ledger version,
+    /// resources deleted since then are not returned.
+    ///
+    /// This requires the node to run the internal indexer with the resource by type index
+    /// enabled.
+    #[oai(
+        path = "/resources/by_type/:resource_type",
+        method = "get",
+        operation_id = "get_resources_by_type",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_resources_by_type(
+        &self,
+        accept_type: AcceptType,
+        /// Name of struct to retrieve e.g. `0x1::account::Account`
+        resource_type: Path<MoveStructTag>,
+        /// Ledger version to get state of accounts
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+        /// Cursor specifying where to start for pagination
+        ///
+        /// Use the cursor returned in the X-Aptos-Cursor header in the response.
+        start: Query<Option<StateKeyWrapper>>,
+        /// Max number of resources to retrieve
+        ///
+        /// If not provided, defaults to default page size.
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<AccountMoveResource>> {
+        resource_type
+            .0
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_resources_by_type")?;
+        self.context
+            .check_api_output_enabled("Get resources by type", &accept_type)?;
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.resources_by_type(
+                &accept_type,
+                resource_type.0,
+                ledger_version.0.map(|inner| inner.0),
+                start.0.map(StateKey::from),
+                limit.0,
+            )
+        })
+        .await
+    }
+
     /// Get account module
     ///
     /// Retrieves an individual module from a given account and at a specific ledger version. If the
@@ -286,27 +359,119 @@ impl StateApi {
             })?;
 
         let (ledger_info, ledger_version, state_view) = self.context.state_view(ledger_version)?;
-        let bytes = state_view
-            .as_converter(self.context.db.clone(), self.context.indexer_reader.clone())
-            .find_resource(&state_view, address, &tag)
-            .context(format!(
-                "Failed to query DB to check for {} at {}",
-                tag, address
-            ))
+        let bytes = read_resource(
+            &self.context,
+            &state_view,
+            address,
+            &tag,
+            ledger_version,
+            &ledger_info,
+        )?;
+
+        match accept_type {
+            AcceptType::Json => {
+                let resource =
+                    resource_to_json(&self.context, &state_view, &tag, &bytes, &ledger_info)?;
+
+                BasicResponse::try_from_json((resource, &ledger_info, BasicResponseStatus::Ok))
+            },
+            AcceptType::Bcs => BasicResponse::try_from_encoded((
+                bytes.to_vec(),
+                &ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+        }
+    }
+
+    /// Read the resources of a type held by any account at the ledger version
+    ///
+    /// JSON: Convert to AccountMoveResource
+    /// BCS: Leave the resources encoded, as [`Vec<(AccountAddress, Vec<u8>)>`]
+    fn resources_by_type(
+        &self,
+        accept_type: &AcceptType,
+        resource_type: MoveStructTag,
+        ledger_version: Option<u64>,
+        start: Option<StateKey>,
+        limit: Option<u16>,
+    ) -> BasicResultWith404<Vec<AccountMoveResource>> {
+        let tag: StructTag = resource_type
+            .try_into()
+            .context("Failed to parse given resource type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+
+        let (ledger_info, ledger_version, state_view) = self.context.state_view(ledger_version)?;
+        let converter =
+            state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
+        if converter.is_resource_group(&tag) || converter.is_resource_group_member(&tag) {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Resources by type are not indexed for resource groups or their members, got {}",
+                    tag
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+        let start_address = start
+            .map(|state_key| match state_key.inner() {
+                StateKeyInner::AccessPath(access_path)
+                    if StateKey::resource(&access_path.address, &tag)
+                        .map_or(false, |key| key == state_key) =>
+                {
+                    Ok(access_path.address)
+                },
+                _ => Err(BasicErrorWith404::bad_request_with_code(
+                    format!("Cursor doesn't point at a resource of type {}", tag),
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                )),
+            })
+            .transpose()?;
+        let max_account_resources_page_size = self.context.max_account_resources_page_size();
+        let limit = determine_limit(
+            limit,
+            max_account_resources_page_size,
+            max_account_resources_page_size,
+            &ledger_info,
+        )?;
+
+        let (resources, next_address) = self
+            .context
+            .get_resources_by_type(&tag, start_address, limit as u64, ledger_version)
+            .context(format!("Failed to find resources by type {}", tag))
             .map_err(|err| {
                 BasicErrorWith404::internal_with_code(
                     err,
                     AptosErrorCode::InternalError,
                     &ledger_info,
                 )
-            })?
-            .ok_or_else(|| resource_not_found(address, &tag, ledger_version, &ledger_info))?;
+            })?;
+        let next_state_key = next_address
+            .map(|address| StateKey::resource(&address, &tag))
+            .transpose()
+            .context("Failed to build cursor")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
 
         match accept_type {
             AcceptType::Json => {
-                let resource = state_view
-                    .as_converter(self.context.db.clone(), self.context.indexer_reader.clone())
-                    .try_into_resource(&tag, &bytes)
+                let resources = resources
+                    .iter()
+                    .map(|(address, state_value)| {
+                        Ok(AccountMoveResource {
+                            address: (*address).into(),
+                            resource: converter.try_into_resource(&tag, state_value.bytes())?,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
                     .context("Failed to deserialize resource data retrieved from DB")
                     .map_err(|err| {
                         BasicErrorWith404::internal_with_code(
@@ -316,13 +481,17 @@ impl StateApi {
                         )
                     })?;
 
-                BasicResponse::try_from_json((resource, &ledger_info, BasicResponseStatus::Ok))
+                BasicResponse::try_from_json((resources, &ledger_info, BasicResponseStatus::Ok))
+                    .map(|v| v.with_cursor(next_state_key))
+            },
+            AcceptType::Bcs => {
+                let resources: Vec<(AccountAddress, Vec<u8>)> = resources
+                    .into_iter()
+                    .map(|(address, state_value)| (address, state_value.bytes().to_vec()))
+                    .collect();
+                BasicResponse::try_from_bcs((resources, &ledger_info, BasicResponseStatus::Ok))
+                    .map(|v| v.with_cursor(next_state_key))
             },
-            AcceptType::Bcs => BasicResponse::try_from_encoded((
-                bytes.to_vec(),
-                &ledger_info,
-                BasicResponseStatus::Ok,
-            )),
         }
     }
 
@@ -384,80 +553,29 @@ impl StateApi {
         table_item_request: TableItemRequest,
         ledger_version: Option<U64>,
     ) -> BasicResultWith404<MoveValue> {
-        // Parse the key and value types for the table
-        let key_type = table_item_request
-            .key_type
-            .try_into()
-            .context("Failed to parse key_type")
-            .map_err(|err| {
-                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
-            })?;
-        let key = table_item_request.key;
-        let value_type = table_item_request
-            .value_type
-            .try_into()
-            .context("Failed to parse value_type")
-            .map_err(|err| {
-                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
-            })?;
-
         // Retrieve local state
         let (ledger_info, ledger_version, state_view) = self
             .context
             .state_view(ledger_version.map(|inner| inner.0))?;
 
-        let converter =
-            state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
-
-        // Convert key to lookup version for DB
-        let vm_key = converter
-            .try_into_vm_value(&key_type, key.clone())
-            .map_err(|err| {
-                BasicErrorWith404::bad_request_with_code(
-                    err,
-                    AptosErrorCode::InvalidInput,
-                    &ledger_info,
-                )
-            })?;
-        let raw_key = vm_key.undecorate().simple_serialize().ok_or_else(|| {
-            BasicErrorWith404::bad_request_with_code(
-                "Failed to serialize table key",
-                AptosErrorCode::InvalidInput,
-                &ledger_info,
-            )
-        })?;
-
-        // Retrieve value from the state key
-        let state_key = StateKey::table_item(&TableHandle(table_handle.into()), &raw_key);
-        let bytes = state_view
-            .get_state_value_bytes(&state_key)
-            .context(format!(
-                "Failed when trying to retrieve table item from the DB with key: {}",
-                key
-            ))
-            .map_err(|err| {
-                BasicErrorWith404::internal_with_code(
-                    err,
-                    AptosErrorCode::InternalError,
-                    &ledger_info,
-                )
-            })?
-            .ok_or_else(|| {
-                table_item_not_found(table_handle, &key, ledger_version, &ledger_info)
-            })?;
+        let (value_type, bytes) = read_table_item(
+            &self.context,
+            &state_view,
+            table_handle,
+            table_item_request,
+            ledger_version,
+            &ledger_info,
+        )?;
 
         match accept_type {
             AcceptType::Json => {
-                let move_value = converter
-                    .try_into_move_value(&value_type, &bytes)
-                    .context("Failed to deserialize table item retrieved from DB")
-                    .map_err(|err| {
-                        BasicErrorWith404::internal_with_code(
-                            err,
-                            AptosErrorCode::InternalError,
-                            &ledger_info,
-                        )
-                    })?;
+                let move_value = table_item_to_json(
+                    &self.context,
+                    &state_view,
+                    &value_type,
+                    &bytes,
+                    &ledger_info,
+                )?;
 
                 BasicResponse::try_from_json((move_value, &ledger_info, BasicResponseStatus::Ok))
             },
@@ -591,3 +709,102 @@ impl StateApi {
         }
     }
 }
+
+/// Reads a resource from the state view, failing if it doesn't exist at the ledger version.
+pub(crate) fn read_resource<E: InternalError + NotFoundError>(
+    context: &Context,
+    state_view: &DbStateView,
+    address: Address,
+    tag: &StructTag,
+    ledger_version: u64,
+    ledger_info: &LedgerInfo,
+) -> Result<Bytes, E> {
+    state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .find_resource(state_view, address, tag)
+        .context(format!(
+            "Failed to query DB to check for {} at {}",
+            tag, address
+        ))
+        .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))?
+        .ok_or_else(|| resource_not_found(address, tag, ledger_version, ledger_info))
+}
+
+/// Converts a resource read with [`read_resource`] to its JSON representation.
+pub(crate) fn resource_to_json<E: InternalError>(
+    context: &Context,
+    state_view: &DbStateView,
+    tag: &StructTag,
+    bytes: &[u8],
+    ledger_info: &LedgerInfo,
+) -> Result<MoveResource, E> {
+    state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .try_into_resource(tag, bytes)
+        .context("Failed to deserialize resource data retrieved from DB")
+        .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
+}
+
+/// Reads a table item from the state view, failing if it doesn't exist at the ledger version.
+/// Returns the type of the value along with the value itself.
+pub(crate) fn read_table_item<E: BadRequestError + InternalError + NotFoundError>(
+    context: &Context,
+    state_view: &DbStateView,
+    table_handle: Address,
+    table_item_request: TableItemRequest,
+    ledger_version: u64,
+    ledger_info: &LedgerInfo,
+) -> Result<(TypeTag, Bytes), E> {
+    // Parse the key and value types for the table
+    let key_type: TypeTag = table_item_request
+        .key_type
+        .try_into()
+        .context("Failed to parse key_type")
+        .map_err(|err| E::bad_request_with_code(err, AptosErrorCode::InvalidInput, ledger_info))?;
+    let key = table_item_request.key;
+    let value_type = table_item_request
+        .value_type
+        .try_into()
+        .context("Failed to parse value_type")
+        .map_err(|err| E::bad_request_with_code(err, AptosErrorCode::InvalidInput, ledger_info))?;
+
+    // Convert key to lookup version for DB
+    let vm_key = state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .try_into_vm_value(&key_type, key.clone())
+        .map_err(|err| E::bad_request_with_code(err, AptosErrorCode::InvalidInput, ledger_info))?;
+    let raw_key = vm_key.undecorate().simple_serialize().ok_or_else(|| {
+        E::bad_request_with_code(
+            "Failed to serialize table key",
+            AptosErrorCode::InvalidInput,
+            ledger_info,
+        )
+    })?;
+
+    // Retrieve value from the state key
+    let state_key = StateKey::table_item(&TableHandle(table_handle.into()), &raw_key);
+    let bytes = state_view
+        .get_state_value_bytes(&state_key)
+        .context(format!(
+            "Failed when trying to retrieve table item from the DB with key: {}",
+            key
+        ))
+        .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))?
+        .ok_or_else(|| table_item_not_found(table_handle, &key, ledger_version, ledger_info))?;
+    Ok((value_type, bytes))
+}
+
+/// Converts a table item read with [`read_table_item`] to its JSON representation.
+pub(crate) fn table_item_to_json<E: InternalError>(
+    context: &Context,
+    state_view: &DbStateView,
+    value_type: &TypeTag,
+    bytes: &[u8],
+    ledger_info: &LedgerInfo,
+) -> Result<MoveValue, E> {
+    state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .try_into_move_value(value_type, bytes)
+        .context("Failed to deserialize table item retrieved from DB")
+        .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
+}
@@ -0,0 +1,520 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Server-sent event (SSE) streams of committed ledger data.
+//!
+//! Each stream starts at a given ledger version (or the latest version, if none is given) and
+//! pushes data to the client as it is committed. Every SSE event carries a cursor as its `id`:
+//! the ledger version it was read from, followed by `:<event_index>` for events emitted by a
+//! transaction. Clients can resume a dropped stream right after the last item they received by
+//! reconnecting with the standard `Last-Event-ID` header (or the `start_version` query parameter).
+//!
+//! These endpoints are served outside of the OpenAPI spec (like `set_failpoint`), because
+//! poem-openapi doesn't support documenting long-lived streaming responses.
+
+use crate::{context::Context, metrics};
+use anyhow::{format_err, Context as AnyhowContext};
+use aptos_api_types::{AsConverter, TransactionOnChainData, VersionedEvent};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    transaction::{Transaction, Version},
+};
+use futures::{stream, Stream};
+use move_core_types::{language_storage::TypeTag, parser::parse_type_tag};
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{
+        sse::{Event, SSE},
+        Data, Query,
+    },
+    Request,
+};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    fmt,
+    str::FromStr,
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+
+/// The SSE event type used for committed transactions
+const TRANSACTION_EVENT_TYPE: &str = "transaction";
+/// The SSE event type used for events emitted by committed transactions
+const EVENT_EVENT_TYPE: &str = "event";
+/// The SSE event type used for new block metadata
+const BLOCK_EVENT_TYPE: &str = "block";
+/// The SSE event type sent before a stream is terminated due to an error
+const ERROR_EVENT_TYPE: &str = "error";
+
+/// The interval at which keep-alive comments are sent on idle streams
+const KEEP_ALIVE_INTERVAL_SECS: u64 = 15;
+
+/// The query parameters accepted by all stream endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamQuery {
+    /// The ledger version to start streaming from (inclusive)
+    start_version: Option<u64>,
+    /// The Move type of the events to stream (only used by the event stream)
+    event_type: Option<String>,
+    /// The account that created the event handle (only used by the event stream)
+    account: Option<AccountAddress>,
+}
+
+/// The type of data pushed by a stream
+#[derive(Clone, Debug)]
+pub(crate) enum StreamKind {
+    /// All committed transactions
+    Transactions,
+    /// Events emitted by committed transactions, optionally filtered
+    Events {
+        event_type: Option<TypeTag>,
+        account: Option<AccountAddress>,
+    },
+    /// Block metadata (and genesis) transactions
+    Blocks,
+}
+
+impl StreamKind {
+    fn label(&self) -> &'static str {
+        match self {
+            StreamKind::Transactions => "transactions",
+            StreamKind::Events { .. } => "events",
+            StreamKind::Blocks => "blocks",
+        }
+    }
+
+    /// Returns true iff the given event should be pushed on this stream
+    fn matches_event(&self, event: &ContractEvent) -> bool {
+        match self {
+            StreamKind::Events {
+                event_type,
+                account,
+            } => {
+                if let Some(event_type) = event_type {
+                    if event.type_tag() != event_type {
+                        return false;
+                    }
+                }
+                if let Some(account) = account {
+                    // Module events (v2) are not associated with an event handle
+                    match event.event_key() {
+                        Some(event_key) => event_key.get_creator_address() == *account,
+                        None => false,
+                    }
+                } else {
+                    true
+                }
+            },
+            _ => false,
+        }
+    }
+}
+
+/// The position of an item on a stream, sent to clients as the SSE event `id`.
+/// Formatted as `<version>` for transactions and blocks, and as
+/// `<version>:<event_index>` for events (the index of the event in its transaction).
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) struct StreamCursor {
+    pub version: Version,
+    pub event_index: Option<u64>,
+}
+
+impl fmt::Display for StreamCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.event_index {
+            Some(event_index) => write!(f, "{}:{}", self.version, event_index),
+            None => write!(f, "{}", self.version),
+        }
+    }
+}
+
+impl FromStr for StreamCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (version, event_index) = match s.split_once(':') {
+            Some((version, event_index)) => (version, Some(event_index.parse()?)),
+            None => (s, None),
+        };
+        Ok(Self {
+            version: version.parse()?,
+            event_index,
+        })
+    }
+}
+
+/// A single item pushed on a stream, tagged with the version it was read from
+#[derive(Debug)]
+pub(crate) struct StreamItem {
+    pub version: Version,
+    /// The index of the event in its transaction (only set for events)
+    pub event_index: Option<u64>,
+    pub event_type: &'static str,
+    pub data: serde_json::Value,
+}
+
+impl StreamItem {
+    pub fn cursor(&self) -> StreamCursor {
+        StreamCursor {
+            version: self.version,
+            event_index: self.event_index,
+        }
+    }
+}
+
+impl From<StreamItem> for Event {
+    fn from(item: StreamItem) -> Self {
+        let id = item.cursor().to_string();
+        Event::message(item.data.to_string())
+            .event_type(item.event_type)
+            .id(id)
+    }
+}
+
+#[handler]
+pub fn stream_transactions_poem(
+    context: Data<&Arc<Context>>,
+    Query(query): Query<StreamQuery>,
+    request: &Request,
+) -> poem::Result<SSE> {
+    start_stream(context.0.clone(), StreamKind::Transactions, query, request)
+}
+
+#[handler]
+pub fn stream_events_poem(
+    context: Data<&Arc<Context>>,
+    Query(query): Query<StreamQuery>,
+    request: &Request,
+) -> poem::Result<SSE> {
+    let event_type = query
+        .event_type
+        .as_deref()
+        .map(parse_type_tag)
+        .transpose()
+        .map_err(|error| {
+            poem::Error::from_string(
+                format!("Invalid event type: {}", error),
+                StatusCode::BAD_REQUEST,
+            )
+        })?;
+    let kind = StreamKind::Events {
+        event_type,
+        account: query.account,
+    };
+    start_stream(context.0.clone(), kind, query, request)
+}
+
+#[handler]
+pub fn stream_blocks_poem(
+    context: Data<&Arc<Context>>,
+    Query(query): Query<StreamQuery>,
+    request: &Request,
+) -> poem::Result<SSE> {
+    start_stream(context.0.clone(), StreamKind::Blocks, query, request)
+}
+
+/// Verifies the stream request and creates the SSE response
+fn start_stream(
+    context: Arc<Context>,
+    kind: StreamKind,
+    query: StreamQuery,
+    request: &Request,
+) -> poem::Result<SSE> {
+    let api_config = &context.node_config.api;
+    if !api_config.stream_enabled {
+        return Err(poem::Error::from_string(
+            "Streaming is not enabled on this node",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    // Determine the version to start from. The Last-Event-ID header (sent by
+    // clients when reconnecting) takes precedence over the query parameter.
+    let last_event_id = request
+        .header("Last-Event-ID")
+        .map(|id| id.parse::<StreamCursor>())
+        .transpose()
+        .map_err(|error| {
+            poem::Error::from_string(
+                format!("Invalid Last-Event-ID header: {}", error),
+                StatusCode::BAD_REQUEST,
+            )
+        })?;
+    let (start_version, resume_after) = match (last_event_id, query.start_version) {
+        // Resume in the middle of the transaction that emitted the last received event
+        (Some(cursor), _) if cursor.event_index.is_some() => (Some(cursor.version), Some(cursor)),
+        (Some(cursor), _) => (Some(cursor.version.saturating_add(1)), None),
+        (None, start_version) => (start_version, None),
+    };
+    let start_version = match start_version {
+        Some(start_version) => start_version,
+        None => context
+            .get_latest_ledger_info_wrapped()
+            .map_err(|error| {
+                poem::Error::from_string(error.to_string(), StatusCode::SERVICE_UNAVAILABLE)
+            })?
+            .version()
+            .saturating_add(1),
+    };
+
+    // Limit the number of concurrently active streams
+    let active_connection = ActiveConnection::acquire(context.clone())?;
+
+    let poll_interval = Duration::from_millis(api_config.stream_poll_interval_ms);
+    let stream = item_stream(
+        context,
+        kind,
+        start_version,
+        resume_after,
+        poll_interval,
+        active_connection,
+    );
+    Ok(SSE::new(stream).keep_alive(Duration::from_secs(KEEP_ALIVE_INTERVAL_SECS)))
+}
+
+/// Tracks an active stream, and releases it on drop
+pub(crate) struct ActiveConnection {
+    context: Arc<Context>,
+}
+
+impl ActiveConnection {
+    /// Registers a new active stream, unless the maximum number
+    /// of concurrently active streams has been reached.
+    pub(crate) fn acquire(context: Arc<Context>) -> poem::Result<Self> {
+        let count = context
+            .stream_active_connections
+            .fetch_add(1, Ordering::Relaxed)
+            + 1;
+        metrics::STREAM_ACTIVE_CONNECTIONS.inc();
+        let max_active_connections = context.node_config.api.stream_max_active_connections;
+        let active_connection = Self { context };
+        if count > max_active_connections {
+            // Dropping the connection releases it again
+            return Err(poem::Error::from_string(
+                "Too many active streams",
+                StatusCode::SERVICE_UNAVAILABLE,
+            ));
+        }
+        Ok(active_connection)
+    }
+}
+
+impl Drop for ActiveConnection {
+    fn drop(&mut self) {
+        self.context
+            .stream_active_connections
+            .fetch_sub(1, Ordering::Relaxed);
+        metrics::STREAM_ACTIVE_CONNECTIONS.dec();
+    }
+}
+
+/// The state of a single stream
+struct StreamState {
+    context: Arc<Context>,
+    kind: StreamKind,
+    next_version: Version,
+    /// The cursor of the last item received by the client before it reconnected
+    resume_after: Option<StreamCursor>,
+    pending_items: VecDeque<StreamItem>,
+    poll_interval: Duration,
+    _active_connection: ActiveConnection,
+}
+
+/// Returns a stream of SSE events, starting at the given version
+fn item_stream(
+    context: Arc<Context>,
+    kind: StreamKind,
+    start_version: Version,
+    resume_after: Option<StreamCursor>,
+    poll_interval: Duration,
+    active_connection: ActiveConnection,
+) -> impl Stream<Item = Event> + Send + 'static {
+    let state = StreamState {
+        context,
+        kind,
+        next_version: start_version,
+        resume_after,
+        pending_items: VecDeque::new(),
+        poll_interval,
+        _active_connection: active_connection,
+    };
+
+    // The state is dropped (ending the stream) once an error has been sent
+    stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        loop {
+            if let Some(item) = state.pending_items.pop_front() {
+                metrics::STREAM_ITEMS_SENT
+                    .with_label_values(&[state.kind.label()])
+                    .inc();
+                return Some((item.into(), Some(state)));
+            }
+
+            let context = state.context.clone();
+            let kind = state.kind.clone();
+            let start_version = state.next_version;
+            let result = tokio::task::spawn_blocking(move || {
+                read_stream_items(&context, &kind, start_version)
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+
+            match result {
+                Ok((next_version, items)) => {
+                    if next_version == state.next_version {
+                        // Nothing new has been committed, wait before polling again
+                        tokio::time::sleep(state.poll_interval).await;
+                    }
+                    state.next_version = next_version;
+                    state
+                        .pending_items
+                        .extend(items_after(items, state.resume_after.as_ref()));
+                    if state
+                        .resume_after
+                        .is_some_and(|cursor| cursor.version < next_version)
+                    {
+                        state.resume_after = None;
+                    }
+                },
+                Err(error) => {
+                    warn!(
+                        "Terminating {} stream at version {}: {}",
+                        state.kind.label(),
+                        state.next_version,
+                        error
+                    );
+                    let event = Event::message(error.to_string()).event_type(ERROR_EVENT_TYPE);
+                    return Some((event, None));
+                },
+            }
+        }
+    })
+}
+
+/// Drops the items at or before the given cursor, i.e., the ones
+/// that were already received by the client before it reconnected.
+pub(crate) fn items_after(
+    items: Vec<StreamItem>,
+    cursor: Option<&StreamCursor>,
+) -> impl Iterator<Item = StreamItem> + '_ {
+    items
+        .into_iter()
+        .filter(move |item| cursor.map_or(true, |cursor| item.cursor() > *cursor))
+}
+
+/// Reads a page of committed transactions starting at the given version and
+/// converts them into stream items. Returns the next version to read from
+/// along with the items (which may be empty, e.g., if the events are filtered).
+pub(crate) fn read_stream_items(
+    context: &Context,
+    kind: &StreamKind,
+    start_version: Version,
+) -> anyhow::Result<(Version, Vec<StreamItem>)> {
+    let ledger_info = context.get_latest_ledger_info_wrapped()?;
+    let ledger_version = ledger_info.version();
+    if start_version > ledger_version {
+        return Ok((start_version, vec![]));
+    }
+    if start_version < ledger_info.oldest_version() {
+        return Err(format_err!(
+            "Version {} has been pruned, the oldest available version is {}",
+            start_version,
+            ledger_info.oldest_version()
+        ));
+    }
+
+    let limit = std::cmp::min(
+        context.max_transactions_page_size() as u64,
+        ledger_version - start_version + 1,
+    ) as u16;
+    let data = context
+        .get_transactions(start_version, limit, ledger_version)
+        .context("Failed to read raw transactions from storage")?;
+    let next_version = start_version + data.len() as u64;
+
+    let state_view = context.latest_state_view()?;
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let items = match kind {
+        StreamKind::Transactions | StreamKind::Blocks => {
+            let mut timestamp = context
+                .db
+                .get_block_timestamp(start_version)
+                .context("Failed to retrieve block timestamp")?;
+            let mut items = vec![];
+            for txn in data {
+                let is_block = is_block_transaction(&txn);
+                if let Some(block_metadata) = txn.transaction.try_as_block_metadata_ext() {
+                    timestamp = block_metadata.timestamp_usecs();
+                } else if let Some(block_metadata) = txn.transaction.try_as_block_metadata() {
+                    timestamp = block_metadata.timestamp_usecs();
+                }
+                if matches!(kind, StreamKind::Blocks) && !is_block {
+                    continue;
+                }
+
+                let version = txn.version;
+                let event_type = match kind {
+                    StreamKind::Blocks => BLOCK_EVENT_TYPE,
+                    _ => TRANSACTION_EVENT_TYPE,
+                };
+                let txn = converter.try_into_onchain_transaction(timestamp, txn)?;
+                items.push(StreamItem {
+                    version,
+                    event_index: None,
+                    event_type,
+                    data: serde_json::to_value(txn)?,
+                });
+            }
+            items
+        },
+        StreamKind::Events { .. } => {
+            // Events are indexed by their position in the transaction (before filtering),
+            // so that the cursors remain stable across streams with different filters.
+            let (event_indices, events): (Vec<_>, Vec<_>) = data
+                .into_iter()
+                .flat_map(|txn| {
+                    let version = txn.version;
+                    txn.events
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(_, event)| kind.matches_event(event))
+                        .map(move |(index, event)| {
+                            (index as u64, EventWithVersion::new(version, event))
+                        })
+                })
+                .unzip();
+            let versioned_events: Vec<VersionedEvent> =
+                converter.try_into_versioned_events(&events)?;
+            events
+                .iter()
+                .zip(event_indices)
+                .zip(versioned_events)
+                .map(|((event, event_index), versioned_event)| {
+                    Ok(StreamItem {
+                        version: event.transaction_version,
+                        event_index: Some(event_index),
+                        event_type: EVENT_EVENT_TYPE,
+                        data: serde_json::to_value(versioned_event)?,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?
+        },
+    };
+
+    Ok((next_version, items))
+}
+
+/// Returns true iff the transaction starts a new block
+fn is_block_transaction(txn: &TransactionOnChainData) -> bool {
+    matches!(
+        txn.transaction,
+        Transaction::BlockMetadata(_)
+            | Transaction::BlockMetadataExt(_)
+            | Transaction::GenesisTransaction(_)
+    )
+}
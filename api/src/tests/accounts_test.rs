@@ -5,8 +5,11 @@
 use super::new_test_context;
 use crate::tests::new_test_context_with_db_sharding_and_internal_indexer;
 use aptos_api_test_context::{current_function_name, find_value, TestContext};
-use aptos_api_types::{MoveModuleBytecode, MoveResource, MoveStructTag, StateKeyWrapper};
+use aptos_api_types::{
+    AccountMoveResource, MoveModuleBytecode, MoveResource, MoveStructTag, StateKeyWrapper,
+};
 use aptos_cached_packages::aptos_stdlib;
+use aptos_types::account_address::AccountAddress;
 use serde_json::json;
 use std::str::FromStr;
 
@@ -432,3 +435,72 @@ fn account_modules_with_ledger_version(address: &str, ledger_version: i128) -> S
         ledger_version
     )
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_resources_by_type() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    context
+        .context
+        .indexer_reader
+        .as_ref()
+        .unwrap()
+        .wait_for_internal_indexer(2)
+        .unwrap();
+
+    let resp = context
+        .get("/resources/by_type/0x1::account::Account")
+        .await;
+    let resources = resp.as_array().unwrap();
+    let addresses: Vec<_> = resources
+        .iter()
+        .map(|resource| AccountAddress::from_str(resource["address"].as_str().unwrap()).unwrap())
+        .collect();
+    assert!(addresses.contains(&account.address()));
+    assert!(addresses.windows(2).all(|pair| pair[0] < pair[1]));
+    for resource in resources {
+        assert_eq!(resource["resource"]["type"], "0x1::account::Account");
+    }
+
+    // Page through the same resources one at a time using the cursor.
+    let mut paged_addresses = vec![];
+    let mut cursor: Option<StateKeyWrapper> = None;
+    loop {
+        let path = match &cursor {
+            Some(cursor) => format!(
+                "/v1/resources/by_type/0x1::account::Account?limit=1&start={}",
+                cursor
+            ),
+            None => "/v1/resources/by_type/0x1::account::Account?limit=1".to_string(),
+        };
+        let req = warp::test::request().method("GET").path(&path);
+        let resp = context.reply(req).await;
+        assert_eq!(resp.status(), 200);
+        let page: Vec<AccountMoveResource> = serde_json::from_slice(resp.body()).unwrap();
+        paged_addresses.extend(page.iter().map(|resource| *resource.address.inner()));
+        match resp.headers().get("X-Aptos-Cursor") {
+            Some(header) => {
+                cursor = Some(StateKeyWrapper::from_str(header.to_str().unwrap()).unwrap())
+            },
+            None => break,
+        }
+    }
+    assert_eq!(paged_addresses, addresses);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_resources_by_type_rejects_resource_groups() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+
+    for resource_type in ["0x1::object::ObjectGroup", "0x1::object::ObjectCore"] {
+        let resp = context
+            .expect_status_code(400)
+            .get(&format!("/resources/by_type/{}", resource_type))
+            .await;
+        assert_eq!(resp["error_code"], "invalid_input");
+    }
+}
@@ -230,3 +230,61 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    context
+        .context
+        .indexer_reader
+        .as_ref()
+        .unwrap()
+        .wait_for_internal_indexer(2)
+        .unwrap();
+
+    let txn = context.get("/transactions/by_version/2").await;
+    let event_type = txn["events"][0]["type"].as_str().unwrap().to_owned();
+    let expected_events: Vec<_> = txn["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|event| event["type"] == event_type)
+        .collect();
+
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?start_version=2&end_version=3",
+                utf8_percent_encode(&event_type, NON_ALPHANUMERIC)
+            )
+            .as_str(),
+        )
+        .await;
+    let events = resp.as_array().unwrap();
+    assert_eq!(events.len(), expected_events.len());
+    for (event, expected_event) in events.iter().zip(expected_events) {
+        assert_eq!(event["version"], "2");
+        assert_eq!(event["type"], expected_event["type"]);
+        assert_eq!(event["data"], expected_event["data"]);
+    }
+
+    // No events of the type after the transaction
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?start_version=3",
+                utf8_percent_encode(&event_type, NON_ALPHANUMERIC)
+            )
+            .as_str(),
+        )
+        .await;
+    assert!(resp
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|event| event["version"] != "2"));
+}
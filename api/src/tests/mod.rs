@@ -11,10 +11,12 @@ mod invalid_post_request_test;
 mod modules;
 mod multisig_transactions_test;
 mod objects;
+mod proofs_test;
 mod resource_groups;
 mod secp256k1_ecdsa;
 mod simulation_test;
 mod state_test;
+mod stream_test;
 mod string_resource_test;
 mod transaction_vector_test;
 mod transactions_test;
@@ -22,21 +24,52 @@ mod view_function;
 mod webauthn_secp256r1_ecdsa;
 
 use aptos_api_test_context::{new_test_context_inner as super_new_test_context, TestContext};
-use aptos_config::config::{internal_indexer_db_config::InternalIndexerDBConfig, NodeConfig};
+use aptos_config::config::{
+    internal_indexer_db_config::InternalIndexerDBConfig, LedgerPrunerConfig, NodeConfig,
+    PrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG,
+};
 
 fn new_test_context(test_name: String) -> TestContext {
     new_test_context_with_config(test_name, NodeConfig::default())
 }
 
 fn new_test_context_with_config(test_name: String, node_config: NodeConfig) -> TestContext {
-    super_new_test_context(test_name, node_config, false, None)
+    super_new_test_context(
+        test_name,
+        node_config,
+        false,
+        None,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+    )
+}
+
+/// Creates a test context that prunes the ledger (and state values) older than the
+/// given prune window, as soon as new versions are committed
+fn new_test_context_with_ledger_pruning(test_name: String, prune_window: u64) -> TestContext {
+    let pruner_config = PrunerConfig {
+        ledger_pruner_config: LedgerPrunerConfig {
+            enable: true,
+            prune_window,
+            batch_size: 1,
+            user_pruning_window_offset: 0,
+        },
+        ..NO_OP_STORAGE_PRUNER_CONFIG
+    };
+    super_new_test_context(test_name, NodeConfig::default(), false, None, pruner_config)
 }
 
 fn new_test_context_with_db_sharding_and_internal_indexer(test_name: String) -> TestContext {
     let mut node_config = NodeConfig::default();
     node_config.storage.rocksdb_configs.enable_storage_sharding = true;
-    node_config.indexer_db_config = InternalIndexerDBConfig::new(true, true, true, 10);
-    super_new_test_context(test_name, node_config, false, None)
+    node_config.indexer_db_config =
+        InternalIndexerDBConfig::new(true, true, true, true, true, true, 10);
+    super_new_test_context(
+        test_name,
+        node_config,
+        false,
+        None,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+    )
 }
 
 fn new_test_context_with_sharding_and_delayed_internal_indexer(
@@ -45,6 +78,13 @@ fn new_test_context_with_sharding_and_delayed_internal_indexer(
 ) -> TestContext {
     let mut node_config = NodeConfig::default();
     node_config.storage.rocksdb_configs.enable_storage_sharding = true;
-    node_config.indexer_db_config = InternalIndexerDBConfig::new(true, true, true, 1);
-    super_new_test_context(test_name, node_config, false, end_version)
+    node_config.indexer_db_config =
+        InternalIndexerDBConfig::new(true, true, true, true, true, true, 1);
+    super_new_test_context(
+        test_name,
+        node_config,
+        false,
+        end_version,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+    )
 }
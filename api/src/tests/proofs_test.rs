@@ -0,0 +1,307 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::current_function_name;
+use aptos_api_types::{
+    EpochChangeProofResponse, StateProofResponse, StateValueProofResponse,
+    StateValueRangeProofResponse, TransactionInfoProof, TransactionInfoProofResponse,
+};
+use aptos_crypto::{
+    hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
+    HashValue,
+};
+use aptos_types::{
+    account_config::AccountResource,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof, SparseMerkleRangeProof,
+        TransactionAccumulatorProof, TransactionInfoWithProof,
+    },
+    state_proof::StateProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let resp = context.get("/proofs/state?known_version=0").await;
+    let resp: StateProofResponse = serde_json::from_value(resp).unwrap();
+    assert_eq!(resp.version.0, ledger_version);
+    assert!(!resp.more);
+
+    let state_proof: StateProof = bcs::from_bytes(resp.proof.inner()).unwrap();
+    assert_eq!(state_proof.latest_ledger_info().version(), ledger_version);
+    assert_eq!(state_proof.latest_ledger_info().epoch(), resp.epoch.0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_proof_with_future_known_version() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    context
+        .expect_status_code(404)
+        .get(&format!(
+            "/proofs/state?known_version={}",
+            ledger_version + 1
+        ))
+        .await;
+}
+
+fn verify_transaction_info(
+    ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    proof: &TransactionInfoProof,
+) -> TransactionInfoWithProof {
+    let txn_info_with_proof = TransactionInfoWithProof::new(
+        TransactionAccumulatorProof::new(
+            proof
+                .accumulator_siblings
+                .iter()
+                .map(|hash| hash.0)
+                .collect(),
+        ),
+        bcs::from_bytes(proof.transaction_info.inner()).unwrap(),
+    );
+    txn_info_with_proof
+        .verify(ledger_info_with_sigs.ledger_info(), proof.version.0)
+        .unwrap();
+    txn_info_with_proof
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_value_with_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    let state_key = StateKey::resource_typed::<AccountResource>(&account.address()).unwrap();
+    let resp = context
+        .post(
+            "/proofs/state_value",
+            json!({ "key": hex::encode(bcs::to_bytes(&state_key).unwrap()) }),
+        )
+        .await;
+    let resp: StateValueProofResponse = serde_json::from_value(resp).unwrap();
+
+    let ledger_info_with_sigs: LedgerInfoWithSignatures =
+        bcs::from_bytes(resp.ledger_info.ledger_info_with_signatures.inner()).unwrap();
+    assert_eq!(
+        ledger_info_with_sigs.ledger_info().version(),
+        resp.version.0
+    );
+    let txn_info_with_proof =
+        verify_transaction_info(&ledger_info_with_sigs, &resp.transaction_info);
+
+    let state_value: StateValue =
+        bcs::from_bytes(resp.state_value.as_ref().unwrap().inner()).unwrap();
+    let leaf = resp.proof.leaf.unwrap();
+    let proof = SparseMerkleProof::new(
+        Some(SparseMerkleLeafNode::new(leaf.key.0, leaf.value_hash.0)),
+        resp.proof.siblings.iter().map(|hash| hash.0).collect(),
+    );
+    proof
+        .verify(
+            txn_info_with_proof
+                .transaction_info()
+                .state_checkpoint_hash()
+                .unwrap(),
+            state_key.hash(),
+            Some(&state_value),
+        )
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_value_with_proof_at_non_checkpoint_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    // The user transaction is followed by the state checkpoint ending the block.
+    let txn_version = context.get_latest_ledger_info().version() - 1;
+    let state_key = StateKey::resource_typed::<AccountResource>(&account.address()).unwrap();
+    context
+        .expect_status_code(400)
+        .post(
+            &format!("/proofs/state_value?ledger_version={}", txn_version),
+            json!({ "key": hex::encode(bcs::to_bytes(&state_key).unwrap()) }),
+        )
+        .await;
+}
+
+/// Computes the root hash of the sparse Merkle tree of the leaves, sorted by key, at the given
+/// depth.
+fn smt_root_hash(leaves: &[SparseMerkleLeafNode], depth: usize) -> HashValue {
+    match leaves {
+        [] => *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        [leaf] => leaf.hash(),
+        _ => {
+            let split = leaves.partition_point(|leaf| !leaf.key().bit(depth));
+            SparseMerkleInternalNode::new(
+                smt_root_hash(&leaves[..split], depth + 1),
+                smt_root_hash(&leaves[split..], depth + 1),
+            )
+            .hash()
+        },
+    }
+}
+
+/// Computes the siblings on the left of the path to the leaf at `index`, from the bottom to the
+/// root, i.e., what a client has to derive from the leaves it already got to verify a range
+/// proof.
+fn smt_left_siblings(leaves: &[SparseMerkleLeafNode], index: usize) -> Vec<HashValue> {
+    let key = leaves[index].key();
+    let mut siblings = vec![];
+    let mut subtree = leaves;
+    let mut depth = 0;
+    while subtree.len() > 1 {
+        let split = subtree.partition_point(|leaf| !leaf.key().bit(depth));
+        if key.bit(depth) {
+            siblings.push(smt_root_hash(&subtree[..split], depth + 1));
+            subtree = &subtree[split..];
+        } else {
+            subtree = &subtree[..split];
+        }
+        depth += 1;
+    }
+    siblings.reverse();
+    siblings
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_values_with_range_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    let ledger_version = context.get_latest_ledger_info().version();
+    let limit = 100;
+    let mut pages = vec![];
+    let mut start = 0;
+    loop {
+        let resp = context
+            .get(&format!(
+                "/proofs/state_values?ledger_version={}&start={}&limit={}",
+                ledger_version, start, limit
+            ))
+            .await;
+        let resp: StateValueRangeProofResponse = serde_json::from_value(resp).unwrap();
+        assert_eq!(resp.version.0, ledger_version);
+        assert_eq!(resp.first_index.0, start);
+        assert_eq!(
+            resp.last_index.0 - resp.first_index.0 + 1,
+            resp.state_values.len() as u64
+        );
+        assert!(resp.state_values.len() <= limit);
+
+        start = resp.last_index.0 + 1;
+        // The last chunk has nothing on its right.
+        let is_last = resp
+            .right_siblings
+            .iter()
+            .all(|hash| hash.0 == *SPARSE_MERKLE_PLACEHOLDER_HASH);
+        pages.push(resp);
+        if is_last {
+            break;
+        }
+    }
+    assert!(pages.len() > 1);
+
+    // Paging past the last state value fails.
+    context
+        .expect_status_code(400)
+        .get(&format!(
+            "/proofs/state_values?ledger_version={}&start={}",
+            ledger_version, start
+        ))
+        .await;
+
+    let leaves = pages
+        .iter()
+        .flat_map(|page| &page.state_values)
+        .map(|item| {
+            let key: StateKey = bcs::from_bytes(item.key.inner()).unwrap();
+            let value: StateValue = bcs::from_bytes(item.value.inner()).unwrap();
+            SparseMerkleLeafNode::new(key.hash(), value.hash())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(leaves.len() as u64, start);
+    assert!(leaves.windows(2).all(|pair| pair[0].key() < pair[1].key()));
+
+    // Together, the pages make up the whole state committed to by the state checkpoint hash,
+    // and each of them can be verified against it.
+    let ledger_info_with_sigs: LedgerInfoWithSignatures =
+        bcs::from_bytes(pages[0].ledger_info.ledger_info_with_signatures.inner()).unwrap();
+    let state_root = verify_transaction_info(&ledger_info_with_sigs, &pages[0].transaction_info)
+        .transaction_info()
+        .state_checkpoint_hash()
+        .unwrap();
+    assert_eq!(smt_root_hash(&leaves, 0), state_root);
+    for page in &pages {
+        let last_index = page.last_index.0 as usize;
+        SparseMerkleRangeProof::new(page.right_siblings.iter().map(|hash| hash.0).collect())
+            .verify(
+                state_root,
+                leaves[last_index],
+                smt_left_siblings(&leaves, last_index),
+            )
+            .unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_values_with_range_proof_out_of_range() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get(&format!("/proofs/state_values?start={}", u64::MAX))
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_info_with_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let txn_version = context.get_latest_ledger_info().version() - 1;
+    let resp = context
+        .get(&format!("/proofs/transaction_info/{}", txn_version))
+        .await;
+    let resp: TransactionInfoProofResponse = serde_json::from_value(resp).unwrap();
+    assert_eq!(resp.transaction_info.version.0, txn_version);
+
+    let ledger_info_with_sigs: LedgerInfoWithSignatures =
+        bcs::from_bytes(resp.ledger_info.ledger_info_with_signatures.inner()).unwrap();
+    let txn_info_with_proof =
+        verify_transaction_info(&ledger_info_with_sigs, &resp.transaction_info);
+    assert_eq!(
+        txn_info_with_proof.transaction_info().transaction_hash(),
+        txn.committed_hash()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_epoch_change_proof() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/proofs/epoch_change?start_epoch=0").await;
+    let resp: EpochChangeProofResponse = serde_json::from_value(resp).unwrap();
+    assert!(!resp.more);
+    assert_eq!(resp.ledger_infos.len(), 1);
+    assert_eq!(resp.ledger_infos[0].epoch.0, 0);
+
+    context
+        .expect_status_code(400)
+        .get("/proofs/epoch_change?start_epoch=1")
+        .await;
+}
@@ -0,0 +1,153 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use crate::stream::{items_after, read_stream_items, ActiveConnection, StreamCursor, StreamKind};
+use aptos_api_test_context::current_function_name;
+use aptos_config::config::NodeConfig;
+use aptos_types::account_address::AccountAddress;
+use move_core_types::parser::parse_type_tag;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_from_genesis() {
+    let context = new_test_context(current_function_name!());
+
+    // Genesis is the only transaction in the ledger
+    let (next_version, items) =
+        read_stream_items(&context.context, &StreamKind::Transactions, 0).unwrap();
+    assert_eq!(next_version, 1);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].version, 0);
+    assert_eq!(items[0].event_type, "transaction");
+    assert_eq!(items[0].data["type"], "genesis_transaction");
+
+    // Nothing new has been committed yet
+    let (next_version, items) =
+        read_stream_items(&context.context, &StreamKind::Transactions, 1).unwrap();
+    assert_eq!(next_version, 1);
+    assert!(items.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_blocks_and_transactions_resume() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&[txn]).await;
+
+    // The committed block contains block metadata, the user transaction and a state checkpoint
+    let (next_version, items) =
+        read_stream_items(&context.context, &StreamKind::Blocks, 1).unwrap();
+    assert_eq!(next_version, 4);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].version, 1);
+    assert_eq!(items[0].event_type, "block");
+
+    // Resume the transaction stream after the block metadata
+    let (next_version, items) =
+        read_stream_items(&context.context, &StreamKind::Transactions, 2).unwrap();
+    assert_eq!(next_version, 4);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].version, 2);
+    assert_eq!(items[0].data["type"], "user_transaction");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_filtered_by_type() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&[txn]).await;
+
+    // Only new block events should be returned
+    let kind = StreamKind::Events {
+        event_type: Some(parse_type_tag("0x1::block::NewBlockEvent").unwrap()),
+        account: None,
+    };
+    let (next_version, items) = read_stream_items(&context.context, &kind, 1).unwrap();
+    assert_eq!(next_version, 4);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].version, 1);
+    assert_eq!(items[0].event_type, "event");
+    assert_eq!(items[0].data["type"], "0x1::block::NewBlockEvent");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_filtered_by_account() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&[txn]).await;
+
+    // Only events with handles created by the framework account should be returned
+    let kind = StreamKind::Events {
+        event_type: None,
+        account: Some(AccountAddress::ONE),
+    };
+    let (next_version, items) = read_stream_items(&context.context, &kind, 1).unwrap();
+    assert_eq!(next_version, 4);
+    assert!(!items.is_empty());
+    for item in &items {
+        assert_eq!(item.data["guid"]["account_address"], "0x1");
+    }
+
+    // No events have handles created by an unrelated account
+    let kind = StreamKind::Events {
+        event_type: None,
+        account: Some(AccountAddress::random()),
+    };
+    let (next_version, items) = read_stream_items(&context.context, &kind, 1).unwrap();
+    assert_eq!(next_version, 4);
+    assert!(items.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_resume_within_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&[txn]).await;
+
+    // The user transaction emits multiple events
+    let kind = StreamKind::Events {
+        event_type: None,
+        account: None,
+    };
+    let (_, items) = read_stream_items(&context.context, &kind, 2).unwrap();
+    let cursors: Vec<_> = items.iter().map(|item| item.cursor()).collect();
+    assert!(cursors.len() > 1);
+    assert_eq!(cursors[0].version, 2);
+    assert_eq!(cursors[1].version, 2);
+
+    // The cursor round trips through the SSE event id
+    let last_event_id = cursors[0].to_string();
+    assert_eq!(
+        last_event_id,
+        format!("2:{}", cursors[0].event_index.unwrap())
+    );
+    let cursor: StreamCursor = last_event_id.parse().unwrap();
+    assert_eq!(cursor, cursors[0]);
+
+    // Resuming after the first event returns the rest of the transaction's events
+    let (_, items) = read_stream_items(&context.context, &kind, cursor.version).unwrap();
+    let resumed: Vec<_> = items_after(items, Some(&cursor))
+        .map(|item| item.cursor())
+        .collect();
+    assert_eq!(resumed, cursors[1..].to_vec());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_max_active_connections() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.stream_max_active_connections = 1;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+    let context = std::sync::Arc::new(context.context.clone());
+
+    // Only a single stream can be active at a time
+    let active_connection = ActiveConnection::acquire(context.clone()).unwrap();
+    assert!(ActiveConnection::acquire(context.clone()).is_err());
+
+    // Closing the stream allows a new one to be opened
+    drop(active_connection);
+    assert!(ActiveConnection::acquire(context).is_ok());
+}
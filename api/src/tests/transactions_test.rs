@@ -813,6 +813,39 @@ async fn test_get_account_transactions() {
     test_account_transaction_with_context(shard_context).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_function() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    context
+        .context
+        .indexer_reader
+        .as_ref()
+        .unwrap()
+        .wait_for_internal_indexer(2)
+        .unwrap();
+
+    let txns = context
+        .get("/transactions/by_function/0x1::aptos_account::create_account")
+        .await;
+    assert_eq!(1, txns.as_array().unwrap().len());
+    let expected_txns = context.get("/transactions?start=2&limit=1").await;
+    assert_json(txns, expected_txns);
+
+    let txns = context
+        .get("/transactions/by_function/0x1::aptos_account::create_account?start_version=3")
+        .await;
+    assert_json(txns, json!([]));
+
+    let txns = context
+        .get("/transactions/by_function/0x1::aptos_account::transfer")
+        .await;
+    assert_json(txns, json!([]));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_transactions_filter_transactions_by_start_sequence_number() {
     let mut context = new_test_context(current_function_name!());
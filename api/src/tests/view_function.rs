@@ -1,10 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{new_test_context, new_test_context_with_config};
+use super::{new_test_context, new_test_context_with_config, new_test_context_with_ledger_pruning};
 use aptos_api_test_context::{current_function_name, TestContext};
 use aptos_cached_packages::aptos_stdlib;
 use aptos_config::config::{NodeConfig, ViewFilter, ViewFunctionId};
+use aptos_storage_interface::DbReader;
 use aptos_types::account_address::AccountAddress;
 use serde_json::{json, Value};
 use std::{path::PathBuf, str::FromStr};
@@ -252,3 +253,193 @@ async fn test_view_aggregator() {
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_view() {
+    let mut context = new_test_context(current_function_name!());
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+    let txn3 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2, txn3]).await;
+
+    // The coin conversion map is created at genesis, and maps APT to its fungible asset.
+    let conversion_map = context
+        .get("/accounts/0x1/resource/0x1::coin::CoinConversionMap?ledger_version=3")
+        .await;
+    let table_handle = conversion_map["data"]["coin_to_fungible_asset_map"]["handle"].clone();
+    let apt_type_info = json!({
+        "account_address": "0x1",
+        "module_name": "0x6170746f735f636f696e",
+        "struct_name": "0x4170746f73436f696e",
+    });
+
+    let mut balance_request = build_coin_balance_request(&owner.address());
+    balance_request["type"] = json!("view_function");
+    let resp = context
+        .post(
+            "/view/batch?ledger_version=3",
+            json!({
+                "items": [
+                    balance_request,
+                    {
+                        "type": "resource",
+                        "address": owner.address().to_string(),
+                        "resource_type": "0x1::account::Account",
+                    },
+                    {
+                        "type": "resource",
+                        "address": "0x1234",
+                        "resource_type": "0x1::account::Account",
+                    },
+                    {
+                        "type": "table_item",
+                        "table_handle": table_handle,
+                        "key_type": "0x1::type_info::TypeInfo",
+                        "value_type": "address",
+                        "key": apt_type_info,
+                    },
+                ],
+            }),
+        )
+        .await;
+
+    // All items are evaluated at the requested version, same as the single requests.
+    let single_resp = context
+        .post(
+            "/view?ledger_version=3",
+            build_coin_balance_request(&owner.address()),
+        )
+        .await;
+    let single_table_item_resp = context
+        .post(
+            &format!(
+                "/tables/{}/item?ledger_version=3",
+                table_handle.as_str().unwrap()
+            ),
+            json!({
+                "key_type": "0x1::type_info::TypeInfo",
+                "value_type": "address",
+                "key": apt_type_info,
+            }),
+        )
+        .await;
+    assert_eq!(resp["ledger_version"], json!("3"));
+    assert_eq!(resp["results"][0]["values"], single_resp);
+    assert_eq!(
+        resp["results"][1]["resource"]["type"],
+        json!("0x1::account::Account")
+    );
+    assert_eq!(
+        resp["results"][2]["error"]["error_code"],
+        json!("resource_not_found")
+    );
+    assert_eq!(resp["results"][3]["value"], single_table_item_resp);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_view_version_pruned() {
+    let mut context = new_test_context_with_ledger_pruning(current_function_name!(), 1);
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    context.commit_block(&vec![txn1]).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+    context.commit_block(&vec![txn2]).await;
+
+    let mut balance_request = build_coin_balance_request(&owner.address());
+    balance_request["type"] = json!("view_function");
+    let resp = context
+        .post(
+            "/view/batch?ledger_version=1",
+            json!({
+                "items": [
+                    balance_request,
+                    {
+                        "type": "resource",
+                        "address": "0x1",
+                        "resource_type": "0x1::account::Account",
+                    },
+                    {
+                        "type": "table_item",
+                        "table_handle": "0x1",
+                        "key_type": "u64",
+                        "value_type": "u64",
+                        "key": "1",
+                    },
+                ],
+            }),
+        )
+        .await;
+
+    // Every item reports the pruned version, along with the oldest version to retry at.
+    let oldest_state_version = std::cmp::max(
+        context.db.get_first_viable_block().unwrap().0,
+        context.db.get_first_state_value_version().unwrap().unwrap(),
+    );
+    assert!(oldest_state_version > 1);
+    let results = resp["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    for result in results {
+        assert_eq!(result["error"]["error_code"], json!("version_pruned"));
+        assert_eq!(
+            result["error"]["oldest_available_version"],
+            json!(oldest_state_version.to_string())
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_view_too_many_items() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_batch_size = 1;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let mut decimals_request = build_coin_decimals_request();
+    decimals_request["type"] = json!("view_function");
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view/batch",
+            json!({ "items": [decimals_request.clone(), decimals_request] }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_view_gas_budget_exhausted() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_gas_view_batch = 0;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let mut decimals_request = build_coin_decimals_request();
+    decimals_request["type"] = json!("view_function");
+    let resp = context
+        .post(
+            "/view/batch",
+            json!({
+                "items": [
+                    decimals_request,
+                    {
+                        "type": "resource",
+                        "address": "0x1",
+                        "resource_type": "0x1::account::Account",
+                    },
+                ],
+            }),
+        )
+        .await;
+
+    // Only the view functions are limited by the gas budget of the batch.
+    assert_eq!(
+        resp["results"][0]["error"]["error_code"],
+        json!("invalid_input")
+    );
+    assert_eq!(
+        resp["results"][1]["resource"]["type"],
+        json!("0x1::account::Account")
+    );
+}
@@ -22,8 +22,8 @@ use crate::{
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
+    AsConverter, EncodeSubmissionRequest, EntryFunctionId, GasEstimation, GasEstimationBcs,
+    HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
     Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
@@ -344,6 +344,53 @@ impl TransactionsApi {
         api_spawn_blocking(move || api.list_by_account(&accept_type, page, address.0)).await
     }
 
+    /// Get transactions by entry function
+    ///
+    /// Retrieves on-chain committed user transactions calling the given entry function,
+    /// including through a multisig account, in ascending order of version. If the start
+    /// version is too far in the past, a 410 will be returned.
+    ///
+    /// This requires the node to run the internal indexer with the transaction by function
+    /// index enabled.
+    #[oai(
+        path = "/transactions/by_function/:function_id",
+        method = "get",
+        operation_id = "get_transactions_by_function",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transactions_by_function(
+        &self,
+        accept_type: AcceptType,
+        /// Entry function id e.g. `0x1::aptos_account::transfer`
+        function_id: Path<EntryFunctionId>,
+        /// Ledger version to start list of transactions
+        ///
+        /// If not provided, defaults to the oldest version not pruned
+        start_version: Query<Option<U64>>,
+        /// Max number of transactions to retrieve.
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        function_id
+            .0
+            .verify()
+            .context("'function_id' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_transactions_by_function")?;
+        self.context
+            .check_api_output_enabled("Get transactions by function", &accept_type)?;
+        let page = Page::new(
+            start_version.0.map(|v| v.0),
+            limit.0,
+            self.context.max_transactions_page_size(),
+        );
+        let api = self.clone();
+        api_spawn_blocking(move || api.list_by_function(&accept_type, page, function_id.0)).await
+    }
+
     /// Submit transaction
     ///
     /// This endpoint accepts transaction submissions in two formats.
@@ -1035,6 +1082,54 @@ impl TransactionsApi {
         }
     }
 
+    /// List all transactions calling an entry function
+    fn list_by_function(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+        function_id: EntryFunctionId,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let oldest_version = latest_ledger_info.oldest_ledger_version.0;
+        let start_version = page.start_option().unwrap_or(oldest_version);
+        if start_version < oldest_version {
+            return Err(version_pruned(start_version, &latest_ledger_info));
+        }
+
+        let module_id: ModuleId = function_id.module.clone().into();
+        let data = self
+            .context
+            .get_transactions_by_function(
+                &module_id,
+                function_id.name.0.as_ident_str(),
+                start_version,
+                page.limit(&latest_ledger_info)?,
+                latest_ledger_info.version(),
+            )
+            .context(format!(
+                "Failed to find transactions calling {}",
+                function_id
+            ))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                self.context
+                    .render_transactions_non_sequential(&latest_ledger_info, data)?,
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((data, &latest_ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
+
     /// Parses a single signed transaction
     fn get_signed_transaction(
         &self,
@@ -1251,6 +1346,10 @@ impl TransactionsApi {
                 mempool_status.message,
                 AptosErrorCode::InvalidTransactionUpdate,
             )),
+            MempoolStatusCode::RejectedByFilter => Err(AptosError::new_with_error_code(
+                "Transaction not allowed by mempool transaction filter",
+                AptosErrorCode::InvalidInput,
+            )),
             MempoolStatusCode::UnknownStatus => Err(AptosError::new_with_error_code(
                 format!("Transaction was rejected with status {}", mempool_status,),
                 AptosErrorCode::InternalError,
@@ -1311,7 +1410,8 @@ impl TransactionsApi {
                 ),
                 AptosErrorCode::VmError
                 | AptosErrorCode::SequenceNumberTooOld
-                | AptosErrorCode::InvalidTransactionUpdate => Err(
+                | AptosErrorCode::InvalidTransactionUpdate
+                | AptosErrorCode::InvalidInput => Err(
                     SubmitTransactionError::bad_request_from_aptos_error(error, ledger_info),
                 ),
                 AptosErrorCode::MempoolIsFull => Err(
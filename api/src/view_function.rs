@@ -7,22 +7,27 @@ use crate::{
     context::{api_spawn_blocking, FunctionStats},
     failpoint::fail_point_poem,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        ForbiddenError, InternalError,
+        api_forbidden, version_not_found, AptosErrorResponse, BadRequestError, BasicErrorWith404,
+        BasicResponse, BasicResponseStatus, BasicResultWith404, ForbiddenError, InternalError,
     },
+    state::{read_resource, read_table_item, resource_to_json, table_item_to_json},
     ApiTags, Context,
 };
 use anyhow::Context as anyhowContext;
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED,
-    U64,
+    AptosErrorCode, AsConverter, BatchResourceRequest, BatchTableItemRequest, BatchViewItemError,
+    BatchViewItemRequest, BatchViewItemResult, BatchViewRequest, BatchViewResponse, LedgerInfo,
+    MoveResource, MoveValue, TableItemRequest, VerifyInput, VerifyInputWithRecursion, ViewFunction,
+    ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_bcs_utils::serialize_uleb128;
+use aptos_storage_interface::state_view::DbStateView;
+use aptos_types::transaction::{Version, ViewFunctionOutput};
 use aptos_vm::AptosVM;
 use itertools::Itertools;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::{param::Query, payload::Json, ApiRequest, OpenApi};
-use std::sync::Arc;
+use std::{cmp::min, convert::TryInto, sync::Arc};
 
 /// API for executing Move view function.
 #[derive(Clone)]
@@ -71,6 +76,378 @@ impl ViewFunctionApi {
         api_spawn_blocking(move || view_request(context, accept_type, request, ledger_version))
             .await
     }
+
+    /// Execute a batch of view functions and state reads
+    ///
+    /// Executes view functions, and reads resources and table items, all against the state at
+    /// the same ledger version. Each item succeeds or fails on its own, and the results are
+    /// returned in the same order as the items of the request.
+    ///
+    /// The view functions of the batch share a total gas budget. Once it is used up, the
+    /// remaining view functions fail, while the other items are still evaluated.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the state at the requested ledger version has been pruned, every item fails with a
+    /// `version_pruned` error, which includes the oldest ledger version still available.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "batch_view",
+        tag = "ApiTags::View"
+    )]
+    async fn batch_view(
+        &self,
+        accept_type: AcceptType,
+        /// The view functions, resources and table items to evaluate
+        request: Json<BatchViewRequest>,
+        /// Ledger version to evaluate all the items at
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<BatchViewResponse> {
+        fail_point_poem("endpoint_batch_view")?;
+
+        if AcceptType::Bcs == accept_type {
+            return Err(api_forbidden(
+                "Batch view",
+                "Only JSON is supported as an AcceptType.",
+            ));
+        }
+        self.context
+            .check_api_output_enabled("Batch view", &accept_type)?;
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            batch_view_request(context, request.0, ledger_version.0.map(|inner| inner.0))
+        })
+        .await
+    }
+}
+
+fn batch_view_request(
+    context: Arc<Context>,
+    request: BatchViewRequest,
+    ledger_version: Option<Version>,
+) -> BasicResultWith404<BatchViewResponse> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    if context.max_view_batch_size() < request.items.len() {
+        return Err(BasicErrorWith404::bad_request_with_code(
+            format!(
+                "Requested too many items: {}, while limit is {}",
+                request.items.len(),
+                context.max_view_batch_size(),
+            ),
+            AptosErrorCode::InvalidInput,
+            &ledger_info,
+        ));
+    }
+
+    let ledger_version = ledger_version.unwrap_or_else(|| ledger_info.version());
+    if ledger_version > ledger_info.version() {
+        return Err(version_not_found(ledger_version, &ledger_info));
+    }
+
+    // Instead of failing the whole request, report the pruned version on each item, so that
+    // the caller can tell which version to retry at.
+    let oldest_state_version = context.get_oldest_state_version(&ledger_info)?;
+    let mut gas_used = 0;
+    let results = if ledger_version < oldest_state_version {
+        request
+            .items
+            .iter()
+            .map(|_| BatchViewItemResult {
+                error: Some(BatchViewItemError::version_pruned(
+                    ledger_version,
+                    oldest_state_version,
+                )),
+                ..Default::default()
+            })
+            .collect()
+    } else {
+        let state_view = context
+            .state_view_at_version(ledger_version)
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+        let mut viewer = BatchViewer {
+            context: &context,
+            state_view: &state_view,
+            ledger_info: &ledger_info,
+            ledger_version,
+            remaining_gas: context.max_gas_view_batch(),
+        };
+        let results = request
+            .items
+            .into_iter()
+            .map(|item| viewer.evaluate(item))
+            .collect::<Vec<_>>();
+        gas_used = context.max_gas_view_batch() - viewer.remaining_gas;
+        results
+    };
+
+    BasicResponse::try_from_json((
+        BatchViewResponse {
+            ledger_version: ledger_version.into(),
+            results,
+        },
+        &ledger_info,
+        BasicResponseStatus::Ok,
+    ))
+    .map(|r| r.with_gas_used(Some(gas_used)))
+}
+
+/// Evaluates the items of a batch view request against the state at a single ledger version.
+struct BatchViewer<'a> {
+    context: &'a Context,
+    state_view: &'a DbStateView,
+    ledger_info: &'a LedgerInfo,
+    ledger_version: Version,
+    /// The gas left for the view functions of the batch.
+    remaining_gas: u64,
+}
+
+impl<'a> BatchViewer<'a> {
+    fn evaluate(&mut self, item: BatchViewItemRequest) -> BatchViewItemResult {
+        let result = match item {
+            BatchViewItemRequest::ViewFunction(request) => {
+                self.view_function(request)
+                    .map(|values| BatchViewItemResult {
+                        values: Some(values),
+                        ..Default::default()
+                    })
+            },
+            BatchViewItemRequest::Resource(request) => {
+                self.resource(request).map(|resource| BatchViewItemResult {
+                    resource: Some(resource),
+                    ..Default::default()
+                })
+            },
+            BatchViewItemRequest::TableItem(request) => {
+                self.table_item(request).map(|value| BatchViewItemResult {
+                    value: Some(value),
+                    ..Default::default()
+                })
+            },
+        };
+        result.unwrap_or_else(|error| BatchViewItemResult {
+            error: Some(self.item_error(error)),
+            ..Default::default()
+        })
+    }
+
+    fn view_function(&mut self, request: ViewRequest) -> Result<Vec<MoveValue>, BasicErrorWith404> {
+        let view_function = self
+            .state_view
+            .as_converter(self.context.db.clone(), self.context.indexer_reader.clone())
+            .convert_view_function(request)
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    self.ledger_info,
+                )
+            })?;
+        check_view_filter(self.context, &view_function)?;
+
+        // Each view function is limited by what's left of the gas budget of the batch.
+        if self.remaining_gas == 0 {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "The batch has used up its gas budget of {}",
+                    self.context.max_gas_view_batch()
+                ),
+                AptosErrorCode::InvalidInput,
+                self.ledger_info,
+            ));
+        }
+        let max_gas_amount = min(
+            self.context.node_config.api.max_gas_view_function,
+            self.remaining_gas,
+        );
+        let output = execute_view_function(
+            self.context,
+            self.state_view,
+            &view_function,
+            max_gas_amount,
+        );
+        self.remaining_gas = self.remaining_gas.saturating_sub(output.gas_used);
+
+        let values = output.values.map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InvalidInput,
+                self.ledger_info,
+            )
+        })?;
+        view_return_values_to_json(
+            self.context,
+            self.state_view,
+            &view_function,
+            values,
+            self.ledger_info,
+        )
+    }
+
+    fn resource(&self, request: BatchResourceRequest) -> Result<MoveResource, BasicErrorWith404> {
+        let BatchResourceRequest {
+            address,
+            resource_type,
+        } = request;
+        resource_type
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    self.ledger_info,
+                )
+            })?;
+        let tag: StructTag = resource_type
+            .try_into()
+            .context("Failed to parse given resource type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    self.ledger_info,
+                )
+            })?;
+
+        let bytes = read_resource(
+            self.context,
+            self.state_view,
+            address,
+            &tag,
+            self.ledger_version,
+            self.ledger_info,
+        )?;
+        resource_to_json(
+            self.context,
+            self.state_view,
+            &tag,
+            &bytes,
+            self.ledger_info,
+        )
+    }
+
+    fn table_item(&self, request: BatchTableItemRequest) -> Result<MoveValue, BasicErrorWith404> {
+        let table_handle = request.table_handle;
+        let request: TableItemRequest = request.into();
+        request
+            .verify()
+            .context("'table_item_request' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    self.ledger_info,
+                )
+            })?;
+
+        let (value_type, bytes) = read_table_item(
+            self.context,
+            self.state_view,
+            table_handle,
+            request,
+            self.ledger_version,
+            self.ledger_info,
+        )?;
+        table_item_to_json(
+            self.context,
+            self.state_view,
+            &value_type,
+            &bytes,
+            self.ledger_info,
+        )
+    }
+
+    /// The state can get pruned while the batch is being evaluated, in which case the errors
+    /// of the items are reported as such.
+    fn item_error(&self, mut err: BasicErrorWith404) -> BatchViewItemError {
+        match self.context.db.get_first_state_value_version() {
+            Ok(Some(oldest_version)) if self.ledger_version < oldest_version => {
+                BatchViewItemError::version_pruned(self.ledger_version, oldest_version)
+            },
+            _ => {
+                let err = err.inner_mut();
+                BatchViewItemError::new(&err.message, err.error_code)
+            },
+        }
+    }
+}
+
+/// Rejects the view function if it's not allowed by the filter.
+fn check_view_filter<E: ForbiddenError>(
+    context: &Context,
+    view_function: &ViewFunction,
+) -> Result<(), E> {
+    if !context.node_config.api.view_filter.allows(
+        view_function.module.address(),
+        view_function.module.name().as_str(),
+        view_function.function.as_str(),
+    ) {
+        return Err(E::forbidden_with_code_no_info(
+            format!(
+                "Function {}::{} is not allowed",
+                view_function.module, view_function.function
+            ),
+            AptosErrorCode::InvalidInput,
+        ));
+    }
+    Ok(())
+}
+
+/// Executes the view function, and records its gas usage in the view function stats.
+fn execute_view_function(
+    context: &Context,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    max_gas_amount: u64,
+) -> ViewFunctionOutput {
+    let output = AptosVM::execute_view_function(
+        state_view,
+        view_function.module.clone(),
+        view_function.function.clone(),
+        view_function.ty_args.clone(),
+        view_function.args.clone(),
+        max_gas_amount,
+    );
+    context.view_function_stats().increment(
+        FunctionStats::function_to_key(&view_function.module, &view_function.function),
+        output.gas_used,
+    );
+    output
+}
+
+/// Converts the BCS encoded return values of the view function to JSON.
+fn view_return_values_to_json<E: BadRequestError>(
+    context: &Context,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    values: Vec<Vec<u8>>,
+    ledger_info: &LedgerInfo,
+) -> Result<Vec<MoveValue>, E> {
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let return_types = converter
+        .function_return_types(view_function)
+        .and_then(|tys| {
+            tys.into_iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .map_err(|err| E::bad_request_with_code(err, AptosErrorCode::InternalError, ledger_info))?;
+
+    values
+        .into_iter()
+        .zip(return_types)
+        .map(|(v, ty)| converter.try_into_move_value(&ty, &v))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|err| E::bad_request_with_code(err, AptosErrorCode::InternalError, ledger_info))
 }
 
 fn view_request(
@@ -118,26 +495,12 @@ fn view_request(
     };
 
     // Reject the request if it's not allowed by the filter.
-    if !context.node_config.api.view_filter.allows(
-        view_function.module.address(),
-        view_function.module.name().as_str(),
-        view_function.function.as_str(),
-    ) {
-        return Err(BasicErrorWith404::forbidden_with_code_no_info(
-            format!(
-                "Function {}::{} is not allowed",
-                view_function.module, view_function.function
-            ),
-            AptosErrorCode::InvalidInput,
-        ));
-    }
+    check_view_filter(&context, &view_function)?;
 
-    let output = AptosVM::execute_view_function(
+    let output = execute_view_function(
+        &context,
         &state_view,
-        view_function.module.clone(),
-        view_function.function.clone(),
-        view_function.ty_args.clone(),
-        view_function.args.clone(),
+        &view_function,
         context.node_config.api.max_gas_view_function,
     );
     let values = output.values.map_err(|err| {
@@ -166,45 +529,16 @@ fn view_request(
             BasicResponse::try_from_encoded((ret, &ledger_info, BasicResponseStatus::Ok))
         },
         AcceptType::Json => {
-            let return_types = state_view
-                .as_converter(context.db.clone(), context.indexer_reader.clone())
-                .function_return_types(&view_function)
-                .and_then(|tys| {
-                    tys.into_iter()
-                        .map(TypeTag::try_from)
-                        .collect::<anyhow::Result<Vec<_>>>()
-                })
-                .map_err(|err| {
-                    BasicErrorWith404::bad_request_with_code(
-                        err,
-                        AptosErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
-
-            let move_vals = values
-                .into_iter()
-                .zip(return_types.into_iter())
-                .map(|(v, ty)| {
-                    state_view
-                        .as_converter(context.db.clone(), context.indexer_reader.clone())
-                        .try_into_move_value(&ty, &v)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()
-                .map_err(|err| {
-                    BasicErrorWith404::bad_request_with_code(
-                        err,
-                        AptosErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
+            let move_vals = view_return_values_to_json(
+                &context,
+                &state_view,
+                &view_function,
+                values,
+                &ledger_info,
+            )?;
 
             BasicResponse::try_from_json((move_vals, &ledger_info, BasicResponseStatus::Ok))
         },
     };
-    context.view_function_stats().increment(
-        FunctionStats::function_to_key(&view_function.module, &view_function.function),
-        output.gas_used,
-    );
     result.map(|r| r.with_gas_used(Some(output.gas_used)))
 }
@@ -10,8 +10,9 @@ use aptos_api_types::{
 use aptos_cached_packages::aptos_stdlib;
 use aptos_config::{
     config::{
-        NodeConfig, RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
-        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+        NodeConfig, PrunerConfig, RocksdbConfigs, StorageDirPaths,
+        BUFFERED_STATE_TARGET_ITEMS_FOR_TEST, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        NO_OP_STORAGE_PRUNER_CONFIG,
     },
     keys::ConfigKey,
 };
@@ -100,14 +101,22 @@ pub fn new_test_context(
     node_config: NodeConfig,
     use_db_with_indexer: bool,
 ) -> TestContext {
-    new_test_context_inner(test_name, node_config, use_db_with_indexer, None)
+    new_test_context_inner(
+        test_name,
+        node_config,
+        use_db_with_indexer,
+        None,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+    )
 }
 
+/// Note: the pruner config is only used if `use_db_with_indexer` is false
 pub fn new_test_context_inner(
     test_name: String,
     mut node_config: NodeConfig,
     use_db_with_indexer: bool,
     end_version: Option<u64>,
+    pruner_config: PrunerConfig,
 ) -> TestContext {
     // Speculative logging uses a global variable and when many instances use it together, they
     // panic, so we disable this to run tests.
@@ -145,8 +154,8 @@ pub fn new_test_context_inner(
     } else {
         let mut aptos_db = AptosDB::open(
             StorageDirPaths::from_path(&tmp_dir),
-            false,                       /* readonly */
-            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            false,         /* readonly */
+            pruner_config, /* pruner */
             RocksdbConfigs {
                 enable_storage_sharding: node_config
                     .storage
@@ -110,6 +110,10 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
         false
     }
 
+    pub fn is_resource_group_member(&self, tag: &StructTag) -> bool {
+        self.inner.view_resource_group_member(tag).is_some()
+    }
+
     pub fn find_resource(
         &self,
         state_view: &impl StateView,
@@ -17,6 +17,7 @@ mod index;
 mod ledger_info;
 pub mod mime_types;
 mod move_types;
+mod proof;
 mod state;
 mod table;
 pub mod transaction;
@@ -34,11 +35,17 @@ pub use headers::*;
 pub use index::{IndexResponse, IndexResponseBcs};
 pub use ledger_info::LedgerInfo;
 pub use move_types::{
-    verify_field_identifier, verify_function_identifier, verify_module_identifier, EntryFunctionId,
-    HexEncodedBytes, MoveAbility, MoveFunction, MoveFunctionGenericTypeParam,
-    MoveFunctionVisibility, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType, MoveValue,
-    ResourceGroup, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
+    verify_field_identifier, verify_function_identifier, verify_module_identifier,
+    AccountMoveResource, EntryFunctionId, HexEncodedBytes, MoveAbility, MoveFunction,
+    MoveFunctionGenericTypeParam, MoveFunctionVisibility, MoveModule, MoveModuleBytecode,
+    MoveModuleId, MoveResource, MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag,
+    MoveType, MoveValue, ResourceGroup, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
+};
+pub use proof::{
+    EpochChangeProofResponse, SignedLedgerInfo, SparseMerkleLeaf, SparseMerkleProofResponse,
+    StateKeyValue, StateProofResponse, StateValueChunkWithProofBcs, StateValueProofResponse,
+    StateValueRangeProofResponse, StateValueWithProofBcs, TransactionInfoProof,
+    TransactionInfoProofResponse, TransactionInfoWithProofBcs,
 };
 use serde::{Deserialize, Deserializer};
 pub use state::RawStateValueRequest;
@@ -57,7 +64,10 @@ pub use transaction::{
     UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
     WriteSetPayload, WriteTableItem,
 };
-pub use view::{ViewFunction, ViewRequest};
+pub use view::{
+    BatchResourceRequest, BatchTableItemRequest, BatchViewItemError, BatchViewItemRequest,
+    BatchViewItemResult, BatchViewRequest, BatchViewResponse, ViewFunction, ViewRequest,
+};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
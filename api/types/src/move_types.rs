@@ -52,6 +52,13 @@ impl TryFrom<AnnotatedMoveStruct> for MoveResource {
     }
 }
 
+/// A parsed Move resource, along with the account holding it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AccountMoveResource {
+    pub address: Address,
+    pub resource: MoveResource,
+}
+
 macro_rules! define_integer_type {
     ($n:ident, $t:ty, $d:literal) => {
         #[doc = $d]
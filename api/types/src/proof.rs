@@ -0,0 +1,314 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{HashValue, HexEncodedBytes, U64};
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{
+    epoch_change::EpochChangeProof,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleProof, TransactionInfoWithProof},
+    state_proof::StateProof,
+    state_store::state_value::{StateValue, StateValueChunkWithProof},
+    transaction::Version,
+};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// A proof of the latest ledger info, along with the epoch changes since a known version
+///
+/// Light clients verify it starting from a trusted waypoint, ratcheting through the epoch
+/// changes to the validator set of the latest epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateProofResponse {
+    /// Version of the latest ledger info
+    pub version: U64,
+    /// Epoch of the latest ledger info
+    pub epoch: U64,
+    /// Whether there are more epoch changes than included in the proof, in which case
+    /// the proof only gets up to the last included epoch change
+    pub more: bool,
+    /// BCS-encoded `StateProof`
+    pub proof: HexEncodedBytes,
+}
+
+impl TryFrom<&StateProof> for StateProofResponse {
+    type Error = bcs::Error;
+
+    fn try_from(state_proof: &StateProof) -> Result<Self, Self::Error> {
+        let ledger_info = state_proof.latest_ledger_info();
+        Ok(Self {
+            version: ledger_info.version().into(),
+            epoch: ledger_info.epoch().into(),
+            more: state_proof.epoch_changes().more,
+            proof: bcs::to_bytes(state_proof)?.into(),
+        })
+    }
+}
+
+/// A signed ledger info, which the proofs in a response are relative to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SignedLedgerInfo {
+    pub epoch: U64,
+    pub version: U64,
+    /// Root hash of the transaction accumulator at `version`
+    pub accumulator_root_hash: HashValue,
+    /// BCS-encoded `LedgerInfoWithSignatures`
+    pub ledger_info_with_signatures: HexEncodedBytes,
+}
+
+impl TryFrom<&LedgerInfoWithSignatures> for SignedLedgerInfo {
+    type Error = bcs::Error;
+
+    fn try_from(ledger_info_with_sigs: &LedgerInfoWithSignatures) -> Result<Self, Self::Error> {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        Ok(Self {
+            epoch: ledger_info.epoch().into(),
+            version: ledger_info.version().into(),
+            accumulator_root_hash: ledger_info.transaction_accumulator_hash().into(),
+            ledger_info_with_signatures: bcs::to_bytes(ledger_info_with_sigs)?.into(),
+        })
+    }
+}
+
+/// A leaf of the sparse Merkle tree
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SparseMerkleLeaf {
+    /// Hash of the state key
+    pub key: HashValue,
+    /// Hash of the state value
+    pub value_hash: HashValue,
+}
+
+/// A proof of the existence, or the absence, of a state key in the sparse Merkle tree
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SparseMerkleProofResponse {
+    /// The leaf found on the path of the key. If it is another key's leaf, or there is none,
+    /// the key does not exist
+    pub leaf: Option<SparseMerkleLeaf>,
+    /// Siblings on the path, ordered from the root to the bottom
+    pub siblings: Vec<HashValue>,
+}
+
+impl From<&SparseMerkleProof> for SparseMerkleProofResponse {
+    fn from(proof: &SparseMerkleProof) -> Self {
+        Self {
+            leaf: proof.leaf().map(|leaf| SparseMerkleLeaf {
+                key: leaf.key().into(),
+                value_hash: leaf.value_hash().into(),
+            }),
+            siblings: proof.siblings().iter().map(|hash| (*hash).into()).collect(),
+        }
+    }
+}
+
+/// A transaction info along with the accumulator proof connecting it to a ledger info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct TransactionInfoProof {
+    pub version: U64,
+    /// BCS-encoded `TransactionInfo`
+    pub transaction_info: HexEncodedBytes,
+    pub transaction_info_hash: HashValue,
+    /// Root hash of the state tree, only present if the version is a state checkpoint
+    pub state_checkpoint_hash: Option<HashValue>,
+    /// Siblings on the path from the transaction info to the accumulator root, ordered from
+    /// the bottom to the root
+    pub accumulator_siblings: Vec<HashValue>,
+}
+
+impl TransactionInfoProof {
+    pub fn try_new(
+        version: Version,
+        txn_info_with_proof: &TransactionInfoWithProof,
+    ) -> Result<Self, bcs::Error> {
+        let txn_info = txn_info_with_proof.transaction_info();
+        Ok(Self {
+            version: version.into(),
+            transaction_info: bcs::to_bytes(txn_info)?.into(),
+            transaction_info_hash: txn_info.hash().into(),
+            state_checkpoint_hash: txn_info.state_checkpoint_hash().map(Into::into),
+            accumulator_siblings: txn_info_with_proof
+                .ledger_info_to_transaction_info_proof()
+                .siblings()
+                .iter()
+                .map(|hash| (*hash).into())
+                .collect(),
+        })
+    }
+}
+
+/// A state value, or the absence of it, along with the proofs connecting it to a signed
+/// ledger info. This is just for the BCS response
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateValueWithProofBcs {
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures,
+    /// Version of the state, which is a state checkpoint
+    pub version: Version,
+    pub state_value: Option<StateValue>,
+    /// Proof of the state value against the state checkpoint hash of the transaction info
+    pub proof: SparseMerkleProof,
+    /// Proof of the transaction info at `version` against the ledger info
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+}
+
+/// A state value, or the absence of it, along with the proofs connecting it to a signed
+/// ledger info. Only for responding in JSON
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateValueProofResponse {
+    pub ledger_info: SignedLedgerInfo,
+    pub version: U64,
+    /// BCS-encoded `StateValue`, if the state key exists
+    pub state_value: Option<HexEncodedBytes>,
+    pub proof: SparseMerkleProofResponse,
+    pub transaction_info: TransactionInfoProof,
+}
+
+impl TryFrom<&StateValueWithProofBcs> for StateValueProofResponse {
+    type Error = bcs::Error;
+
+    fn try_from(value: &StateValueWithProofBcs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ledger_info: (&value.ledger_info_with_signatures).try_into()?,
+            version: value.version.into(),
+            state_value: value
+                .state_value
+                .as_ref()
+                .map(|state_value| bcs::to_bytes(state_value).map(Into::into))
+                .transpose()?,
+            proof: (&value.proof).into(),
+            transaction_info: TransactionInfoProof::try_new(
+                value.version,
+                &value.transaction_info_with_proof,
+            )?,
+        })
+    }
+}
+
+/// A chunk of consecutive state values, ordered by the hashes of their keys, along with the
+/// proofs connecting it to a signed ledger info. This is just for the BCS response
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateValueChunkWithProofBcs {
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures,
+    /// Version of the state, which is a state checkpoint
+    pub version: Version,
+    /// The state values along with the range proof against the state checkpoint hash
+    pub chunk: StateValueChunkWithProof,
+    /// Proof of the transaction info at `version` against the ledger info
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+}
+
+/// A state key and its value
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateKeyValue {
+    /// BCS-encoded `StateKey`
+    pub key: HexEncodedBytes,
+    /// BCS-encoded `StateValue`
+    pub value: HexEncodedBytes,
+}
+
+/// A chunk of consecutive state values, ordered by the hashes of their keys, along with the
+/// proofs connecting it to a signed ledger info. Only for responding in JSON
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateValueRangeProofResponse {
+    pub ledger_info: SignedLedgerInfo,
+    pub version: U64,
+    /// Index of the first state value, among all state values ordered by key hash
+    pub first_index: U64,
+    /// Index of the last state value
+    pub last_index: U64,
+    pub state_values: Vec<StateKeyValue>,
+    /// Siblings on the right of the path to the last state value, ordered from the bottom to
+    /// the root. Together with the state values, they prove there are no keys in between
+    pub right_siblings: Vec<HashValue>,
+    pub transaction_info: TransactionInfoProof,
+}
+
+impl TryFrom<&StateValueChunkWithProofBcs> for StateValueRangeProofResponse {
+    type Error = bcs::Error;
+
+    fn try_from(value: &StateValueChunkWithProofBcs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ledger_info: (&value.ledger_info_with_signatures).try_into()?,
+            version: value.version.into(),
+            first_index: value.chunk.first_index.into(),
+            last_index: value.chunk.last_index.into(),
+            state_values: value
+                .chunk
+                .raw_values
+                .iter()
+                .map(|(key, value)| {
+                    Ok(StateKeyValue {
+                        key: bcs::to_bytes(key)?.into(),
+                        value: bcs::to_bytes(value)?.into(),
+                    })
+                })
+                .collect::<Result<_, bcs::Error>>()?,
+            right_siblings: value
+                .chunk
+                .proof
+                .right_siblings()
+                .iter()
+                .map(|hash| (*hash).into())
+                .collect(),
+            transaction_info: TransactionInfoProof::try_new(
+                value.version,
+                &value.transaction_info_with_proof,
+            )?,
+        })
+    }
+}
+
+/// A transaction info along with the accumulator proof connecting it to a signed ledger info.
+/// This is just for the BCS response
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionInfoWithProofBcs {
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures,
+    pub version: Version,
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+}
+
+/// A transaction info along with the accumulator proof connecting it to a signed ledger info.
+/// Only for responding in JSON
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct TransactionInfoProofResponse {
+    pub ledger_info: SignedLedgerInfo,
+    pub transaction_info: TransactionInfoProof,
+}
+
+impl TryFrom<&TransactionInfoWithProofBcs> for TransactionInfoProofResponse {
+    type Error = bcs::Error;
+
+    fn try_from(value: &TransactionInfoWithProofBcs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ledger_info: (&value.ledger_info_with_signatures).try_into()?,
+            transaction_info: TransactionInfoProof::try_new(
+                value.version,
+                &value.transaction_info_with_proof,
+            )?,
+        })
+    }
+}
+
+/// The epoch change ledger infos of a range of epochs, each signed by the validators of
+/// the epoch it ends
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct EpochChangeProofResponse {
+    pub ledger_infos: Vec<SignedLedgerInfo>,
+    /// Whether the range was cut short, in which case the next request should start from
+    /// the epoch after the last included ledger info
+    pub more: bool,
+}
+
+impl TryFrom<&EpochChangeProof> for EpochChangeProofResponse {
+    type Error = bcs::Error;
+
+    fn try_from(proof: &EpochChangeProof) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ledger_infos: proof
+                .ledger_info_with_sigs
+                .iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            more: proof.more,
+        })
+    }
+}
@@ -1,14 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{
+    Address, AptosErrorCode, EntryFunctionId, MoveResource, MoveStructTag, MoveType, MoveValue,
+    TableItemRequest, U64,
+};
 use aptos_types::serde_helper::vec_bytes;
 use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
 };
-use poem_openapi::Object;
+use poem_openapi::{Object, Union};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// View request for the Move View Function API
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
@@ -28,3 +32,104 @@ pub struct ViewFunction {
     #[serde(with = "vec_bytes")]
     pub args: Vec<Vec<u8>>,
 }
+
+/// Request for the batch view API
+///
+/// All items are evaluated against the state at the same ledger version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BatchViewRequest {
+    pub items: Vec<BatchViewItemRequest>,
+}
+
+/// A single item of a batch view request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
+pub enum BatchViewItemRequest {
+    ViewFunction(ViewRequest),
+    Resource(BatchResourceRequest),
+    TableItem(BatchTableItemRequest),
+}
+
+/// Resource read of a batch view request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BatchResourceRequest {
+    pub address: Address,
+    pub resource_type: MoveStructTag,
+}
+
+/// Table item read of a batch view request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BatchTableItemRequest {
+    pub table_handle: Address,
+    pub key_type: MoveType,
+    pub value_type: MoveType,
+    /// The value of the table item's key
+    pub key: Value,
+}
+
+impl From<BatchTableItemRequest> for TableItemRequest {
+    fn from(request: BatchTableItemRequest) -> Self {
+        Self {
+            key_type: request.key_type,
+            value_type: request.value_type,
+            key: request.key,
+        }
+    }
+}
+
+/// Response of the batch view API
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct BatchViewResponse {
+    /// The ledger version all items were evaluated at
+    pub ledger_version: U64,
+    /// The results of the items, in the same order as in the request
+    pub results: Vec<BatchViewItemResult>,
+}
+
+/// Result of a single item of a batch view request
+///
+/// Exactly one of the fields is set, depending on the type of the item and whether it succeeded.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Object)]
+pub struct BatchViewItemResult {
+    /// Return values of a view function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<MoveValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<MoveResource>,
+    /// Value of a table item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<MoveValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchViewItemError>,
+}
+
+/// Error of a single item of a batch view request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct BatchViewItemError {
+    /// A message describing the error
+    pub message: String,
+    pub error_code: AptosErrorCode,
+    /// Set if the state at the requested ledger version has been pruned, to the oldest ledger
+    /// version the state is still available at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_available_version: Option<U64>,
+}
+
+impl BatchViewItemError {
+    pub fn new<ErrorType: std::fmt::Display>(error: ErrorType, error_code: AptosErrorCode) -> Self {
+        Self {
+            message: format!("{:#}", error),
+            error_code,
+            oldest_available_version: None,
+        }
+    }
+
+    pub fn version_pruned(ledger_version: u64, oldest_available_version: u64) -> Self {
+        Self {
+            message: format!("Ledger version({}) has been pruned", ledger_version),
+            error_code: AptosErrorCode::VersionPruned,
+            oldest_available_version: Some(oldest_available_version.into()),
+        }
+    }
+}
@@ -1,7 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{bail, format_err, Result};
+use crate::{
+    framework_override::{FrameworkOverrideStateView, FrameworkOverrides},
+    transaction_diff::TransactionDiff,
+};
+use anyhow::{bail, ensure, format_err, Result};
 use aptos_block_executor::txn_commit_hook::NoOpTransactionCommitHook;
 use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
 use aptos_rest_client::Client;
@@ -10,7 +14,7 @@ use aptos_types::{
     block_executor::config::{
         BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
     },
-    state_store::TStateView,
+    state_store::{StateView, TStateView},
     transaction::{
         signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput,
         SignedTransaction, Transaction, TransactionInfo, TransactionOutput, TransactionPayload,
@@ -33,11 +37,15 @@ use std::{path::Path, sync::Arc, time::Instant};
 
 pub struct AptosDebugger {
     debugger: Arc<dyn AptosValidatorInterface + Send>,
+    framework_overrides: FrameworkOverrides,
 }
 
 impl AptosDebugger {
     pub fn new(debugger: Arc<dyn AptosValidatorInterface + Send>) -> Self {
-        Self { debugger }
+        Self {
+            debugger,
+            framework_overrides: FrameworkOverrides::default(),
+        }
     }
 
     pub fn rest_client(rest_client: Client) -> Result<Self> {
@@ -50,6 +58,13 @@ impl AptosDebugger {
         )?)))
     }
 
+    /// Executes all transactions with the given framework overrides (e.g., the modules of a
+    /// modified framework), instead of the on-chain modules.
+    pub fn with_framework_overrides(mut self, framework_overrides: FrameworkOverrides) -> Self {
+        self.framework_overrides = framework_overrides;
+        self
+    }
+
     pub fn execute_transactions_at_version(
         &self,
         version: Version,
@@ -59,7 +74,7 @@ impl AptosDebugger {
     ) -> Result<Vec<TransactionOutput>> {
         let sig_verified_txns: Vec<SignatureVerifiedTransaction> =
             txns.into_iter().map(|x| x.into()).collect::<Vec<_>>();
-        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let state_view = self.execution_state_view_at_version(version);
 
         print_transaction_stats(&sig_verified_txns, version);
 
@@ -107,7 +122,26 @@ impl AptosDebugger {
         version: Version,
         txn: SignedTransaction,
     ) -> Result<(VMStatus, VMOutput, TransactionGasLog)> {
-        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        self.execute_transaction_at_version_with_gas_profiler_and_overrides(
+            version,
+            txn,
+            self.framework_overrides.clone(),
+        )
+    }
+
+    /// Same as `execute_transaction_at_version_with_gas_profiler`, but with the given overrides
+    /// instead of the ones of the debugger. This allows profiling the same transaction under,
+    /// e.g., two different gas schedules.
+    pub fn execute_transaction_at_version_with_gas_profiler_and_overrides(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        framework_overrides: FrameworkOverrides,
+    ) -> Result<(VMStatus, VMOutput, TransactionGasLog)> {
+        let state_view = FrameworkOverrideStateView::new(
+            self.state_view_at_version(version),
+            framework_overrides,
+        );
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
         let txn = txn
             .check_signature()
@@ -185,6 +219,44 @@ impl AptosDebugger {
         }
     }
 
+    /// Replays the given range of committed transactions, and returns the divergences between
+    /// the on-chain outputs and the replayed ones (only for the transactions that diverge).
+    pub async fn replay_and_diff_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+        use_same_block_boundaries: bool,
+        concurrency_levels: &[usize],
+    ) -> Result<Vec<TransactionDiff>> {
+        let (txns, txn_infos, expected_outputs) = self
+            .debugger
+            .get_committed_transactions_with_outputs(begin, limit)
+            .await?;
+
+        let actual_outputs = if use_same_block_boundaries {
+            self.execute_transactions_by_block(begin, txns, 1, concurrency_levels)
+                .await?
+        } else {
+            self.execute_transactions_by_epoch(limit, begin, txns, 1, concurrency_levels, txn_infos)
+                .await?
+        };
+        ensure!(
+            actual_outputs.len() == expected_outputs.len(),
+            "Replayed {} transactions, but expected {}",
+            actual_outputs.len(),
+            expected_outputs.len()
+        );
+
+        Ok(expected_outputs
+            .iter()
+            .zip(actual_outputs.iter())
+            .enumerate()
+            .filter_map(|(idx, (expected, actual))| {
+                TransactionDiff::new(begin + idx as Version, expected, actual)
+            })
+            .collect())
+    }
+
     fn print_mismatches(
         txn_outputs: &[TransactionOutput],
         expected_txn_infos: &[TransactionInfo],
@@ -348,6 +420,17 @@ impl AptosDebugger {
     pub fn state_view_at_version(&self, version: Version) -> DebuggerStateView {
         DebuggerStateView::new(self.debugger.clone(), version)
     }
+
+    /// Returns the state view used to execute transactions (i.e., with the framework overrides)
+    fn execution_state_view_at_version(
+        &self,
+        version: Version,
+    ) -> FrameworkOverrideStateView<DebuggerStateView> {
+        FrameworkOverrideStateView::new(
+            self.state_view_at_version(version),
+            self.framework_overrides.clone(),
+        )
+    }
 }
 
 fn print_transaction_stats(sig_verified_txns: &[SignatureVerifiedTransaction], version: u64) {
@@ -421,7 +504,7 @@ fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
 
 fn execute_block_no_limit(
     sig_verified_txns: &[SignatureVerifiedTransaction],
-    state_view: &DebuggerStateView,
+    state_view: &(impl StateView + Sync),
     concurrency_level: usize,
 ) -> Result<Vec<TransactionOutput>, VMStatus> {
     BlockAptosVM::execute_block::<_, NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>>(
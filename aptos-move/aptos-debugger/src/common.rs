@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{execute_past_transactions, execute_pending_block};
+use crate::{execute_past_transactions, execute_pending_block, gas_profile_diff, replay_and_diff};
 use anyhow::Result;
 use clap::Parser;
 use std::path::PathBuf;
@@ -35,6 +35,8 @@ pub struct Opts {
 pub enum Command {
     ExecutePastTransactions(execute_past_transactions::Command),
     ExecutePendingBlock(execute_pending_block::Command),
+    ReplayAndDiff(replay_and_diff::Command),
+    GasProfileDiff(gas_profile_diff::Command),
 }
 
 impl Command {
@@ -42,6 +44,8 @@ impl Command {
         match self {
             Command::ExecutePastTransactions(cmd) => cmd.run().await,
             Command::ExecutePendingBlock(cmd) => cmd.run().await,
+            Command::ReplayAndDiff(cmd) => cmd.run().await,
+            Command::GasProfileDiff(cmd) => cmd.run().await,
         }
     }
 }
@@ -0,0 +1,85 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_framework::ReleaseBundle;
+use aptos_types::{
+    on_chain_config::GasScheduleV2,
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        Result as StateViewResult, StateViewId, TStateView,
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// The state that should be served instead of the one stored on-chain, e.g., the modules of a
+/// modified framework that is being validated against past transactions, or a proposed gas
+/// schedule.
+#[derive(Clone, Default)]
+pub struct FrameworkOverrides(Arc<HashMap<StateKey, StateValue>>);
+
+impl FrameworkOverrides {
+    /// Overrides all modules in the given release bundle. Note: only the module code is
+    /// swapped, the package metadata (i.e., the `PackageRegistry`) is left untouched.
+    pub fn from_release_bundle(bundle: &ReleaseBundle) -> Self {
+        let overrides = bundle
+            .code_and_compiled_modules()
+            .into_iter()
+            .map(|(code, module)| {
+                (
+                    StateKey::module_id(&module.self_id()),
+                    StateValue::new_legacy(code.to_vec().into()),
+                )
+            })
+            .collect();
+        Self(Arc::new(overrides))
+    }
+
+    /// Overrides the on-chain gas schedule.
+    pub fn with_gas_schedule(mut self, gas_schedule: &GasScheduleV2) -> Result<Self> {
+        Arc::make_mut(&mut self.0).insert(
+            StateKey::on_chain_config::<GasScheduleV2>()?,
+            StateValue::new_legacy(bcs::to_bytes(gas_schedule)?.into()),
+        );
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A state view that serves the framework overrides, and falls back to the base state view
+/// for everything else.
+pub struct FrameworkOverrideStateView<S> {
+    base: S,
+    overrides: FrameworkOverrides,
+}
+
+impl<S> FrameworkOverrideStateView<S> {
+    pub fn new(base: S, overrides: FrameworkOverrides) -> Self {
+        Self { base, overrides }
+    }
+}
+
+impl<S> TStateView for FrameworkOverrideStateView<S>
+where
+    S: TStateView<Key = StateKey>,
+{
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        match self.overrides.0.get(state_key) {
+            Some(state_value) => Ok(Some(state_value.clone())),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    aptos_debugger::AptosDebugger, common::Target, framework_override::FrameworkOverrides,
+};
+use anyhow::{bail, ensure, Result};
+use aptos_framework::ReleaseBundle;
+use aptos_gas_profiling::TransactionGasLogDiff;
+use aptos_rest_client::Client;
+use aptos_types::{
+    on_chain_config::GasScheduleV2,
+    transaction::{Transaction, Version},
+};
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Executes a committed user transaction twice with the gas profiler, e.g., under the on-chain
+/// and a proposed gas schedule, or under two framework release bundles, and generates a report
+/// with the gas deltas, as differential flamegraphs and JSON.
+///
+/// Each side runs with the on-chain state unless overridden.
+#[derive(Parser)]
+pub struct Command {
+    #[clap(flatten)]
+    target: Target,
+
+    #[clap(long)]
+    version: Version,
+
+    /// A framework release bundle (e.g., `head.mrb`) to execute the first run with.
+    #[clap(long)]
+    before_framework_path: Option<PathBuf>,
+
+    /// A framework release bundle (e.g., `head.mrb`) to execute the second run with.
+    #[clap(long)]
+    after_framework_path: Option<PathBuf>,
+
+    /// A gas schedule (`GasScheduleV2` as JSON) to execute the first run with.
+    #[clap(long)]
+    before_gas_schedule_path: Option<PathBuf>,
+
+    /// A gas schedule (`GasScheduleV2` as JSON) to execute the second run with.
+    #[clap(long)]
+    after_gas_schedule_path: Option<PathBuf>,
+
+    /// The directory to save the report to. Defaults to `gas-profiling/txn-<version>-diff`.
+    #[clap(long)]
+    output_path: Option<PathBuf>,
+}
+
+fn load_overrides(
+    framework_path: Option<&Path>,
+    gas_schedule_path: Option<&Path>,
+) -> Result<FrameworkOverrides> {
+    let mut overrides = match framework_path {
+        Some(path) => {
+            FrameworkOverrides::from_release_bundle(&ReleaseBundle::read(path.to_path_buf())?)
+        },
+        None => FrameworkOverrides::default(),
+    };
+    if let Some(path) = gas_schedule_path {
+        let gas_schedule: GasScheduleV2 = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        overrides = overrides.with_gas_schedule(&gas_schedule)?;
+    }
+    Ok(overrides)
+}
+
+/// Executes the committed transaction at `version` with the gas profiler under both sets of
+/// overrides, and saves the gas diff report to `report_path`. Returns None if it is not a
+/// user transaction.
+pub(crate) async fn generate_gas_profile_diff(
+    debugger: &AptosDebugger,
+    version: Version,
+    before: FrameworkOverrides,
+    after: FrameworkOverrides,
+    report_path: &Path,
+) -> Result<Option<TransactionGasLogDiff>> {
+    let (txn, _txn_info) = debugger
+        .get_committed_transaction_at_version(version)
+        .await?;
+    let txn = match txn {
+        Transaction::UserTransaction(txn) => txn,
+        _ => return Ok(None),
+    };
+
+    let (_, _, before_log) = debugger
+        .execute_transaction_at_version_with_gas_profiler_and_overrides(
+            version,
+            txn.clone(),
+            before,
+        )?;
+    let (_, _, after_log) = debugger
+        .execute_transaction_at_version_with_gas_profiler_and_overrides(version, txn, after)?;
+
+    let diff = before_log.generate_diff_report(
+        &after_log,
+        report_path,
+        format!("Gas Diff - Transaction {}", version),
+    )?;
+    Ok(Some(diff))
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let before = load_overrides(
+            self.before_framework_path.as_deref(),
+            self.before_gas_schedule_path.as_deref(),
+        )?;
+        let after = load_overrides(
+            self.after_framework_path.as_deref(),
+            self.after_gas_schedule_path.as_deref(),
+        )?;
+        ensure!(
+            !before.is_empty() || !after.is_empty(),
+            "At least one framework or gas schedule override must be provided"
+        );
+
+        let debugger = if let Some(rest_endpoint) = self.target.rest_endpoint {
+            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+        } else if let Some(db_path) = self.target.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            unreachable!("Must provide one target.");
+        };
+
+        let report_path = self.output_path.unwrap_or_else(|| {
+            Path::new("gas-profiling").join(format!("txn-{}-diff", self.version))
+        });
+        let diff =
+            match generate_gas_profile_diff(&debugger, self.version, before, after, &report_path)
+                .await?
+            {
+                Some(diff) => diff,
+                None => bail!("not a user transaction"),
+            };
+
+        println!(
+            "Execution & IO: {} -> {} gas units ({:+})",
+            diff.exec_io.total.before, diff.exec_io.total.after, diff.exec_io.total.delta
+        );
+        println!(
+            "Storage fee: {} -> {} Octa ({:+})",
+            diff.storage.total.before, diff.storage.total.after, diff.storage.total.delta
+        );
+        println!("Gas diff report saved to {}.", report_path.display());
+
+        Ok(())
+    }
+}
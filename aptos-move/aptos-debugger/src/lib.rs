@@ -6,3 +6,7 @@ pub mod bcs_txn_decoder;
 pub mod common;
 pub mod execute_past_transactions;
 pub mod execute_pending_block;
+pub mod framework_override;
+pub mod gas_profile_diff;
+pub mod replay_and_diff;
+pub mod transaction_diff;
@@ -0,0 +1,135 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    aptos_debugger::AptosDebugger, common::Opts, framework_override::FrameworkOverrides,
+    gas_profile_diff::generate_gas_profile_diff, transaction_diff::TransactionDiff,
+};
+use anyhow::{ensure, Result};
+use aptos_framework::ReleaseBundle;
+use aptos_rest_client::Client;
+use aptos_types::transaction::Version;
+use clap::Parser;
+use serde::Serialize;
+use std::{cmp::min, path::PathBuf};
+use url::Url;
+
+/// Replays a range of committed transactions, and reports (as JSON) the divergences between
+/// the on-chain outputs and the replayed ones, i.e., the gas used, status, events and write ops.
+#[derive(Parser)]
+pub struct Command {
+    #[clap(flatten)]
+    opts: Opts,
+
+    #[clap(long)]
+    begin_version: Version,
+
+    #[clap(long)]
+    limit: u64,
+
+    /// The number of transactions fetched and replayed at a time. Each batch is replayed on top
+    /// of the on-chain state, so divergences don't accumulate across batches.
+    #[clap(long, default_value_t = 1000)]
+    batch_size: u64,
+
+    /// A framework release bundle (e.g., `head.mrb`) to replay the transactions with, instead
+    /// of the on-chain framework.
+    #[clap(long)]
+    framework_path: Option<PathBuf>,
+
+    #[clap(long)]
+    use_same_block_boundaries: bool,
+
+    /// The directory to save a gas diff report (see `gas-profile-diff`) to for each diverged
+    /// user transaction, comparing the on-chain framework against `framework_path`.
+    #[clap(long, requires = "framework_path")]
+    gas_profile_diff_path: Option<PathBuf>,
+
+    /// The file to write the JSON report to. Printed to stdout if not set.
+    #[clap(long)]
+    output_path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    begin_version: Version,
+    limit: u64,
+    num_diverged_transactions: usize,
+    transactions: Vec<TransactionDiff>,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        ensure!(self.batch_size > 0, "batch_size must be > 0");
+
+        let mut debugger = if let Some(rest_endpoint) = self.opts.target.rest_endpoint {
+            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+        } else if let Some(db_path) = self.opts.target.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            unreachable!("Must provide one target.");
+        };
+        let mut framework_overrides = FrameworkOverrides::default();
+        if let Some(framework_path) = self.framework_path {
+            let bundle = ReleaseBundle::read(framework_path)?;
+            framework_overrides = FrameworkOverrides::from_release_bundle(&bundle);
+            debugger = debugger.with_framework_overrides(framework_overrides.clone());
+        }
+
+        let concurrency_levels = if self.opts.concurrency_level.is_empty() {
+            vec![num_cpus::get()]
+        } else {
+            self.opts.concurrency_level
+        };
+
+        let mut diffs = vec![];
+        let end_version = self.begin_version + self.limit;
+        let mut begin = self.begin_version;
+        while begin < end_version {
+            let limit = min(self.batch_size, end_version - begin);
+            diffs.extend(
+                debugger
+                    .replay_and_diff_past_transactions(
+                        begin,
+                        limit,
+                        self.use_same_block_boundaries,
+                        &concurrency_levels,
+                    )
+                    .await?,
+            );
+            begin += limit;
+        }
+
+        if let Some(gas_profile_diff_path) = &self.gas_profile_diff_path {
+            for diff in &diffs {
+                let report_path = gas_profile_diff_path.join(format!("txn-{}-diff", diff.version));
+                if generate_gas_profile_diff(
+                    &debugger,
+                    diff.version,
+                    FrameworkOverrides::default(),
+                    framework_overrides.clone(),
+                    &report_path,
+                )
+                .await?
+                .is_some()
+                {
+                    eprintln!("Gas diff report saved to {}.", report_path.display());
+                }
+            }
+        }
+
+        let report = Report {
+            begin_version: self.begin_version,
+            limit: self.limit,
+            num_diverged_transactions: diffs.len(),
+            transactions: diffs,
+        };
+        let json = serde_json::to_string_pretty(&report)?;
+        match self.output_path {
+            Some(output_path) => std::fs::write(output_path, json)?,
+            None => println!("{}", json),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::state_key::StateKey,
+    transaction::{TransactionOutput, Version},
+    write_set::{TransactionWrite, WriteOp, WriteOpKind},
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// The divergences between the on-chain (expected) output of a transaction and the output
+/// produced by replaying it (actual).
+#[derive(Debug, Serialize)]
+pub struct TransactionDiff {
+    pub version: Version,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<ValueDiff<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ValueDiff<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EventDiff>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub write_set: Vec<WriteOpDiff>,
+}
+
+impl TransactionDiff {
+    /// Returns the diff between the expected and actual outputs, or None if they match. The
+    /// auxiliary data isn't compared, as it isn't persisted on-chain.
+    pub fn new(
+        version: Version,
+        expected: &TransactionOutput,
+        actual: &TransactionOutput,
+    ) -> Option<Self> {
+        let gas_used = ValueDiff::new(expected.gas_used(), actual.gas_used());
+        let status = ValueDiff::new(
+            format!("{:?}", expected.status()),
+            format!("{:?}", actual.status()),
+        );
+        let events = diff_events(expected.events(), actual.events());
+        let write_set = diff_write_sets(expected, actual);
+
+        if gas_used.is_none() && status.is_none() && events.is_empty() && write_set.is_empty() {
+            return None;
+        }
+        Some(Self {
+            version,
+            gas_used,
+            status,
+            events,
+            write_set,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueDiff<T> {
+    pub expected: T,
+    pub actual: T,
+}
+
+impl<T: PartialEq> ValueDiff<T> {
+    fn new(expected: T, actual: T) -> Option<Self> {
+        (expected != actual).then_some(Self { expected, actual })
+    }
+}
+
+/// A mismatching event, at the given index in the emitted events. The expected (or actual)
+/// event is missing if fewer events were emitted.
+#[derive(Debug, Serialize)]
+pub struct EventDiff {
+    pub index: usize,
+    pub expected: Option<EventSummary>,
+    pub actual: Option<EventSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventSummary {
+    pub type_tag: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence_number: Option<u64>,
+    pub data: String,
+}
+
+impl From<&ContractEvent> for EventSummary {
+    fn from(event: &ContractEvent) -> Self {
+        let (event_key, sequence_number) = match event {
+            ContractEvent::V1(event) => {
+                (Some(event.key().to_string()), Some(event.sequence_number()))
+            },
+            ContractEvent::V2(_) => (None, None),
+        };
+        Self {
+            type_tag: event.type_tag().to_canonical_string(),
+            event_key,
+            sequence_number,
+            data: hex::encode(event.event_data()),
+        }
+    }
+}
+
+/// A mismatching write to the given state key. The expected (or actual) write is missing if
+/// the state key wasn't written to.
+#[derive(Debug, Serialize)]
+pub struct WriteOpDiff {
+    pub state_key: String,
+    pub expected: Option<WriteOpSummary>,
+    pub actual: Option<WriteOpSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WriteOpSummary {
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub metadata: String,
+}
+
+impl From<&WriteOp> for WriteOpSummary {
+    fn from(write_op: &WriteOp) -> Self {
+        let kind = match write_op.write_op_kind() {
+            WriteOpKind::Creation => "creation",
+            WriteOpKind::Modification => "modification",
+            WriteOpKind::Deletion => "deletion",
+        };
+        Self {
+            kind,
+            value: write_op.bytes().map(hex::encode),
+            metadata: format!("{:?}", write_op.metadata()),
+        }
+    }
+}
+
+fn diff_events(expected: &[ContractEvent], actual: &[ContractEvent]) -> Vec<EventDiff> {
+    (0..std::cmp::max(expected.len(), actual.len()))
+        .filter_map(|index| {
+            let (expected, actual) = (expected.get(index), actual.get(index));
+            (expected != actual).then(|| EventDiff {
+                index,
+                expected: expected.map(EventSummary::from),
+                actual: actual.map(EventSummary::from),
+            })
+        })
+        .collect()
+}
+
+fn diff_write_sets(expected: &TransactionOutput, actual: &TransactionOutput) -> Vec<WriteOpDiff> {
+    let (expected, actual) = (expected.write_set(), actual.write_set());
+    let state_keys: BTreeSet<&StateKey> = expected
+        .iter()
+        .chain(actual.iter())
+        .map(|(state_key, _)| state_key)
+        .collect();
+
+    state_keys
+        .into_iter()
+        .filter_map(|state_key| {
+            let (expected, actual) = (expected.get(state_key), actual.get(state_key));
+            (expected != actual).then(|| WriteOpDiff {
+                state_key: format!("{:?}", state_key),
+                expected: expected.map(WriteOpSummary::from),
+                actual: actual.map(WriteOpSummary::from),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        transaction::{ExecutionStatus, TransactionAuxiliaryData, TransactionStatus},
+        write_set::WriteSetMut,
+    };
+
+    #[test]
+    fn test_transaction_diff() {
+        let state_key = |name: &[u8]| StateKey::raw(name);
+        let write_set = |ops: Vec<(StateKey, WriteOp)>| WriteSetMut::new(ops).freeze().unwrap();
+        let output = |write_set, events, gas_used, status| {
+            TransactionOutput::new(
+                write_set,
+                events,
+                gas_used,
+                TransactionStatus::Keep(status),
+                TransactionAuxiliaryData::default(),
+            )
+        };
+        let event = ContractEvent::new_v2_with_type_tag_str("0x1::event::Event", vec![1]);
+
+        // Identical outputs don't diverge
+        let expected = output(
+            write_set(vec![
+                (
+                    state_key(b"a"),
+                    WriteOp::legacy_modification(vec![1].into()),
+                ),
+                (state_key(b"b"), WriteOp::legacy_deletion()),
+            ]),
+            vec![event.clone()],
+            10,
+            ExecutionStatus::Success,
+        );
+        assert!(TransactionDiff::new(5, &expected, &expected.clone()).is_none());
+
+        // Verify all divergences are reported
+        let actual = output(
+            write_set(vec![
+                (
+                    state_key(b"a"),
+                    WriteOp::legacy_modification(vec![2].into()),
+                ),
+                (state_key(b"c"), WriteOp::legacy_creation(vec![3].into())),
+            ]),
+            vec![event.clone(), event],
+            11,
+            ExecutionStatus::OutOfGas,
+        );
+        let diff = TransactionDiff::new(5, &expected, &actual).unwrap();
+        assert_eq!(diff.version, 5);
+        let gas_used = diff.gas_used.unwrap();
+        assert_eq!((gas_used.expected, gas_used.actual), (10, 11));
+        assert!(diff.status.is_some());
+        assert_eq!(diff.events.len(), 1);
+        assert_eq!(diff.events[0].index, 1);
+        assert!(diff.events[0].expected.is_none());
+        let write_kinds: Vec<_> = diff
+            .write_set
+            .iter()
+            .map(|diff| {
+                (
+                    diff.expected.as_ref().map(|op| op.kind),
+                    diff.actual.as_ref().map(|op| op.kind),
+                )
+            })
+            .collect();
+        assert_eq!(write_kinds, vec![
+            (Some("modification"), Some("modification")),
+            (Some("deletion"), None),
+            (None, Some("creation")),
+        ]);
+    }
+}
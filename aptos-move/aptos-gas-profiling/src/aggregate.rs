@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    log::{ExecutionAndIOCosts, ExecutionGasEvent},
+    log::{CallFrame, ExecutionAndIOCosts, ExecutionGasEvent},
     render::{Render, TableKey},
 };
 use aptos_gas_algebra::{GasQuantity, GasScalingFactor, InternalGas};
@@ -22,6 +22,8 @@ pub struct AggregatedExecutionGasEvents {
 
     // TODO: Make this more strongly typed?
     pub ops: Vec<(String, usize, InternalGas)>,
+    /// The self costs of the functions (excluding the callees), along with the number of calls.
+    pub functions: Vec<(String, usize, InternalGas)>,
     pub transaction_write: InternalGas,
     pub event_writes: Vec<(String, usize, InternalGas)>,
     pub storage_reads: Vec<(String, usize, InternalGas)>,
@@ -64,12 +66,38 @@ where
     v
 }
 
+fn aggregate_function_costs(
+    frame: &CallFrame,
+    functions: &mut BTreeMap<String, (usize, InternalGas)>,
+) {
+    use ExecutionGasEvent::*;
+
+    let mut self_cost = InternalGas::zero();
+    for event in &frame.events {
+        match event {
+            Loc(..) => (),
+            Call(inner_frame) => aggregate_function_costs(inner_frame, functions),
+            Bytecode { cost, .. }
+            | CallNative { cost, .. }
+            | LoadResource { cost, .. }
+            | CreateTy { cost } => self_cost += *cost,
+        }
+    }
+
+    let entry = functions
+        .entry(format!("{}", frame.name))
+        .or_insert((0, InternalGas::zero()));
+    entry.0 += 1;
+    entry.1 += self_cost;
+}
+
 impl ExecutionAndIOCosts {
     /// Counts the number of hits and aggregates the gas costs for each type of event.
     pub fn aggregate_gas_events(&self) -> AggregatedExecutionGasEvents {
         use ExecutionGasEvent::*;
 
         let mut ops = BTreeMap::new();
+        let mut functions = BTreeMap::new();
         let mut storage_reads = BTreeMap::new();
         let mut storage_writes = BTreeMap::new();
         let mut event_writes = BTreeMap::new();
@@ -104,6 +132,8 @@ impl ExecutionAndIOCosts {
             }
         }
 
+        aggregate_function_costs(&self.call_graph, &mut functions);
+
         for event in &self.events_transient {
             insert_or_add(
                 &mut event_writes,
@@ -131,6 +161,7 @@ impl ExecutionAndIOCosts {
             total: self.total,
 
             ops: into_sorted_vec(ops),
+            functions: into_sorted_vec(functions),
             transaction_write: self.transaction_transient.unwrap_or_else(|| 0.into()),
             event_writes: into_sorted_vec(event_writes),
             storage_reads: into_sorted_vec(storage_reads),
@@ -138,3 +169,59 @@ impl ExecutionAndIOCosts {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format_common::Opcodes;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+
+    fn function(name: &str) -> CallFrame {
+        CallFrame::new_function(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("m").unwrap()),
+            Identifier::new(name).unwrap(),
+            vec![],
+        )
+    }
+
+    fn bytecode(cost: u64) -> ExecutionGasEvent {
+        ExecutionGasEvent::Bytecode {
+            op: Opcodes::ADD,
+            cost: InternalGas::new(cost),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_function_costs() {
+        let mut leaf = function("leaf");
+        leaf.events.push(bytecode(5));
+        leaf.events.push(ExecutionGasEvent::CreateTy {
+            cost: InternalGas::new(2),
+        });
+
+        let mut middle = function("middle");
+        middle.events.push(bytecode(10));
+        middle.events.push(ExecutionGasEvent::Call(leaf.clone()));
+        middle.events.push(ExecutionGasEvent::Loc(3));
+        middle.events.push(ExecutionGasEvent::Call(leaf.clone()));
+
+        let mut root = CallFrame::new_script();
+        root.events.push(bytecode(1));
+        root.events.push(ExecutionGasEvent::Call(middle.clone()));
+        root.events.push(ExecutionGasEvent::Call(leaf.clone()));
+
+        let mut functions = BTreeMap::new();
+        aggregate_function_costs(&root, &mut functions);
+
+        // Callee costs are not attributed to the callers, while the hits and the
+        // self costs of the same function are summed up across all of its calls.
+        let expected = BTreeMap::from([
+            (format!("{}", root.name), (1, InternalGas::new(1))),
+            (format!("{}", middle.name), (1, InternalGas::new(10))),
+            (format!("{}", leaf.name), (3, InternalGas::new(21))),
+        ]);
+        assert_eq!(functions, expected);
+    }
+}
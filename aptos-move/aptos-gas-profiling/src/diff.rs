@@ -0,0 +1,402 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the gas logs of the same transaction executed twice, e.g., under the current and a
+//! proposed gas schedule, or under two different framework versions.
+
+use crate::{
+    log::{ExecutionAndIOCosts, StorageFees, TransactionGasLog},
+    misc::replace_sample_counts,
+    render::Render,
+    report::ensure_dirs_exist,
+};
+use anyhow::Result;
+use aptos_gas_algebra::{Fee, GasScalingFactor, InternalGas};
+use inferno::flamegraph::TextTruncateDirection;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// A cost before and after the change.
+///
+/// Execution & IO costs are measured in (external) gas units, so that the two sides remain
+/// comparable even if the gas scaling factor has changed. Storage fees are measured in Octa.
+#[derive(Debug, Clone, Serialize)]
+pub struct CostDelta {
+    pub before: f64,
+    pub after: f64,
+    pub delta: f64,
+}
+
+impl CostDelta {
+    fn new(before: f64, after: f64) -> Self {
+        Self {
+            before,
+            after,
+            delta: after - before,
+        }
+    }
+}
+
+/// The aggregated cost of a named item (e.g., a function or an instruction), along with the
+/// number of times it has been hit on each side. An item that only shows up on one side has
+/// zero hits and zero cost on the other.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemDelta {
+    pub name: String,
+    pub before_hits: usize,
+    pub after_hits: usize,
+    #[serde(flatten)]
+    pub cost: CostDelta,
+}
+
+/// Execution & IO cost deltas, in gas units.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionAndIODiff {
+    pub total: CostDelta,
+    pub intrinsic: CostDelta,
+    pub keyless: CostDelta,
+    pub dependencies: Vec<ItemDelta>,
+    /// Self costs of the functions, excluding the costs of their callees.
+    pub functions: Vec<ItemDelta>,
+    /// Costs of the bytecode instructions and the native functions.
+    pub instructions: Vec<ItemDelta>,
+    pub storage_reads: Vec<ItemDelta>,
+    pub storage_writes: Vec<ItemDelta>,
+    pub event_writes: Vec<ItemDelta>,
+    pub transaction_write: CostDelta,
+}
+
+/// Storage fee deltas, in Octa.
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageFeesDiff {
+    pub total: CostDelta,
+    pub total_refund: CostDelta,
+    pub transaction: CostDelta,
+    pub write_set: Vec<ItemDelta>,
+    pub events: Vec<ItemDelta>,
+    pub event_discount: CostDelta,
+}
+
+/// A machine-readable comparison of two gas logs of the same transaction.
+///
+/// All item lists are sorted by the absolute value of their deltas, from high to low.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionGasLogDiff {
+    pub entry_point: String,
+    pub exec_io: ExecutionAndIODiff,
+    pub storage: StorageFeesDiff,
+}
+
+fn scale(amount: InternalGas, scaling_factor: GasScalingFactor) -> f64 {
+    u64::from(amount) as f64 / u64::from(scaling_factor) as f64
+}
+
+fn octa(fee: Fee) -> f64 {
+    u64::from(fee) as f64
+}
+
+/// Matches the items on both sides by name, and sorts them by the absolute value of their
+/// deltas. Items sharing the same name on one side are summed up.
+fn diff_items(
+    before: impl IntoIterator<Item = (String, usize, f64)>,
+    after: impl IntoIterator<Item = (String, usize, f64)>,
+) -> Vec<ItemDelta> {
+    let mut items: BTreeMap<String, [(usize, f64); 2]> = BTreeMap::new();
+    let mut add = |side: usize, (name, hits, cost): (String, usize, f64)| {
+        let entry = &mut items.entry(name).or_default()[side];
+        entry.0 += hits;
+        entry.1 += cost;
+    };
+    before.into_iter().for_each(|item| add(0, item));
+    after.into_iter().for_each(|item| add(1, item));
+
+    let mut items = items
+        .into_iter()
+        .map(
+            |(name, [(before_hits, before), (after_hits, after)])| ItemDelta {
+                name,
+                before_hits,
+                after_hits,
+                cost: CostDelta::new(before, after),
+            },
+        )
+        .collect::<Vec<_>>();
+    items.sort_by(|lhs, rhs| rhs.cost.delta.abs().total_cmp(&lhs.cost.delta.abs()));
+    items
+}
+
+impl ExecutionAndIOCosts {
+    fn diff(&self, other: &Self) -> ExecutionAndIODiff {
+        let scaled = |log: &Self, items: Vec<(String, usize, InternalGas)>| {
+            items
+                .into_iter()
+                .map(|(name, hits, cost)| (name, hits, scale(cost, log.gas_scaling_factor)))
+                .collect::<Vec<_>>()
+        };
+        let dependencies = |log: &Self| {
+            log.dependencies
+                .iter()
+                .map(|dep| {
+                    (
+                        format!("{}", Render(&dep.id)),
+                        1,
+                        scale(dep.cost, log.gas_scaling_factor),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let delta = |f: fn(&Self) -> InternalGas| {
+            CostDelta::new(
+                scale(f(self), self.gas_scaling_factor),
+                scale(f(other), other.gas_scaling_factor),
+            )
+        };
+
+        let lhs = self.aggregate_gas_events();
+        let rhs = other.aggregate_gas_events();
+
+        ExecutionAndIODiff {
+            total: delta(|log| log.total),
+            intrinsic: delta(|log| log.intrinsic_cost),
+            keyless: delta(|log| log.keyless_cost),
+            dependencies: diff_items(dependencies(self), dependencies(other)),
+            functions: diff_items(scaled(self, lhs.functions), scaled(other, rhs.functions)),
+            instructions: diff_items(scaled(self, lhs.ops), scaled(other, rhs.ops)),
+            storage_reads: diff_items(
+                scaled(self, lhs.storage_reads),
+                scaled(other, rhs.storage_reads),
+            ),
+            storage_writes: diff_items(
+                scaled(self, lhs.storage_writes),
+                scaled(other, rhs.storage_writes),
+            ),
+            event_writes: diff_items(
+                scaled(self, lhs.event_writes),
+                scaled(other, rhs.event_writes),
+            ),
+            transaction_write: CostDelta::new(
+                scale(lhs.transaction_write, self.gas_scaling_factor),
+                scale(rhs.transaction_write, other.gas_scaling_factor),
+            ),
+        }
+    }
+}
+
+impl StorageFees {
+    fn diff(&self, other: &Self) -> StorageFeesDiff {
+        let write_set = |log: &Self| {
+            log.write_set_storage
+                .iter()
+                .map(|write| (format!("{}", Render(&write.key)), 1, octa(write.cost)))
+                .collect::<Vec<_>>()
+        };
+        let events = |log: &Self| {
+            log.events
+                .iter()
+                .map(|event| (format!("{}", event.ty), 1, octa(event.cost)))
+                .collect::<Vec<_>>()
+        };
+        let delta = |f: fn(&Self) -> Fee| CostDelta::new(octa(f(self)), octa(f(other)));
+
+        StorageFeesDiff {
+            total: delta(|log| log.total),
+            total_refund: delta(|log| log.total_refund),
+            transaction: delta(|log| log.txn_storage),
+            write_set: diff_items(write_set(self), write_set(other)),
+            events: diff_items(events(self), events(other)),
+            event_discount: delta(|log| log.event_discount),
+        }
+    }
+}
+
+/// Rescales the counts of folded stack lines from one gas scaling factor to another.
+fn rescale_folded_stack_lines(lines: Vec<String>, from: u64, to: u64) -> Vec<String> {
+    if from == to {
+        return lines;
+    }
+
+    lines
+        .into_iter()
+        .map(|line| match line.rsplit_once(' ') {
+            Some((stack, count)) => {
+                let count: u64 = count.parse().expect("count should be a valid u64");
+                let count = (count as u128 * to as u128 / from as u128) as u64;
+                format!("{} {}", stack, count)
+            },
+            None => line,
+        })
+        .collect()
+}
+
+/// Tries to generate a differential flamegraph from two sets of folded stack lines.
+/// Frame widths follow the second set, while the colors indicate the changes: red for
+/// the frames that became more expensive, blue for those that became cheaper.
+/// None will be returned if both sets are empty.
+fn to_differential_flamegraph(
+    before: &[String],
+    after: &[String],
+    title: String,
+    format_count: impl Fn(u64) -> String,
+) -> Result<Option<Vec<u8>>> {
+    if before.is_empty() && after.is_empty() {
+        return Ok(None);
+    }
+
+    let mut folded = vec![];
+    inferno::differential::from_readers(
+        inferno::differential::Options::default(),
+        before.join("\n").as_bytes(),
+        after.join("\n").as_bytes(),
+        &mut folded,
+    )?;
+    let folded = String::from_utf8_lossy(&folded);
+
+    let mut options = inferno::flamegraph::Options::default();
+    options.text_truncate_direction = TextTruncateDirection::Right;
+    options.title = title;
+
+    let mut graph_content = vec![];
+    inferno::flamegraph::from_lines(&mut options, folded.lines(), &mut graph_content)?;
+    let graph_content = String::from_utf8_lossy(&graph_content);
+
+    let graph_content = replace_sample_counts(&graph_content, format_count);
+
+    Ok(Some(graph_content.as_bytes().to_vec()))
+}
+
+impl TransactionGasLogDiff {
+    /// Compares the gas log of the original execution (`before`) against the gas log of
+    /// the same transaction executed under different conditions (`after`).
+    pub fn new(before: &TransactionGasLog, after: &TransactionGasLog) -> Self {
+        Self {
+            entry_point: format!("{}", before.entry_point()),
+            exec_io: before.exec_io.diff(&after.exec_io),
+            storage: before.storage.diff(&after.storage),
+        }
+    }
+}
+
+impl TransactionGasLog {
+    /// Generates a diff report against another gas log of the same transaction, consisting of
+    /// differential flamegraphs and a JSON file containing the deltas.
+    pub fn generate_diff_report(
+        &self,
+        other: &TransactionGasLog,
+        path: impl AsRef<Path>,
+        header: String,
+    ) -> Result<TransactionGasLogDiff> {
+        let diff = TransactionGasLogDiff::new(self, other);
+
+        // The counts of the folded stack lines are in internal gas units, so they need to be
+        // brought to the same scale first.
+        let scaling_factor = u64::from(self.exec_io.gas_scaling_factor);
+        let graph_exec_io = to_differential_flamegraph(
+            &self.exec_io.to_folded_stack_lines(),
+            &rescale_folded_stack_lines(
+                other.exec_io.to_folded_stack_lines(),
+                u64::from(other.exec_io.gas_scaling_factor),
+                scaling_factor,
+            ),
+            format!("{} - Execution & IO", header),
+            |count| {
+                format!(
+                    "{} gas units",
+                    crate::misc::strip_trailing_zeros_and_decimal_point(&format!(
+                        "{:.8}",
+                        count as f64 / scaling_factor as f64
+                    ))
+                )
+            },
+        )?;
+        let graph_storage = to_differential_flamegraph(
+            &self.storage.to_folded_stack_lines(),
+            &other.storage.to_folded_stack_lines(),
+            format!("{} - Storage", header),
+            |count| format!("{} Octa", count),
+        )?;
+
+        // Writing to disk
+        let path_root = path.as_ref();
+
+        ensure_dirs_exist(path_root)?;
+        let path_assets = path_root.join("assets");
+        ensure_dirs_exist(&path_assets)?;
+
+        if let Some(graph_bytes) = graph_exec_io {
+            fs::write(path_assets.join("exec_io.diff.svg"), graph_bytes)?;
+        }
+        if let Some(graph_bytes) = graph_storage {
+            fs::write(path_assets.join("storage.diff.svg"), graph_bytes)?;
+        }
+        fs::write(
+            path_root.join("diff.json"),
+            serde_json::to_string_pretty(&diff)?,
+        )?;
+
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, hits: usize, cost: f64) -> (String, usize, f64) {
+        (name.to_string(), hits, cost)
+    }
+
+    #[test]
+    fn test_diff_items() {
+        let items = diff_items(
+            vec![
+                item("a", 1, 10.0),
+                item("b", 2, 5.0),
+                item("b", 1, 3.0),
+                item("c", 1, 7.0),
+            ],
+            vec![item("a", 1, 12.0), item("b", 1, 1.0), item("d", 4, 20.0)],
+        );
+
+        let summary = items
+            .iter()
+            .map(|item| {
+                (
+                    item.name.as_str(),
+                    item.before_hits,
+                    item.after_hits,
+                    item.cost.before,
+                    item.cost.after,
+                    item.cost.delta,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Items only present on one side are compared against zero, and the result is
+        // sorted by the absolute value of the deltas, ties keeping the order of the names.
+        assert_eq!(summary, vec![
+            ("d", 0, 4, 0.0, 20.0, 20.0),
+            ("b", 3, 1, 8.0, 1.0, -7.0),
+            ("c", 1, 0, 7.0, 0.0, -7.0),
+            ("a", 1, 1, 10.0, 12.0, 2.0),
+        ]);
+    }
+
+    #[test]
+    fn test_diff_items_empty() {
+        assert!(diff_items(vec![], vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_rescale_folded_stack_lines() {
+        let lines = vec!["a;b 300".to_string(), "c 7".to_string(), "d".to_string()];
+
+        assert_eq!(rescale_folded_stack_lines(lines.clone(), 100, 100), lines);
+        assert_eq!(rescale_folded_stack_lines(lines.clone(), 100, 200), vec![
+            "a;b 600", "c 14", "d"
+        ]);
+        // Counts are rounded down when scaling to a smaller factor.
+        assert_eq!(rescale_folded_stack_lines(lines, 100, 50), vec![
+            "a;b 150", "c 3", "d"
+        ]);
+    }
+}
@@ -3,11 +3,11 @@
 
 use crate::{
     log::{CallFrame, ExecutionAndIOCosts, ExecutionGasEvent, StorageFees},
+    misc::replace_sample_counts,
     render::Render,
 };
 use inferno::flamegraph::TextTruncateDirection;
 use move_core_types::gas_algebra::InternalGas;
-use regex::Captures;
 
 #[derive(Debug)]
 struct LineBuffer(Vec<String>);
@@ -33,7 +33,7 @@ impl LineBuffer {
 impl StorageFees {
     /// Convert the storage fee log into folded stack lines, which can
     /// then be used to generate a flamegraph.
-    fn to_folded_stack_lines(&self) -> Vec<String> {
+    pub(crate) fn to_folded_stack_lines(&self) -> Vec<String> {
         let mut lines = LineBuffer::new();
 
         lines.push("transaction", self.txn_storage);
@@ -76,18 +76,8 @@ impl StorageFees {
         )?;
         let graph_content = String::from_utf8_lossy(&graph_content);
 
-        // Inferno does not allow us to customize some of the text in the resulting graph,
-        // so we have to do it through regex replacement.
-        let re = regex::Regex::new("([1-9][0-9]*(,[0-9]+)*) samples")
-            .expect("should be able to build regex successfully");
-        let graph_content = re.replace_all(&graph_content, |caps: &Captures| {
-            let count: u64 = caps[1]
-                .replace(',', "")
-                .parse()
-                .expect("should be able parse count as u64");
-
-            format!("{} Octa", count)
-        });
+        let graph_content =
+            replace_sample_counts(&graph_content, |count| format!("{} Octa", count));
 
         Ok(Some(graph_content.as_bytes().to_vec()))
     }
@@ -96,7 +86,7 @@ impl StorageFees {
 impl ExecutionAndIOCosts {
     /// Convert the execution gas log into folded stack lines, which can
     /// then be used to generate a flamegraph.
-    fn to_folded_stack_lines(&self) -> Vec<String> {
+    pub(crate) fn to_folded_stack_lines(&self) -> Vec<String> {
         let mut lines = LineBuffer::new();
 
         lines.push("intrinsic", self.intrinsic_cost);
@@ -215,16 +205,7 @@ impl ExecutionAndIOCosts {
         )?;
         let graph_content = String::from_utf8_lossy(&graph_content);
 
-        // Inferno does not allow us to customize some of the text in the resulting graph,
-        // so we have to do it through regex replacement.
-        let re = regex::Regex::new("([1-9][0-9]*(,[0-9]+)*) samples")
-            .expect("should be able to build regex successfully");
-        let graph_content = re.replace_all(&graph_content, |caps: &Captures| {
-            let count: u64 = caps[1]
-                .replace(',', "")
-                .parse()
-                .expect("should be able parse count as u64");
-
+        let graph_content = replace_sample_counts(&graph_content, |count| {
             let count_scaled = count as f64 / u64::from(self.gas_scaling_factor) as f64;
 
             format!(
@@ -16,3 +16,24 @@ pub fn strip_trailing_zeros_and_decimal_point(mut s: &str) -> &str {
         None => s,
     }
 }
+
+/// Replaces the "N samples" labels inferno puts in a flamegraph with custom text.
+///
+/// Inferno does not allow us to customize some of the text in the resulting graph,
+/// so we have to do it through regex replacement.
+pub(crate) fn replace_sample_counts(
+    graph_content: &str,
+    format_count: impl Fn(u64) -> String,
+) -> String {
+    let re = regex::Regex::new("([1-9][0-9]*(,[0-9]+)*) samples")
+        .expect("should be able to build regex successfully");
+    re.replace_all(graph_content, |caps: &regex::Captures| {
+        let count: u64 = caps[1]
+            .replace(',', "")
+            .parse()
+            .expect("should be able parse count as u64");
+
+        format_count(count)
+    })
+    .into_owned()
+}
@@ -10,11 +10,16 @@ use anyhow::Result;
 use aptos_framework::natives::code::PackageMetadata;
 use aptos_types::{
     account_address::AccountAddress,
+    contract_event::ContractEvent,
     state_store::{
         state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
         Result as StateViewResult, StateViewId, TStateView,
     },
-    transaction::{Transaction, TransactionInfo, Version},
+    transaction::{
+        Transaction, TransactionAuxiliaryData, TransactionInfo, TransactionOutput,
+        TransactionStatus, Version,
+    },
+    write_set::WriteSet,
 };
 use lru::LruCache;
 use move_core_types::language_storage::ModuleId;
@@ -48,6 +53,17 @@ pub trait AptosValidatorInterface: Sync {
         limit: u64,
     ) -> Result<(Vec<Transaction>, Vec<TransactionInfo>)>;
 
+    /// Returns the committed transactions, their infos and the outputs they produced on-chain
+    async fn get_committed_transactions_with_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<(
+        Vec<Transaction>,
+        Vec<TransactionInfo>,
+        Vec<TransactionOutput>,
+    )>;
+
     async fn get_and_filter_committed_transactions(
         &self,
         start: Version,
@@ -82,6 +98,22 @@ pub trait AptosValidatorInterface: Sync {
     ) -> Result<Option<Version>>;
 }
 
+/// Reconstructs the output of a committed transaction from its (on-chain) write set, events and
+/// info. Note: the auxiliary data is not persisted, so it is always empty.
+fn committed_transaction_output(
+    write_set: WriteSet,
+    events: Vec<ContractEvent>,
+    txn_info: &TransactionInfo,
+) -> TransactionOutput {
+    TransactionOutput::new(
+        write_set,
+        events,
+        txn_info.gas_used(),
+        TransactionStatus::Keep(txn_info.status().clone()),
+        TransactionAuxiliaryData::default(),
+    )
+}
+
 pub struct DebuggerStateView {
     query_sender: Mutex<
         UnboundedSender<(
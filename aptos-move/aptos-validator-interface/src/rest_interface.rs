@@ -1,13 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{AptosValidatorInterface, FilterCondition};
+use crate::{committed_transaction_output, AptosValidatorInterface, FilterCondition};
 use anyhow::{anyhow, Result};
-use aptos_api_types::{AptosError, AptosErrorCode};
+use aptos_api_types::{AptosError, AptosErrorCode, TransactionOnChainData};
 use aptos_framework::{
     natives::code::{PackageMetadata, PackageRegistry},
     APTOS_PACKAGES,
 };
+use aptos_logger::info;
 use aptos_rest_client::{
     error::{AptosErrorResponse, RestError},
     Client,
@@ -17,7 +18,7 @@ use aptos_types::{
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{
         EntryFunction, ExecutionStatus::MiscellaneousError, Transaction, TransactionInfo,
-        TransactionPayload, Version,
+        TransactionOutput, TransactionPayload, Version,
     },
 };
 use async_recursion::async_recursion;
@@ -30,6 +31,38 @@ impl RestDebuggerInterface {
     pub fn new(client: Client) -> Self {
         Self(client)
     }
+
+    /// Fetches up to `limit` committed transactions starting at `start`, a page at a time.
+    /// Stops early if the node runs out of transactions.
+    async fn get_transactions_paged(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        let mut txns = Vec::with_capacity(limit as usize);
+
+        while (txns.len() as u64) < limit {
+            let page_start = start
+                .checked_add(txns.len() as u64)
+                .ok_or_else(|| anyhow!("Version overflow fetching transactions at {}", start))?;
+            let remaining = limit
+                .checked_sub(txns.len() as u64)
+                .ok_or_else(|| anyhow!("Fetched more than {} transactions", limit))?;
+            let page_size = u16::try_from(remaining).unwrap_or(u16::MAX);
+            let page = self
+                .0
+                .get_transactions_bcs(Some(page_start), Some(page_size))
+                .await?
+                .into_inner();
+            if page.is_empty() {
+                break;
+            }
+            txns.extend(page);
+            info!("Got {}/{} txns from RestApi.", txns.len(), limit);
+        }
+
+        Ok(txns)
+    }
 }
 
 #[async_recursion]
@@ -223,26 +256,38 @@ impl AptosValidatorInterface for RestDebuggerInterface {
         start: Version,
         limit: u64,
     ) -> Result<(Vec<Transaction>, Vec<TransactionInfo>)> {
+        Ok(self
+            .get_transactions_paged(start, limit)
+            .await?
+            .into_iter()
+            .map(|txn| (txn.transaction, txn.info))
+            .unzip())
+    }
+
+    async fn get_committed_transactions_with_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<(
+        Vec<Transaction>,
+        Vec<TransactionInfo>,
+        Vec<TransactionOutput>,
+    )> {
         let mut txns = Vec::with_capacity(limit as usize);
         let mut txn_infos = Vec::with_capacity(limit as usize);
+        let mut txn_outputs = Vec::with_capacity(limit as usize);
 
-        while txns.len() < limit as usize {
-            self.0
-                .get_transactions_bcs(
-                    Some(start + txns.len() as u64),
-                    Some(limit as u16 - txns.len() as u16),
-                )
-                .await?
-                .into_inner()
-                .into_iter()
-                .for_each(|txn| {
-                    txns.push(txn.transaction);
-                    txn_infos.push(txn.info);
-                });
-            println!("Got {}/{} txns from RestApi.", txns.len(), limit);
+        for txn in self.get_transactions_paged(start, limit).await? {
+            txn_outputs.push(committed_transaction_output(
+                txn.changes,
+                txn.events,
+                &txn.info,
+            ));
+            txns.push(txn.transaction);
+            txn_infos.push(txn.info);
         }
 
-        Ok((txns, txn_infos))
+        Ok((txns, txn_infos, txn_outputs))
     }
 
     async fn get_and_filter_committed_transactions(
@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{AptosValidatorInterface, FilterCondition};
+use crate::{committed_transaction_output, AptosValidatorInterface, FilterCondition};
 use anyhow::{ensure, Result};
 use aptos_config::config::{
     RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
@@ -14,8 +14,9 @@ use aptos_storage_interface::DbReader;
 use aptos_types::{
     account_address::AccountAddress,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{Transaction, TransactionInfo, Version},
+    transaction::{Transaction, TransactionInfo, TransactionOutput, Version},
 };
+use itertools::izip;
 use move_core_types::language_storage::ModuleId;
 use std::{collections::HashMap, path::Path, sync::Arc};
 
@@ -68,6 +69,36 @@ impl AptosValidatorInterface for DBDebuggerInterface {
         Ok((txns, txn_infos))
     }
 
+    async fn get_committed_transactions_with_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<(
+        Vec<Transaction>,
+        Vec<TransactionInfo>,
+        Vec<TransactionOutput>,
+    )> {
+        let (txns, txn_infos) = self.get_committed_transactions(start, limit).await?;
+        let write_sets = self
+            .0
+            .get_write_set_iterator(start, limit)?
+            .map(|res| res.map_err(Into::into))
+            .collect::<Result<Vec<_>>>()?;
+        let events = self
+            .0
+            .get_events_iterator(start, limit)?
+            .map(|res| res.map_err(Into::into))
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(txns.len() == write_sets.len() && txns.len() == events.len());
+
+        let txn_outputs = izip!(write_sets, events, &txn_infos)
+            .map(|(write_set, events, txn_info)| {
+                committed_transaction_output(write_set, events, txn_info)
+            })
+            .collect();
+        Ok((txns, txn_infos, txn_outputs))
+    }
+
     async fn get_and_filter_committed_transactions(
         &self,
         _start: Version,
@@ -0,0 +1,394 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, ensure, Result};
+use aptos_backup_cli::{
+    coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+    metadata::cache::MetadataCacheOpt,
+    storage::local_fs::LocalFs,
+    utils::{GlobalRestoreOptions, RestoreRunMode},
+};
+use aptos_config::config::{BootstrappingMode, NodeConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_db::{get_restore_handler::GetRestoreHandler, AptosDB};
+use aptos_logger::prelude::*;
+use aptos_storage_interface::DbReader;
+use aptos_types::{transaction::Version, waypoint::Waypoint};
+use std::{collections::HashMap, fs, path::Path, sync::Arc, time::Instant};
+
+/// The name of the marker file that indicates a restore is in progress
+const RESTORE_IN_PROGRESS_MARKER: &str = "backup_restore_in_progress";
+
+/// Restores the database from the local backup archive specified in the node
+/// config (if the node is configured to bootstrap using `RestoreFromBackup`).
+/// The restored data is verified against the node waypoint and the trusted
+/// waypoints of the backup archive config. Once the restore is complete, state
+/// sync continues to sync from the network (starting at the restored version).
+///
+/// If a previous restore was interrupted (i.e., the restore marker file still
+/// exists), the restore is resumed.
+///
+/// Note: this must be called before the database is opened by the node.
+pub fn restore_from_backup(node_config: &NodeConfig) -> Result<()> {
+    // Only restore if the node is configured to do so
+    let bootstrapping_mode = node_config.state_sync.state_sync_driver.bootstrapping_mode;
+    if bootstrapping_mode != BootstrappingMode::RestoreFromBackup {
+        return Ok(());
+    }
+
+    // Identify the backup archive directory
+    let backup_archive_config = &node_config.storage.backup_archive;
+    let backup_dir = backup_archive_config
+        .backup_dir
+        .clone()
+        .ok_or_else(|| anyhow!("The backup archive directory was not specified!"))?;
+
+    // Open the database (in restore mode)
+    let storage_config = &node_config.storage;
+    let aptos_db = Arc::new(
+        AptosDB::open_kv_only(
+            storage_config.get_dir_paths(),
+            false, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            storage_config.rocksdb_configs,
+            false, /* indexer */
+            storage_config.buffered_state_target_items,
+            storage_config.max_num_nodes_per_lru_cache_shard,
+            None, /* internal_indexer_db */
+        )
+        .map_err(|error| anyhow!("Failed to open the database for the restore: {:?}", error))?,
+    );
+
+    // Check if there's anything to restore
+    let restore_marker = storage_config.dir().join(RESTORE_IN_PROGRESS_MARKER);
+    if !restore_required(&aptos_db, &restore_marker)? {
+        info!("The database has already been initialized. Skipping the restore from backup!");
+        return Ok(());
+    }
+    fs::write(&restore_marker, b"")
+        .map_err(|error| anyhow!("Failed to create the restore marker file: {:?}", error))?;
+
+    // Restore the database from the backup archive
+    info!(
+        "Restoring the database from the backup archive at: {:?}",
+        backup_dir
+    );
+    let start_time = Instant::now();
+    let global_restore_options = GlobalRestoreOptions {
+        target_version: Version::MAX,
+        trusted_waypoints: Arc::new(get_trusted_waypoints(node_config)?),
+        run_mode: Arc::new(RestoreRunMode::Restore {
+            restore_handler: aptos_db.get_restore_handler(),
+        }),
+        concurrent_downloads: backup_archive_config.concurrent_downloads,
+        replay_concurrency_level: backup_archive_config.replay_concurrency_level,
+    };
+    let restore_coordinator = RestoreCoordinator::new(
+        RestoreCoordinatorOpt {
+            metadata_cache_opt: MetadataCacheOpt::new(
+                backup_archive_config.metadata_cache_dir.as_ref(),
+            ),
+            replay_all: false,
+            ledger_history_start_version: None,
+            skip_epoch_endings: false,
+        },
+        global_restore_options,
+        Arc::new(LocalFs::new(backup_dir)),
+    );
+    let runtime = aptos_runtimes::spawn_named_runtime("backup-restore".into(), None);
+    runtime
+        .block_on(restore_coordinator.run())
+        .map_err(|error| {
+            anyhow!(
+                "Failed to restore the database from the backup archive: {:?}",
+                error
+            )
+        })?;
+
+    // Verify the restored database against the waypoint
+    let restored_version = verify_restored_database(&aptos_db, node_config)?;
+    info!(
+        "Restored the database from the backup archive at version: {:?}. Time taken: {:?}",
+        restored_version,
+        start_time.elapsed()
+    );
+
+    // Remove the restore marker (the restore is complete)
+    fs::remove_file(restore_marker)
+        .map_err(|error| anyhow!("Failed to remove the restore marker file: {:?}", error))
+}
+
+/// Returns true iff the database must be restored, i.e., the database is empty,
+/// or a previous restore was interrupted (and the restore marker still exists).
+fn restore_required(aptos_db: &AptosDB, restore_marker: &Path) -> Result<bool> {
+    let latest_ledger_info = aptos_db.get_latest_ledger_info_option()?;
+    Ok(latest_ledger_info.is_none() || restore_marker.exists())
+}
+
+/// Returns the waypoints trusted by the restore: the node waypoint and the
+/// trusted waypoints of the backup archive config.
+fn get_trusted_waypoints(node_config: &NodeConfig) -> Result<HashMap<Version, Waypoint>> {
+    let mut trusted_waypoints = HashMap::new();
+    let node_waypoint = node_config.base.waypoint.waypoint();
+    for waypoint in std::iter::once(node_waypoint).chain(
+        node_config
+            .storage
+            .backup_archive
+            .trusted_waypoints
+            .iter()
+            .copied(),
+    ) {
+        if let Some(existing_waypoint) = trusted_waypoints.insert(waypoint.version(), waypoint) {
+            ensure!(
+                existing_waypoint == waypoint,
+                "Conflicting trusted waypoints at version {}: {} and {}",
+                waypoint.version(),
+                existing_waypoint,
+                waypoint
+            );
+        }
+    }
+    Ok(trusted_waypoints)
+}
+
+/// Verifies that the restored database is consistent with the waypoint and
+/// returns the restored version. If the waypoint is ahead of the restored
+/// version, the waypoint will be verified by state sync (when syncing to it).
+fn verify_restored_database(aptos_db: &AptosDB, node_config: &NodeConfig) -> Result<Version> {
+    // Get the latest restored ledger info
+    let latest_ledger_info = aptos_db
+        .get_latest_ledger_info_option()?
+        .ok_or_else(|| anyhow!("The backup archive did not contain any ledger infos!"))?;
+    let restored_version = latest_ledger_info.ledger_info().version();
+
+    // Verify the waypoint (if it has been restored)
+    let waypoint = node_config.base.waypoint.waypoint();
+    if waypoint.version() <= restored_version {
+        let epoch_ending_ledger_info = aptos_db.get_epoch_ending_ledger_info(waypoint.version())?;
+        waypoint
+            .verify(epoch_ending_ledger_info.ledger_info())
+            .map_err(|error| {
+                anyhow!(
+                    "The restored database does not match the waypoint: {:?}",
+                    error
+                )
+            })?;
+    }
+
+    Ok(restored_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_backup_cli::{
+        backup_types::{
+            epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+            state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+        },
+        storage::BackupStorage,
+        utils::{
+            backup_service_client::BackupServiceClient, test_utils::start_local_backup_service,
+            GlobalBackupOpt,
+        },
+    };
+    use aptos_config::config::{RocksdbConfigs, StorageDirPaths, WaypointConfig};
+    use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+    use aptos_temppath::TempPath;
+    use std::{path::PathBuf, time::Duration};
+
+    #[test]
+    fn test_restore_from_local_archive() {
+        // Create a database and back it up to a local archive
+        let source_db = test_execution_with_storage_impl();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        create_backup_archive(source_db.clone(), backup_dir.path().to_path_buf());
+
+        // Restore the database from the archive
+        let db_dir = TempPath::new();
+        db_dir.create_as_dir().unwrap();
+        let genesis_waypoint = Waypoint::new_epoch_boundary(
+            source_db
+                .get_epoch_ending_ledger_info(0)
+                .unwrap()
+                .ledger_info(),
+        )
+        .unwrap();
+        let node_config = create_restore_config(
+            db_dir.path().to_path_buf(),
+            backup_dir.path().to_path_buf(),
+            genesis_waypoint,
+        );
+        restore_from_backup(&node_config).unwrap();
+
+        // Verify the restored database matches the source database
+        let restored_db = open_db(&node_config.storage.dir());
+        let source_version = source_db.get_synced_version().unwrap();
+        assert_eq!(restored_db.get_synced_version().unwrap(), source_version);
+        let restore_marker = node_config.storage.dir().join(RESTORE_IN_PROGRESS_MARKER);
+        assert!(!restore_marker.exists());
+
+        // Verify the restore is skipped once the database is initialized, and
+        // resumed if the restore marker still exists (i.e., it was interrupted).
+        assert!(!restore_required(&restored_db, &restore_marker).unwrap());
+        fs::write(&restore_marker, b"").unwrap();
+        assert!(restore_required(&restored_db, &restore_marker).unwrap());
+        drop(restored_db);
+        restore_from_backup(&node_config).unwrap();
+        assert!(!restore_marker.exists());
+        let restored_db = open_db(&node_config.storage.dir());
+        assert_eq!(restored_db.get_synced_version().unwrap(), source_version);
+        drop(restored_db);
+
+        // Verify the restore fails if a trusted waypoint doesn't match the archive
+        let db_dir = TempPath::new();
+        db_dir.create_as_dir().unwrap();
+        let mut node_config = create_restore_config(
+            db_dir.path().to_path_buf(),
+            backup_dir.path().to_path_buf(),
+            genesis_waypoint,
+        );
+        let epoch_one_version = source_db
+            .get_epoch_ending_ledger_infos(1, 2)
+            .unwrap()
+            .ledger_info_with_sigs[0]
+            .ledger_info()
+            .version();
+        let bad_waypoint: Waypoint = format!(
+            "{}:0000000000000000000000000000000000000000000000000000000000000001",
+            epoch_one_version
+        )
+        .parse()
+        .unwrap();
+        node_config.storage.backup_archive.trusted_waypoints = vec![bad_waypoint];
+        assert!(restore_from_backup(&node_config).is_err());
+        assert!(node_config
+            .storage
+            .dir()
+            .join(RESTORE_IN_PROGRESS_MARKER)
+            .exists());
+    }
+
+    #[test]
+    fn test_restore_required() {
+        // Verify a restore is required for an empty database
+        let db_dir = TempPath::new();
+        db_dir.create_as_dir().unwrap();
+        let empty_db = open_db(db_dir.path());
+        let restore_marker = db_dir.path().join(RESTORE_IN_PROGRESS_MARKER);
+        assert!(restore_required(&empty_db, &restore_marker).unwrap());
+
+        // Verify that a restore is resumed if the restore marker exists
+        fs::write(&restore_marker, b"").unwrap();
+        assert!(restore_required(&empty_db, &restore_marker).unwrap());
+    }
+
+    #[test]
+    fn test_conflicting_trusted_waypoints() {
+        let db_dir = TempPath::new();
+        let mut node_config = create_restore_config(
+            db_dir.path().to_path_buf(),
+            db_dir.path().to_path_buf(),
+            Waypoint::default(),
+        );
+
+        // Verify that duplicate (but identical) waypoints are accepted
+        node_config.storage.backup_archive.trusted_waypoints = vec![Waypoint::default()];
+        let trusted_waypoints = get_trusted_waypoints(&node_config).unwrap();
+        assert_eq!(trusted_waypoints, HashMap::from([(0, Waypoint::default())]));
+
+        // Verify that conflicting waypoints are rejected
+        let conflicting_waypoint: Waypoint =
+            "0:0000000000000000000000000000000000000000000000000000000000000001"
+                .parse()
+                .unwrap();
+        node_config.storage.backup_archive.trusted_waypoints = vec![conflicting_waypoint];
+        assert!(get_trusted_waypoints(&node_config).is_err());
+    }
+
+    /// Backs up the epoch endings, a state snapshot and all transactions of
+    /// the given database to the local backup directory.
+    fn create_backup_archive(db: Arc<AptosDB>, backup_dir: PathBuf) {
+        let latest_ledger_info = db.get_latest_ledger_info().unwrap();
+        let latest_version = latest_ledger_info.ledger_info().version();
+        let next_epoch = latest_ledger_info.ledger_info().next_block_epoch();
+
+        let (runtime, port) = start_local_backup_service(db);
+        let client = Arc::new(BackupServiceClient::new(format!(
+            "http://localhost:{}",
+            port
+        )));
+        let storage: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir));
+        let global_opt = GlobalBackupOpt {
+            max_chunk_size: 1024 * 1024,
+            concurrent_data_requests: 2,
+        };
+        runtime.block_on(async {
+            EpochEndingBackupController::new(
+                EpochEndingBackupOpt {
+                    start_epoch: 0,
+                    end_epoch: next_epoch,
+                },
+                global_opt.clone(),
+                client.clone(),
+                storage.clone(),
+            )
+            .run()
+            .await
+            .unwrap();
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt { epoch: 0 },
+                global_opt.clone(),
+                client.clone(),
+                storage.clone(),
+            )
+            .run()
+            .await
+            .unwrap();
+            TransactionBackupController::new(
+                TransactionBackupOpt {
+                    start_version: 0,
+                    num_transactions: latest_version as usize + 1,
+                },
+                global_opt,
+                client,
+                storage,
+            )
+            .run()
+            .await
+            .unwrap();
+        });
+        runtime.shutdown_timeout(Duration::from_secs(1));
+    }
+
+    /// Creates a node config that restores from the given backup directory
+    fn create_restore_config(
+        db_dir: PathBuf,
+        backup_dir: PathBuf,
+        waypoint: Waypoint,
+    ) -> NodeConfig {
+        let mut node_config = NodeConfig::default();
+        node_config.set_data_dir(db_dir);
+        node_config.base.waypoint = WaypointConfig::FromConfig(waypoint);
+        node_config.state_sync.state_sync_driver.bootstrapping_mode =
+            BootstrappingMode::RestoreFromBackup;
+        node_config.storage.backup_archive.backup_dir = Some(backup_dir);
+        node_config
+    }
+
+    /// Opens the database in the given directory
+    fn open_db(db_dir: &Path) -> AptosDB {
+        AptosDB::open_kv_only(
+            StorageDirPaths::from_path(db_dir),
+            false, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs::default(),
+            false, /* indexer */
+            1000,  /* buffered_state_target_items */
+            1000,  /* max_num_nodes_per_lru_cache_shard */
+            None,  /* internal_indexer_db */
+        )
+        .unwrap()
+    }
+}
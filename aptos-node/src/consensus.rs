@@ -4,7 +4,7 @@
 use crate::{network::ApplicationNetworkInterfaces, services};
 use aptos_admin_service::AdminService;
 use aptos_channels::aptos_channel::Receiver;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{transaction_filter_type::LiveTransactionFilters, NodeConfig};
 use aptos_consensus::{
     consensus_observer::{
         network::{
@@ -45,6 +45,7 @@ pub fn create_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    transaction_filters: &LiveTransactionFilters,
     admin_service: &mut AdminService,
 ) -> Option<Runtime> {
     consensus_network_interfaces.map(|consensus_network_interfaces| {
@@ -57,6 +58,7 @@ pub fn create_consensus_runtime(
             consensus_to_mempool_sender.clone(),
             vtxn_pool,
             consensus_publisher.clone(),
+            transaction_filters,
         );
         admin_service.set_consensus_dbs(consensus_db, quorum_store_db);
 
@@ -149,6 +151,7 @@ pub fn create_consensus_observer_and_publisher(
     consensus_observer_reconfig_subscription: Option<
         ReconfigNotificationListener<DbBackedOnChainConfig>,
     >,
+    transaction_filters: &LiveTransactionFilters,
 ) -> (
     Option<Runtime>,
     Option<Runtime>,
@@ -195,6 +198,7 @@ pub fn create_consensus_observer_and_publisher(
         consensus_to_mempool_sender,
         db_rw,
         consensus_observer_reconfig_subscription,
+        transaction_filters,
     );
 
     (
@@ -217,6 +221,7 @@ fn create_consensus_observer(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     db_rw: DbReaderWriter,
     observer_reconfig_subscription: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    transaction_filters: &LiveTransactionFilters,
 ) {
     // If the observer is not enabled, return early
     if !node_config.consensus_observer.observer_enabled {
@@ -234,6 +239,7 @@ fn create_consensus_observer(
         consensus_to_mempool_sender,
         db_rw,
         observer_reconfig_subscription,
+        transaction_filters.consensus.clone(),
     );
 }
 
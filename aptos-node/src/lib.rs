@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod backup_restorer;
 mod consensus;
 mod indexer;
 mod logger;
@@ -20,7 +21,10 @@ use anyhow::anyhow;
 use aptos_admin_service::AdminService;
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
-use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
+use aptos_config::config::{
+    merge_node_config, transaction_filter_type::LiveTransactionFilters, NodeConfig,
+    PersistableConfig,
+};
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
@@ -622,10 +626,23 @@ pub fn setup_environment_and_start_node(
     let mut admin_service = services::start_admin_service(&node_config);
 
     // Set up the storage database and any RocksDB checkpoints
-    let (db_rw, backup_service, genesis_waypoint, indexer_db_opt, update_receiver) =
-        storage::initialize_database_and_checkpoints(&mut node_config)?;
+    let (
+        db_rw,
+        backup_service,
+        genesis_waypoint,
+        indexer_db_opt,
+        update_receiver,
+        rolling_checkpointer,
+    ) = storage::initialize_database_and_checkpoints(&mut node_config)?;
 
     admin_service.set_aptos_db(db_rw.clone().into());
+    if let Some(rolling_checkpointer) = rolling_checkpointer {
+        admin_service.set_rolling_checkpointer(rolling_checkpointer);
+    }
+
+    // Create the transaction filters (these can be updated at runtime via the admin service)
+    let transaction_filters = LiveTransactionFilters::new(&node_config);
+    admin_service.set_transaction_filters(transaction_filters.clone());
 
     // Set the Aptos VM configurations
     utils::set_aptos_vm_configurations(&node_config);
@@ -724,6 +741,7 @@ pub fn setup_environment_and_start_node(
             mempool_listener,
             mempool_client_receiver,
             peers_and_metadata,
+            transaction_filters.mempool.clone(),
         );
 
     // Create the DKG runtime and get the VTxn pool
@@ -752,6 +770,7 @@ pub fn setup_environment_and_start_node(
             consensus_to_mempool_sender.clone(),
             db_rw.clone(),
             consensus_observer_reconfig_subscription,
+            &transaction_filters,
         );
 
     // Create the consensus runtime (if enabled)
@@ -764,6 +783,7 @@ pub fn setup_environment_and_start_node(
         consensus_to_mempool_sender.clone(),
         vtxn_pool,
         consensus_publisher.clone(),
+        &transaction_filters,
         &mut admin_service,
     );
 
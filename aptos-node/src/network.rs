@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::services::start_netbench_service;
+use anyhow::{anyhow, ensure};
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
     config::{NetworkConfig, NodeConfig},
@@ -14,7 +15,7 @@ use aptos_consensus::{
 use aptos_dkg_runtime::DKGMessage;
 use aptos_event_notifications::EventSubscriptionService;
 use aptos_jwk_consensus::types::JWKConsensusMsg;
-use aptos_logger::debug;
+use aptos_logger::{debug, error, info};
 use aptos_mempool::network::MempoolSyncMsg;
 use aptos_network::{
     application::{
@@ -34,7 +35,7 @@ use aptos_storage_service_types::StorageServiceMessage;
 use aptos_time_service::TimeService;
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
 use tokio::runtime::Runtime;
 
 /// A simple struct that holds both the network client
@@ -107,7 +108,14 @@ pub fn jwk_consensus_network_configuration(node_config: &NodeConfig) -> NetworkA
 
 /// Returns the network application config for the mempool client and service
 pub fn mempool_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
-    let direct_send_protocols = vec![ProtocolId::MempoolDirectSend];
+    // Supported protocols in preferred order (from highest priority to lowest)
+    let mut direct_send_protocols = vec![
+        ProtocolId::MempoolDirectSendZstd,
+        ProtocolId::MempoolDirectSend,
+    ];
+    if is_compression_dictionary_registered(ProtocolId::MempoolDirectSendZstdDictionary) {
+        direct_send_protocols.insert(0, ProtocolId::MempoolDirectSendZstdDictionary);
+    }
     let rpc_protocols = vec![]; // Mempool does not use RPC
 
     let network_client_config =
@@ -146,7 +154,15 @@ pub fn peer_monitoring_network_configuration(node_config: &NodeConfig) -> Networ
 /// Returns the network application config for the storage service client and server
 pub fn storage_service_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
     let direct_send_protocols = vec![]; // The storage service does not use direct send
-    let rpc_protocols = vec![ProtocolId::StorageServiceRpc];
+
+    // Supported protocols in preferred order (from highest priority to lowest)
+    let mut rpc_protocols = vec![
+        ProtocolId::StorageServiceRpcZstd,
+        ProtocolId::StorageServiceRpc,
+    ];
+    if is_compression_dictionary_registered(ProtocolId::StorageServiceRpcZstdDictionary) {
+        rpc_protocols.insert(0, ProtocolId::StorageServiceRpcZstdDictionary);
+    }
     let max_network_channel_size = node_config
         .state_sync
         .storage_service
@@ -217,6 +233,69 @@ pub fn netbench_network_configuration(
     ))
 }
 
+/// Registers the compression dictionaries specified in the node config. Dictionaries
+/// that fail to load (e.g., because they have an unexpected ID) are skipped, so the
+/// corresponding protocols won't be advertised to peers.
+fn register_compression_dictionaries(node_config: &NodeConfig) {
+    let compression_config = &node_config.compression;
+    for (dictionary_path, protocol_id) in [
+        (
+            &compression_config.mempool_dictionary_path,
+            ProtocolId::MempoolDirectSendZstdDictionary,
+        ),
+        (
+            &compression_config.storage_service_dictionary_path,
+            ProtocolId::StorageServiceRpcZstdDictionary,
+        ),
+    ] {
+        if let Some(dictionary_path) = dictionary_path {
+            match register_compression_dictionary(dictionary_path, protocol_id) {
+                Ok(()) => info!(
+                    "Registered the compression dictionary for {} from: {:?}",
+                    protocol_id, dictionary_path
+                ),
+                Err(error) => error!(
+                    "Failed to register the compression dictionary for {} from: {:?}! Error: {:?}",
+                    protocol_id, dictionary_path, error
+                ),
+            }
+        }
+    }
+}
+
+/// Loads and registers the compression dictionary for the given protocol
+fn register_compression_dictionary(
+    dictionary_path: &Path,
+    protocol_id: ProtocolId,
+) -> anyhow::Result<()> {
+    // Get the expected dictionary ID for the protocol
+    let expected_dictionary_id = protocol_id
+        .compression_dictionary_id()
+        .ok_or_else(|| anyhow!("The protocol does not use a compression dictionary!"))?;
+
+    // Load the dictionary and verify the dictionary ID
+    let dictionary = fs::read(dictionary_path)?;
+    let dictionary_id = aptos_compression::dictionary::get_dictionary_id(&dictionary)?;
+    ensure!(
+        dictionary_id == expected_dictionary_id,
+        "Unexpected dictionary ID! Expected: {}, found: {}",
+        expected_dictionary_id,
+        dictionary_id
+    );
+
+    // Register the dictionary
+    aptos_compression::dictionary::register_dictionary(&dictionary)?;
+    Ok(())
+}
+
+/// Returns true iff the compression dictionary for the given protocol is registered
+fn is_compression_dictionary_registered(protocol_id: ProtocolId) -> bool {
+    protocol_id
+        .compression_dictionary_id()
+        .map(aptos_compression::dictionary::is_dictionary_registered)
+        .unwrap_or(false)
+}
+
 /// Extracts all network configs from the given node config
 fn extract_network_configs(node_config: &NodeConfig) -> Vec<NetworkConfig> {
     let mut network_configs: Vec<NetworkConfig> = node_config.full_node_networks.to_vec();
@@ -260,6 +339,9 @@ pub fn setup_networks_and_get_interfaces(
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
 ) {
+    // Register the compression dictionaries (before the protocols are advertised)
+    register_compression_dictionaries(node_config);
+
     // Gather all network configs
     let network_configs = extract_network_configs(node_config);
 
@@ -4,7 +4,10 @@
 use anyhow::{anyhow, Result};
 use aptos_backup_service::start_backup_service;
 use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
-use aptos_db::{fast_sync_storage_wrapper::FastSyncStorageWrapper, AptosDB};
+use aptos_db::{
+    fast_sync_storage_wrapper::FastSyncStorageWrapper, rolling_checkpointer::RollingCheckpointer,
+    AptosDB,
+};
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_indexer_grpc_table_info::internal_indexer_db_service::InternalIndexerDBService;
@@ -51,6 +54,7 @@ pub(crate) fn bootstrap_db(
     Option<Runtime>,
     Option<InternalIndexerDB>,
     Option<WatchReceiver<u64>>,
+    Option<Arc<RollingCheckpointer>>,
 )> {
     let internal_indexer_db = InternalIndexerDBService::get_indexer_db(node_config);
     let (update_sender, update_receiver) = if internal_indexer_db.is_some() {
@@ -60,7 +64,7 @@ pub(crate) fn bootstrap_db(
         (None, None)
     };
 
-    let (aptos_db_reader, db_rw, backup_service) = match FastSyncStorageWrapper::initialize_dbs(
+    let (aptos_db_reader, db_rw, backup_service, db) = match FastSyncStorageWrapper::initialize_dbs(
         node_config,
         internal_indexer_db.clone(),
         update_sender,
@@ -70,7 +74,12 @@ pub(crate) fn bootstrap_db(
             let db_backup_service =
                 start_backup_service(node_config.storage.backup_service_address, db_arc.clone());
             maybe_apply_genesis(&db_rw, node_config)?;
-            (db_arc as Arc<dyn DbReader>, db_rw, Some(db_backup_service))
+            (
+                db_arc.clone() as Arc<dyn DbReader>,
+                db_rw,
+                Some(db_backup_service),
+                db_arc,
+            )
         },
         Either::Right(fast_sync_db_wrapper) => {
             let temp_db = fast_sync_db_wrapper.get_temporary_db_with_genesis();
@@ -92,17 +101,33 @@ pub(crate) fn bootstrap_db(
                 // commit the genesis ledger info to the DB.
                 fast_sync_db.commit_genesis_ledger_info(&ledger_info)?;
             }
-            let db_backup_service =
-                start_backup_service(node_config.storage.backup_service_address, fast_sync_db);
-            (db_arc as Arc<dyn DbReader>, db_rw, Some(db_backup_service))
+            let db_backup_service = start_backup_service(
+                node_config.storage.backup_service_address,
+                fast_sync_db.clone(),
+            );
+            (
+                db_arc as Arc<dyn DbReader>,
+                db_rw,
+                Some(db_backup_service),
+                fast_sync_db,
+            )
         },
     };
+    // The checkpointer (and the admin endpoint creating checkpoints) only exists if enabled
+    let rolling_checkpointer = node_config.storage.rolling_checkpoint.enable.then(|| {
+        Arc::new(RollingCheckpointer::new(
+            db,
+            &node_config.storage.rolling_checkpoint,
+            node_config.storage.rolling_checkpoint_dir(),
+        ))
+    });
     Ok((
         aptos_db_reader,
         db_rw,
         backup_service,
         internal_indexer_db,
         update_receiver,
+        rolling_checkpointer,
     ))
 }
 
@@ -177,6 +202,7 @@ pub fn initialize_database_and_checkpoints(
     Waypoint,
     Option<InternalIndexerDB>,
     Option<WatchReceiver<Version>>,
+    Option<Arc<RollingCheckpointer>>,
 )> {
     // If required, create RocksDB checkpoints and change the working directory.
     // This is test-only.
@@ -184,9 +210,13 @@ pub fn initialize_database_and_checkpoints(
         create_rocksdb_checkpoint_and_change_working_dir(node_config, working_dir);
     }
 
+    // If required, restore the database from a local backup archive
+    crate::backup_restorer::restore_from_backup(node_config)
+        .map_err(|error| anyhow!("Failed to restore from the backup archive: {}", error))?;
+
     // Open the database
     let instant = Instant::now();
-    let (_aptos_db, db_rw, backup_service, indexer_db_opt, update_receiver) =
+    let (_aptos_db, db_rw, backup_service, indexer_db_opt, update_receiver, rolling_checkpointer) =
         bootstrap_db(node_config)?;
 
     // Log the duration to open storage
@@ -201,5 +231,6 @@ pub fn initialize_database_and_checkpoints(
         node_config.base.waypoint.genesis_waypoint(),
         indexer_db_opt,
         update_receiver,
+        rolling_checkpointer,
     ))
 }
@@ -18,7 +18,8 @@ pub struct AdminServiceConfig {
     pub enabled: Option<bool>,
     pub address: String,
     pub port: u16,
-    // If empty, will allow all requests without authentication. (Not allowed on mainnet.)
+    // If empty, will allow all GET requests without authentication, and reject the others, which
+    // change the state of the node. (Not allowed on mainnet.)
     pub authentication_configs: Vec<AuthenticationConfig>,
 }
 
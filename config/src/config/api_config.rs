@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::transaction_filter_type::{Filter, Matcher};
+use super::transaction_filter_type::Filter;
 use crate::{
     config::{
         config_sanitizer::ConfigSanitizer, gas_estimation_config::GasEstimationConfig,
@@ -65,6 +65,13 @@ pub struct ApiConfig {
     ///
     /// This limits the execution length of a view function to the given gas used.
     pub max_gas_view_function: u64,
+    /// Maximum number of items that can be sent with the Batch view API
+    pub max_view_batch_size: usize,
+    /// Maximum gas unit limit for all the view functions of a Batch view API request
+    ///
+    /// Each view function is limited to the smaller of `max_gas_view_function` and what's left
+    /// of this budget.
+    pub max_gas_view_batch: u64,
     /// Optional: Maximum number of worker threads for the API.
     ///
     /// If not set, `runtime_worker_multiplier` will multiply times the number of CPU cores on the machine
@@ -89,6 +96,13 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// Enables the server-sent event streams of committed transactions, events and blocks
+    #[serde(default = "default_disabled")]
+    pub stream_enabled: bool,
+    /// The interval at which streams poll the storage for newly committed transactions.
+    pub stream_poll_interval_ms: u64,
+    /// The number of streams that can be active at any given time.
+    pub stream_max_active_connections: usize,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
@@ -96,9 +110,11 @@ const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
+pub const DEFAULT_MAX_VIEW_BATCH_SIZE: usize = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_VIEW_GAS: u64 = 2_000_000; // We keep this value the same as the max number of gas allowed for one single transaction defined in aptos-gas.
+const DEFAULT_MAX_VIEW_BATCH_GAS: u64 = 10 * DEFAULT_MAX_VIEW_GAS;
 
 fn default_enabled() -> bool {
     true
@@ -131,6 +147,8 @@ impl Default for ApiConfig {
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            max_view_batch_size: DEFAULT_MAX_VIEW_BATCH_SIZE,
+            max_gas_view_batch: DEFAULT_MAX_VIEW_BATCH_GAS,
             max_runtime_workers: None,
             runtime_worker_multiplier: 2,
             gas_estimation: GasEstimationConfig::default(),
@@ -141,6 +159,9 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            stream_enabled: default_disabled(),
+            stream_poll_interval_ms: 100,
+            stream_max_active_connections: 100,
         }
     }
 }
@@ -190,14 +211,20 @@ impl ConfigSanitizer for ApiConfig {
             ));
         }
 
+        // Streams poll the storage in a loop, so they need a non-zero interval
+        if api_config.stream_enabled && api_config.stream_poll_interval_ms == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "stream_poll_interval_ms must be greater than 0!".into(),
+            ));
+        }
+
         // We don't support Block ID based simulation filters.
-        for rule in api_config.simulation_filter.rules() {
-            if let Matcher::BlockId(_) = rule.matcher() {
-                return Err(Error::ConfigSanitizerFailed(
-                    sanitizer_name,
-                    "Block ID based simulation filters are not supported!".into(),
-                ));
-            }
+        if api_config.simulation_filter.contains_block_id_matcher() {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Block ID based simulation filters are not supported!".into(),
+            ));
         }
 
         // Sanitize the gas estimation config
@@ -315,4 +342,23 @@ mod tests {
                 .unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_zero_stream_poll_interval() {
+        // Create a node config with streams polling continuously
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                stream_enabled: true,
+                stream_poll_interval_ms: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // the stream poll interval is invalid.
+        let error = ApiConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
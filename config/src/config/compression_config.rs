@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+};
+use aptos_types::chain_id::ChainId;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The configuration for network message compression. Each dictionary is used
+/// by a single network protocol (with a fixed dictionary ID), and the protocol
+/// is only advertised to peers if the dictionary is provided and matches the ID.
+/// Dictionaries can be trained using the `aptos-db-tool train-compression-dictionary`
+/// command, and all peers must use identical dictionaries.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompressionConfig {
+    /// The path to the zstd dictionary for mempool transaction broadcasts
+    pub mempool_dictionary_path: Option<PathBuf>,
+    /// The path to the zstd dictionary for storage service responses
+    pub storage_service_dictionary_path: Option<PathBuf>,
+}
+
+impl ConfigSanitizer for CompressionConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let compression_config = &node_config.compression;
+
+        // Verify that all dictionary files exist
+        for dictionary_path in [
+            &compression_config.mempool_dictionary_path,
+            &compression_config.storage_service_dictionary_path,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !dictionary_path.is_file() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The compression dictionary file does not exist: {:?}",
+                        dictionary_path
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_sanitize_missing_dictionary() {
+        // Create a node config with a missing dictionary file
+        let node_config = NodeConfig {
+            compression: CompressionConfig {
+                mempool_dictionary_path: Some(PathBuf::from("missing_dictionary")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            CompressionConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_existing_dictionary() {
+        // Create a node config with an existing dictionary file
+        let dictionary_file = TempPath::new();
+        dictionary_file.create_as_file().unwrap();
+        let node_config = NodeConfig {
+            compression: CompressionConfig {
+                storage_service_dictionary_path: Some(dictionary_file.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization succeeds
+        CompressionConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+    }
+}
@@ -34,6 +34,9 @@ pub struct ConsensusObserverConfig {
     pub max_concurrent_subscriptions: u64,
     /// Maximum number of blocks to keep in memory (e.g., pending blocks, ordered blocks, etc.)
     pub max_num_pending_blocks: u64,
+    /// Maximum lag (in milliseconds) behind the first publisher to deliver a message,
+    /// before the delivery counts against the score of a (slower) publisher.
+    pub max_publisher_lag_ms: u64,
     /// Maximum timeout (in milliseconds) for active subscriptions
     pub max_subscription_timeout_ms: u64,
     /// Maximum timeout (in milliseconds) we'll wait for the synced version to
@@ -58,6 +61,7 @@ impl Default for ConsensusObserverConfig {
             garbage_collection_interval_ms: 60_000,            // 60 seconds
             max_concurrent_subscriptions: 2,                   // 2 streams should be sufficient
             max_num_pending_blocks: 100,                       // 100 blocks
+            max_publisher_lag_ms: 1_000,                       // 1 second
             max_subscription_timeout_ms: 30_000,               // 30 seconds
             max_synced_version_timeout_ms: 60_000,             // 60 seconds
             progress_check_interval_ms: 5_000,                 // 5 seconds
@@ -13,6 +13,9 @@ pub struct InternalIndexerDBConfig {
     pub enable_transaction: bool,
     pub enable_event: bool,
     pub enable_statekeys: bool,
+    pub enable_event_by_type: bool,
+    pub enable_transaction_by_function: bool,
+    pub enable_resource_by_type: bool,
     pub batch_size: usize,
 }
 
@@ -21,12 +24,18 @@ impl InternalIndexerDBConfig {
         enable_transaction: bool,
         enable_event: bool,
         enable_statekeys: bool,
+        enable_event_by_type: bool,
+        enable_transaction_by_function: bool,
+        enable_resource_by_type: bool,
         batch_size: usize,
     ) -> Self {
         Self {
             enable_transaction,
             enable_event,
             enable_statekeys,
+            enable_event_by_type,
+            enable_transaction_by_function,
+            enable_resource_by_type,
             batch_size,
         }
     }
@@ -43,8 +52,25 @@ impl InternalIndexerDBConfig {
         self.enable_statekeys
     }
 
+    pub fn enable_event_by_type(&self) -> bool {
+        self.enable_event_by_type
+    }
+
+    pub fn enable_transaction_by_function(&self) -> bool {
+        self.enable_transaction_by_function
+    }
+
+    pub fn enable_resource_by_type(&self) -> bool {
+        self.enable_resource_by_type
+    }
+
     pub fn is_internal_indexer_db_enabled(&self) -> bool {
-        self.enable_transaction || self.enable_event || self.enable_statekeys
+        self.enable_transaction
+            || self.enable_event
+            || self.enable_statekeys
+            || self.enable_event_by_type
+            || self.enable_transaction_by_function
+            || self.enable_resource_by_type
     }
 
     pub fn batch_size(&self) -> usize {
@@ -58,6 +84,9 @@ impl Default for InternalIndexerDBConfig {
             enable_transaction: false,
             enable_event: false,
             enable_statekeys: false,
+            enable_event_by_type: false,
+            enable_transaction_by_function: false,
+            enable_resource_by_type: false,
             batch_size: 10_000,
         }
     }
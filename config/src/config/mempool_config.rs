@@ -4,7 +4,8 @@
 
 use crate::config::{
     config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
-    node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
+    node_config_loader::NodeType, transaction_filter_type::Filter, Error, NodeConfig,
+    MAX_APPLICATION_MESSAGE_SIZE,
 };
 use aptos_global_constants::DEFAULT_BUCKETS;
 use aptos_types::chain_id::ChainId;
@@ -37,6 +38,54 @@ impl Default for LoadBalancingThresholdConfig {
     }
 }
 
+/// A priority lane, which gets to fill a share of each batch pulled by consensus before the
+/// rest of mempool is considered.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriorityLaneConfig {
+    /// The use case of the transactions in this lane. Either a use case as tracked from the recent
+    /// blocks (e.g., "entry_platform", "non_entry" or "entry_user_top_1"), or the address of the
+    /// contract the entry functions belong to.
+    pub use_case: String,
+    /// The maximum percentage of each batch the lane can take ahead of other transactions.
+    pub max_batch_percentage: u8,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolFairnessConfig {
+    /// The maximum number of transactions from a single sender returned per batch pulled by
+    /// consensus. If not set, there is no limit.
+    pub max_txns_per_sender_per_batch: Option<u64>,
+    /// Transactions with a gas unit price at or below this are considered low-fee.
+    pub low_fee_max_gas_unit_price: u64,
+    /// The percentage of each batch reserved for low-fee transactions, which are otherwise
+    /// only considered after all the transactions paying more. 0 disables the low-fee lane.
+    pub low_fee_reserved_batch_percentage: u8,
+    /// Priority lanes, considered in order, before the low-fee lane and the rest of mempool.
+    pub priority_lanes: Vec<PriorityLaneConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolPersistenceConfig {
+    /// Whether or not to journal the mempool contents to disk, and restore them on startup.
+    /// Restored transactions go through VM validation again, and expired ones are dropped.
+    pub enabled: bool,
+    /// Interval to write the changes to the mempool contents to the journal. Transactions
+    /// received within this interval of a crash are lost.
+    pub flush_interval_ms: u64,
+}
+
+impl Default for MempoolPersistenceConfig {
+    fn default() -> MempoolPersistenceConfig {
+        MempoolPersistenceConfig {
+            enabled: false,
+            flush_interval_ms: 1_000,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
@@ -100,6 +149,14 @@ pub struct MempoolConfig {
     /// up to 10 minutes (shared_mempool_priority_update_interval_secs) to enable the load balancing. If this flag is enabled,
     /// then the PFNs will always do load balancing irrespective of the load.
     pub enable_max_load_balancing_at_any_load: bool,
+    /// The filter applied to transactions on mempool admission. Transactions that are denied
+    /// by the filter are rejected before VM validation. Block ID based rules are not supported,
+    /// and the current time is used in place of the block timestamp.
+    pub transaction_filter: Filter,
+    /// Fairness lanes and per-sender quotas applied to the batches pulled by consensus.
+    pub fairness: MempoolFairnessConfig,
+    /// Journaling of the mempool contents, so they survive node restarts.
+    pub persistence: MempoolPersistenceConfig,
 }
 
 impl Default for MempoolConfig {
@@ -164,17 +221,67 @@ impl Default for MempoolConfig {
                 },
             ],
             enable_max_load_balancing_at_any_load: false,
+            transaction_filter: Filter::empty(),
+            fairness: MempoolFairnessConfig::default(),
+            persistence: MempoolPersistenceConfig::default(),
         }
     }
 }
 
 impl ConfigSanitizer for MempoolConfig {
     fn sanitize(
-        _node_config: &NodeConfig,
+        node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add reasonable verifications
+        let sanitizer_name = Self::get_sanitizer_name();
+        let mempool_config = &node_config.mempool;
+
+        // We don't support Block ID based mempool filters (there is no block on admission)
+        if mempool_config
+            .transaction_filter
+            .contains_block_id_matcher()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Block ID based mempool transaction filters are not supported!".into(),
+            ));
+        }
+
+        // Verify the fairness lanes fit in a batch
+        let fairness_config = &mempool_config.fairness;
+        if fairness_config.max_txns_per_sender_per_batch == Some(0) {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The max transactions per sender per batch must be greater than 0!".into(),
+            ));
+        }
+        let total_lane_percentage = fairness_config
+            .priority_lanes
+            .iter()
+            .map(|lane| lane.max_batch_percentage as u64)
+            .sum::<u64>()
+            + fairness_config.low_fee_reserved_batch_percentage as u64;
+        if total_lane_percentage > 100 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The priority and low-fee lanes take {}% of a batch, more than 100%!",
+                    total_lane_percentage
+                ),
+            ));
+        }
+
+        // Verify the journal is flushed periodically
+        let persistence_config = &mempool_config.persistence;
+        if persistence_config.enabled && persistence_config.flush_interval_ms == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The mempool journal flush interval must be greater than 0!".into(),
+            ));
+        }
+
+        Ok(()) // TODO: add more reasonable verifications
     }
 }
 
@@ -240,6 +347,9 @@ impl ConfigOptimizer for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::transaction_filter_type::Matcher;
+    use aptos_crypto::HashValue;
+    use aptos_types::account_address::AccountAddress;
 
     #[test]
     fn test_optimize_vfn_configs() {
@@ -346,4 +456,83 @@ mod tests {
             local_max_broadcasts_per_peer
         );
     }
+
+    #[test]
+    fn test_sanitize_block_id_filter() {
+        // Create a node config with a nested block ID based mempool filter
+        let node_config = NodeConfig {
+            mempool: MempoolConfig {
+                transaction_filter: Filter::empty().add_deny(Matcher::Not(Box::new(
+                    Matcher::BlockId(HashValue::random()),
+                ))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Create a node config with a sender based mempool filter
+        let node_config = NodeConfig {
+            mempool: MempoolConfig {
+                transaction_filter: Filter::empty().add_deny_sender(AccountAddress::random()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it succeeds
+        MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_fairness_lanes() {
+        // Create a node config with lanes that take more than an entire batch
+        let mut node_config = NodeConfig {
+            mempool: MempoolConfig {
+                fairness: MempoolFairnessConfig {
+                    low_fee_reserved_batch_percentage: 30,
+                    priority_lanes: vec![
+                        PriorityLaneConfig {
+                            use_case: "entry_platform".into(),
+                            max_batch_percentage: 50,
+                        },
+                        PriorityLaneConfig {
+                            use_case: AccountAddress::random().to_hex_literal(),
+                            max_batch_percentage: 30,
+                        },
+                    ],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Shrink the low-fee lane and verify that the config is now valid
+        node_config
+            .mempool
+            .fairness
+            .low_fee_reserved_batch_percentage = 20;
+        MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+            .unwrap();
+
+        // Set a per-sender limit of 0 and verify that it fails
+        node_config.mempool.fairness.max_txns_per_sender_per_batch = Some(0);
+        let error =
+            MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
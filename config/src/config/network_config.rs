@@ -124,6 +124,8 @@ pub struct NetworkConfig {
     pub max_parallel_deserialization_tasks: Option<usize>,
     /// Whether or not to enable latency aware peer dialing
     pub enable_latency_aware_dialing: bool,
+    /// The outbound message scheduler configuration (for each peer connection)
+    pub outbound_scheduler_config: OutboundSchedulerConfig,
 }
 
 impl Default for NetworkConfig {
@@ -165,6 +167,7 @@ impl NetworkConfig {
             outbound_tx_buffer_size_bytes: None,
             max_parallel_deserialization_tasks: None,
             enable_latency_aware_dialing: true,
+            outbound_scheduler_config: OutboundSchedulerConfig::default(),
         };
 
         // Configure the number of parallel deserialization tasks
@@ -388,6 +391,39 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// The outbound message scheduler configuration. Outbound messages to each
+/// peer are queued by protocol class (consensus, mempool, state sync and
+/// netbench) and written to the wire using weighted (deficit) round-robin,
+/// where each class may write up to its byte budget per scheduling round.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboundSchedulerConfig {
+    /// Bytes per round for consensus messages (including DKG, JWK
+    /// consensus, consensus observer and health checker messages)
+    pub consensus_byte_budget: u64,
+    /// Bytes per round for mempool messages
+    pub mempool_byte_budget: u64,
+    /// Bytes per round for state sync messages (including storage
+    /// service and peer monitoring messages)
+    pub state_sync_byte_budget: u64,
+    /// Bytes per round for netbench messages
+    pub netbench_byte_budget: u64,
+    /// Maximum number of queued messages per class (the oldest are dropped)
+    pub max_queued_messages_per_class: usize,
+}
+
+impl Default for OutboundSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            consensus_byte_budget: 4 * 1024 * 1024, // 4 MiB
+            mempool_byte_budget: 1024 * 1024,       // 1 MiB
+            state_sync_byte_budget: 512 * 1024,     // 512 KiB
+            netbench_byte_budget: 128 * 1024,       // 128 KiB
+            max_queued_messages_per_class: NETWORK_CHANNEL_SIZE,
+        }
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
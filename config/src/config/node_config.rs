@@ -9,8 +9,8 @@ use crate::{
         jwk_consensus_config::JWKConsensusConfig, netbench_config::NetbenchConfig,
         node_config_loader::NodeConfigLoader, node_startup_config::NodeStartupConfig,
         persistable_config::PersistableConfig, utils::RootPath, AdminServiceConfig, ApiConfig,
-        BaseConfig, ConsensusConfig, Error, ExecutionConfig, IndexerConfig, IndexerGrpcConfig,
-        InspectionServiceConfig, LoggerConfig, MempoolConfig, NetworkConfig,
+        BaseConfig, CompressionConfig, ConsensusConfig, Error, ExecutionConfig, IndexerConfig,
+        IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig, NetworkConfig,
         PeerMonitoringServiceConfig, SafetyRulesTestConfig, StateSyncConfig, StorageConfig,
     },
     network_id::NetworkId,
@@ -41,6 +41,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub base: BaseConfig,
     #[serde(default)]
+    pub compression: CompressionConfig,
+    #[serde(default)]
     pub consensus: ConsensusConfig,
     #[serde(default)]
     pub consensus_observer: ConsensusObserverConfig,
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::{
-    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType,
+    transaction_filter_type::Filter, Error, NodeConfig,
 };
 use aptos_global_constants::DEFAULT_BUCKETS;
 use aptos_types::chain_id::ChainId;
@@ -97,6 +98,10 @@ pub struct QuorumStoreConfig {
     pub batch_buckets: Vec<u64>,
     pub allow_batches_without_pos_in_proposal: bool,
     pub enable_opt_quorum_store: bool,
+    /// The filter applied to the batches received from other validators. Batches with any
+    /// transaction denied by the filter are dropped. Block ID based rules are not supported, and
+    /// the current time is used in place of the block timestamp.
+    pub transaction_filter: Filter,
 }
 
 impl Default for QuorumStoreConfig {
@@ -136,6 +141,7 @@ impl Default for QuorumStoreConfig {
             batch_buckets: DEFAULT_BUCKETS.to_vec(),
             allow_batches_without_pos_in_proposal: true,
             enable_opt_quorum_store: false,
+            transaction_filter: Filter::empty(),
         }
     }
 }
@@ -261,6 +267,19 @@ impl ConfigSanitizer for QuorumStoreConfig {
         // Sanitize the batch total limits
         Self::sanitize_batch_total_limits(&sanitizer_name, &node_config.consensus.quorum_store)?;
 
+        // We don't support Block ID based batch filters (batches are received outside of blocks)
+        if node_config
+            .consensus
+            .quorum_store
+            .transaction_filter
+            .contains_block_id_matcher()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Block ID based quorum store transaction filters are not supported!".into(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -51,8 +51,10 @@ impl Default for SafetyRulesConfig {
 
 impl SafetyRulesConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
-        if let SecureBackend::OnDiskStorage(backend) = &mut self.backend {
-            backend.set_data_dir(data_dir);
+        match &mut self.backend {
+            SecureBackend::OnDiskStorage(backend) => backend.set_data_dir(data_dir),
+            SecureBackend::EncryptedOnDiskStorage(backend) => backend.set_data_dir(data_dir),
+            SecureBackend::InMemoryStorage | SecureBackend::Vault(_) => {},
         }
     }
 
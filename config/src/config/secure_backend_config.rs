@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Error;
-use aptos_secure_storage::{InMemoryStorage, Namespaced, OnDiskStorage, Storage, VaultStorage};
+use aptos_secure_storage::{
+    EncryptedOnDiskStorage, EncryptionKeySource, InMemoryStorage, Namespaced, OnDiskStorage,
+    Storage, VaultStorage,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
@@ -20,15 +23,18 @@ pub enum SecureBackend {
     InMemoryStorage,
     Vault(VaultConfig),
     OnDiskStorage(OnDiskStorageConfig),
+    EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig),
 }
 
 impl SecureBackend {
     pub fn namespace(&self) -> Option<&str> {
         match self {
             SecureBackend::Vault(VaultConfig { namespace, .. })
-            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
-                namespace.as_deref()
-            },
+            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig {
+                namespace,
+                ..
+            }) => namespace.as_deref(),
             SecureBackend::InMemoryStorage => None,
         }
     }
@@ -36,7 +42,11 @@ impl SecureBackend {
     pub fn clear_namespace(&mut self) {
         match self {
             SecureBackend::Vault(VaultConfig { namespace, .. })
-            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
+            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig {
+                namespace,
+                ..
+            }) => {
                 *namespace = None;
             },
             SecureBackend::InMemoryStorage => {},
@@ -97,6 +107,76 @@ pub struct OnDiskStorageConfig {
     data_dir: PathBuf,
 }
 
+/// OnDiskStorage that is encrypted at rest. The data is encrypted with a data key that is in
+/// turn encrypted using the configured key encryption key (or a key derived from a passphrase).
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedOnDiskStorageConfig {
+    // Required path for encrypted on disk storage
+    pub path: PathBuf,
+    /// A namespace is an optional portion of the path to a key stored within the storage (see
+    /// OnDiskStorageConfig).
+    pub namespace: Option<String>,
+    /// The key used to encrypt the storage
+    pub encryption_key: EncryptionKeyConfig,
+    /// The key previously used to encrypt the storage. This is only required when rotating
+    /// keys: if the storage can't be decrypted using `encryption_key`, the previous key is
+    /// used instead, and the storage is re-encrypted using `encryption_key`.
+    pub previous_encryption_key: Option<EncryptionKeyConfig>,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl EncryptedOnDiskStorageConfig {
+    pub fn new(path: PathBuf, encryption_key: EncryptionKeyConfig) -> Self {
+        Self {
+            path,
+            namespace: None,
+            encryption_key,
+            previous_encryption_key: None,
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        if self.path.is_relative() {
+            self.data_dir.join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
+
+/// The key used to encrypt secure storage at rest
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionKeyConfig {
+    /// A passphrase, from which the key encryption key is derived
+    Passphrase(Token),
+    /// A hex-encoded 32-byte key encryption key
+    KeyEncryptionKey(Token),
+}
+
+impl EncryptionKeyConfig {
+    pub fn read_key_source(&self) -> Result<EncryptionKeySource, Error> {
+        match self {
+            EncryptionKeyConfig::Passphrase(token) => {
+                Ok(EncryptionKeySource::Passphrase(token.read_token()?))
+            },
+            EncryptionKeyConfig::KeyEncryptionKey(token) => {
+                let key = hex::decode(token.read_token()?.trim()).map_err(|error| {
+                    Error::Unexpected(format!("Invalid key encryption key: {}", error))
+                })?;
+                Ok(EncryptionKeySource::KeyEncryptionKey(key))
+            },
+        }
+    }
+}
+
 /// Tokens can either be directly within this config or stored somewhere on disk.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -172,6 +252,37 @@ impl From<&SecureBackend> for Storage {
                     storage
                 }
             },
+            SecureBackend::EncryptedOnDiskStorage(config) => {
+                let key_source = config
+                    .encryption_key
+                    .read_key_source()
+                    .expect("Unable to read the encryption key");
+                let storage = match &config.previous_encryption_key {
+                    Some(previous_encryption_key) => {
+                        EncryptedOnDiskStorage::new_with_previous_key_source(
+                            config.path(),
+                            key_source,
+                            previous_encryption_key
+                                .read_key_source()
+                                .expect("Unable to read the previous encryption key"),
+                        )
+                    },
+                    None => EncryptedOnDiskStorage::new(config.path(), key_source),
+                }
+                .unwrap_or_else(|error| {
+                    panic!(
+                        "Unable to open encrypted storage at path: {:?}. Error: {}",
+                        config.path(),
+                        error
+                    )
+                });
+                let storage = Storage::from(storage);
+                if let Some(namespace) = &config.namespace {
+                    Storage::from(Namespaced::new(namespace, Box::new(storage)))
+                } else {
+                    storage
+                }
+            },
             SecureBackend::Vault(config) => {
                 let storage = Storage::from(VaultStorage::new(
                     config.server.clone(),
@@ -290,6 +401,58 @@ vault:
         serde_yaml::to_string(&from_disk).unwrap();
     }
 
+    #[test]
+    fn test_encrypted_on_disk_parsing() {
+        #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+        struct Config {
+            backend: SecureBackend,
+        }
+
+        let mut storage_config = EncryptedOnDiskStorageConfig::new(
+            PathBuf::from("secure_storage.json"),
+            EncryptionKeyConfig::Passphrase(Token::FromDisk(PathBuf::from("/passphrase"))),
+        );
+        storage_config.previous_encryption_key = Some(EncryptionKeyConfig::KeyEncryptionKey(
+            Token::FromConfig("00".repeat(32)),
+        ));
+        let from_config = Config {
+            backend: SecureBackend::EncryptedOnDiskStorage(storage_config),
+        };
+
+        let text_from_config = format!(
+            r#"
+backend:
+    type: "encrypted_on_disk_storage"
+    path: "secure_storage.json"
+    encryption_key:
+        passphrase:
+            from_disk: "/passphrase"
+    previous_encryption_key:
+        key_encryption_key:
+            from_config: "{}"
+        "#,
+            "00".repeat(32)
+        );
+
+        let de_from_config: Config = serde_yaml::from_str(&text_from_config).unwrap();
+        assert_eq!(de_from_config, from_config);
+        // Just assert that it can be serialized, no need to do string comparison
+        serde_yaml::to_string(&from_config).unwrap();
+    }
+
+    #[test]
+    fn test_encryption_key_reading() {
+        let key_encryption_key =
+            EncryptionKeyConfig::KeyEncryptionKey(Token::FromConfig(hex::encode([1u8; 32])));
+        assert!(matches!(
+            key_encryption_key.read_key_source().unwrap(),
+            EncryptionKeySource::KeyEncryptionKey(key) if key == vec![1u8; 32]
+        ));
+
+        let invalid_key = EncryptionKeyConfig::KeyEncryptionKey(Token::FromConfig("xyz".into()));
+        assert!(invalid_key.read_key_source().is_err());
+    }
+
     #[test]
     fn test_token_reading() {
         let temppath = aptos_temppath::TempPath::new();
@@ -44,6 +44,9 @@ pub enum BootstrappingMode {
     ExecuteTransactionsFromGenesis,
     /// Executes transactions or applies outputs from genesis (whichever is faster)
     ExecuteOrApplyFromGenesis,
+    /// Restores from a local backup archive (see `BackupArchiveConfig`)
+    /// and then applies transaction outputs (starting at the restored version)
+    RestoreFromBackup,
 }
 
 impl BootstrappingMode {
@@ -57,6 +60,7 @@ impl BootstrappingMode {
                 "execute_transactions_from_genesis"
             },
             BootstrappingMode::ExecuteOrApplyFromGenesis => "execute_or_apply_from_genesis",
+            BootstrappingMode::RestoreFromBackup => "restore_from_backup",
         }
     }
 
@@ -483,6 +487,18 @@ impl ConfigSanitizer for StateSyncDriverConfig {
             ));
         }
 
+        // Verify that a backup archive is specified for
+        // nodes that are restoring from a backup.
+        if state_sync_driver_config.bootstrapping_mode == BootstrappingMode::RestoreFromBackup
+            && node_config.storage.backup_archive.backup_dir.is_none()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The backup archive directory must be specified when restoring from a backup!"
+                    .to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -788,6 +804,32 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_restore_from_backup() {
+        // Create a node config that restores from a backup (without a backup directory)
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    bootstrapping_mode: BootstrappingMode::RestoreFromBackup,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Set the backup directory and verify that sanitization passes
+        node_config.storage.backup_archive.backup_dir = Some("/opt/aptos/backup".into());
+        StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+            .unwrap();
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
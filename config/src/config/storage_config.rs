@@ -8,7 +8,7 @@ use crate::{
 };
 use anyhow::{bail, ensure, Result};
 use aptos_logger::warn;
-use aptos_types::chain_id::ChainId;
+use aptos_types::{chain_id::ChainId, waypoint::Waypoint};
 use arr_macro::arr;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -197,6 +197,73 @@ pub struct StorageConfig {
     /// If not specificed, will use `dir` as default.
     /// Only allowed when sharding is enabled.
     pub db_path_overrides: Option<DbPathConfig>,
+    /// The local backup archive to restore from when bootstrapping
+    /// with `BootstrappingMode::RestoreFromBackup`.
+    pub backup_archive: BackupArchiveConfig,
+    /// Periodic checkpoints of the DB taken while the node is running.
+    pub rolling_checkpoint: RollingCheckpointConfig,
+}
+
+/// Configuration for restoring the database from a local (or mounted)
+/// backup archive, e.g., one created by the backup-cli using local storage.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupArchiveConfig {
+    /// The directory of the backup archive (containing the backup metadata)
+    pub backup_dir: Option<PathBuf>,
+    /// The directory to cache the backup metadata. If not specified,
+    /// a temporary directory is used.
+    pub metadata_cache_dir: Option<PathBuf>,
+    /// Number of concurrent backup file downloads (i.e., reads)
+    pub concurrent_downloads: usize,
+    /// Number of concurrent transaction chunks to replay
+    pub replay_concurrency_level: usize,
+    /// Additional waypoints to trust when verifying the epoch endings in the
+    /// backup archive (on top of the node waypoint), e.g., for epochs where
+    /// the validator set changed too much to be verified from the previous epoch.
+    pub trusted_waypoints: Vec<Waypoint>,
+}
+
+impl Default for BackupArchiveConfig {
+    fn default() -> Self {
+        Self {
+            backup_dir: None,
+            metadata_cache_dir: None,
+            concurrent_downloads: 8,
+            replay_concurrency_level: 4,
+            trusted_waypoints: vec![],
+        }
+    }
+}
+
+/// Configuration for taking hard-linked checkpoints of the ledger db, the state kv db and the
+/// state merkle db periodically, while the node keeps running. Each checkpoint is a complete DB
+/// at a committed version and can be opened like any other DB.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RollingCheckpointConfig {
+    /// Whether to take checkpoints, both periodically and on demand via the admin service. When
+    /// this is off, the admin service doesn't serve the checkpoints endpoint.
+    pub enable: bool,
+    /// The directory to put the checkpoints in. If not specified, `checkpoints` under the storage
+    /// dir is used. Must be on the same file system as the DB for the checkpoints to be
+    /// hard-linked instead of copied.
+    pub checkpoint_dir: Option<PathBuf>,
+    /// The interval between two periodic checkpoints.
+    pub interval_secs: u64,
+    /// The number of most recent checkpoints to keep, older ones are deleted.
+    pub num_checkpoints_to_keep: usize,
+}
+
+impl Default for RollingCheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            checkpoint_dir: None,
+            interval_secs: 3600,
+            num_checkpoints_to_keep: 3,
+        }
+    }
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -346,6 +413,8 @@ impl Default for StorageConfig {
             db_path_overrides: None,
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            backup_archive: BackupArchiveConfig::default(),
+            rolling_checkpoint: RollingCheckpointConfig::default(),
         }
     }
 }
@@ -359,6 +428,13 @@ impl StorageConfig {
         }
     }
 
+    pub fn rolling_checkpoint_dir(&self) -> PathBuf {
+        match self.rolling_checkpoint.checkpoint_dir.as_ref() {
+            Some(checkpoint_dir) => checkpoint_dir.clone(),
+            None => self.dir().join("checkpoints"),
+        }
+    }
+
     pub fn get_dir_paths(&self) -> StorageDirPaths {
         let default_dir = self.dir();
         let mut ledger_db_path = None;
@@ -541,6 +617,22 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        let rolling_checkpoint = &config.rolling_checkpoint;
+        if rolling_checkpoint.enable {
+            if rolling_checkpoint.interval_secs == 0 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "rolling_checkpoint.interval_secs must be positive.".to_string(),
+                ));
+            }
+            if rolling_checkpoint.num_checkpoints_to_keep == 0 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "rolling_checkpoint.num_checkpoints_to_keep must be positive.".to_string(),
+                ));
+            }
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
             if !config.rocksdb_configs.enable_storage_sharding {
                 return Err(Error::ConfigSanitizerFailed(
@@ -598,7 +690,37 @@ impl ConfigSanitizer for StorageConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::config::{PrunerConfig, ShardPathConfig, ShardedDbPathConfig};
+    use crate::config::{
+        config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+        PrunerConfig, RollingCheckpointConfig, ShardPathConfig, ShardedDbPathConfig, StorageConfig,
+    };
+
+    #[test]
+    pub fn test_sanitize_rolling_checkpoint_config() {
+        // Create a node config that keeps no rolling checkpoints
+        let mut node_config = NodeConfig::default();
+        node_config.storage.rolling_checkpoint = RollingCheckpointConfig {
+            enable: true,
+            num_checkpoints_to_keep: 0,
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error = StorageConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that sanitization succeeds if rolling checkpoints are disabled
+        node_config.storage.rolling_checkpoint.enable = false;
+        StorageConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+
+        // Verify that sanitization succeeds if at least one checkpoint is kept
+        node_config.storage.rolling_checkpoint = RollingCheckpointConfig {
+            enable: true,
+            num_checkpoints_to_keep: 1,
+            ..Default::default()
+        };
+        StorageConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+    }
 
     #[test]
     pub fn test_default_prune_window() {
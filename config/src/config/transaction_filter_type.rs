@@ -1,12 +1,83 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::config::NodeConfig;
+use anyhow::{anyhow, ensure, Result};
 use aptos_crypto::HashValue;
+use aptos_infallible::RwLock;
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{SignedTransaction, TransactionPayload},
+    transaction::{authenticator::AccountAuthenticator, SignedTransaction, TransactionPayload},
 };
 use serde::{Deserialize, Serialize};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+const CODE_MODULE_NAME: &str = "code";
+const PUBLISH_PACKAGE_FUNCTION_NAME: &str = "publish_package_txn";
+
+/// The type of payload carried by a transaction
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PayloadType {
+    Script,
+    EntryFunction,
+    Multisig,
+    /// Entry function calls to `0x1::code::publish_package_txn` (and deprecated module bundles)
+    ModulePublish,
+}
+
+impl PayloadType {
+    fn matches(&self, payload: &TransactionPayload) -> bool {
+        match (self, payload) {
+            (PayloadType::Script, TransactionPayload::Script(_)) => true,
+            (PayloadType::EntryFunction, TransactionPayload::EntryFunction(_)) => true,
+            (PayloadType::Multisig, TransactionPayload::Multisig(_)) => true,
+            (PayloadType::ModulePublish, TransactionPayload::ModuleBundle(_)) => true,
+            (PayloadType::ModulePublish, TransactionPayload::EntryFunction(entry_function)) => {
+                entry_function.module().address() == &AccountAddress::ONE
+                    && entry_function.module().name().as_str() == CODE_MODULE_NAME
+                    && entry_function.function().as_str() == PUBLISH_PACKAGE_FUNCTION_NAME
+            },
+            _ => false,
+        }
+    }
+}
+
+/// The authentication scheme used by the sender of a transaction
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuthenticatorScheme {
+    Ed25519,
+    MultiEd25519,
+    SingleKey,
+    MultiKey,
+    NoAccountAuthenticator,
+}
+
+impl AuthenticatorScheme {
+    fn matches(&self, authenticator: &AccountAuthenticator) -> bool {
+        matches!(
+            (self, authenticator),
+            (
+                AuthenticatorScheme::Ed25519,
+                AccountAuthenticator::Ed25519 { .. }
+            ) | (
+                AuthenticatorScheme::MultiEd25519,
+                AccountAuthenticator::MultiEd25519 { .. }
+            ) | (
+                AuthenticatorScheme::SingleKey,
+                AccountAuthenticator::SingleKey { .. }
+            ) | (
+                AuthenticatorScheme::MultiKey,
+                AccountAuthenticator::MultiKey { .. }
+            ) | (
+                AuthenticatorScheme::NoAccountAuthenticator,
+                AccountAuthenticator::NoAccountAuthenticator
+            )
+        )
+    }
+}
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Matcher {
@@ -18,6 +89,18 @@ pub enum Matcher {
     Sender(AccountAddress),
     ModuleAddress(AccountAddress),
     EntryFunction(AccountAddress, String, String),
+    PayloadType(PayloadType),
+    GasUnitPriceGreaterThan(u64),
+    GasUnitPriceLessThan(u64),
+    MaxGasAmountGreaterThan(u64),
+    MaxGasAmountLessThan(u64),
+    AuthenticatorScheme(AuthenticatorScheme),
+    /// Matches if all of the inner matchers match (an empty list always matches)
+    And(Vec<Matcher>),
+    /// Matches if any of the inner matchers match (an empty list never matches)
+    Or(Vec<Matcher>),
+    /// Matches if the inner matcher does not match
+    Not(Box<Matcher>),
 }
 
 impl Matcher {
@@ -43,6 +126,33 @@ impl Matcher {
                 },
                 _ => false,
             },
+            Matcher::PayloadType(payload_type) => payload_type.matches(txn.payload()),
+            Matcher::GasUnitPriceGreaterThan(price) => txn.gas_unit_price() > *price,
+            Matcher::GasUnitPriceLessThan(price) => txn.gas_unit_price() < *price,
+            Matcher::MaxGasAmountGreaterThan(amount) => txn.max_gas_amount() > *amount,
+            Matcher::MaxGasAmountLessThan(amount) => txn.max_gas_amount() < *amount,
+            Matcher::AuthenticatorScheme(scheme) => {
+                scheme.matches(&txn.authenticator_ref().sender())
+            },
+            Matcher::And(matchers) => matchers
+                .iter()
+                .all(|matcher| matcher.matches(block_id, timestamp, txn)),
+            Matcher::Or(matchers) => matchers
+                .iter()
+                .any(|matcher| matcher.matches(block_id, timestamp, txn)),
+            Matcher::Not(matcher) => !matcher.matches(block_id, timestamp, txn),
+        }
+    }
+
+    /// Returns true iff this matcher (or any nested matcher) depends on the block ID
+    pub fn contains_block_id_matcher(&self) -> bool {
+        match self {
+            Matcher::BlockId(_) => true,
+            Matcher::And(matchers) | Matcher::Or(matchers) => matchers
+                .iter()
+                .any(|matcher| matcher.contains_block_id_matcher()),
+            Matcher::Not(matcher) => matcher.contains_block_id_matcher(),
+            _ => false,
         }
     }
 }
@@ -113,6 +223,18 @@ impl Rule {
 /// This filter allows transactions from the sender with address f8871acf2c827d40e23b71f6ff2b9accef8dbb17709b88bd9eb95e6bb748c25a or
 /// from the module with address 0000000000000000000000000000000000000000000000000000000000000001 or entry functions
 /// test::check and test::new from the module 0000000000000000000000000000000000000000000000000000000000000001. All other transactions are denied.
+///
+/// Matchers can also be composed using `And`, `Or` and `Not`. For example:
+///             rules:
+///                 - Deny:
+///                     And:
+///                         - PayloadType: Script
+///                         - GasUnitPriceLessThan: 150
+///                 - Deny:
+///                     Not:
+///                         AuthenticatorScheme: Ed25519
+/// This filter denies all script transactions with a gas unit price below 150, and all
+/// transactions whose sender does not authenticate with an Ed25519 key.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Filter {
     rules: Vec<Rule>,
@@ -127,6 +249,16 @@ impl Filter {
         self.rules.is_empty()
     }
 
+    pub fn add_allow(mut self, matcher: Matcher) -> Self {
+        self.rules.push(Rule::Allow(matcher));
+        self
+    }
+
+    pub fn add_deny(mut self, matcher: Matcher) -> Self {
+        self.rules.push(Rule::Deny(matcher));
+        self
+    }
+
     pub fn add_deny_all(mut self) -> Self {
         self.rules.push(Rule::Deny(Matcher::All));
         self
@@ -201,16 +333,204 @@ impl Filter {
         &self.rules
     }
 
+    /// Returns true iff any of the rules depend on the block ID
+    pub fn contains_block_id_matcher(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.matcher().contains_block_id_matcher())
+    }
+
     pub fn allows(&self, block_id: HashValue, timestamp: u64, txn: &SignedTransaction) -> bool {
-        for rule in &self.rules {
+        self.first_matching_rule(block_id, timestamp, txn)
+            .map_or(true, |(_, allowed)| allowed)
+    }
+
+    /// Returns the index of the first rule that matches the transaction, and whether the rule
+    /// allows it. Returns None if no rule matches.
+    fn first_matching_rule(
+        &self,
+        block_id: HashValue,
+        timestamp: u64,
+        txn: &SignedTransaction,
+    ) -> Option<(usize, bool)> {
+        for (index, rule) in self.rules.iter().enumerate() {
             // Rules are evaluated in the order and the first rule that matches is used. If no rule
             // matches, the transaction is allowed.
             match rule.eval(block_id, timestamp, txn) {
-                EvalResult::Allow => return true,
-                EvalResult::Deny => return false,
+                EvalResult::Allow => return Some((index, true)),
+                EvalResult::Deny => return Some((index, false)),
                 EvalResult::NoMatch => continue,
             }
         }
-        true
+        None
+    }
+}
+
+/// A rule of a `LiveFilter`, with its ID and the number of transactions it allowed or denied.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RuleWithHits {
+    pub id: u64,
+    pub rule: Rule,
+    pub hits: u64,
+}
+
+#[derive(Debug)]
+struct RuleState {
+    id: u64,
+    // The number of transactions decided by the rule
+    hits: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct LiveFilterState {
+    filter: Filter,
+    // The state of each rule, in the same order
+    rules: Vec<RuleState>,
+    next_rule_id: u64,
+}
+
+impl LiveFilterState {
+    fn new_rule_state(&mut self) -> RuleState {
+        let id = self.next_rule_id;
+        self.next_rule_id += 1;
+        RuleState {
+            id,
+            hits: AtomicU64::new(0),
+        }
+    }
+}
+
+/// A `Filter` that can be updated while the node is running (e.g., through the admin service),
+/// shared by the component applying it and the one updating it. Each rule gets an ID that stays
+/// the same while other rules are inserted or removed. The number of transactions decided by
+/// each rule are counted, and reset when the rule is removed.
+#[derive(Debug, Default)]
+pub struct LiveFilter {
+    state: RwLock<LiveFilterState>,
+}
+
+impl LiveFilter {
+    pub fn new(filter: Filter) -> Self {
+        let mut state = LiveFilterState::default();
+        for _ in &filter.rules {
+            let rule_state = state.new_rule_state();
+            state.rules.push(rule_state);
+        }
+        state.filter = filter;
+        Self {
+            state: RwLock::new(state),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.read().filter.is_empty()
+    }
+
+    /// Returns a copy of the current filter
+    pub fn filter(&self) -> Filter {
+        self.state.read().filter.clone()
+    }
+
+    pub fn allows(&self, block_id: HashValue, timestamp: u64, txn: &SignedTransaction) -> bool {
+        let state = self.state.read();
+        match state.filter.first_matching_rule(block_id, timestamp, txn) {
+            Some((index, allowed)) => {
+                state.rules[index].hits.fetch_add(1, Ordering::Relaxed);
+                allowed
+            },
+            None => true,
+        }
+    }
+
+    /// Returns the rules, in the order they are evaluated, with their hit counts
+    pub fn rules_with_hits(&self) -> Vec<RuleWithHits> {
+        let state = self.state.read();
+        state
+            .filter
+            .rules
+            .iter()
+            .zip(state.rules.iter())
+            .map(|(rule, rule_state)| RuleWithHits {
+                id: rule_state.id,
+                rule: rule.clone(),
+                hits: rule_state.hits.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Inserts the rule at the given index, or after all the other rules if no index is given.
+    /// Returns the ID of the rule.
+    pub fn insert_rule(&self, index: Option<usize>, rule: Rule) -> Result<u64> {
+        let mut state = self.state.write();
+        let num_rules = state.filter.rules.len();
+        let index = index.unwrap_or(num_rules);
+        ensure!(
+            index <= num_rules,
+            "Rule index {} is out of bounds, there are {} rules!",
+            index,
+            num_rules
+        );
+        let rule_state = state.new_rule_state();
+        let id = rule_state.id;
+        state.filter.rules.insert(index, rule);
+        state.rules.insert(index, rule_state);
+        Ok(id)
+    }
+
+    /// Removes the rule with the given ID, and returns it
+    pub fn remove_rule(&self, id: u64) -> Result<Rule> {
+        let mut state = self.state.write();
+        let index = state
+            .rules
+            .iter()
+            .position(|rule_state| rule_state.id == id)
+            .ok_or_else(|| anyhow!("There is no rule with ID {}!", id))?;
+        state.rules.remove(index);
+        Ok(state.filter.rules.remove(index))
+    }
+
+    /// Removes the first rule equal to the given one, and returns its ID
+    pub fn remove_matching_rule(&self, rule: &Rule) -> Result<u64> {
+        let mut state = self.state.write();
+        let index = state
+            .filter
+            .rules
+            .iter()
+            .position(|r| r == rule)
+            .ok_or_else(|| anyhow!("There is no rule {:?}!", rule))?;
+        state.filter.rules.remove(index);
+        Ok(state.rules.remove(index).id)
+    }
+}
+
+/// The transaction filters of a node that can be updated while it's running, initialized from
+/// the node config.
+#[derive(Clone, Debug)]
+pub struct LiveTransactionFilters {
+    /// Applied to the transactions of each block before execution
+    pub consensus: Arc<LiveFilter>,
+    /// Applied to the batches received from other validators
+    pub quorum_store: Arc<LiveFilter>,
+    /// Applied on mempool admission
+    pub mempool: Arc<LiveFilter>,
+}
+
+impl LiveTransactionFilters {
+    pub fn new(node_config: &NodeConfig) -> Self {
+        Self {
+            consensus: Arc::new(LiveFilter::new(
+                node_config.execution.transaction_filter.clone(),
+            )),
+            quorum_store: Arc::new(LiveFilter::new(
+                node_config
+                    .consensus
+                    .quorum_store
+                    .transaction_filter
+                    .clone(),
+            )),
+            mempool: Arc::new(LiveFilter::new(
+                node_config.mempool.transaction_filter.clone(),
+            )),
+        }
     }
 }
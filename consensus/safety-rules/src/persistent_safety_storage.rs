@@ -8,11 +8,12 @@ use crate::{
     Error,
 };
 use aptos_consensus_types::{common::Author, safety_data::SafetyData};
-use aptos_crypto::{bls12381, PrivateKey};
+use aptos_crypto::{bls12381, hash::CryptoHash, PrivateKey, SigningKey};
 use aptos_global_constants::{CONSENSUS_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT};
 use aptos_logger::prelude::*;
 use aptos_secure_storage::{KVStorage, Storage};
 use aptos_types::waypoint::Waypoint;
+use serde::Serialize;
 
 /// SafetyRules needs an abstract storage interface to act as a common utility for storing
 /// persistent data to local disk, cloud, secrets managers, or even memory (for tests)
@@ -22,9 +23,15 @@ use aptos_types::waypoint::Waypoint;
 /// only ever be used by safety rules, we maintain an in-memory copy to avoid issuing reads
 /// to the internal storage if the SafetyData hasn't changed. On writes, we update the
 /// cache and internal storage.
+///
+/// Similarly, cached_consensus_key holds the consensus key resolved for the current epoch
+/// (see `ensure_consensus_key_by_pk`), so that signing doesn't read from the internal storage.
+/// The cache is replaced whenever a (new) key is resolved, and cleared whenever the internal
+/// storage is modified directly (e.g., when consensus keys are rotated).
 pub struct PersistentSafetyStorage {
     enable_cached_safety_data: bool,
     cached_safety_data: Option<SafetyData>,
+    cached_consensus_key: Option<(bls12381::PublicKey, bls12381::PrivateKey)>,
     internal_store: Storage,
 }
 
@@ -47,6 +54,7 @@ impl PersistentSafetyStorage {
         let mut persisent_safety_storage = Self {
             enable_cached_safety_data,
             cached_safety_data: Some(safety_data.clone()),
+            cached_consensus_key: None,
             internal_store,
         };
 
@@ -87,6 +95,7 @@ impl PersistentSafetyStorage {
         Self {
             enable_cached_safety_data,
             cached_safety_data: None,
+            cached_consensus_key: None,
             internal_store,
         }
     }
@@ -96,10 +105,49 @@ impl PersistentSafetyStorage {
         Ok(self.internal_store.get(OWNER_ACCOUNT).map(|v| v.value)?)
     }
 
-    pub fn consensus_sk_by_pk(
+    /// Ensures that the consensus key matching the given public key is in storage, and
+    /// caches the key for signing (e.g., for the rest of the epoch).
+    pub fn ensure_consensus_key_by_pk(&mut self, pk: &bls12381::PublicKey) -> Result<(), Error> {
+        self.cached_consensus_key = None;
+        let consensus_key = self.consensus_sk_by_pk(pk.clone())?;
+        self.cached_consensus_key = Some((pk.clone(), consensus_key));
+        Ok(())
+    }
+
+    /// Signs the message with the consensus key matching the given public key. The private
+    /// key is never handed to the caller. If the key hasn't been cached (see
+    /// `ensure_consensus_key_by_pk`), it is read from the internal store.
+    pub fn consensus_sign_by_pk<T: CryptoHash + Serialize>(
+        &self,
+        pk: &bls12381::PublicKey,
+        message: &T,
+    ) -> Result<bls12381::Signature, Error> {
+        let signature = match &self.cached_consensus_key {
+            Some((cached_pk, consensus_key)) if cached_pk == pk => {
+                let _timer = counters::start_timer("sign", CONSENSUS_KEY);
+                consensus_key.sign(message)
+            },
+            _ => {
+                let consensus_key = self.consensus_sk_by_pk(pk.clone())?;
+                let _timer = counters::start_timer("sign", CONSENSUS_KEY);
+                consensus_key.sign(message)
+            },
+        };
+        signature.map_err(|error| Error::SerializationError(error.to_string()))
+    }
+
+    /// Exports the consensus key matching the given public key. This is only meant for the
+    /// components that derive key material from the consensus key or hold it for the epoch
+    /// (i.e., DKG, randomness and JWK consensus). Safety rules signs with
+    /// `consensus_sign_by_pk` instead, so the key never leaves the storage.
+    pub fn export_consensus_key_by_pk(
         &self,
         pk: bls12381::PublicKey,
     ) -> Result<bls12381::PrivateKey, Error> {
+        self.consensus_sk_by_pk(pk)
+    }
+
+    fn consensus_sk_by_pk(&self, pk: bls12381::PublicKey) -> Result<bls12381::PrivateKey, Error> {
         let _timer = counters::start_timer("get", CONSENSUS_KEY);
         let pk_hex = hex::encode(pk.to_bytes());
         let explicit_storage_key = format!("{}_{}", CONSENSUS_KEY, pk_hex);
@@ -181,6 +229,8 @@ impl PersistentSafetyStorage {
     }
 
     pub fn internal_store(&mut self) -> &mut Storage {
+        // The caller may modify the consensus keys, so the cached key is no longer valid
+        self.cached_consensus_key = None;
         &mut self.internal_store
     }
 }
@@ -217,6 +267,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_consensus_key_cache() {
+        // Create the safety storage
+        let signer = ValidatorSigner::from_int(0);
+        let mut safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            Waypoint::default(),
+            true,
+        );
+        assert!(safety_storage.cached_consensus_key.is_none());
+
+        // Ensure the consensus key and verify it is cached
+        let public_key = signer.public_key();
+        safety_storage
+            .ensure_consensus_key_by_pk(&public_key)
+            .unwrap();
+        assert!(safety_storage.cached_consensus_key.is_some());
+
+        // Verify that signing uses the consensus key
+        let message = LedgerInfo::new(BlockInfo::random(1), HashValue::random());
+        let signature = safety_storage
+            .consensus_sign_by_pk(&public_key, &message)
+            .unwrap();
+        assert_eq!(signature, signer.sign(&message).unwrap());
+
+        // Rotate the consensus key and verify the cache is invalidated
+        let new_signer = ValidatorSigner::from_int(1);
+        safety_storage
+            .internal_store()
+            .set(CONSENSUS_KEY, new_signer.private_key().clone())
+            .unwrap();
+        assert!(safety_storage.cached_consensus_key.is_none());
+
+        // Verify that the old key can no longer be used to sign
+        assert!(safety_storage
+            .consensus_sign_by_pk(&public_key, &message)
+            .is_err());
+
+        // Verify that ensuring the old key fails (and nothing is cached)
+        assert!(safety_storage
+            .ensure_consensus_key_by_pk(&public_key)
+            .is_err());
+        assert!(safety_storage.cached_consensus_key.is_none());
+
+        // Ensure the new key and verify that signing uses it
+        let new_public_key = new_signer.public_key();
+        safety_storage
+            .ensure_consensus_key_by_pk(&new_public_key)
+            .unwrap();
+        let signature = safety_storage
+            .consensus_sign_by_pk(&new_public_key, &message)
+            .unwrap();
+        assert_eq!(signature, new_signer.sign(&message).unwrap());
+    }
+
     fn test_safety_data_counters(safety_storage: &mut PersistentSafetyStorage) {
         let safety_data = safety_storage.safety_data().unwrap();
         assert_eq!(safety_data.epoch, 1);
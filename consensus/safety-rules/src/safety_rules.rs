@@ -28,20 +28,37 @@ use aptos_types::{
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    validator_signer::ValidatorSigner,
     waypoint::Waypoint,
 };
 use serde::Serialize;
-use std::{cmp::Ordering, sync::Arc};
+use std::cmp::Ordering;
 
 pub(crate) fn next_round(round: Round) -> Result<Round, Error> {
     u64::checked_add(round, 1).ok_or(Error::IncorrectRound(round))
 }
 
+/// The validator identity used by safety rules to sign. The consensus private key is kept in
+/// the persistent storage, which signs on behalf of safety rules.
+#[derive(Clone, Debug)]
+pub(crate) struct ConsensusSigner {
+    author: Author,
+    public_key: bls12381::PublicKey,
+}
+
+impl ConsensusSigner {
+    pub(crate) fn author(&self) -> Author {
+        self.author
+    }
+
+    pub(crate) fn public_key(&self) -> bls12381::PublicKey {
+        self.public_key.clone()
+    }
+}
+
 /// @TODO consider a cache of verified QCs to cut down on verification costs
 pub struct SafetyRules {
     pub(crate) persistent_storage: PersistentSafetyStorage,
-    pub(crate) validator_signer: Option<ValidatorSigner>,
+    pub(crate) validator_signer: Option<ConsensusSigner>,
     pub(crate) epoch_state: Option<EpochState>,
 }
 
@@ -110,12 +127,11 @@ impl SafetyRules {
         message: &T,
     ) -> Result<bls12381::Signature, Error> {
         let signer = self.signer()?;
-        signer
-            .sign(message)
-            .map_err(|err| Error::SerializationError(err.to_string()))
+        self.persistent_storage
+            .consensus_sign_by_pk(&signer.public_key, message)
     }
 
-    pub(crate) fn signer(&self) -> Result<&ValidatorSigner, Error> {
+    pub(crate) fn signer(&self) -> Result<&ConsensusSigner, Error> {
         self.validator_signer
             .as_ref()
             .ok_or_else(|| Error::NotInitialized("validator_signer".into()))
@@ -315,11 +331,17 @@ impl SafetyRules {
                     );
                     Ok(())
                 } else {
-                    // Try to export the consensus key directly from storage.
-                    match self.persistent_storage.consensus_sk_by_pk(expected_key) {
-                        Ok(consensus_key) => {
-                            self.validator_signer =
-                                Some(ValidatorSigner::new(author, Arc::new(consensus_key)));
+                    // Ensure the consensus key is in storage (it never leaves the storage,
+                    // which signs on behalf of safety rules).
+                    match self
+                        .persistent_storage
+                        .ensure_consensus_key_by_pk(&expected_key)
+                    {
+                        Ok(_) => {
+                            self.validator_signer = Some(ConsensusSigner {
+                                author,
+                                public_key: expected_key,
+                            });
                             Ok(())
                         },
                         Err(Error::SecureStorageMissingDataError(error)) => {
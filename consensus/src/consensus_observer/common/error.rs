@@ -18,6 +18,9 @@ pub enum Error {
     #[error("Subscription disconnected: {0}")]
     SubscriptionDisconnected(String),
 
+    #[error("Subscription low score: {0}")]
+    SubscriptionLowScore(String),
+
     #[error("Subscription progress stopped: {0}")]
     SubscriptionProgressStopped(String),
 
@@ -42,6 +45,7 @@ impl Error {
             Self::NetworkError(_) => "network_error",
             Self::RpcError(_) => "rpc_error",
             Self::SubscriptionDisconnected(_) => "subscription_disconnected",
+            Self::SubscriptionLowScore(_) => "subscription_low_score",
             Self::SubscriptionProgressStopped(_) => "subscription_progress_stopped",
             Self::SubscriptionsReset(_) => "subscriptions_reset",
             Self::SubscriptionSuboptimal(_) => "subscription_suboptimal",
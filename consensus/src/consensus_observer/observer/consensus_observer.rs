@@ -18,7 +18,7 @@ use crate::{
         observer::{
             active_state::ActiveObserverState, ordered_blocks::OrderedBlockStore,
             payload_store::BlockPayloadStore, pending_blocks::PendingBlockStore,
-            subscription_manager::SubscriptionManager,
+            publisher_scores::ErrorType, subscription_manager::SubscriptionManager,
         },
         publisher::consensus_publisher::ConsensusPublisher,
     },
@@ -316,7 +316,7 @@ impl ConsensusObserver {
 
         // Process the ready ordered block (if it exists)
         if let Some(ready_ordered_block) = ready_ordered_block {
-            self.process_ordered_block(ready_ordered_block).await;
+            self.process_ordered_block(ready_ordered_block, None).await;
         }
     }
 
@@ -343,6 +343,12 @@ impl ConsensusObserver {
         if payload_out_of_date || payload_exists {
             // Update the metrics for the dropped block payload
             update_metrics_for_dropped_block_payload_message(peer_network_id, &block_payload);
+
+            // Penalize the publisher if it is lagging behind
+            self.subscription_manager.record_duplicate_message_receipt(
+                peer_network_id,
+                (block_epoch, block_round, metrics::BLOCK_PAYLOAD_LABEL),
+            );
             return;
         }
 
@@ -358,6 +364,8 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .update_publisher_score_error(peer_network_id, ErrorType::Malicious);
             return;
         }
 
@@ -372,9 +380,19 @@ impl ConsensusObserver {
                         block_payload.block(), error
                     ))
                 );
+                self.subscription_manager
+                    .update_publisher_score_error(peer_network_id, ErrorType::Malicious);
                 return;
             }
 
+            // Record the message receipt (to score the publisher on latency)
+            self.record_message_receipt(
+                peer_network_id,
+                block_epoch,
+                block_round,
+                metrics::BLOCK_PAYLOAD_LABEL,
+            );
+
             true // We have successfully verified the signatures
         } else {
             false // We can't verify the signatures yet
@@ -408,6 +426,12 @@ impl ConsensusObserver {
         if (commit_epoch, commit_round) <= self.get_highest_committed_epoch_round() {
             // Update the metrics for the dropped commit decision
             update_metrics_for_dropped_commit_decision_message(peer_network_id, &commit_decision);
+
+            // Penalize the publisher if it is lagging behind
+            self.subscription_manager.record_duplicate_message_receipt(
+                peer_network_id,
+                (commit_epoch, commit_round, metrics::COMMIT_DECISION_LABEL),
+            );
             return;
         }
 
@@ -426,9 +450,19 @@ impl ConsensusObserver {
                         error
                     ))
                 );
+                self.subscription_manager
+                    .update_publisher_score_error(peer_network_id, ErrorType::Malicious);
                 return;
             }
 
+            // Record the message receipt (to score the publisher on latency)
+            self.record_message_receipt(
+                peer_network_id,
+                commit_epoch,
+                commit_round,
+                metrics::COMMIT_DECISION_LABEL,
+            );
+
             // Update the pending blocks with the commit decision
             if self.process_commit_decision_for_pending_block(&commit_decision) {
                 return; // The commit decision was successfully processed
@@ -596,6 +630,8 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .update_publisher_score_error(peer_network_id, ErrorType::Malicious);
             return;
         };
 
@@ -616,6 +652,17 @@ impl ConsensusObserver {
         if block_out_of_date || block_pending {
             // Update the metrics for the dropped ordered block
             update_metrics_for_dropped_ordered_block_message(peer_network_id, &ordered_block);
+
+            // Penalize the publisher if it is lagging behind
+            let proof_block_info = ordered_block.proof_block_info();
+            self.subscription_manager.record_duplicate_message_receipt(
+                peer_network_id,
+                (
+                    proof_block_info.epoch(),
+                    proof_block_info.round(),
+                    metrics::ORDERED_BLOCK_LABEL,
+                ),
+            );
             return;
         }
 
@@ -625,7 +672,8 @@ impl ConsensusObserver {
         // If all payloads exist, process the block. Otherwise, store it
         // in the pending block store and wait for the payloads to arrive.
         if self.all_payloads_exist(ordered_block.blocks()) {
-            self.process_ordered_block(ordered_block).await;
+            self.process_ordered_block(ordered_block, Some(peer_network_id))
+                .await;
         } else {
             self.pending_block_store
                 .lock()
@@ -634,8 +682,14 @@ impl ConsensusObserver {
     }
 
     /// Processes the ordered block. This assumes the ordered block
-    /// has been sanity checked and that all payloads exist.
-    async fn process_ordered_block(&mut self, ordered_block: OrderedBlock) {
+    /// has been sanity checked and that all payloads exist. If the
+    /// block was just received from a publisher, the publisher is
+    /// scored once the ordered proof has been verified.
+    async fn process_ordered_block(
+        &mut self,
+        ordered_block: OrderedBlock,
+        peer_network_id: Option<PeerNetworkId>,
+    ) {
         // Verify the ordered block proof
         let epoch_state = self.get_epoch_state();
         if ordered_block.proof_block_info().epoch() == epoch_state.epoch {
@@ -649,6 +703,17 @@ impl ConsensusObserver {
                 );
                 return;
             }
+
+            // Record the message receipt (to score the publisher on latency)
+            if let Some(peer_network_id) = peer_network_id {
+                let proof_block_info = ordered_block.proof_block_info();
+                self.record_message_receipt(
+                    peer_network_id,
+                    proof_block_info.epoch(),
+                    proof_block_info.round(),
+                    metrics::ORDERED_BLOCK_LABEL,
+                );
+            }
         } else {
             // Drop the block and log an error (the block should always be for the current epoch)
             error!(
@@ -698,6 +763,34 @@ impl ConsensusObserver {
         }
     }
 
+    /// Records the receipt of the given (verified) message from the publisher.
+    /// Messages outside the current epoch, or too far ahead of the last
+    /// ordered block, are ignored by the publisher scores.
+    fn record_message_receipt(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message_epoch: u64,
+        message_round: Round,
+        message_label: &'static str,
+    ) {
+        // Get the last ordered round in the current epoch
+        let current_epoch = self.get_epoch_state().epoch;
+        let last_ordered_block = self.get_last_ordered_block();
+        let last_ordered_round = if last_ordered_block.epoch() == current_epoch {
+            last_ordered_block.round()
+        } else {
+            0 // The epoch has just started
+        };
+
+        // Record the message receipt
+        self.subscription_manager.record_message_receipt(
+            peer_network_id,
+            (message_epoch, message_round, message_label),
+            current_epoch,
+            last_ordered_round,
+        );
+    }
+
     /// Processes the sync complete notification for the given epoch and round
     async fn process_sync_notification(&mut self, epoch: u64, round: Round) {
         // Log the sync notification
@@ -6,6 +6,7 @@ pub mod consensus_observer;
 pub mod ordered_blocks;
 pub mod payload_store;
 pub mod pending_blocks;
+pub mod publisher_scores;
 pub mod subscription;
 pub mod subscription_manager;
 pub mod subscription_utils;
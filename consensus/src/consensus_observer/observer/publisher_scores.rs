@@ -0,0 +1,437 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::common::logging::{LogEntry, LogSchema};
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_consensus_types::common::Round;
+use aptos_logger::{info, warn};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// Scores for publisher rankings based on behavior (these
+/// mirror the peer scores used by the Aptos data client).
+const MAX_SCORE: f64 = 100.0;
+const MIN_SCORE: f64 = 0.0;
+const STARTING_SCORE: f64 = 50.0;
+/// Add this score when a publisher is the first to deliver a message.
+const FIRST_DELIVERY_DELTA: f64 = 1.0;
+/// Not necessarily a malicious publisher, but not super useful (e.g., it lags behind).
+const NOT_USEFUL_MULTIPLIER: f64 = 0.95;
+/// Likely to be a malicious publisher (e.g., the message failed verification).
+const MALICIOUS_MULTIPLIER: f64 = 0.8;
+/// Ignore a publisher when their score dips below this threshold.
+const IGNORE_PUBLISHER_THRESHOLD: f64 = 25.0;
+
+/// The types of errors that reduce the score of a publisher
+pub enum ErrorType {
+    /// An error that's not actively malicious but also doesn't help us make
+    /// progress, e.g., lagging behind the other publishers, timeouts, etc.
+    NotUseful,
+    /// An error that appears to be actively hindering progress or
+    /// attempting to deceive us, e.g., messages that fail verification.
+    Malicious,
+}
+
+/// A simple struct to score the publishers we've subscribed to, based on
+/// their latency (relative to the other publishers) and correctness.
+pub struct PublisherScores {
+    // The configuration of the consensus observer
+    consensus_observer_config: ConsensusObserverConfig,
+
+    // The first receipt time of each message (indexed by epoch, round and message type)
+    first_message_receipts: BTreeMap<(u64, Round, &'static str), Instant>,
+
+    // The scores of the publishers
+    publisher_scores: HashMap<PeerNetworkId, f64>,
+
+    // The time service (used to measure publisher lag)
+    time_service: TimeService,
+}
+
+impl PublisherScores {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            consensus_observer_config,
+            first_message_receipts: BTreeMap::new(),
+            publisher_scores: HashMap::new(),
+            time_service,
+        }
+    }
+
+    /// Returns the score of the given publisher
+    pub fn get_score(&self, peer_network_id: &PeerNetworkId) -> f64 {
+        *self
+            .publisher_scores
+            .get(peer_network_id)
+            .unwrap_or(&STARTING_SCORE)
+    }
+
+    /// Returns true iff the given publisher should be ignored (i.e., its score is too low)
+    pub fn is_ignored(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.get_score(peer_network_id) <= IGNORE_PUBLISHER_THRESHOLD
+    }
+
+    /// Records the receipt of the given (verified) message from the publisher. The first
+    /// publisher to deliver a message is rewarded, and publishers that deliver the same
+    /// message too long after the first one are penalized. Messages outside the current
+    /// epoch, or too far ahead of the last ordered round, are ignored.
+    pub fn record_message_receipt(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message_key: (u64, Round, &'static str),
+        current_epoch: u64,
+        last_ordered_round: Round,
+    ) {
+        // Ignore messages that are not for the current epoch
+        let (message_epoch, message_round, _) = message_key;
+        if message_epoch != current_epoch {
+            return;
+        }
+
+        // Ignore messages that are too far ahead of the last ordered round
+        let max_round_window = self.consensus_observer_config.max_num_pending_blocks;
+        if message_round > last_ordered_round.saturating_add(max_round_window) {
+            return;
+        }
+
+        // Remove the message receipts for any previous epochs
+        if let Some(((oldest_epoch, _, _), _)) = self.first_message_receipts.first_key_value() {
+            if *oldest_epoch < current_epoch {
+                self.first_message_receipts =
+                    self.first_message_receipts
+                        .split_off(&(current_epoch, 0, ""));
+            }
+        }
+
+        // If the message was already received (or is too old), check if the publisher is lagging
+        if self.penalize_lagging_publisher(peer_network_id, &message_key) {
+            return;
+        }
+
+        // Otherwise, this is the first delivery of the message
+        self.first_message_receipts
+            .insert(message_key, self.time_service.now());
+        self.update_score_success(peer_network_id);
+
+        // Garbage collect the oldest message receipts
+        let max_num_message_receipts =
+            (self.consensus_observer_config.max_num_pending_blocks as usize) * 3;
+        while self.first_message_receipts.len() > max_num_message_receipts {
+            self.first_message_receipts.pop_first();
+        }
+    }
+
+    /// Records the receipt of a duplicate message from the publisher, i.e., a message
+    /// that was dropped without verification because it was already received. The
+    /// publisher is only penalized if it is lagging behind (it is never rewarded).
+    pub fn record_duplicate_message_receipt(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message_key: (u64, Round, &'static str),
+    ) {
+        self.penalize_lagging_publisher(peer_network_id, &message_key);
+    }
+
+    /// Penalizes the publisher if the message is older than all tracked messages, or if
+    /// it was first received too long ago. Returns true iff the message was already
+    /// received (or is too old to be tracked), and false if it is a new message.
+    fn penalize_lagging_publisher(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message_key: &(u64, Round, &'static str),
+    ) -> bool {
+        // If the message is older than all tracked messages, the publisher is lagging behind
+        if let Some((oldest_message_key, _)) = self.first_message_receipts.first_key_value() {
+            if message_key < oldest_message_key {
+                self.update_score_error(peer_network_id, ErrorType::NotUseful);
+                return true;
+            }
+        }
+
+        // If the message was already received, check how far behind the publisher is
+        match self.first_message_receipts.get(message_key) {
+            Some(first_receipt_time) => {
+                let max_publisher_lag =
+                    Duration::from_millis(self.consensus_observer_config.max_publisher_lag_ms);
+                let time_now = self.time_service.now();
+                if time_now.duration_since(*first_receipt_time) > max_publisher_lag {
+                    self.update_score_error(peer_network_id, ErrorType::NotUseful);
+                }
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Removes the scores of the publishers that are no longer connected
+    pub fn remove_disconnected_publishers(&mut self, connected_peers: &[PeerNetworkId]) {
+        self.publisher_scores
+            .retain(|peer_network_id, _| connected_peers.contains(peer_network_id));
+    }
+
+    /// Updates the score of the publisher according to a successful delivery
+    pub fn update_score_success(&mut self, peer_network_id: PeerNetworkId) {
+        let old_score = self.get_score(&peer_network_id);
+        let new_score = f64::min(old_score + FIRST_DELIVERY_DELTA, MAX_SCORE);
+        self.publisher_scores.insert(peer_network_id, new_score);
+
+        // Log if the publisher is no longer ignored
+        if old_score <= IGNORE_PUBLISHER_THRESHOLD && new_score > IGNORE_PUBLISHER_THRESHOLD {
+            info!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Publisher {} is no longer ignored! Score: {:.2}",
+                    peer_network_id, new_score
+                ))
+            );
+        }
+    }
+
+    /// Updates the score of the publisher according to an error
+    pub fn update_score_error(&mut self, peer_network_id: PeerNetworkId, error: ErrorType) {
+        let multiplier = match error {
+            ErrorType::NotUseful => NOT_USEFUL_MULTIPLIER,
+            ErrorType::Malicious => MALICIOUS_MULTIPLIER,
+        };
+        let old_score = self.get_score(&peer_network_id);
+        let new_score = f64::max(old_score * multiplier, MIN_SCORE);
+        self.publisher_scores.insert(peer_network_id, new_score);
+
+        // Log if the publisher is now ignored
+        if old_score > IGNORE_PUBLISHER_THRESHOLD && new_score <= IGNORE_PUBLISHER_THRESHOLD {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Ignoring publisher {} due to a low score! Score: {:.2}",
+                    peer_network_id, new_score
+                ))
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::common::metrics::COMMIT_DECISION_LABEL;
+    use aptos_time_service::TimeService;
+
+    // Useful test constants
+    const CURRENT_EPOCH: u64 = 10;
+
+    #[test]
+    fn test_record_message_receipt() {
+        // Create the publisher scores
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let time_service = TimeService::mock();
+        let mut publisher_scores =
+            PublisherScores::new(consensus_observer_config, time_service.clone());
+
+        // Verify the publishers have the starting score
+        let fast_publisher = PeerNetworkId::random();
+        let slow_publisher = PeerNetworkId::random();
+        assert_eq!(publisher_scores.get_score(&fast_publisher), STARTING_SCORE);
+        assert_eq!(publisher_scores.get_score(&slow_publisher), STARTING_SCORE);
+
+        // Deliver a message from both publishers (within the max lag)
+        let message_key = create_message_key(CURRENT_EPOCH, 1);
+        record_message_receipt(&mut publisher_scores, fast_publisher, &message_key);
+        record_message_receipt(&mut publisher_scores, slow_publisher, &message_key);
+
+        // Verify only the first publisher was rewarded
+        assert_eq!(
+            publisher_scores.get_score(&fast_publisher),
+            STARTING_SCORE + FIRST_DELIVERY_DELTA
+        );
+        assert_eq!(publisher_scores.get_score(&slow_publisher), STARTING_SCORE);
+
+        // Deliver another message from both publishers (with the second after the max lag)
+        let message_key = create_message_key(CURRENT_EPOCH, 2);
+        record_message_receipt(&mut publisher_scores, fast_publisher, &message_key);
+        time_service.into_mock().advance(Duration::from_millis(
+            consensus_observer_config.max_publisher_lag_ms + 1,
+        ));
+        record_message_receipt(&mut publisher_scores, slow_publisher, &message_key);
+
+        // Verify the slow publisher was penalized
+        assert_eq!(
+            publisher_scores.get_score(&fast_publisher),
+            STARTING_SCORE + 2.0 * FIRST_DELIVERY_DELTA
+        );
+        assert_eq!(
+            publisher_scores.get_score(&slow_publisher),
+            STARTING_SCORE * NOT_USEFUL_MULTIPLIER
+        );
+
+        // Deliver many new messages, and verify the old receipts are garbage collected
+        let max_num_message_receipts = consensus_observer_config.max_num_pending_blocks * 3;
+        for round in 3..max_num_message_receipts + 10 {
+            let message_key = create_message_key(CURRENT_EPOCH, round);
+            record_message_receipt(&mut publisher_scores, fast_publisher, &message_key);
+        }
+        assert_eq!(
+            publisher_scores.first_message_receipts.len(),
+            max_num_message_receipts as usize
+        );
+        assert_eq!(publisher_scores.get_score(&fast_publisher), MAX_SCORE);
+
+        // Deliver a message older than all tracked messages and verify the publisher is penalized
+        let message_key = create_message_key(CURRENT_EPOCH, 1);
+        record_message_receipt(&mut publisher_scores, slow_publisher, &message_key);
+        assert_eq!(
+            publisher_scores.get_score(&slow_publisher),
+            STARTING_SCORE * NOT_USEFUL_MULTIPLIER * NOT_USEFUL_MULTIPLIER
+        );
+    }
+
+    #[test]
+    fn test_record_message_receipt_ignored() {
+        // Create the publisher scores
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let mut publisher_scores =
+            PublisherScores::new(consensus_observer_config, TimeService::mock());
+
+        // Deliver messages for previous and future epochs, and verify they are ignored
+        let publisher = PeerNetworkId::random();
+        for epoch in [CURRENT_EPOCH - 1, CURRENT_EPOCH + 1] {
+            let message_key = create_message_key(epoch, 1);
+            publisher_scores.record_message_receipt(publisher, message_key, CURRENT_EPOCH, 0);
+        }
+        assert!(publisher_scores.first_message_receipts.is_empty());
+        assert_eq!(publisher_scores.get_score(&publisher), STARTING_SCORE);
+
+        // Deliver a message too far ahead of the last ordered round, and verify it is ignored
+        let last_ordered_round = 100;
+        let max_round = last_ordered_round + consensus_observer_config.max_num_pending_blocks;
+        let message_key = create_message_key(CURRENT_EPOCH, max_round + 1);
+        publisher_scores.record_message_receipt(
+            publisher,
+            message_key,
+            CURRENT_EPOCH,
+            last_ordered_round,
+        );
+        assert!(publisher_scores.first_message_receipts.is_empty());
+        assert_eq!(publisher_scores.get_score(&publisher), STARTING_SCORE);
+
+        // Deliver a message at the edge of the round window, and verify it is recorded
+        let message_key = create_message_key(CURRENT_EPOCH, max_round);
+        publisher_scores.record_message_receipt(
+            publisher,
+            message_key,
+            CURRENT_EPOCH,
+            last_ordered_round,
+        );
+        assert_eq!(publisher_scores.first_message_receipts.len(), 1);
+        assert_eq!(
+            publisher_scores.get_score(&publisher),
+            STARTING_SCORE + FIRST_DELIVERY_DELTA
+        );
+
+        // Deliver a message for the next epoch (once it is current), and verify
+        // the receipts for the previous epoch are removed.
+        let message_key = create_message_key(CURRENT_EPOCH + 1, 1);
+        publisher_scores.record_message_receipt(publisher, message_key, CURRENT_EPOCH + 1, 0);
+        assert_eq!(publisher_scores.first_message_receipts.len(), 1);
+        assert!(publisher_scores
+            .first_message_receipts
+            .contains_key(&message_key));
+    }
+
+    #[test]
+    fn test_record_duplicate_message_receipt() {
+        // Create the publisher scores
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let time_service = TimeService::mock();
+        let mut publisher_scores =
+            PublisherScores::new(consensus_observer_config, time_service.clone());
+
+        // Deliver a duplicate of an unknown message, and verify it is neither tracked nor scored
+        let publisher = PeerNetworkId::random();
+        let message_key = create_message_key(CURRENT_EPOCH, 10);
+        publisher_scores.record_duplicate_message_receipt(publisher, message_key);
+        assert!(publisher_scores.first_message_receipts.is_empty());
+        assert_eq!(publisher_scores.get_score(&publisher), STARTING_SCORE);
+
+        // Deliver the verified message from another publisher
+        let first_publisher = PeerNetworkId::random();
+        record_message_receipt(&mut publisher_scores, first_publisher, &message_key);
+
+        // Deliver a duplicate within the max lag, and verify the publisher is not scored
+        publisher_scores.record_duplicate_message_receipt(publisher, message_key);
+        assert_eq!(publisher_scores.get_score(&publisher), STARTING_SCORE);
+
+        // Deliver a duplicate after the max lag, and verify the publisher is penalized
+        time_service.into_mock().advance(Duration::from_millis(
+            consensus_observer_config.max_publisher_lag_ms + 1,
+        ));
+        publisher_scores.record_duplicate_message_receipt(publisher, message_key);
+        assert_eq!(
+            publisher_scores.get_score(&publisher),
+            STARTING_SCORE * NOT_USEFUL_MULTIPLIER
+        );
+
+        // Deliver a duplicate older than all tracked messages, and verify the publisher is penalized
+        let message_key = create_message_key(CURRENT_EPOCH, 1);
+        publisher_scores.record_duplicate_message_receipt(publisher, message_key);
+        assert_eq!(
+            publisher_scores.get_score(&publisher),
+            STARTING_SCORE * NOT_USEFUL_MULTIPLIER * NOT_USEFUL_MULTIPLIER
+        );
+        assert_eq!(publisher_scores.first_message_receipts.len(), 1);
+    }
+
+    #[test]
+    fn test_update_scores() {
+        // Create the publisher scores
+        let mut publisher_scores =
+            PublisherScores::new(ConsensusObserverConfig::default(), TimeService::mock());
+
+        // Penalize a publisher until it is ignored
+        let publisher = PeerNetworkId::random();
+        while !publisher_scores.is_ignored(&publisher) {
+            publisher_scores.update_score_error(publisher, ErrorType::Malicious);
+        }
+        assert!(publisher_scores.get_score(&publisher) <= IGNORE_PUBLISHER_THRESHOLD);
+
+        // Reward the publisher until it is no longer ignored
+        while publisher_scores.is_ignored(&publisher) {
+            publisher_scores.update_score_success(publisher);
+        }
+        assert!(publisher_scores.get_score(&publisher) > IGNORE_PUBLISHER_THRESHOLD);
+
+        // Remove the scores of disconnected publishers and verify the score is reset
+        let other_publisher = PeerNetworkId::random();
+        publisher_scores.update_score_error(other_publisher, ErrorType::NotUseful);
+        publisher_scores.remove_disconnected_publishers(&[other_publisher]);
+        assert_eq!(publisher_scores.get_score(&publisher), STARTING_SCORE);
+        assert_eq!(
+            publisher_scores.get_score(&other_publisher),
+            STARTING_SCORE * NOT_USEFUL_MULTIPLIER
+        );
+    }
+
+    /// Creates a commit decision message key for the given epoch and round
+    fn create_message_key(epoch: u64, round: Round) -> (u64, Round, &'static str) {
+        (epoch, round, COMMIT_DECISION_LABEL)
+    }
+
+    /// Records the message receipt for the current epoch (with
+    /// the message round as the last ordered round).
+    fn record_message_receipt(
+        publisher_scores: &mut PublisherScores,
+        peer_network_id: PeerNetworkId,
+        message_key: &(u64, Round, &'static str),
+    ) {
+        let (_, message_round, _) = *message_key;
+        publisher_scores.record_message_receipt(
+            peer_network_id,
+            *message_key,
+            CURRENT_EPOCH,
+            message_round,
+        );
+    }
+}
@@ -13,10 +13,15 @@ use crate::consensus_observer::{
             ConsensusObserverMessage, ConsensusObserverRequest, ConsensusObserverResponse,
         },
     },
-    observer::{subscription::ConsensusObserverSubscription, subscription_utils},
+    observer::{
+        publisher_scores::{ErrorType, PublisherScores},
+        subscription::ConsensusObserverSubscription,
+        subscription_utils,
+    },
     publisher::consensus_publisher::ConsensusPublisher,
 };
 use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_consensus_types::common::Round;
 use aptos_infallible::Mutex;
 use aptos_logger::{info, warn};
 use aptos_network::application::{interface::NetworkClient, metadata::PeerMetadata};
@@ -48,6 +53,9 @@ pub struct SubscriptionManager {
     // A handle to storage (used to read the latest state and check progress)
     db_reader: Arc<dyn DbReader>,
 
+    // The scores of the publishers (used to select and terminate subscriptions)
+    publisher_scores: Arc<Mutex<PublisherScores>>,
+
     // The time service (used to check progress)
     time_service: TimeService,
 }
@@ -69,6 +77,10 @@ impl SubscriptionManager {
             consensus_observer_config,
             consensus_publisher,
             db_reader,
+            publisher_scores: Arc::new(Mutex::new(PublisherScores::new(
+                consensus_observer_config,
+                time_service.clone(),
+            ))),
             time_service,
         }
     }
@@ -87,31 +99,59 @@ impl SubscriptionManager {
         // Check the health of the subscription
         match active_subscription {
             Some(active_subscription) => {
-                active_subscription.check_subscription_health(connected_peers_and_metadata)
+                active_subscription.check_subscription_health(connected_peers_and_metadata)?;
             },
-            None => Err(Error::UnexpectedError(format!(
-                "The subscription to peer: {:?} is not active!",
-                peer_network_id
-            ))),
+            None => {
+                return Err(Error::UnexpectedError(format!(
+                    "The subscription to peer: {:?} is not active!",
+                    peer_network_id
+                )))
+            },
+        }
+
+        // Verify the publisher score is not too low. Note: we only terminate
+        // low score subscriptions if there are other active subscriptions.
+        if active_observer_subscriptions.len() > 1
+            && self.publisher_scores.lock().is_ignored(&peer_network_id)
+        {
+            return Err(Error::SubscriptionLowScore(format!(
+                "The score of peer: {:?} is too low! Score: {:.2}",
+                peer_network_id,
+                self.publisher_scores.lock().get_score(&peer_network_id)
+            )));
         }
+
+        Ok(())
     }
 
     /// Checks the health of the active subscriptions. If any subscription is
     /// unhealthy, it will be terminated and new subscriptions will be created.
-    /// This returns an error iff all subscriptions were unhealthy and terminated.
+    /// This returns an error iff all subscriptions were unhealthy and terminated,
+    /// and at least one of them was terminated because syncing progress stopped.
+    /// Otherwise, we can simply switch to new publishers (all received blocks
+    /// are verified independently of the publisher that sent them).
     pub async fn check_and_manage_subscriptions(&mut self) -> Result<(), Error> {
         // Get the subscription and connected peers
         let initial_subscription_peers = self.get_active_subscription_peers();
         let connected_peers_and_metadata = self.get_connected_peers_and_metadata();
 
+        // Remove the scores of any disconnected publishers
+        let connected_peers: Vec<_> = connected_peers_and_metadata.keys().cloned().collect();
+        self.publisher_scores
+            .lock()
+            .remove_disconnected_publishers(&connected_peers);
+
         // Terminate any unhealthy subscriptions
         let terminated_subscriptions =
             self.terminate_unhealthy_subscriptions(&connected_peers_and_metadata);
 
-        // Check if all subscriptions were terminated
+        // Check if all subscriptions were terminated, and if syncing progress stopped
         let num_terminated_subscriptions = terminated_subscriptions.len();
         let all_subscriptions_terminated = num_terminated_subscriptions > 0
             && num_terminated_subscriptions == initial_subscription_peers.len();
+        let syncing_progress_stopped = terminated_subscriptions
+            .iter()
+            .any(|(_, error)| matches!(error, Error::SubscriptionProgressStopped(_)));
 
         // Calculate the number of new subscriptions to create
         let remaining_subscription_peers = self.get_active_subscription_peers();
@@ -132,8 +172,8 @@ impl SubscriptionManager {
         )
         .await;
 
-        // Return an error if all subscriptions were terminated
-        if all_subscriptions_terminated {
+        // Return an error if all subscriptions were terminated and syncing progress stopped
+        if all_subscriptions_terminated && syncing_progress_stopped {
             Err(Error::SubscriptionsReset(format!(
                 "All {:?} subscriptions were unhealthy and terminated!",
                 num_terminated_subscriptions,
@@ -198,6 +238,16 @@ impl SubscriptionManager {
         let db_reader = self.db_reader.clone();
         let time_service = self.time_service.clone();
 
+        // Identify the connected peers with low publisher scores
+        let low_score_peers = {
+            let publisher_scores = self.publisher_scores.lock();
+            connected_peers_and_metadata
+                .keys()
+                .filter(|peer_network_id| publisher_scores.is_ignored(peer_network_id))
+                .cloned()
+                .collect()
+        };
+
         // Spawn a new subscription creation task
         let subscription_creation_task = tokio::spawn(async move {
             // Identify the terminated subscription peers
@@ -217,6 +267,7 @@ impl SubscriptionManager {
                 num_subscriptions_to_create,
                 active_subscription_peers,
                 terminated_subscription_peers,
+                low_score_peers,
             )
             .await;
 
@@ -273,6 +324,11 @@ impl SubscriptionManager {
                     ))
                 );
 
+                // Penalize the publisher if it stopped sending messages
+                if matches!(error, Error::SubscriptionTimeout(_)) {
+                    self.update_publisher_score_error(subscription_peer, ErrorType::NotUseful);
+                }
+
                 // Unsubscribe from the peer and remove the subscription
                 self.unsubscribe_from_peer(subscription_peer);
 
@@ -284,6 +340,40 @@ impl SubscriptionManager {
         terminated_subscriptions
     }
 
+    /// Records the receipt of the given (verified) message from the publisher (to score the publisher)
+    pub fn record_message_receipt(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message_key: (u64, Round, &'static str),
+        current_epoch: u64,
+        last_ordered_round: Round,
+    ) {
+        self.publisher_scores.lock().record_message_receipt(
+            peer_network_id,
+            message_key,
+            current_epoch,
+            last_ordered_round,
+        );
+    }
+
+    /// Records the receipt of a duplicate (unverified) message from the publisher
+    pub fn record_duplicate_message_receipt(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message_key: (u64, Round, &'static str),
+    ) {
+        self.publisher_scores
+            .lock()
+            .record_duplicate_message_receipt(peer_network_id, message_key);
+    }
+
+    /// Updates the score of the publisher according to the given error
+    pub fn update_publisher_score_error(&self, peer_network_id: PeerNetworkId, error: ErrorType) {
+        self.publisher_scores
+            .lock()
+            .update_score_error(peer_network_id, error);
+    }
+
     /// Unsubscribes from the given peer by sending an unsubscribe request
     fn unsubscribe_from_peer(&mut self, peer_network_id: PeerNetworkId) {
         // Remove the peer from the active subscriptions
@@ -514,7 +604,61 @@ mod test {
         // Check and manage the subscriptions
         let result = subscription_manager.check_and_manage_subscriptions().await;
 
-        // Verify that the second subscription was terminated and an error was returned
+        // Verify that the second subscription was terminated, but no error was
+        // returned (syncing progress didn't stop, so we can simply switch publishers).
+        verify_active_subscription_peers(&subscription_manager, vec![]);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_and_manage_subscriptions_progress_stopped() {
+        // Create a consensus observer config
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_subscription_timeout_ms: 100_000_000, // Use a large value so that we don't time out
+            ..ConsensusObserverConfig::default()
+        };
+
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager
+        let db_reader = create_mock_db_reader();
+        let time_service = TimeService::mock();
+        let mut subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            db_reader.clone(),
+            time_service.clone(),
+        );
+
+        // Add two connected peers and subscriptions
+        let connected_peer_1 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        let connected_peer_2 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        for connected_peer in [connected_peer_1, connected_peer_2] {
+            create_observer_subscription(
+                &mut subscription_manager,
+                consensus_observer_config,
+                db_reader.clone(),
+                connected_peer,
+                time_service.clone(),
+            );
+        }
+
+        // Elapse time to simulate a DB progress error
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.max_synced_version_timeout_ms + 1,
+        ));
+
+        // Check and manage the subscriptions
+        let result = subscription_manager.check_and_manage_subscriptions().await;
+
+        // Verify that all subscriptions were terminated and an error was returned
         verify_active_subscription_peers(&subscription_manager, vec![]);
         assert_matches!(result, Err(Error::SubscriptionsReset(_)));
     }
@@ -635,6 +779,63 @@ mod test {
         verify_active_subscription_peers(&subscription_manager, vec![]);
     }
 
+    #[tokio::test]
+    async fn test_check_subscription_health_low_score() {
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let db_reader = create_mock_db_reader();
+        let time_service = TimeService::mock();
+        let mut subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            db_reader.clone(),
+            time_service.clone(),
+        );
+
+        // Add a new connected peer and subscription
+        let connected_peer_1 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        create_observer_subscription(
+            &mut subscription_manager,
+            consensus_observer_config,
+            db_reader.clone(),
+            connected_peer_1,
+            time_service.clone(),
+        );
+
+        // Penalize the first peer until its score is too low
+        for _ in 0..10 {
+            subscription_manager
+                .update_publisher_score_error(connected_peer_1, ErrorType::Malicious);
+        }
+
+        // Verify the subscription is still healthy (it is the only active subscription)
+        verify_terminated_unhealthy_subscriptions(&mut subscription_manager, vec![]);
+
+        // Add another connected peer and subscription
+        let connected_peer_2 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        create_observer_subscription(
+            &mut subscription_manager,
+            consensus_observer_config,
+            db_reader.clone(),
+            connected_peer_2,
+            time_service.clone(),
+        );
+
+        // Verify the subscription to the first peer is now terminated
+        verify_terminated_unhealthy_subscriptions(&mut subscription_manager, vec![
+            connected_peer_1,
+        ]);
+        verify_active_subscription_peers(&subscription_manager, vec![connected_peer_2]);
+    }
+
     #[tokio::test]
     async fn test_check_subscription_health_timeout() {
         // Create a consensus observer client
@@ -44,12 +44,14 @@ pub async fn create_new_subscriptions(
     num_subscriptions_to_create: usize,
     active_subscription_peers: Vec<PeerNetworkId>,
     unhealthy_subscription_peers: Vec<PeerNetworkId>,
+    low_score_peers: Vec<PeerNetworkId>,
 ) -> Vec<ConsensusObserverSubscription> {
     // Sort the potential peers for subscription requests
     let mut sorted_potential_peers = match sort_peers_for_subscriptions(
         connected_peers_and_metadata,
         unhealthy_subscription_peers,
         active_subscription_peers,
+        low_score_peers,
         consensus_publisher,
     ) {
         Some(sorted_peers) => sorted_peers,
@@ -241,11 +243,14 @@ fn get_latency_for_peer(
 
 /// Produces a list of sorted peers to service the subscription requests.
 /// Any active or unhealthy subscriptions are excluded from the selection process.
-/// Likewise, any peers currently subscribed to us are also excluded.
+/// Likewise, any peers currently subscribed to us are also excluded. Peers with
+/// low publisher scores are moved to the end of the list (so that they are only
+/// selected if there are no other options).
 fn sort_peers_for_subscriptions(
     mut connected_peers_and_metadata: HashMap<PeerNetworkId, PeerMetadata>,
     active_subscription_peers: Vec<PeerNetworkId>,
     unhealthy_subscription_peers: Vec<PeerNetworkId>,
+    low_score_peers: Vec<PeerNetworkId>,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
 ) -> Option<Vec<PeerNetworkId>> {
     // Remove any peers we're already subscribed to
@@ -266,7 +271,10 @@ fn sort_peers_for_subscriptions(
     }
 
     // Sort the peers by subscription optimality
-    let sorted_peers = sort_peers_by_subscription_optimality(&connected_peers_and_metadata);
+    let mut sorted_peers = sort_peers_by_subscription_optimality(&connected_peers_and_metadata);
+
+    // Move any peers with low scores to the end (the sort is stable, so the order is preserved)
+    sorted_peers.sort_by_key(|peer| low_score_peers.contains(peer));
 
     // Return the sorted peers
     Some(sorted_peers)
@@ -430,6 +438,7 @@ mod tests {
                 num_subscriptions_to_create,
                 vec![],
                 vec![],
+                vec![],
             )
             .await
         });
@@ -842,6 +851,7 @@ mod tests {
                 num_subscriptions_to_create,
                 vec![],
                 vec![],
+                vec![],
             )
             .await
         });
@@ -1148,6 +1158,7 @@ mod tests {
             connected_peers_and_metadata,
             unhealthy_subscription_peers,
             active_subscription_peers,
+            vec![],
             Some(consensus_publisher),
         )
         .unwrap()
@@ -26,7 +26,10 @@ use crate::{
 };
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::aptos_channel::Receiver;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{
+    transaction_filter_type::{LiveFilter, LiveTransactionFilters},
+    NodeConfig,
+};
 use aptos_consensus_notifications::ConsensusNotificationSender;
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_executor::block_executor::BlockExecutor;
@@ -54,6 +57,7 @@ pub fn start_consensus(
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    transaction_filters: &LiveTransactionFilters,
 ) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
@@ -69,7 +73,7 @@ pub fn start_consensus(
         txn_notifier,
         state_sync_notifier,
         runtime.handle(),
-        TransactionFilter::new(node_config.execution.transaction_filter.clone()),
+        TransactionFilter::new_live(transaction_filters.consensus.clone()),
         node_config.consensus.enable_pre_commit,
     );
 
@@ -114,6 +118,7 @@ pub fn start_consensus(
         vtxn_pool,
         rand_storage,
         consensus_publisher,
+        transaction_filters.quorum_store.clone(),
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
@@ -138,6 +143,7 @@ pub fn start_consensus_observer(
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     aptos_db: DbReaderWriter,
     reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    consensus_transaction_filter: Arc<LiveFilter>,
 ) {
     // Create the (dummy) consensus network client
     let (self_sender, _self_receiver) =
@@ -162,7 +168,7 @@ pub fn start_consensus_observer(
             txn_notifier,
             state_sync_notifier,
             consensus_observer_runtime.handle(),
-            TransactionFilter::new(node_config.execution.transaction_filter.clone()),
+            TransactionFilter::new_live(consensus_transaction_filter),
             node_config.consensus.enable_pre_commit,
         );
 
@@ -56,7 +56,10 @@ use crate::{
 use anyhow::{anyhow, bail, ensure, Context};
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::config::{ConsensusConfig, DagConsensusConfig, ExecutionConfig, NodeConfig};
+use aptos_config::config::{
+    transaction_filter_type::LiveFilter, ConsensusConfig, DagConsensusConfig, ExecutionConfig,
+    NodeConfig,
+};
 use aptos_consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
@@ -170,6 +173,7 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
     pending_blocks: Arc<Mutex<PendingBlocks>>,
     key_storage: PersistentSafetyStorage,
+    quorum_store_transaction_filter: Arc<LiveFilter>,
 }
 
 impl<P: OnChainConfigProvider> EpochManager<P> {
@@ -190,6 +194,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         vtxn_pool: VTxnPoolState,
         rand_storage: Arc<dyn RandStorage<AugmentedData>>,
         consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        quorum_store_transaction_filter: Arc<LiveFilter>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -241,6 +246,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             consensus_publisher,
             pending_blocks: Arc::new(Mutex::new(PendingBlocks::new())),
             key_storage,
+            quorum_store_transaction_filter,
         }
     }
 
@@ -698,6 +704,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 self.config.safety_rules.backend.clone(),
                 self.quorum_store_storage.clone(),
                 !consensus_config.is_dag_enabled(),
+                self.quorum_store_transaction_filter.clone(),
             ))
         } else {
             info!("Building DirectMempool");
@@ -1760,7 +1767,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             .get_public_key(&self.author)
             .ok_or_else(|| anyhow!("i am not in the validator set!"))?;
         self.key_storage
-            .consensus_sk_by_pk(pk)
+            .export_consensus_key_by_pk(pk)
             .map_err(|e| anyhow!("could not find sk by pk: {:?}", e))
     }
 }
@@ -12,6 +12,8 @@ use crate::{
     },
 };
 use anyhow::ensure;
+use aptos_config::config::transaction_filter_type::LiveFilter;
+use aptos_infallible::duration_since_epoch;
 use aptos_logger::prelude::*;
 use aptos_types::PeerId;
 use std::sync::Arc;
@@ -37,6 +39,7 @@ pub struct BatchCoordinator {
     max_batch_bytes: u64,
     max_total_txns: u64,
     max_total_bytes: u64,
+    transaction_filter: Arc<LiveFilter>,
 }
 
 impl BatchCoordinator {
@@ -50,6 +53,7 @@ impl BatchCoordinator {
         max_batch_bytes: u64,
         max_total_txns: u64,
         max_total_bytes: u64,
+        transaction_filter: Arc<LiveFilter>,
     ) -> Self {
         Self {
             my_peer_id,
@@ -61,6 +65,7 @@ impl BatchCoordinator {
             max_batch_bytes,
             max_total_txns,
             max_total_bytes,
+            transaction_filter,
         }
     }
 
@@ -131,6 +136,17 @@ impl BatchCoordinator {
         Ok(())
     }
 
+    fn ensure_allowed_by_filter(&self, batches: &[Batch]) -> anyhow::Result<()> {
+        if self.transaction_filter.is_empty() {
+            return Ok(());
+        }
+        let timestamp = duration_since_epoch().as_micros() as u64;
+        for batch in batches.iter() {
+            batch.ensure_allowed_by(&self.transaction_filter, timestamp)?;
+        }
+        Ok(())
+    }
+
     async fn handle_batches_msg(&mut self, author: PeerId, batches: Vec<Batch>) {
         if let Err(e) = self.ensure_max_limits(&batches) {
             error!("Batch from {}: {}", author, e);
@@ -138,6 +154,12 @@ impl BatchCoordinator {
             return;
         }
 
+        if let Err(e) = self.ensure_allowed_by_filter(&batches) {
+            error!("Batch from {}: {}", author, e);
+            counters::RECEIVED_BATCH_FILTERED.inc();
+            return;
+        }
+
         let mut persist_requests = vec![];
         for batch in batches.into_iter() {
             // TODO: maybe don't message batch generator if the persist is unsuccessful?
@@ -620,6 +620,15 @@ pub static RECEIVED_BATCH_MAX_LIMIT_FAILED: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the received batches that were dropped by the transaction filter.
+pub static RECEIVED_BATCH_FILTERED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_received_batch_filtered",
+        "Count of the received batches that were dropped by the transaction filter."
+    )
+    .unwrap()
+});
+
 /// Count of the missed batches when execute.
 pub static MISSED_BATCHES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -7,7 +7,9 @@ use crate::{
         batch_requester::BatchRequester,
         types::{Batch, BatchRequest, BatchResponse},
     },
+    test_utils::create_vec_signed_transactions,
 };
+use aptos_config::config::transaction_filter_type::{Filter, LiveFilter};
 use aptos_consensus_types::{
     common::Author,
     proof_of_store::{BatchId, ProofOfStore, SignedBatchInfo},
@@ -110,6 +112,51 @@ async fn test_batch_request_exists() {
     assert!(rx.try_recv().is_ok());
 }
 
+#[tokio::test]
+async fn test_batch_request_ignores_transaction_filter() {
+    let batch = Batch::new(
+        BatchId::new_for_test(1),
+        create_vec_signed_transactions(2),
+        1,
+        1,
+        AccountAddress::random(),
+        0,
+    );
+    let batch_response = BatchResponse::Batch(batch.clone());
+
+    // The batch would be denied by a filter on its broadcast, but ordered blocks already reference
+    // it, so it's fetched regardless and left to the execution time filter
+    let filter = LiveFilter::new(Filter::empty().add_deny_sender(batch.txns()[1].sender()));
+    assert!(batch.ensure_allowed_by(&filter, 0).is_err());
+
+    let validator_signer = ValidatorSigner::random(None);
+    let (tx, mut rx) = tokio::sync::oneshot::channel();
+    let batch_requester = BatchRequester::new(
+        1,
+        AccountAddress::random(),
+        1,
+        2,
+        1,
+        1_000,
+        MockBatchRequester::new(batch_response),
+        ValidatorVerifier::new_single(validator_signer.author(), validator_signer.public_key())
+            .into(),
+    );
+
+    let (_, subscriber_rx) = oneshot::channel();
+    let result = batch_requester
+        .request_batch(
+            *batch.digest(),
+            batch.expiration(),
+            vec![AccountAddress::random()],
+            tx,
+            subscriber_rx,
+        )
+        .await;
+    assert_eq!(result.unwrap().1, batch.txns().to_vec());
+    assert_eq!(rx.try_recv().unwrap().unwrap(), batch.txns().to_vec());
+}
+
 fn create_ledger_info_with_timestamp(
     timestamp: u64,
 ) -> (LedgerInfoWithSignatures, ValidatorVerifier) {
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::ensure;
+use aptos_config::config::transaction_filter_type::LiveFilter;
 use aptos_consensus_types::{
     common::{BatchPayload, TxnSummaryWithExpiration},
     proof_of_store::{BatchId, BatchInfo},
@@ -193,6 +194,25 @@ impl Batch {
         self.payload.into_transactions()
     }
 
+    pub fn txns(&self) -> &[SignedTransaction] {
+        self.payload.txns()
+    }
+
+    /// Batches are not part of a block yet, so the filter is evaluated with a zero block id
+    /// and the given time.
+    pub fn ensure_allowed_by(&self, filter: &LiveFilter, timestamp: u64) -> anyhow::Result<()> {
+        for txn in self.txns() {
+            ensure!(
+                filter.allows(HashValue::zero(), timestamp, txn),
+                "Batch {} contains denied txn {}:{}",
+                self.digest(),
+                txn.sender(),
+                txn.sequence_number(),
+            );
+        }
+        Ok(())
+    }
+
     pub fn batch_info(&self) -> &BatchInfo {
         &self.batch_info
     }
@@ -1,16 +1,23 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::transaction_filter_type::Filter;
+use aptos_config::config::transaction_filter_type::{Filter, LiveFilter};
 use aptos_crypto::HashValue;
 use aptos_types::transaction::SignedTransaction;
+use std::sync::Arc;
 
 pub struct TransactionFilter {
-    filter: Filter,
+    filter: Arc<LiveFilter>,
 }
 
 impl TransactionFilter {
+    #[cfg(test)]
     pub(crate) fn new(filter: Filter) -> Self {
+        Self::new_live(Arc::new(LiveFilter::new(filter)))
+    }
+
+    /// Creates a filter whose rules can be updated while it's in use
+    pub(crate) fn new_live(filter: Arc<LiveFilter>) -> Self {
         Self { filter }
     }
 
@@ -33,20 +40,21 @@ impl TransactionFilter {
 #[cfg(test)]
 mod test {
     use crate::transaction_filter::TransactionFilter;
-    use aptos_config::config::transaction_filter_type::Filter;
+    use aptos_config::config::transaction_filter_type::{
+        AuthenticatorScheme, Filter, LiveFilter, Matcher, PayloadType, Rule, RuleWithHits,
+    };
     use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, SigningKey, Uniform};
     use aptos_types::{
         chain_id::ChainId,
         move_utils::MemberId,
-        transaction::{EntryFunction, RawTransaction, SignedTransaction, TransactionPayload},
+        transaction::{
+            EntryFunction, RawTransaction, Script, SignedTransaction, TransactionPayload,
+        },
     };
     use move_core_types::account_address::AccountAddress;
+    use std::sync::Arc;
 
     fn create_signed_transaction(function: MemberId) -> SignedTransaction {
-        let private_key = Ed25519PrivateKey::generate_for_testing();
-        let public_key = private_key.public_key();
-        let sender = AccountAddress::random();
-        let sequence_number = 0;
         let MemberId {
             module_id,
             member_id: function_id,
@@ -58,8 +66,28 @@ mod test {
             vec![],
             vec![],
         ));
-        let raw_transaction =
-            RawTransaction::new(sender, sequence_number, payload, 0, 0, 0, ChainId::new(10));
+        create_signed_transaction_with_payload(payload, 0, 0)
+    }
+
+    fn create_signed_transaction_with_payload(
+        payload: TransactionPayload,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+    ) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let sender = AccountAddress::random();
+        let sequence_number = 0;
+
+        let raw_transaction = RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            max_gas_amount,
+            gas_unit_price,
+            0,
+            ChainId::new(10),
+        );
 
         SignedTransaction::new(
             raw_transaction.clone(),
@@ -287,4 +315,166 @@ mod test {
         let filtered_txns = allow_list_filter.filter(block_id, 0, txns.clone());
         assert_eq!(filtered_txns, txns[4..].to_vec());
     }
+
+    #[test]
+    fn test_payload_type_filter() {
+        let mut txns = get_transactions();
+        txns.push(create_signed_transaction(
+            str::parse("0x1::code::publish_package_txn").unwrap(),
+        ));
+        txns.push(create_signed_transaction_with_payload(
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            0,
+            0,
+        ));
+        let block_id = HashValue::random();
+
+        let script_filter = TransactionFilter::new(
+            Filter::empty().add_deny(Matcher::PayloadType(PayloadType::Script)),
+        );
+        let filtered_txns = script_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[0..8].to_vec());
+
+        let module_publish_filter = TransactionFilter::new(
+            Filter::empty().add_deny(Matcher::PayloadType(PayloadType::ModulePublish)),
+        );
+        let filtered_txns = module_publish_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, [&txns[0..7], &txns[8..]].concat());
+    }
+
+    #[test]
+    fn test_gas_filter() {
+        let txns: Vec<_> = (1..=5)
+            .map(|i| {
+                create_signed_transaction_with_payload(
+                    TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                    i * 1000,
+                    i * 100,
+                )
+            })
+            .collect();
+        let block_id = HashValue::random();
+
+        // Only allow gas unit prices in the range (100, 500)
+        let gas_unit_price_filter = TransactionFilter::new(
+            Filter::empty()
+                .add_allow(Matcher::And(vec![
+                    Matcher::GasUnitPriceGreaterThan(100),
+                    Matcher::GasUnitPriceLessThan(500),
+                ]))
+                .add_deny_all(),
+        );
+        let filtered_txns = gas_unit_price_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[1..4].to_vec());
+
+        // Deny max gas amounts outside the range [2000, 4000]
+        let max_gas_amount_filter =
+            TransactionFilter::new(Filter::empty().add_deny(Matcher::Or(vec![
+                Matcher::MaxGasAmountLessThan(2000),
+                Matcher::MaxGasAmountGreaterThan(4000),
+            ])));
+        let filtered_txns = max_gas_amount_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[1..4].to_vec());
+    }
+
+    #[test]
+    fn test_authenticator_scheme_filter() {
+        let txns = get_transactions();
+        let block_id = HashValue::random();
+
+        let ed25519_filter = TransactionFilter::new(Filter::empty().add_deny(Matcher::Not(
+            Box::new(Matcher::AuthenticatorScheme(AuthenticatorScheme::Ed25519)),
+        )));
+        let filtered_txns = ed25519_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns);
+
+        let multi_key_filter = TransactionFilter::new(
+            Filter::empty().add_deny(Matcher::AuthenticatorScheme(AuthenticatorScheme::MultiKey)),
+        );
+        let filtered_txns = multi_key_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns);
+    }
+
+    #[test]
+    fn test_composite_expression_filter() {
+        let txns = get_transactions();
+        let block_id = HashValue::random();
+        let filter = serde_yaml::from_str::<Filter>(r#"
+            rules:
+                - Deny:
+                    And:
+                        - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000001"
+                        - Not:
+                            EntryFunction:
+                                - "0000000000000000000000000000000000000000000000000000000000000001"
+                                - test
+                                - new
+                - Deny:
+                    Or:
+                        - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000003"
+                        - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000004"
+              "#).unwrap();
+
+        let expression_filter = TransactionFilter::new(filter);
+        let filtered_txns = expression_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, vec![txns[2].clone(), txns[4].clone()]);
+    }
+
+    #[test]
+    fn test_live_filter() {
+        let txns = get_transactions();
+        let block_id = HashValue::random();
+        let live_filter = Arc::new(LiveFilter::new(Filter::empty()));
+        let filter = TransactionFilter::new_live(live_filter.clone());
+        assert_eq!(filter.filter(block_id, 0, txns.clone()), txns);
+
+        // Deny all txns to module address 0x1, except for 0x1::test::new
+        let deny_rule_id = live_filter
+            .insert_rule(
+                None,
+                Rule::Deny(Matcher::ModuleAddress(get_module_address(&txns[0]))),
+            )
+            .unwrap();
+        let allow_rule = Rule::Allow(Matcher::EntryFunction(
+            get_module_address(&txns[2]),
+            get_module_name(&txns[2]),
+            get_function_name(&txns[2]),
+        ));
+        let allow_rule_id = live_filter
+            .insert_rule(Some(0), allow_rule.clone())
+            .unwrap();
+        assert_ne!(deny_rule_id, allow_rule_id);
+        assert!(live_filter
+            .insert_rule(Some(3), Rule::Deny(Matcher::All))
+            .is_err());
+
+        let filtered_txns = filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, [&txns[2..3], &txns[4..]].concat());
+        let ids_and_hits: Vec<_> = live_filter
+            .rules_with_hits()
+            .iter()
+            .map(|rule| (rule.id, rule.hits))
+            .collect();
+        assert_eq!(ids_and_hits, vec![(allow_rule_id, 1), (deny_rule_id, 3)]);
+
+        // Remove the deny rule by ID, and its hits. IDs aren't reused.
+        assert!(live_filter.remove_rule(allow_rule_id + 1).is_err());
+        let rule = live_filter.remove_rule(deny_rule_id).unwrap();
+        assert!(matches!(rule, Rule::Deny(_)));
+        assert!(live_filter.remove_rule(deny_rule_id).is_err());
+        assert_eq!(filter.filter(block_id, 0, txns.clone()), txns);
+        assert_eq!(live_filter.rules_with_hits(), vec![RuleWithHits {
+            id: allow_rule_id,
+            rule: allow_rule.clone(),
+            hits: 2,
+        }]);
+
+        // Remove the allow rule by content
+        assert_eq!(
+            live_filter.remove_matching_rule(&allow_rule).unwrap(),
+            allow_rule_id
+        );
+        assert!(live_filter.remove_matching_rule(&allow_rule).is_err());
+        assert!(live_filter.is_empty());
+    }
 }
@@ -1,10 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::{AuthenticationConfig, NodeConfig};
+use aptos_config::config::{
+    transaction_filter_type::LiveTransactionFilters, AuthenticationConfig, NodeConfig,
+};
 use aptos_consensus::{
     persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
 };
+use aptos_db::rolling_checkpointer::RollingCheckpointer;
 use aptos_infallible::RwLock;
 use aptos_logger::info;
 use aptos_storage_interface::DbReaderWriter;
@@ -26,6 +29,8 @@ use std::{
 use tokio::runtime::Runtime;
 
 mod consensus;
+mod storage;
+mod transaction_filter;
 
 #[derive(Default)]
 pub struct Context {
@@ -34,6 +39,8 @@ pub struct Context {
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    rolling_checkpointer: RwLock<Option<Arc<RollingCheckpointer>>>,
+    transaction_filters: RwLock<Option<LiveTransactionFilters>>,
 }
 
 impl Context {
@@ -49,6 +56,14 @@ impl Context {
         *self.consensus_db.write() = Some(consensus_db);
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
+
+    fn set_rolling_checkpointer(&self, rolling_checkpointer: Arc<RollingCheckpointer>) {
+        *self.rolling_checkpointer.write() = Some(rolling_checkpointer);
+    }
+
+    fn set_transaction_filters(&self, transaction_filters: LiveTransactionFilters) {
+        *self.transaction_filters.write() = Some(transaction_filters);
+    }
 }
 
 pub struct AdminService {
@@ -107,6 +122,14 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_rolling_checkpointer(&self, rolling_checkpointer: Arc<RollingCheckpointer>) {
+        self.context.set_rolling_checkpointer(rolling_checkpointer)
+    }
+
+    pub fn set_transaction_filters(&self, transaction_filters: LiveTransactionFilters) {
+        self.context.set_transaction_filters(transaction_filters)
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -166,6 +189,18 @@ impl AdminService {
             ));
         }
 
+        // Requests that change the state of the node are only served if authentication is set up
+        if req.method() != hyper::Method::GET && context.authentication_configs.is_empty() {
+            return Ok(reply_with_status(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "{} {} requires an authentication config.",
+                    req.method(),
+                    req.uri().path()
+                ),
+            ));
+        }
+
         match (req.method().clone(), req.uri().path()) {
             #[cfg(target_os = "linux")]
             (hyper::Method::GET, "/profilez") => handle_cpu_profiling_request(req).await,
@@ -210,7 +245,147 @@ impl AdminService {
                     ))
                 }
             },
+            // The checkpointer is only set if rolling checkpoints are enabled. Note: creating a
+            // checkpoint (POST) also requires an authentication config (see above).
+            (hyper::Method::GET | hyper::Method::POST, "/debug/storage/checkpoints") => {
+                let rolling_checkpointer = context.rolling_checkpointer.read().clone();
+                if let Some(rolling_checkpointer) = rolling_checkpointer {
+                    storage::handle_checkpoints_request(req, rolling_checkpointer).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "DB checkpoints are not enabled.",
+                    ))
+                }
+            },
+            (
+                hyper::Method::GET | hyper::Method::POST | hyper::Method::DELETE,
+                "/transaction_filter",
+            ) => {
+                let transaction_filters = context.transaction_filters.read().clone();
+                if let Some(transaction_filters) = transaction_filters {
+                    transaction_filter::handle_transaction_filter_request(req, transaction_filters)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Transaction filters are not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::config::transaction_filter_type::{Matcher, Rule};
+    use aptos_types::account_address::AccountAddress;
+    use hyper::Method;
+
+    fn new_context(authentication_configs: Vec<AuthenticationConfig>) -> Arc<Context> {
+        let context = Arc::new(Context {
+            authentication_configs,
+            ..Default::default()
+        });
+        context.set_transaction_filters(LiveTransactionFilters::new(&NodeConfig::default()));
+        context
+    }
+
+    async fn send_request(
+        context: &Arc<Context>,
+        method: Method,
+        uri: &str,
+        body: String,
+    ) -> StatusCode {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::from(body))
+            .unwrap();
+        AdminService::serve_requests(context.clone(), req, true)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_mutating_requests_require_authentication_config() {
+        let rule =
+            serde_json::to_string(&Rule::Deny(Matcher::Sender(AccountAddress::ONE))).unwrap();
+
+        // Without authentication configs, the transaction filters can be read but not changed
+        let context = new_context(vec![]);
+        for (method, expected_status) in [
+            (Method::GET, StatusCode::OK),
+            (Method::POST, StatusCode::FORBIDDEN),
+            (Method::DELETE, StatusCode::FORBIDDEN),
+        ] {
+            let status = send_request(
+                &context,
+                method,
+                "/transaction_filter?component=mempool",
+                rule.clone(),
+            )
+            .await;
+            assert_eq!(status, expected_status);
+        }
+
+        // With an authentication config, they can be changed by authenticated requests only
+        let passcode = "passcode";
+        let context = new_context(vec![AuthenticationConfig::PasscodeSha256(sha256::digest(
+            passcode,
+        ))]);
+        let status = send_request(
+            &context,
+            Method::POST,
+            "/transaction_filter?component=mempool",
+            rule.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NETWORK_AUTHENTICATION_REQUIRED);
+        let status = send_request(
+            &context,
+            Method::POST,
+            &format!(
+                "/transaction_filter?component=mempool&passcode={}",
+                passcode
+            ),
+            rule,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_not_enabled() {
+        // Without an authentication config, creating a checkpoint is forbidden
+        let context = new_context(vec![]);
+        let status = send_request(
+            &context,
+            Method::POST,
+            "/debug/storage/checkpoints",
+            String::new(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Without a checkpointer (i.e., rolling checkpoints are disabled), the endpoint isn't served
+        let passcode = "passcode";
+        let context = new_context(vec![AuthenticationConfig::PasscodeSha256(sha256::digest(
+            passcode,
+        ))]);
+        for method in [Method::GET, Method::POST] {
+            let status = send_request(
+                &context,
+                method,
+                &format!("/debug/storage/checkpoints?passcode={}", passcode),
+                String::new(),
+            )
+            .await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_db::rolling_checkpointer::RollingCheckpointer;
+use aptos_logger::info;
+use aptos_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use std::{collections::HashMap, sync::Arc};
+
+/// Handles the requests to the DB checkpoints endpoint:
+///   - GET returns the existing checkpoints (version and path), as JSON.
+///   - POST creates a checkpoint of the running DB at the latest committed version, or truncated
+///     to the (optional) `version` query param, removes the checkpoints out of retention, and
+///     returns the new checkpoint as JSON.
+pub async fn handle_checkpoints_request(
+    req: Request<Body>,
+    rolling_checkpointer: Arc<RollingCheckpointer>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+    let version: Option<u64> = match query_pairs.get("version") {
+        Some(val) => match val.parse() {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    let result = match req.method().clone() {
+        Method::GET => {
+            spawn_blocking(move || {
+                Ok(serde_json::to_string_pretty(
+                    &rolling_checkpointer.list_checkpoints()?,
+                )?)
+            })
+            .await
+        },
+        Method::POST => {
+            info!("Creating DB checkpoint, target version: {version:?}.");
+            spawn_blocking(move || {
+                let checkpoint = rolling_checkpointer.create_checkpoint(version)?;
+                info!(
+                    "Created DB checkpoint at version {} in {:?}.",
+                    checkpoint.version, checkpoint.path
+                );
+                Ok(serde_json::to_string(&checkpoint)?)
+            })
+            .await
+        },
+        _ => {
+            return Ok(reply_with_status(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Method not allowed.",
+            ))
+        },
+    };
+
+    match result {
+        Ok(result) => {
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))];
+            Ok(reply_with(headers, result))
+        },
+        Err(e) => {
+            info!("Failed to handle DB checkpoints request: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::config::RollingCheckpointConfig;
+    use aptos_db::{
+        db::test_helper::{arb_blocks_to_commit, update_in_memory_state},
+        AptosDB,
+    };
+    use aptos_proptest_helpers::ValueGenerator;
+    use aptos_temppath::TempPath;
+    use serde_json::{json, Value};
+
+    async fn send_request(
+        rolling_checkpointer: &Arc<RollingCheckpointer>,
+        method: Method,
+        query: &str,
+    ) -> (StatusCode, String) {
+        let req = Request::builder()
+            .method(method)
+            .uri(format!("/debug/storage/checkpoints?{}", query))
+            .body(Body::empty())
+            .unwrap();
+        let response = handle_checkpoints_request(req, rolling_checkpointer.clone())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn checkpoint_version(body: &str) -> u64 {
+        let checkpoint: Value = serde_json::from_str(body).unwrap();
+        checkpoint["version"].as_u64().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_requests() {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(AptosDB::new_for_test(&tmp_dir));
+        let mut in_memory_state = db.buffered_state().lock().current_state().clone();
+        let blocks = ValueGenerator::new().generate(arb_blocks_to_commit());
+        let mut version = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &blocks {
+            update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+            db.save_transactions_for_test(
+                txns_to_commit,
+                version,
+                version.checked_sub(1),
+                Some(ledger_info_with_sigs),
+                true, /* sync_commit */
+                in_memory_state.clone(),
+            )
+            .unwrap();
+            version += txns_to_commit.len() as u64;
+        }
+        let latest_version = version - 1;
+
+        let checkpoint_dir = TempPath::new();
+        let rolling_checkpointer = Arc::new(RollingCheckpointer::new(
+            db,
+            &RollingCheckpointConfig::default(),
+            checkpoint_dir.path().to_path_buf(),
+        ));
+
+        // No checkpoints yet
+        let (status, body) = send_request(&rolling_checkpointer, Method::GET, "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap(), json!([]));
+
+        // Checkpoint at the latest committed version
+        let (status, body) = send_request(&rolling_checkpointer, Method::POST, "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(checkpoint_version(&body), latest_version);
+
+        // Checkpoint truncated to the end of the first block
+        let target_version = blocks[0].0.len() as u64 - 1;
+        let (status, body) = send_request(
+            &rolling_checkpointer,
+            Method::POST,
+            &format!("version={}", target_version),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let truncated_version = checkpoint_version(&body);
+        assert!(truncated_version <= target_version);
+
+        // Both checkpoints are listed, in ascending order of version
+        let (status, body) = send_request(&rolling_checkpointer, Method::GET, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let versions: Vec<_> = serde_json::from_str::<Vec<Value>>(&body)
+            .unwrap()
+            .iter()
+            .map(|checkpoint| checkpoint["version"].as_u64().unwrap())
+            .collect();
+        if truncated_version == latest_version {
+            assert_eq!(versions, vec![latest_version]);
+        } else {
+            assert_eq!(versions, vec![truncated_version, latest_version]);
+        }
+
+        // A version that isn't committed yet can't be checkpointed
+        let (status, _) = send_request(
+            &rolling_checkpointer,
+            Method::POST,
+            &format!("version={}", latest_version + 1),
+        )
+        .await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        // Invalid version and method
+        let (status, _) = send_request(&rolling_checkpointer, Method::POST, "version=latest").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send_request(&rolling_checkpointer, Method::DELETE, "").await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,295 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail};
+use aptos_config::config::transaction_filter_type::{LiveFilter, LiveTransactionFilters, Rule};
+use aptos_logger::info;
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use std::{collections::HashMap, sync::Arc};
+
+const COMPONENT_CONSENSUS: &str = "consensus";
+const COMPONENT_QUORUM_STORE: &str = "quorum_store";
+const COMPONENT_MEMPOOL: &str = "mempool";
+
+/// Handles the requests to the transaction filter endpoint. All requests take a `component`
+/// query param (`consensus`, `quorum_store` or `mempool`):
+///   - GET returns the rules of the filter (in evaluation order) with their IDs and hit counts,
+///     as JSON.
+///   - POST inserts the JSON encoded rule in the body at the (optional) `index` query param,
+///     or after all the other rules, and returns the ID of the rule.
+///   - DELETE removes the rule with the `id` query param, or else the first rule equal to the
+///     JSON encoded rule in the body, and returns the removed rule.
+pub async fn handle_transaction_filter_request(
+    req: Request<Body>,
+    transaction_filters: LiveTransactionFilters,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let component = match query_pairs.get("component") {
+        Some(component) => component.to_string(),
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing the component query param.",
+            ))
+        },
+    };
+    let filter = match get_filter(&transaction_filters, &component) {
+        Ok(filter) => filter,
+        Err(e) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string())),
+    };
+    let index: Option<usize> = match query_pairs.get("index") {
+        Some(val) => match val.parse() {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+    let id: Option<u64> = match query_pairs.get("id") {
+        Some(val) => match val.parse() {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    let result = match req.method().clone() {
+        Method::GET => get_rules(&filter),
+        Method::POST => {
+            let body = hyper::body::to_bytes(req.into_body()).await?;
+            insert_rule(&filter, &component, index, &body)
+        },
+        Method::DELETE => {
+            let body = hyper::body::to_bytes(req.into_body()).await?;
+            remove_rule(&filter, &component, id, &body)
+        },
+        _ => {
+            return Ok(reply_with_status(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Method not allowed.",
+            ))
+        },
+    };
+
+    match result {
+        Ok(result) => {
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))];
+            Ok(reply_with(headers, result))
+        },
+        Err(e) => Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string())),
+    }
+}
+
+fn get_filter(
+    transaction_filters: &LiveTransactionFilters,
+    component: &str,
+) -> anyhow::Result<Arc<LiveFilter>> {
+    match component {
+        COMPONENT_CONSENSUS => Ok(transaction_filters.consensus.clone()),
+        COMPONENT_QUORUM_STORE => Ok(transaction_filters.quorum_store.clone()),
+        COMPONENT_MEMPOOL => Ok(transaction_filters.mempool.clone()),
+        _ => bail!("Unknown transaction filter component: {}", component),
+    }
+}
+
+fn get_rules(filter: &LiveFilter) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&filter.rules_with_hits())?)
+}
+
+fn insert_rule(
+    filter: &LiveFilter,
+    component: &str,
+    index: Option<usize>,
+    body: &[u8],
+) -> anyhow::Result<String> {
+    let rule: Rule =
+        serde_json::from_slice(body).map_err(|e| anyhow!("Failed to parse the rule: {}", e))?;
+
+    // Transactions are filtered outside of blocks by mempool and quorum store
+    if component != COMPONENT_CONSENSUS && rule.matcher().contains_block_id_matcher() {
+        bail!(
+            "Block ID based rules are not supported by the {} filter!",
+            component
+        );
+    }
+
+    let id = filter.insert_rule(index, rule.clone())?;
+    info!(
+        "Inserted {} transaction filter rule {:?} with ID {}.",
+        component, rule, id
+    );
+
+    Ok(serde_json::to_string(&id)?)
+}
+
+fn remove_rule(
+    filter: &LiveFilter,
+    component: &str,
+    id: Option<u64>,
+    body: &[u8],
+) -> anyhow::Result<String> {
+    let (id, rule) = match id {
+        Some(id) => (id, filter.remove_rule(id)?),
+        None => {
+            if body.is_empty() {
+                bail!("Missing the id query param, or the rule in the body.");
+            }
+            let rule: Rule = serde_json::from_slice(body)
+                .map_err(|e| anyhow!("Failed to parse the rule: {}", e))?;
+            (filter.remove_matching_rule(&rule)?, rule)
+        },
+    };
+    info!(
+        "Removed {} transaction filter rule {:?} with ID {}.",
+        component, rule, id
+    );
+
+    Ok(serde_json::to_string(&rule)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::config::{
+        transaction_filter_type::{Matcher, RuleWithHits},
+        NodeConfig,
+    };
+    use aptos_crypto::HashValue;
+    use aptos_types::account_address::AccountAddress;
+
+    async fn send_request(
+        transaction_filters: &LiveTransactionFilters,
+        method: Method,
+        query: &str,
+        body: String,
+    ) -> (StatusCode, String) {
+        let req = Request::builder()
+            .method(method)
+            .uri(format!("/transaction_filter?{}", query))
+            .body(Body::from(body))
+            .unwrap();
+        let response = handle_transaction_filter_request(req, transaction_filters.clone())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_filter_requests() {
+        let transaction_filters = LiveTransactionFilters::new(&NodeConfig::default());
+        let deny_rule = Rule::Deny(Matcher::Sender(AccountAddress::ONE));
+        let allow_rule = Rule::Allow(Matcher::Sender(AccountAddress::TWO));
+
+        // Insert the rules, the allow rule first
+        let (status, body) = send_request(
+            &transaction_filters,
+            Method::POST,
+            "component=mempool",
+            serde_json::to_string(&deny_rule).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let deny_rule_id: u64 = serde_json::from_str(&body).unwrap();
+        let (status, body) = send_request(
+            &transaction_filters,
+            Method::POST,
+            "component=mempool&index=0",
+            serde_json::to_string(&allow_rule).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let allow_rule_id: u64 = serde_json::from_str(&body).unwrap();
+
+        // Only the mempool filter is updated
+        let (status, body) = send_request(
+            &transaction_filters,
+            Method::GET,
+            "component=mempool",
+            "".into(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let rules: Vec<RuleWithHits> = serde_json::from_str(&body).unwrap();
+        assert_eq!(rules, vec![
+            RuleWithHits {
+                id: allow_rule_id,
+                rule: allow_rule.clone(),
+                hits: 0,
+            },
+            RuleWithHits {
+                id: deny_rule_id,
+                rule: deny_rule.clone(),
+                hits: 0,
+            },
+        ]);
+        assert!(transaction_filters.consensus.is_empty());
+        assert!(transaction_filters.quorum_store.is_empty());
+
+        // Remove the deny rule by ID, then the allow rule by content
+        let (status, body) = send_request(
+            &transaction_filters,
+            Method::DELETE,
+            &format!("component=mempool&id={}", deny_rule_id),
+            "".into(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(serde_json::from_str::<Rule>(&body).unwrap(), deny_rule);
+        let (status, _) = send_request(
+            &transaction_filters,
+            Method::DELETE,
+            &format!("component=mempool&id={}", deny_rule_id),
+            "".into(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, body) = send_request(
+            &transaction_filters,
+            Method::DELETE,
+            "component=mempool",
+            serde_json::to_string(&allow_rule).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(serde_json::from_str::<Rule>(&body).unwrap(), allow_rule);
+        assert!(transaction_filters.mempool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_filter_bad_requests() {
+        let transaction_filters = LiveTransactionFilters::new(&NodeConfig::default());
+        let rule = serde_json::to_string(&Rule::Deny(Matcher::BlockId(HashValue::zero()))).unwrap();
+        for (method, query, body) in [
+            // Missing or unknown component
+            (Method::GET, "", String::new()),
+            (Method::GET, "component=unknown", String::new()),
+            // Block ID based rules outside of consensus
+            (Method::POST, "component=quorum_store", rule.clone()),
+            // Malformed rule
+            (Method::POST, "component=mempool", "{}".to_string()),
+            // Nothing to remove
+            (Method::DELETE, "component=mempool", String::new()),
+            (Method::DELETE, "component=mempool&id=0", String::new()),
+            (Method::DELETE, "component=mempool", rule.clone()),
+        ] {
+            let (status, _) = send_request(&transaction_filters, method, query, body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+        }
+
+        // Block ID based rules are fine for consensus
+        let (status, _) = send_request(
+            &transaction_filters,
+            Method::POST,
+            "component=consensus",
+            rule,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Zstd dictionaries significantly improve the compression ratio of small
+//! payloads that share a common structure (e.g., BCS-encoded transactions
+//! and state values). Dictionaries are trained offline from sample payloads
+//! (see `train_dictionary`) and registered at startup (see `register_dictionary`).
+//!
+//! Each dictionary is identified by the ID stored in its header. Peers never
+//! infer the dictionary from the compressed frames: the dictionary ID is fixed
+//! by the network protocol (and the protocol is only advertised during the
+//! handshake if the dictionary is registered). As a result, data compressed with
+//! `CompressionCodec::ZstdWithDictionary(id)` can only be decompressed using the
+//! same codec (and the frame is rejected if it references a different dictionary).
+
+use crate::{zstd_codec::ZSTD_COMPRESSION_LEVEL, Error};
+use aptos_infallible::RwLock;
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, ops::Range, sync::Arc};
+use zstd::{
+    dict::{DecoderDictionary, EncoderDictionary},
+    zstd_safe,
+};
+
+/// The ID of the dictionary used to compress mempool transaction broadcasts
+pub const MEMPOOL_DICTIONARY_ID: u32 = 1_000_001;
+
+/// The ID of the dictionary used to compress storage service responses
+pub const STORAGE_SERVICE_DICTIONARY_ID: u32 = 1_000_002;
+
+/// The range of dictionary IDs that can be used by private deployments. All
+/// other IDs are reserved by the zstd format specification (see RFC 8878).
+const PRIVATE_DICTIONARY_ID_RANGE: Range<u32> = 32_768..(1 << 31);
+
+/// The magic number at the start of each zstd dictionary (little endian)
+const DICTIONARY_MAGIC_NUMBER: [u8; 4] = [0x37, 0xA4, 0x30, 0xEC];
+
+/// The registry of all known dictionaries (indexed by dictionary ID)
+static DICTIONARY_REGISTRY: Lazy<RwLock<HashMap<u32, Arc<CompressionDictionary>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// A registered dictionary, prepared for both compression and decompression
+pub(crate) struct CompressionDictionary {
+    pub(crate) encoder_dictionary: EncoderDictionary<'static>,
+    pub(crate) decoder_dictionary: DecoderDictionary<'static>,
+}
+
+/// Trains a new dictionary (of at most `max_dictionary_size` bytes) using the
+/// given data samples, and stamps the given ID into the dictionary header.
+pub fn train_dictionary<S: AsRef<[u8]>>(
+    samples: &[S],
+    max_dictionary_size: usize,
+    dictionary_id: u32,
+) -> Result<Vec<u8>, Error> {
+    // Verify the dictionary ID is in the private range
+    if !PRIVATE_DICTIONARY_ID_RANGE.contains(&dictionary_id) {
+        return Err(Error::DictionaryError(format!(
+            "The dictionary ID must be in the range {:?}! Got: {}",
+            PRIVATE_DICTIONARY_ID_RANGE, dictionary_id
+        )));
+    }
+
+    // Train the dictionary
+    let mut dictionary =
+        zstd::dict::from_samples(samples, max_dictionary_size).map_err(|error| {
+            Error::DictionaryError(format!("Failed to train dictionary: {}", error))
+        })?;
+
+    // Replace the (content derived) dictionary ID with the given ID. The ID
+    // immediately follows the magic number in the dictionary header.
+    if dictionary.len() < 8 || dictionary[0..4] != DICTIONARY_MAGIC_NUMBER {
+        return Err(Error::DictionaryError(
+            "The trained dictionary is missing the dictionary header!".into(),
+        ));
+    }
+    dictionary[4..8].copy_from_slice(&dictionary_id.to_le_bytes());
+
+    Ok(dictionary)
+}
+
+/// Registers the given dictionary, so that it can be used by the
+/// `CompressionCodec::ZstdWithDictionary` codec (with the same ID).
+/// Returns the ID of the dictionary.
+pub fn register_dictionary(dictionary: &[u8]) -> Result<u32, Error> {
+    // Get the dictionary ID
+    let dictionary_id = get_dictionary_id(dictionary)?;
+
+    // Update the registry
+    let compression_dictionary = CompressionDictionary {
+        encoder_dictionary: EncoderDictionary::copy(dictionary, ZSTD_COMPRESSION_LEVEL),
+        decoder_dictionary: DecoderDictionary::copy(dictionary),
+    };
+    DICTIONARY_REGISTRY
+        .write()
+        .insert(dictionary_id, Arc::new(compression_dictionary));
+
+    Ok(dictionary_id)
+}
+
+/// Returns the ID of the given dictionary. Only dictionaries with an ID are supported
+/// (i.e., trained dictionaries, and not raw content), otherwise the dictionary can't
+/// be identified.
+pub fn get_dictionary_id(dictionary: &[u8]) -> Result<u32, Error> {
+    match zstd_safe::get_dict_id_from_dict(dictionary) {
+        Some(dictionary_id) => Ok(dictionary_id.get()),
+        None => Err(Error::DictionaryError(
+            "The dictionary is missing an ID! Only trained dictionaries are supported.".into(),
+        )),
+    }
+}
+
+/// Returns true iff a dictionary with the given ID has been registered
+pub fn is_dictionary_registered(dictionary_id: u32) -> bool {
+    DICTIONARY_REGISTRY.read().contains_key(&dictionary_id)
+}
+
+/// Returns the dictionary with the given ID (if any)
+pub(crate) fn get_dictionary(dictionary_id: u32) -> Option<Arc<CompressionDictionary>> {
+    DICTIONARY_REGISTRY.read().get(&dictionary_id).cloned()
+}
@@ -13,16 +13,21 @@ use thiserror::Error;
 /// This crate provides a simple library interface for data compression.
 /// It is useful for compressing large data chunks that are
 /// sent across the network (e.g., by state sync and consensus).
-/// Internally, it uses LZ4 in fast mode to compress the data.
+/// By default, it uses LZ4 in fast mode to compress the data.
 /// See <https://github.com/10xGenomics/lz4-rs> for more information.
+/// Zstd is also supported, for protocols that prefer a better
+/// compression ratio over compression speed (optionally, with trained
+/// dictionaries, see the `dictionary` module for more information).
 ///
 /// Note: the crate also exposes some basic compression metrics
 /// that can be used to track the cumulative compression ratio
 /// and compression/decompression durations during the runtime.
 pub mod client;
+pub mod dictionary;
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod zstd_codec;
 
 /// The acceleration parameter to use for FAST compression mode.
 /// This was determined anecdotally.
@@ -31,6 +36,30 @@ const ACCELERATION_PARAMETER: i32 = 1;
 /// A useful wrapper for representing compressed data
 pub type CompressedData = Vec<u8>;
 
+/// The codecs that can be used to compress data. Note: the codec is not
+/// encoded in the compressed data, so the same codec must be used to
+/// decompress the data (e.g., as determined by the network protocol).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompressionCodec {
+    /// LZ4 block compression (in fast mode), prefixed with the raw data size
+    Lz4,
+    /// Zstd compression (without a dictionary)
+    Zstd,
+    /// Zstd compression with the registered dictionary of the given ID
+    ZstdWithDictionary(u32),
+}
+
+impl CompressionCodec {
+    /// Returns a summary label for the codec
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Lz4 => "lz4",
+            Self::Zstd => "zstd",
+            Self::ZstdWithDictionary(_) => "zstd_dictionary",
+        }
+    }
+}
+
 /// An error type for capturing compression/decompression failures
 #[derive(Clone, Debug, Error, Eq, PartialEq)]
 pub enum Error {
@@ -38,13 +67,25 @@ pub enum Error {
     CompressionError(String),
     #[error("Encountered a decompression error! Error: {0}")]
     DecompressionError(String),
+    #[error("Encountered a dictionary error! Error: {0}")]
+    DictionaryError(String),
 }
 
-/// Compresses the raw data stream
+/// Compresses the raw data stream (using LZ4)
 pub fn compress(
     raw_data: Vec<u8>,
     client: CompressionClient,
     max_bytes: usize,
+) -> Result<CompressedData, Error> {
+    compress_with_codec(raw_data, CompressionCodec::Lz4, client, max_bytes)
+}
+
+/// Compresses the raw data stream using the given codec
+pub fn compress_with_codec(
+    raw_data: Vec<u8>,
+    codec: CompressionCodec,
+    client: CompressionClient,
+    max_bytes: usize,
 ) -> Result<CompressedData, Error> {
     // Start the compression timer
     let start_time = Instant::now();
@@ -56,16 +97,25 @@ pub fn compress(
             raw_data.len(),
             max_bytes
         );
-        return create_compression_error(&client, error_string);
+        return create_compression_error(&client, codec, error_string);
     }
 
     // Compress the data
-    let compression_mode = CompressionMode::FAST(ACCELERATION_PARAMETER);
-    let compressed_data = match lz4::block::compress(&raw_data, Some(compression_mode), true) {
+    let compressed_data = match codec {
+        CompressionCodec::Lz4 => {
+            let compression_mode = CompressionMode::FAST(ACCELERATION_PARAMETER);
+            lz4::block::compress(&raw_data, Some(compression_mode), true)
+        },
+        CompressionCodec::Zstd => zstd_codec::compress(&raw_data, None),
+        CompressionCodec::ZstdWithDictionary(dictionary_id) => {
+            zstd_codec::compress(&raw_data, Some(dictionary_id))
+        },
+    };
+    let compressed_data = match compressed_data {
         Ok(compressed_data) => compressed_data,
         Err(error) => {
             let error_string = format!("Failed to compress the data: {}", error);
-            return create_compression_error(&client, error_string);
+            return create_compression_error(&client, codec, error_string);
         },
     };
 
@@ -78,44 +128,71 @@ pub fn compress(
             compressed_data.len(),
             max_bytes
         );
-        return create_compression_error(&client, error_string);
+        return create_compression_error(&client, codec, error_string);
     }
 
     // Stop the timer and update the metrics
-    metrics::observe_compression_operation_time(&client, start_time);
-    metrics::update_compression_metrics(&client, &raw_data, &compressed_data);
+    metrics::observe_compression_operation_time(&client, codec, start_time);
+    metrics::update_compression_metrics(&client, codec, &raw_data, &compressed_data);
 
     Ok(compressed_data)
 }
 
-/// Decompresses the compressed data stream
+/// Decompresses the compressed data stream (using LZ4)
 pub fn decompress(
     compressed_data: &CompressedData,
     client: CompressionClient,
     max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    decompress_with_codec(compressed_data, CompressionCodec::Lz4, client, max_size)
+}
+
+/// Decompresses the compressed data stream using the given codec
+pub fn decompress_with_codec(
+    compressed_data: &CompressedData,
+    codec: CompressionCodec,
+    client: CompressionClient,
+    max_size: usize,
 ) -> Result<Vec<u8>, Error> {
     // Start the decompression timer
     let start_time = Instant::now();
 
     // Check size of the data and initialize raw_data
-    let decompressed_size = match get_decompressed_size(compressed_data, max_size) {
+    let decompressed_size = match codec {
+        CompressionCodec::Lz4 => get_decompressed_size(compressed_data, max_size),
+        CompressionCodec::Zstd | CompressionCodec::ZstdWithDictionary(_) => {
+            zstd_codec::get_decompressed_size(compressed_data, max_size)
+        },
+    };
+    let decompressed_size = match decompressed_size {
         Ok(size) => size,
         Err(error) => {
             let error_string = format!("Failed to get decompressed size: {}", error);
-            return create_decompression_error(&client, error_string);
+            return create_decompression_error(&client, codec, error_string);
         },
     };
     let mut raw_data = vec![0u8; decompressed_size];
 
     // Decompress the data
-    if let Err(error) = lz4::block::decompress_to_buffer(compressed_data, None, &mut raw_data) {
+    let result = match codec {
+        CompressionCodec::Lz4 => {
+            lz4::block::decompress_to_buffer(compressed_data, None, &mut raw_data).map(|_| ())
+        },
+        CompressionCodec::Zstd => {
+            zstd_codec::decompress_to_buffer(compressed_data, &mut raw_data, None)
+        },
+        CompressionCodec::ZstdWithDictionary(dictionary_id) => {
+            zstd_codec::decompress_to_buffer(compressed_data, &mut raw_data, Some(dictionary_id))
+        },
+    };
+    if let Err(error) = result {
         let error_string = format!("Failed to decompress the data: {}", error);
-        return create_decompression_error(&client, error_string);
+        return create_decompression_error(&client, codec, error_string);
     };
 
     // Stop the timer and update the metrics
-    metrics::observe_decompression_operation_time(&client, start_time);
-    metrics::update_decompression_metrics(&client, compressed_data, &raw_data);
+    metrics::observe_decompression_operation_time(&client, codec, start_time);
+    metrics::update_decompression_metrics(&client, codec, compressed_data, &raw_data);
 
     Ok(raw_data)
 }
@@ -123,10 +200,11 @@ pub fn decompress(
 /// A simple utility function that wraps the given error string in a compression error
 fn create_compression_error(
     client: &CompressionClient,
+    codec: CompressionCodec,
     error_string: String,
 ) -> Result<CompressedData, Error> {
     // Increment the compression error counter
-    metrics::increment_compression_error(client);
+    metrics::increment_compression_error(client, codec);
 
     // Create and return the error
     Err(CompressionError(error_string))
@@ -135,10 +213,11 @@ fn create_compression_error(
 /// A simple utility function that wraps the given error string in a decompression error
 fn create_decompression_error(
     client: &CompressionClient,
+    codec: CompressionCodec,
     error_string: String,
 ) -> Result<Vec<u8>, Error> {
     // Increment the decompression error counter
-    metrics::increment_decompression_error(client);
+    metrics::increment_decompression_error(client, codec);
 
     // Create and return the error
     Err(DecompressionError(error_string))
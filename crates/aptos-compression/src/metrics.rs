@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::client::CompressionClient;
+use crate::{client::CompressionClient, CompressionCodec};
 use aptos_metrics_core::{
     exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
     IntCounterVec,
@@ -20,7 +20,7 @@ pub static BYTE_COUNTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_compression_byte_count",
         "Counters for tracking the data compression ratio",
-        &["operation", "data_type", "client"]
+        &["operation", "data_type", "client", "codec"]
     )
     .unwrap()
 });
@@ -30,7 +30,7 @@ pub static ERROR_COUNTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_compression_error_count",
         "Counters for tracking the data compression errors",
-        &["operation", "client"]
+        &["operation", "client", "codec"]
     )
     .unwrap()
 });
@@ -40,7 +40,7 @@ pub static OPERATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_compression_operation_latency",
         "Time it takes to perform a compression/decompression operation",
-        &["operation", "client"],
+        &["operation", "client", "codec"],
         exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
     )
     .unwrap()
@@ -51,63 +51,79 @@ fn increment_compression_byte_count(
     operation: &str,
     data_type: &str,
     client: &CompressionClient,
+    codec: CompressionCodec,
     byte_count: u64,
 ) {
     BYTE_COUNTS
-        .with_label_values(&[operation, data_type, client.get_label()])
+        .with_label_values(&[operation, data_type, client.get_label(), codec.get_label()])
         .inc_by(byte_count)
 }
 
 /// Increments the compression error count based on the given operation
-pub fn increment_compression_error(client: &CompressionClient) {
-    increment_error_count(COMPRESS, client)
+pub fn increment_compression_error(client: &CompressionClient, codec: CompressionCodec) {
+    increment_error_count(COMPRESS, client, codec)
 }
 
 /// Increments the decompression error count based on the given operation
-pub fn increment_decompression_error(client: &CompressionClient) {
-    increment_error_count(DECOMPRESS, client)
+pub fn increment_decompression_error(client: &CompressionClient, codec: CompressionCodec) {
+    increment_error_count(DECOMPRESS, client, codec)
 }
 
 /// Increments the error count based on the given operation
-fn increment_error_count(operation: &str, client: &CompressionClient) {
+fn increment_error_count(operation: &str, client: &CompressionClient, codec: CompressionCodec) {
     ERROR_COUNTS
-        .with_label_values(&[operation, client.get_label()])
+        .with_label_values(&[operation, client.get_label(), codec.get_label()])
         .inc()
 }
 
 /// Observes the compression operation time
-pub fn observe_compression_operation_time(client: &CompressionClient, start_time: Instant) {
-    observe_operation_time(COMPRESS, client, start_time)
+pub fn observe_compression_operation_time(
+    client: &CompressionClient,
+    codec: CompressionCodec,
+    start_time: Instant,
+) {
+    observe_operation_time(COMPRESS, client, codec, start_time)
 }
 
 /// Observes the decompression operation time
-pub fn observe_decompression_operation_time(client: &CompressionClient, start_time: Instant) {
-    observe_operation_time(DECOMPRESS, client, start_time)
+pub fn observe_decompression_operation_time(
+    client: &CompressionClient,
+    codec: CompressionCodec,
+    start_time: Instant,
+) {
+    observe_operation_time(DECOMPRESS, client, codec, start_time)
 }
 
 /// Observes the operation time based on the given operation
-fn observe_operation_time(operation: &str, client: &CompressionClient, start_time: Instant) {
+fn observe_operation_time(
+    operation: &str,
+    client: &CompressionClient,
+    codec: CompressionCodec,
+    start_time: Instant,
+) {
     OPERATION_LATENCY
-        .with_label_values(&[operation, client.get_label()])
+        .with_label_values(&[operation, client.get_label(), codec.get_label()])
         .observe(start_time.elapsed().as_secs_f64());
 }
 
 /// Updates the compression metrics for the given data sets
 pub fn update_compression_metrics(
     client: &CompressionClient,
+    codec: CompressionCodec,
     raw_data: &[u8],
     compressed_data: &[u8],
 ) {
-    update_operation_metrics(COMPRESS, client, raw_data, compressed_data);
+    update_operation_metrics(COMPRESS, client, codec, raw_data, compressed_data);
 }
 
 /// Updates the decompression metrics for the given data sets
 pub fn update_decompression_metrics(
     client: &CompressionClient,
+    codec: CompressionCodec,
     compressed_data: &[u8],
     raw_data: &[u8],
 ) {
-    update_operation_metrics(DECOMPRESS, client, raw_data, compressed_data);
+    update_operation_metrics(DECOMPRESS, client, codec, raw_data, compressed_data);
 }
 
 /// Updates the operation metrics based on the given data
@@ -115,14 +131,16 @@ pub fn update_decompression_metrics(
 fn update_operation_metrics(
     operation: &str,
     client: &CompressionClient,
+    codec: CompressionCodec,
     raw_data: &[u8],
     compressed_data: &[u8],
 ) {
-    increment_compression_byte_count(operation, RAW_BYTES, client, raw_data.len() as u64);
+    increment_compression_byte_count(operation, RAW_BYTES, client, codec, raw_data.len() as u64);
     increment_compression_byte_count(
         operation,
         COMPRESSED_BYTES,
         client,
+        codec,
         compressed_data.len() as u64,
     );
 }
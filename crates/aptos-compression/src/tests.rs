@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::CompressionClient;
+use crate::{dictionary, CompressionClient, CompressionCodec};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, hash::HashValue, PrivateKey, SigningKey, Uniform};
 use aptos_types::{
     account_address::AccountAddress,
@@ -72,27 +72,150 @@ fn test_compression_limits() {
         too_small_bytes,
     );
     assert!(maybe_decompressed_bytes.is_err());
-}
 
-/// Ensures that the given object can be compressed and decompressed successfully
-/// when BCS encoded.
-fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serialize>(object: T) {
-    let bcs_encoded_bytes = bcs::to_bytes(&object).unwrap();
-    let compressed_bytes = crate::compress(
+    // Test zstd decompression limit
+    let bcs_encoded_bytes = bcs::to_bytes(&transactions_with_proof).unwrap();
+    let compressed_bytes = crate::compress_with_codec(
         bcs_encoded_bytes,
+        CompressionCodec::Zstd,
         CompressionClient::StateSync,
         MAX_COMPRESSION_SIZE,
     )
     .unwrap();
-    let decompressed_bytes = crate::decompress(
+    let maybe_decompressed_bytes = crate::decompress_with_codec(
+        &compressed_bytes,
+        CompressionCodec::Zstd,
+        CompressionClient::StateSync,
+        too_small_bytes,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
+}
+
+#[test]
+fn test_dictionary_compression() {
+    // Train and register a dictionary using BCS encoded transactions
+    let dictionary_id = 1_000_100;
+    let dictionary = train_test_dictionary(dictionary_id);
+    assert_eq!(
+        dictionary::register_dictionary(&dictionary).unwrap(),
+        dictionary_id
+    );
+    assert!(dictionary::is_dictionary_registered(dictionary_id));
+
+    // Compress a transaction with and without the dictionary
+    let transaction = create_test_transaction(1001);
+    let compressed_size =
+        test_compress_and_decompress_with_codec(&transaction, CompressionCodec::Zstd);
+    let dictionary_compressed_size = test_compress_and_decompress_with_codec(
+        &transaction,
+        CompressionCodec::ZstdWithDictionary(dictionary_id),
+    );
+
+    // Verify the dictionary improves the compression ratio
+    assert!(dictionary_compressed_size < compressed_size);
+
+    // Verify that dictionary frames can't be decompressed without the
+    // dictionary, or with a different dictionary (and vice versa).
+    let transaction_bytes = bcs::to_bytes(&transaction).unwrap();
+    let dictionary_compressed_bytes = compress_with_codec(
+        transaction_bytes.clone(),
+        CompressionCodec::ZstdWithDictionary(dictionary_id),
+    );
+    let compressed_bytes = compress_with_codec(transaction_bytes, CompressionCodec::Zstd);
+    for (compressed_bytes, codec) in [
+        (&dictionary_compressed_bytes, CompressionCodec::Zstd),
+        (
+            &dictionary_compressed_bytes,
+            CompressionCodec::ZstdWithDictionary(dictionary_id + 1),
+        ),
+        (
+            &compressed_bytes,
+            CompressionCodec::ZstdWithDictionary(dictionary_id),
+        ),
+    ] {
+        let maybe_decompressed_bytes = crate::decompress_with_codec(
+            compressed_bytes,
+            codec,
+            CompressionClient::Mempool,
+            MAX_COMPRESSION_SIZE,
+        );
+        assert!(maybe_decompressed_bytes.is_err());
+    }
+}
+
+#[test]
+fn test_dictionary_registration() {
+    // Verify that dictionaries can't be trained with reserved IDs
+    let samples = create_test_samples();
+    for dictionary_id in [0, 32_767, 1 << 31, u32::MAX] {
+        assert!(dictionary::train_dictionary(&samples, 16 * 1024, dictionary_id).is_err());
+    }
+
+    // Verify that raw content dictionaries (without an ID) are rejected
+    assert!(dictionary::register_dictionary(&[0u8; 1024]).is_err());
+
+    // Verify that data can't be compressed with an unregistered dictionary
+    let dictionary_id = 1_000_200;
+    assert!(!dictionary::is_dictionary_registered(dictionary_id));
+    let maybe_compressed_bytes = crate::compress_with_codec(
+        bcs::to_bytes(&create_test_transaction(0)).unwrap(),
+        CompressionCodec::ZstdWithDictionary(dictionary_id),
+        CompressionClient::Mempool,
+        MAX_COMPRESSION_SIZE,
+    );
+    assert!(maybe_compressed_bytes.is_err());
+}
+
+/// Ensures that the given object can be compressed and decompressed successfully
+/// when BCS encoded (for all codecs).
+fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serialize>(object: T) {
+    for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+        test_compress_and_decompress_with_codec(&object, codec);
+    }
+}
+
+/// Ensures that the given object can be compressed and decompressed successfully
+/// when BCS encoded (using the given codec). Returns the compressed size.
+fn test_compress_and_decompress_with_codec<T: Debug + DeserializeOwned + PartialEq + Serialize>(
+    object: &T,
+    codec: CompressionCodec,
+) -> usize {
+    let bcs_encoded_bytes = bcs::to_bytes(object).unwrap();
+    let compressed_bytes = compress_with_codec(bcs_encoded_bytes, codec);
+    let decompressed_bytes = crate::decompress_with_codec(
         &compressed_bytes,
+        codec,
         CompressionClient::StateSync,
         MAX_COMPRESSION_SIZE,
     )
     .unwrap();
     let decoded_object = bcs::from_bytes::<T>(&decompressed_bytes).unwrap();
+    assert_eq!(object, &decoded_object);
+
+    compressed_bytes.len()
+}
+
+/// Compresses the given bytes using the codec (and verifies compression succeeds)
+fn compress_with_codec(raw_bytes: Vec<u8>, codec: CompressionCodec) -> Vec<u8> {
+    crate::compress_with_codec(
+        raw_bytes,
+        codec,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap()
+}
+
+/// Creates a set of BCS encoded transactions (to train dictionaries)
+fn create_test_samples() -> Vec<Vec<u8>> {
+    (0..1000)
+        .map(|sequence_number| bcs::to_bytes(&create_test_transaction(sequence_number)).unwrap())
+        .collect()
+}
 
-    assert_eq!(object, decoded_object);
+/// Trains a test dictionary with the given ID
+fn train_test_dictionary(dictionary_id: u32) -> Vec<u8> {
+    dictionary::train_dictionary(&create_test_samples(), 16 * 1024, dictionary_id).unwrap()
 }
 
 /// Creates a test epoch change proof
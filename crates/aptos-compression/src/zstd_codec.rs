@@ -0,0 +1,122 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{dictionary, CompressedData, Error, Error::DecompressionError};
+use std::{io, sync::Arc};
+use zstd::{
+    bulk::{Compressor, Decompressor},
+    zstd_safe,
+};
+
+/// The zstd compression level to use. This offers a good trade-off
+/// between the compression ratio and CPU time for network payloads.
+pub(crate) const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// Compresses the raw data using zstd (with the given dictionary, if any)
+pub(crate) fn compress(raw_data: &[u8], dictionary_id: Option<u32>) -> io::Result<Vec<u8>> {
+    match dictionary_id {
+        Some(dictionary_id) => {
+            let dictionary = get_dictionary(dictionary_id)?;
+            let mut compressor =
+                Compressor::with_prepared_dictionary(&dictionary.encoder_dictionary)?;
+
+            // Include a checksum, so that frames compressed with a different
+            // dictionary (that has the same ID) are detected by the receiver.
+            compressor.include_checksum(true)?;
+            compressor.compress(raw_data)
+        },
+        None => Compressor::new(ZSTD_COMPRESSION_LEVEL)?.compress(raw_data),
+    }
+}
+
+/// Decompresses the zstd frame into the given buffer (with the given dictionary,
+/// if any). The buffer must be exactly the size of the decompressed data.
+pub(crate) fn decompress_to_buffer(
+    compressed_data: &CompressedData,
+    raw_data: &mut [u8],
+    dictionary_id: Option<u32>,
+) -> io::Result<()> {
+    // Verify the frame uses the expected dictionary. Dictionaries are
+    // negotiated with the protocol, so they are never inferred from the frame.
+    let frame_dictionary_id =
+        zstd_safe::get_dict_id_from_frame(compressed_data).map(|dictionary_id| dictionary_id.get());
+    if frame_dictionary_id != dictionary_id {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Unexpected frame dictionary ID! Expected: {:?}, found: {:?}",
+                dictionary_id, frame_dictionary_id
+            ),
+        ));
+    }
+
+    // Decompress the data
+    let decompressed_size = match dictionary_id {
+        Some(dictionary_id) => {
+            let dictionary = get_dictionary(dictionary_id)?;
+            Decompressor::with_prepared_dictionary(&dictionary.decoder_dictionary)?
+                .decompress_to_buffer(compressed_data, raw_data)?
+        },
+        None => Decompressor::new()?.decompress_to_buffer(compressed_data, raw_data)?,
+    };
+
+    // Verify the decompressed size matches the frame
+    if decompressed_size != raw_data.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Decompressed size does not match the frame content size: {} != {}",
+                decompressed_size,
+                raw_data.len()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the registered dictionary with the given ID
+fn get_dictionary(dictionary_id: u32) -> io::Result<Arc<dictionary::CompressionDictionary>> {
+    dictionary::get_dictionary(dictionary_id).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "The dictionary is not registered! Dictionary ID: {}",
+                dictionary_id
+            ),
+        )
+    })
+}
+
+/// Returns the decompressed size of the zstd frame (as specified
+/// in the frame header).
+pub(crate) fn get_decompressed_size(
+    compressed_data: &CompressedData,
+    max_size: usize,
+) -> Result<usize, Error> {
+    // Parse the content size from the frame header
+    let size = match zstd_safe::get_frame_content_size(compressed_data) {
+        Ok(Some(size)) => size,
+        Ok(None) => {
+            return Err(DecompressionError(
+                "The frame header is missing the content size!".into(),
+            ));
+        },
+        Err(error) => {
+            return Err(DecompressionError(format!(
+                "Failed to parse the frame content size: {}",
+                error
+            )));
+        },
+    };
+
+    // Ensure that the size is not greater than the max size limit
+    if size > max_size as u64 {
+        return Err(DecompressionError(format!(
+            "Parsed frame content size is too big: {} > {}",
+            size, max_size
+        )));
+    }
+
+    Ok(size as usize)
+}
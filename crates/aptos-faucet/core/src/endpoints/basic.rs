@@ -68,6 +68,11 @@ impl BasicApi {
             )));
         }
 
-        Ok(PlainText("tap:ok".to_string()))
+        // Include any details the Funder reported, e.g. the health of each of
+        // its accounts.
+        match funder_health.message {
+            Some(message) => Ok(PlainText(format!("tap:ok\n{}", message))),
+            None => Ok(PlainText("tap:ok".to_string())),
+        }
     }
 }
@@ -4,13 +4,16 @@
 mod common;
 mod fake;
 mod mint;
+mod pooled;
 mod transfer;
 
 pub use self::{
     common::{ApiConnectionConfig, TransactionSubmissionConfig},
     mint::MintFunderConfig,
 };
-use self::{fake::FakeFunderConfig, transfer::TransferFunderConfig};
+use self::{
+    fake::FakeFunderConfig, pooled::PooledTransferFunderConfig, transfer::TransferFunderConfig,
+};
 use crate::endpoints::AptosTapError;
 use anyhow::{Context, Result};
 use aptos_sdk::types::{account_address::AccountAddress, transaction::SignedTransaction};
@@ -18,6 +21,7 @@ use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
 pub use fake::FakeFunder;
 pub use mint::MintFunder;
+pub use pooled::PooledTransferFunder;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 pub use transfer::TransferFunder;
@@ -75,6 +79,11 @@ pub enum FunderConfig {
     /// This funder creates and funds accounts by using + transferring
     /// coins from a pre-funded account provided in configuration.
     TransferFunder(TransferFunderConfig),
+
+    /// This funder spreads requests across a pool of pre-funded accounts,
+    /// topping them up from a root account provided in configuration when
+    /// they run low.
+    PooledTransferFunder(PooledTransferFunderConfig),
 }
 
 impl FunderConfig {
@@ -93,6 +102,12 @@ impl FunderConfig {
                     .await
                     .context("Failed to build TransferFunder")?,
             ))),
+            FunderConfig::PooledTransferFunder(config) => Ok(Arc::new(Funder::from(
+                config
+                    .build_funder()
+                    .await
+                    .context("Failed to build PooledTransferFunder")?,
+            ))),
         }
     }
 }
@@ -103,6 +118,7 @@ pub enum Funder {
     FakeFunder,
     MintFunder,
     TransferFunder,
+    PooledTransferFunder,
 }
 
 #[derive(Debug, Clone)]
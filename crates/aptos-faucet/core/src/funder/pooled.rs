@@ -0,0 +1,671 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    common::{read_private_key_file, ApiConnectionConfig, TransactionSubmissionConfig},
+    transfer::{account_address_from_private_key, AmountToFund, MinimumFunds},
+    FunderHealthMessage, FunderTrait, TransferFunder,
+};
+use crate::{
+    endpoints::{AptosTapError, AptosTapErrorCode},
+    middleware::POOLED_FUNDER_ACCOUNT_BALANCE,
+};
+use anyhow::{bail, Result};
+use aptos_logger::{info, warn};
+use aptos_sdk::{
+    crypto::ed25519::Ed25519PrivateKey,
+    types::{account_address::AccountAddress, transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PooledTransferFunderConfig {
+    /// The key in this config is for the root account, which is only used to
+    /// top up the funding accounts when they run low.
+    #[serde(flatten)]
+    pub api_connection_config: ApiConnectionConfig,
+
+    #[serde(flatten)]
+    pub transaction_submission_config: TransactionSubmissionConfig,
+
+    /// Paths to the private keys of the funding accounts. Requests are spread
+    /// across these accounts, so each one gets its own stream of sequence
+    /// numbers.
+    pub funding_account_key_file_paths: Vec<PathBuf>,
+
+    /// The minimum amount of coins each funding account should have. Funding
+    /// accounts below this will not be used until they've been topped up.
+    pub minimum_funds: MinimumFunds,
+
+    /// The amount of coins to fund the receiver account.
+    pub amount_to_fund: AmountToFund,
+
+    /// If the balance of a funding account falls below this amount, it will be
+    /// topped up from the root account.
+    pub rebalance_threshold: u64,
+
+    /// The balance a funding account is topped up to when rebalancing.
+    pub rebalance_target: u64,
+
+    /// How often to check the balances of the funding accounts and rebalance
+    /// them. Rebalancing is also triggered early whenever a request leaves a
+    /// funding account below the rebalance threshold.
+    #[serde(default = "PooledTransferFunderConfig::default_rebalance_interval_secs")]
+    pub rebalance_interval_secs: u64,
+}
+
+impl PooledTransferFunderConfig {
+    fn default_rebalance_interval_secs() -> u64 {
+        60
+    }
+
+    pub async fn build_funder(&self) -> Result<PooledTransferFunder> {
+        if self.funding_account_key_file_paths.is_empty() {
+            bail!("At least one funding account must be configured");
+        }
+        if self.rebalance_threshold < self.minimum_funds.0 {
+            bail!(
+                "rebalance_threshold ({}) must not be less than minimum_funds ({})",
+                self.rebalance_threshold,
+                self.minimum_funds
+            );
+        }
+        if self.rebalance_target <= self.rebalance_threshold {
+            bail!(
+                "rebalance_target ({}) must be greater than rebalance_threshold ({})",
+                self.rebalance_target,
+                self.rebalance_threshold
+            );
+        }
+
+        // Build the funder for the root account.
+        let (root_address, root_funder) =
+            self.build_transfer_funder(self.api_connection_config.get_key()?);
+
+        // Build the funders for each of the funding accounts.
+        let mut funding_accounts = vec![];
+        for key_file_path in &self.funding_account_key_file_paths {
+            let (address, funder) =
+                self.build_transfer_funder(read_private_key_file(key_file_path.as_path())?);
+            funding_accounts.push(FundingAccount {
+                address,
+                funder,
+                balance: AtomicU64::new(0),
+                num_in_flight_requests: AtomicU64::new(0),
+            });
+        }
+        let funding_accounts = Arc::new(funding_accounts);
+
+        // Fetch the initial balances and top up any accounts that are low before
+        // we start handling requests, then keep rebalancing in the background.
+        let rebalancer = Arc::new(Rebalancer {
+            root_address,
+            root_funder,
+            funding_accounts: funding_accounts.clone(),
+            rebalance_threshold: self.rebalance_threshold,
+            rebalance_target: self.rebalance_target,
+            rebalance_interval: Duration::from_secs(self.rebalance_interval_secs),
+            rebalance_requested: Notify::new(),
+        });
+        rebalancer.rebalance().await;
+        tokio::spawn(rebalancer.clone().run());
+
+        Ok(PooledTransferFunder {
+            funding_accounts,
+            next_funding_account_index: AtomicUsize::new(0),
+            rebalancer,
+            minimum_funds: self.minimum_funds,
+            amount_to_fund: self.amount_to_fund,
+        })
+    }
+
+    fn build_transfer_funder(&self, key: Ed25519PrivateKey) -> (AccountAddress, TransferFunder) {
+        let account_address = account_address_from_private_key(&key);
+        let funder = TransferFunder::new(
+            LocalAccount::new(account_address, key, 0),
+            self.api_connection_config.chain_id,
+            self.api_connection_config.node_url.clone(),
+            self.minimum_funds,
+            self.amount_to_fund,
+            self.transaction_submission_config
+                .get_gas_unit_price_ttl_secs(),
+            self.transaction_submission_config.gas_unit_price_override,
+            self.transaction_submission_config.max_gas_amount,
+            self.transaction_submission_config
+                .transaction_expiration_secs,
+            self.transaction_submission_config
+                .wait_for_outstanding_txns_secs,
+            self.transaction_submission_config.wait_for_transactions,
+        );
+        (account_address, funder)
+    }
+}
+
+struct FundingAccount {
+    address: AccountAddress,
+
+    /// Each funding account has its own TransferFunder, so requests dispatched
+    /// to different accounts don't contend on the same sequence numbers.
+    funder: TransferFunder,
+
+    /// The last known balance of the account. This is refreshed from the chain
+    /// when rebalancing, and is optimistically decremented whenever we fund an
+    /// account from it.
+    balance: AtomicU64,
+
+    /// The number of requests currently being handled by this account.
+    num_in_flight_requests: AtomicU64,
+}
+
+impl FundingAccount {
+    fn set_balance(&self, balance: u64) {
+        self.balance.store(balance, Ordering::Relaxed);
+        POOLED_FUNDER_ACCOUNT_BALANCE
+            .with_label_values(&[&self.address.to_hex_literal()])
+            .set(balance as i64);
+    }
+
+    fn deduct_from_balance(&self, amount: u64) -> u64 {
+        let previous_balance = self
+            .balance
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |balance| {
+                Some(balance.saturating_sub(amount))
+            })
+            .unwrap();
+        previous_balance.saturating_sub(amount)
+    }
+
+    fn can_fund(&self, amount: u64, minimum_funds: MinimumFunds) -> bool {
+        self.balance.load(Ordering::Relaxed) >= minimum_funds.0.saturating_add(amount)
+    }
+}
+
+/// Decrements the in flight request count of a funding account when dropped,
+/// so the count stays accurate even if the request is cancelled.
+struct InFlightRequestGuard<'a> {
+    funding_account: &'a FundingAccount,
+}
+
+impl<'a> InFlightRequestGuard<'a> {
+    fn new(funding_account: &'a FundingAccount) -> Self {
+        funding_account
+            .num_in_flight_requests
+            .fetch_add(1, Ordering::Relaxed);
+        Self { funding_account }
+    }
+}
+
+impl Drop for InFlightRequestGuard<'_> {
+    fn drop(&mut self) {
+        self.funding_account
+            .num_in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Tops up the funding accounts from the root account whenever their balance
+/// falls below the rebalance threshold.
+struct Rebalancer {
+    root_address: AccountAddress,
+    root_funder: TransferFunder,
+    funding_accounts: Arc<Vec<FundingAccount>>,
+    rebalance_threshold: u64,
+    rebalance_target: u64,
+    rebalance_interval: Duration,
+    rebalance_requested: Notify,
+}
+
+impl Rebalancer {
+    async fn run(self: Arc<Self>) {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(self.rebalance_interval) => {},
+                _ = self.rebalance_requested.notified() => {},
+            }
+            self.rebalance().await;
+        }
+    }
+
+    /// Request a rebalance ahead of the next scheduled one.
+    fn request_rebalance(&self) {
+        self.rebalance_requested.notify_one();
+    }
+
+    /// Refresh the balances of all the funding accounts and top up those that
+    /// are low. The funding accounts are handled concurrently, the transfers
+    /// from the root account are sequenced by its TransferFunder.
+    async fn rebalance(&self) {
+        join_all(
+            self.funding_accounts
+                .iter()
+                .map(|funding_account| self.rebalance_funding_account(funding_account)),
+        )
+        .await;
+    }
+
+    async fn rebalance_funding_account(&self, funding_account: &FundingAccount) {
+        let balance = match funding_account.funder.get_account_balance().await {
+            Ok(balance) => balance,
+            Err(e) => {
+                warn!(
+                    "Failed to get the balance of funding account {}: {:#}",
+                    funding_account.address, e
+                );
+                return;
+            },
+        };
+        funding_account.set_balance(balance);
+
+        let Some(amount) = self.get_top_up_amount(balance) else {
+            return;
+        };
+        match self
+            .root_funder
+            .transfer(funding_account.address, amount)
+            .await
+        {
+            Ok(txn) => {
+                info!(
+                    hash = txn.committed_hash().to_hex_literal(),
+                    address = funding_account.address,
+                    amount = amount,
+                    event = "funding_account_rebalanced"
+                );
+                funding_account.set_balance(self.rebalance_target);
+            },
+            Err(e) => warn!(
+                "Failed to top up funding account {} from root account {}: {:#}",
+                funding_account.address, self.root_address, e
+            ),
+        }
+    }
+
+    /// The amount to transfer to a funding account with the given balance, if
+    /// it has fallen below the rebalance threshold.
+    fn get_top_up_amount(&self, balance: u64) -> Option<u64> {
+        if balance >= self.rebalance_threshold {
+            return None;
+        }
+        Some(self.rebalance_target - balance)
+    }
+}
+
+/// This funder spreads requests across a pool of funding accounts, each of
+/// which works like a TransferFunder. This avoids all requests contending on
+/// the sequence number of a single account. Funding accounts are topped up
+/// from a root account when they run low.
+pub struct PooledTransferFunder {
+    funding_accounts: Arc<Vec<FundingAccount>>,
+
+    /// Used to rotate between funding accounts with the same load.
+    next_funding_account_index: AtomicUsize,
+
+    rebalancer: Arc<Rebalancer>,
+
+    /// The minimum amount of funds each funding account should have to be used.
+    minimum_funds: MinimumFunds,
+
+    /// Maximum amount we'll fund an account.
+    amount_to_fund: AmountToFund,
+}
+
+impl PooledTransferFunder {
+    /// Pick the funding account with the fewest in flight requests out of those
+    /// with enough funds to handle the request.
+    fn select_funding_account(&self, amount: u64) -> Result<&FundingAccount, AptosTapError> {
+        let num_funding_accounts = self.funding_accounts.len();
+        let start_index = self
+            .next_funding_account_index
+            .fetch_add(1, Ordering::Relaxed);
+        (0..num_funding_accounts)
+            .map(|offset| &self.funding_accounts[(start_index + offset) % num_funding_accounts])
+            .filter(|funding_account| funding_account.can_fund(amount, self.minimum_funds))
+            .min_by_key(|funding_account| {
+                funding_account
+                    .num_in_flight_requests
+                    .load(Ordering::Relaxed)
+            })
+            .ok_or_else(|| {
+                // Make sure the accounts get topped up soon.
+                self.rebalancer.request_rebalance();
+                AptosTapError::new(
+                    "Tap PooledTransferFunder has no funding accounts with sufficient funds right now".to_string(),
+                    AptosTapErrorCode::FunderAccountProblem,
+                )
+            })
+    }
+}
+
+#[async_trait]
+impl FunderTrait for PooledTransferFunder {
+    async fn fund(
+        &self,
+        amount: Option<u64>,
+        receiver_address: AccountAddress,
+        check_only: bool,
+        did_bypass_checkers: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        let amount = self.get_amount(amount, did_bypass_checkers);
+
+        let funding_account = self.select_funding_account(amount)?;
+        let _in_flight_request_guard = InFlightRequestGuard::new(funding_account);
+
+        let transactions = funding_account
+            .funder
+            .fund(
+                Some(amount),
+                receiver_address,
+                check_only,
+                did_bypass_checkers,
+            )
+            .await?;
+
+        if !transactions.is_empty()
+            && funding_account.deduct_from_balance(amount) < self.rebalancer.rebalance_threshold
+        {
+            self.rebalancer.request_rebalance();
+        }
+
+        Ok(transactions)
+    }
+
+    fn get_amount(
+        &self,
+        amount: Option<u64>,
+        // Ignored for now, same as with the TransferFunder.
+        _did_bypass_checkers: bool,
+    ) -> u64 {
+        match amount {
+            Some(amount) => std::cmp::min(amount, self.amount_to_fund.0),
+            None => self.amount_to_fund.0,
+        }
+    }
+
+    /// Report the health of each funding account. The funder can process
+    /// requests as long as at least one funding account has the minimum funds.
+    /// This uses the balances cached by the rebalancer (and decremented as
+    /// requests are handled), so health checks don't hit the node.
+    async fn is_healthy(&self) -> FunderHealthMessage {
+        let mut can_process_requests = false;
+        let mut needs_rebalance = false;
+        let mut messages = vec![];
+        for funding_account in self.funding_accounts.iter() {
+            let balance = funding_account.balance.load(Ordering::Relaxed);
+            needs_rebalance |= balance < self.rebalancer.rebalance_threshold;
+            let message = if balance >= self.minimum_funds.0 {
+                can_process_requests = true;
+                format!("ok (balance: {})", balance)
+            } else {
+                format!(
+                    "insufficient funds (balance: {}, minimum: {})",
+                    balance, self.minimum_funds
+                )
+            };
+            messages.push(format!(
+                "Funding account {}: {}",
+                funding_account.address, message
+            ));
+        }
+
+        if needs_rebalance {
+            self.rebalancer.request_rebalance();
+        }
+
+        FunderHealthMessage {
+            can_process_requests,
+            message: Some(messages.join("\n")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::{crypto::Uniform, types::chain_id::ChainId};
+    use rand::{
+        rngs::{OsRng, StdRng},
+        Rng, SeedableRng,
+    };
+    use reqwest::Url;
+    use std::str::FromStr;
+
+    const MINIMUM_FUNDS: u64 = 1_000;
+    const AMOUNT_TO_FUND: u64 = 100;
+    const REBALANCE_THRESHOLD: u64 = 5_000;
+    const REBALANCE_TARGET: u64 = 10_000;
+
+    // The funders never reach this node, none of these tests submit transactions.
+    fn build_transfer_funder() -> (AccountAddress, TransferFunder) {
+        let key = Ed25519PrivateKey::generate(&mut StdRng::from_seed(OsRng.gen()));
+        let account_address = account_address_from_private_key(&key);
+        let funder = TransferFunder::new(
+            LocalAccount::new(account_address, key, 0),
+            ChainId::test(),
+            Url::from_str("http://127.0.0.1:1").unwrap(),
+            MinimumFunds(MINIMUM_FUNDS),
+            AmountToFund(AMOUNT_TO_FUND),
+            Duration::from_secs(30),
+            Some(100),
+            1_000,
+            30,
+            30,
+            false,
+        );
+        (account_address, funder)
+    }
+
+    fn build_pooled_funder(balances: &[u64]) -> PooledTransferFunder {
+        let funding_accounts = Arc::new(
+            balances
+                .iter()
+                .map(|balance| {
+                    let (address, funder) = build_transfer_funder();
+                    FundingAccount {
+                        address,
+                        funder,
+                        balance: AtomicU64::new(*balance),
+                        num_in_flight_requests: AtomicU64::new(0),
+                    }
+                })
+                .collect::<Vec<_>>(),
+        );
+        let (root_address, root_funder) = build_transfer_funder();
+        let rebalancer = Arc::new(Rebalancer {
+            root_address,
+            root_funder,
+            funding_accounts: funding_accounts.clone(),
+            rebalance_threshold: REBALANCE_THRESHOLD,
+            rebalance_target: REBALANCE_TARGET,
+            rebalance_interval: Duration::from_secs(60),
+            rebalance_requested: Notify::new(),
+        });
+        PooledTransferFunder {
+            funding_accounts,
+            next_funding_account_index: AtomicUsize::new(0),
+            rebalancer,
+            minimum_funds: MinimumFunds(MINIMUM_FUNDS),
+            amount_to_fund: AmountToFund(AMOUNT_TO_FUND),
+        }
+    }
+
+    /// Returns whether a rebalance was requested (consuming the request).
+    async fn rebalance_was_requested(funder: &PooledTransferFunder) -> bool {
+        tokio::time::timeout(
+            Duration::from_millis(100),
+            funder.rebalancer.rebalance_requested.notified(),
+        )
+        .await
+        .is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_select_funding_account_least_loaded() {
+        let funder = build_pooled_funder(&[REBALANCE_TARGET; 3]);
+
+        // With no load, the accounts are used in turn.
+        for i in 0..3 {
+            let funding_account = funder.select_funding_account(AMOUNT_TO_FUND).unwrap();
+            assert_eq!(funding_account.address, funder.funding_accounts[i].address);
+        }
+
+        // Accounts with in flight requests are avoided until the requests finish.
+        let guard_0 = InFlightRequestGuard::new(&funder.funding_accounts[0]);
+        let guard_1 = InFlightRequestGuard::new(&funder.funding_accounts[1]);
+        for _ in 0..3 {
+            let funding_account = funder.select_funding_account(AMOUNT_TO_FUND).unwrap();
+            assert_eq!(funding_account.address, funder.funding_accounts[2].address);
+        }
+        drop(guard_0);
+        let funding_account = funder.select_funding_account(AMOUNT_TO_FUND).unwrap();
+        assert_ne!(funding_account.address, funder.funding_accounts[1].address);
+        drop(guard_1);
+        assert!(funder
+            .funding_accounts
+            .iter()
+            .all(|funding_account| funding_account
+                .num_in_flight_requests
+                .load(Ordering::Relaxed)
+                == 0));
+        assert!(!rebalance_was_requested(&funder).await);
+    }
+
+    #[tokio::test]
+    async fn test_select_funding_account_insufficient_funds() {
+        let funder = build_pooled_funder(&[0, MINIMUM_FUNDS + AMOUNT_TO_FUND, MINIMUM_FUNDS]);
+
+        // Only the account that can cover the amount on top of the minimum is used.
+        for _ in 0..3 {
+            let funding_account = funder.select_funding_account(AMOUNT_TO_FUND).unwrap();
+            assert_eq!(funding_account.address, funder.funding_accounts[1].address);
+        }
+
+        // Once no account can cover the amount, we fail and request a rebalance.
+        funder.funding_accounts[1].deduct_from_balance(AMOUNT_TO_FUND);
+        let error = funder.select_funding_account(AMOUNT_TO_FUND).err().unwrap();
+        assert_eq!(error.error_code, AptosTapErrorCode::FunderAccountProblem);
+        assert!(rebalance_was_requested(&funder).await);
+    }
+
+    #[test]
+    fn test_deduct_from_balance() {
+        let funder = build_pooled_funder(&[AMOUNT_TO_FUND * 2]);
+        let funding_account = &funder.funding_accounts[0];
+        assert_eq!(
+            funding_account.deduct_from_balance(AMOUNT_TO_FUND),
+            AMOUNT_TO_FUND
+        );
+        assert_eq!(funding_account.deduct_from_balance(AMOUNT_TO_FUND * 2), 0);
+        assert_eq!(funding_account.balance.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_get_top_up_amount() {
+        let funder = build_pooled_funder(&[]);
+        let rebalancer = &funder.rebalancer;
+        assert_eq!(rebalancer.get_top_up_amount(REBALANCE_TARGET), None);
+        assert_eq!(rebalancer.get_top_up_amount(REBALANCE_THRESHOLD), None);
+        assert_eq!(
+            rebalancer.get_top_up_amount(REBALANCE_THRESHOLD - 1),
+            Some(REBALANCE_TARGET - REBALANCE_THRESHOLD + 1)
+        );
+        assert_eq!(rebalancer.get_top_up_amount(0), Some(REBALANCE_TARGET));
+    }
+
+    #[tokio::test]
+    async fn test_is_healthy_uses_cached_balances() {
+        // All accounts are funded, nothing to rebalance.
+        let funder = build_pooled_funder(&[REBALANCE_TARGET, REBALANCE_THRESHOLD]);
+        let health = funder.is_healthy().await;
+        assert!(health.can_process_requests);
+        assert!(!rebalance_was_requested(&funder).await);
+
+        // One account is below the minimum, we can still process requests with
+        // the other one but the low account gets rebalanced.
+        funder.funding_accounts[0].set_balance(MINIMUM_FUNDS - 1);
+        let health = funder.is_healthy().await;
+        assert!(health.can_process_requests);
+        assert!(health.message.unwrap().contains("insufficient funds"));
+        assert!(rebalance_was_requested(&funder).await);
+
+        // All accounts are below the minimum.
+        funder.funding_accounts[1].set_balance(0);
+        assert!(!funder.is_healthy().await.can_process_requests);
+        assert!(rebalance_was_requested(&funder).await);
+    }
+
+    #[test]
+    fn test_get_amount() {
+        let funder = build_pooled_funder(&[REBALANCE_TARGET]);
+        assert_eq!(funder.get_amount(None, false), AMOUNT_TO_FUND);
+        assert_eq!(funder.get_amount(Some(10), false), 10);
+        assert_eq!(
+            funder.get_amount(Some(AMOUNT_TO_FUND + 1), true),
+            AMOUNT_TO_FUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_funder_validates_config() {
+        let build_funder = |funding_account_key_file_paths: &str,
+                            rebalance_threshold: u64,
+                            rebalance_target: u64| {
+            let config: PooledTransferFunderConfig = serde_yaml::from_str(&format!(
+                r#"
+node_url: "http://127.0.0.1:1"
+chain_id: 4
+key_file_path: "/tmp/pooled_transfer_funder_root.key"
+funding_account_key_file_paths: {}
+minimum_funds: {}
+amount_to_fund: {}
+rebalance_threshold: {}
+rebalance_target: {}
+"#,
+                funding_account_key_file_paths,
+                MINIMUM_FUNDS,
+                AMOUNT_TO_FUND,
+                rebalance_threshold,
+                rebalance_target
+            ))
+            .unwrap();
+            async move { config.build_funder().await }
+        };
+
+        let error = build_funder("[]", REBALANCE_THRESHOLD, REBALANCE_TARGET)
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("At least one funding account"));
+
+        let funding_account_key_file_paths = r#"["/tmp/pooled_transfer_funder_0.key"]"#;
+        let error = build_funder(
+            funding_account_key_file_paths,
+            MINIMUM_FUNDS - 1,
+            REBALANCE_TARGET,
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("rebalance_threshold"));
+
+        let error = build_funder(
+            funding_account_key_file_paths,
+            REBALANCE_THRESHOLD,
+            REBALANCE_THRESHOLD,
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("rebalance_target"));
+    }
+}
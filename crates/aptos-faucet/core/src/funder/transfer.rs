@@ -200,6 +200,45 @@ impl TransferFunder {
         .await
     }
 
+    /// Get the balance of the funder account.
+    pub async fn get_account_balance(&self) -> Result<u64> {
+        let account_address = self.faucet_account.read().await.address();
+        Ok(self
+            .get_api_client()
+            .get_account_balance_bcs::<AptosCoinType>(account_address)
+            .await?
+            .into_inner())
+    }
+
+    /// Transfer coins from the funder account to an account that may already
+    /// exist. Unlike `fund`, this does no eligibility checks on the receiver,
+    /// so it should only be used for internal transfers, e.g. topping up the
+    /// funding accounts of a PooledTransferFunder.
+    pub async fn transfer(
+        &self,
+        receiver_address: AccountAddress,
+        amount: u64,
+    ) -> Result<SignedTransaction, AptosTapError> {
+        let client = self.get_api_client();
+
+        update_sequence_numbers(
+            &client,
+            &self.faucet_account,
+            &self.outstanding_requests,
+            receiver_address,
+            amount,
+            self.wait_for_outstanding_txns_secs,
+        )
+        .await?;
+
+        self.execute_transaction(
+            &client,
+            aptos_stdlib::aptos_account_transfer(receiver_address, amount),
+            &receiver_address,
+        )
+        .await
+    }
+
     async fn is_healthy_as_result(&self) -> Result<(), AptosTapError> {
         let funder_health = self.is_healthy().await;
         if !funder_health.can_process_requests {
@@ -312,12 +351,8 @@ impl FunderTrait for TransferFunder {
     /// Assert funder account actually exists and has the minimum funds.
     async fn is_healthy(&self) -> FunderHealthMessage {
         let account_address = self.faucet_account.read().await.address();
-        let funder_balance = match self
-            .get_api_client()
-            .get_account_balance_bcs::<AptosCoinType>(account_address)
-            .await
-        {
-            Ok(response) => response.into_inner(),
+        let funder_balance = match self.get_account_balance().await {
+            Ok(funder_balance) => funder_balance,
             Err(e) => return FunderHealthMessage {
                 can_process_requests: false,
                 message: Some(format!(
@@ -346,7 +381,7 @@ impl FunderTrait for TransferFunder {
     }
 }
 
-fn account_address_from_private_key(private_key: &Ed25519PrivateKey) -> AccountAddress {
+pub fn account_address_from_private_key(private_key: &Ed25519PrivateKey) -> AccountAddress {
     let public_key = private_key.public_key();
     let auth_key = AuthenticationKey::ed25519(&public_key);
     AccountAddress::new(*auth_key.account_address())
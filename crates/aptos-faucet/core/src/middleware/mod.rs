@@ -8,6 +8,6 @@ pub use self::{
     log::middleware_log,
     metrics::{
         bump_rejection_reason_counters, NUM_OUTSTANDING_TRANSACTIONS,
-        TRANSFER_FUNDER_ACCOUNT_BALANCE,
+        POOLED_FUNDER_ACCOUNT_BALANCE, TRANSFER_FUNDER_ACCOUNT_BALANCE,
     },
 };
@@ -782,6 +782,112 @@ mod test {
         Ok(())
     }
 
+    // Create an account with a fresh private key stored at the given path, and
+    // fund it with the given amount using the prod devnet faucet.
+    async fn create_devnet_account(key_file_path: &str, amount: u64) -> Result<AccountAddress> {
+        let private_key = Ed25519PrivateKey::generate(&mut StdRng::from_seed(OsRng.gen()));
+        let serialized_keys = aptos_sdk::bcs::to_bytes(&private_key)?;
+        let mut key_file = std::fs::File::create(key_file_path)?;
+        key_file.write_all(&serialized_keys)?;
+
+        let account_address =
+            AuthenticationKey::ed25519(&private_key.public_key()).account_address();
+        unwrap_reqwest_result(
+            reqwest::Client::new()
+                .post("https://faucet.devnet.aptoslabs.com/fund")
+                .body(
+                    FundRequest {
+                        amount: Some(amount),
+                        address: Some(account_address.to_string()),
+                        ..Default::default()
+                    }
+                    .to_json_string(),
+                )
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+                .await,
+        )
+        .await?;
+        Ok(account_address)
+    }
+
+    // We skip this for now since we have no current need to use the
+    // PooledTransferFunder outside of devnet.
+    #[ignore]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pooled_transfer_funder() -> Result<()> {
+        // Create the root account and the funding accounts at the paths expected
+        // by the config for this test. The funding accounts start out below the
+        // rebalance threshold, so they get topped up from the root account when
+        // the funder is built.
+        create_devnet_account("/tmp/pooled_transfer_funder_root_devnet.key", 500_000_000).await?;
+        let mut funding_account_addresses = vec![];
+        for i in 0..4 {
+            funding_account_addresses.push(
+                create_devnet_account(
+                    &format!("/tmp/pooled_transfer_funder_devnet_{}.key", i),
+                    10_000_000,
+                )
+                .await?,
+            );
+        }
+
+        // Wait a few seconds for all the fullnodes to catch up.
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        // Start the server, using the accounts we just created.
+        init();
+        let config_content = include_str!("../../../configs/testing_pooled_transfer_funder.yaml");
+        let (port, _handle) = start_server(config_content).await?;
+
+        // Assert that `/` returns healthy.
+        unwrap_reqwest_result(
+            reqwest::Client::new()
+                .get(get_root_endpoint(port))
+                .send()
+                .await,
+        )
+        .await?;
+
+        // Make more requests than there are funding accounts and assert that they
+        // all work, even when sent concurrently.
+        let responses = futures::future::join_all((0..8).map(|_| {
+            reqwest::Client::new()
+                .post(get_fund_endpoint(port))
+                .body(get_fund_request(None).to_json_string())
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+        }))
+        .await;
+        for response in responses {
+            unwrap_reqwest_result(response).await?;
+        }
+
+        // Wait a few seconds for all the fullnodes to catch up.
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        // Assert that the funding accounts were topped up from the root account
+        // at startup (less what they've given out since).
+        let aptos_node_api_client = aptos_sdk::rest_client::Client::new(reqwest::Url::from_str(
+            "https://fullnode.devnet.aptoslabs.com",
+        )?);
+        for funding_account_address in funding_account_addresses {
+            let balance = aptos_node_api_client
+                .get_account_balance(funding_account_address)
+                .await?
+                .into_inner()
+                .get();
+            assert!(
+                balance > 20_000_000,
+                "Funding account {} was not topped up, balance: {}",
+                funding_account_address,
+                balance
+            );
+        }
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_mint_funder() -> Result<()> {
         // Assert that a localnet is alive.
@@ -213,9 +213,14 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 .verifier
                 .get_public_key(&self.my_addr)
                 .ok_or_else(|| anyhow!("my pk not found in validator set"))?;
-            let my_sk = self.key_storage.consensus_sk_by_pk(my_pk).map_err(|e| {
-                anyhow!("jwk-consensus new epoch handling failed with consensus sk lookup err: {e}")
-            })?;
+            let my_sk = self
+                .key_storage
+                .export_consensus_key_by_pk(my_pk)
+                .map_err(|e| {
+                    anyhow!(
+                        "jwk-consensus new epoch handling failed with consensus sk lookup err: {e}"
+                    )
+                })?;
             let jwk_consensus_manager = JWKManager::new(
                 Arc::new(my_sk),
                 self.my_addr,
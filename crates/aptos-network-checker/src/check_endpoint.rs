@@ -9,10 +9,10 @@ use aptos_config::{
 };
 use aptos_crypto::x25519::{self, PRIVATE_KEY_SIZE};
 use aptos_network::{
-    noise::{HandshakeAuthMode, NoiseUpgrader},
+    noise::{stream::NoiseStream, HandshakeAuthMode, NoiseUpgrader},
     protocols::wire::handshake::v1::ProtocolIdSet,
     transport::{
-        resolve_and_connect, upgrade_outbound, TCPBufferCfg, TcpSocket, UpgradeContext,
+        resolve_and_connect, upgrade_outbound, Connection, TCPBufferCfg, TcpSocket, UpgradeContext,
         SUPPORTED_MESSAGING_PROTOCOL,
     },
 };
@@ -54,6 +54,40 @@ pub async fn check_endpoint(
     .await
 }
 
+/// Connects via Noise and performs the AptosNet handshake, returning the open
+/// connection so the caller can speak protocols over it. Like [`check_endpoint`],
+/// a dummy private key is used if none is provided.
+pub async fn connect_to_endpoint(
+    args: &CheckEndpointArgs,
+    private_key: Option<x25519::PrivateKey>,
+) -> Result<Connection<NoiseStream<TcpSocket>>> {
+    let private_key = private_key.unwrap_or_else(|| {
+        let dummy = [0; PRIVATE_KEY_SIZE];
+        x25519::PrivateKey::from(dummy)
+    });
+    let (peer_id, _) = private_key_to_public_info(&private_key);
+    let address = &args.node_address_args.address;
+    let remote_pubkey = address.find_noise_proto().with_context(|| {
+        format!(
+            "Failed to find noise protocol in {}, /noise-ik/<pubkey> missing",
+            address
+        )
+    })?;
+    let upgrade_context = build_upgrade_context(
+        args.node_address_args.chain_id,
+        args.handshake_args.network_id,
+        peer_id,
+        private_key,
+    );
+
+    tokio::time::timeout(
+        Duration::from_secs(args.handshake_args.timeout_seconds),
+        connect_with_handshake(upgrade_context, address.clone(), remote_pubkey),
+    )
+    .await
+    .with_context(|| format!("Timed out while connecting to endpoint {}", address))?
+}
+
 async fn check_endpoint_wrapper(
     upgrade_context: Arc<UpgradeContext>,
     address: &NetworkAddress,
@@ -85,6 +119,20 @@ async fn check_endpoint_with_handshake(
     address: NetworkAddress,
     remote_pubkey: x25519::PublicKey,
 ) -> Result<String> {
+    let conn = connect_with_handshake(upgrade_context, address, remote_pubkey).await?;
+    let msg = format!("Successfully connected to {}", conn.metadata.addr);
+
+    // Disconnect.
+    drop(conn);
+    Ok(msg)
+}
+
+/// Connects via Noise and performs the AptosNet handshake.
+async fn connect_with_handshake(
+    upgrade_context: Arc<UpgradeContext>,
+    address: NetworkAddress,
+    remote_pubkey: x25519::PublicKey,
+) -> Result<Connection<NoiseStream<TcpSocket>>> {
     // Connect to the address, this should handle DNS resolution if necessary.
     let fut_socket = async {
         resolve_and_connect(address.clone(), TCPBufferCfg::new())
@@ -108,11 +156,7 @@ async fn check_endpoint_with_handshake(
             address, error
         ))
     })?;
-    let msg = format!("Successfully connected to {}", conn.metadata.addr);
-
-    // Disconnect.
-    drop(conn);
-    Ok(msg)
+    Ok(conn)
 }
 
 const INVALID_NOISE_HEADER: &[u8; 152] = &[7; 152];
@@ -3,5 +3,7 @@
 
 pub mod args;
 pub mod check_endpoint;
+pub mod rpc_client;
 
-pub use check_endpoint::check_endpoint;
+pub use check_endpoint::{check_endpoint, connect_to_endpoint};
+pub use rpc_client::RpcClient;
@@ -0,0 +1,163 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal RPC client that speaks AptosNet directly over a single noise
+//! connection, without spinning up a peer manager. This is enough for tools
+//! that want to query a node over the wire, e.g. hitting the storage service.
+
+use crate::{args::CheckEndpointArgs, check_endpoint::connect_to_endpoint};
+use anyhow::{bail, Context, Result};
+use aptos_config::config::{MAX_FRAME_SIZE, MAX_MESSAGE_SIZE};
+use aptos_crypto::x25519;
+use aptos_network::{
+    noise::stream::NoiseStream,
+    protocols::{
+        stream::{InboundStreamBuffer, StreamMessage},
+        wire::{
+            handshake::v1::ProtocolId,
+            messaging::v1::{
+                MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream, NetworkMessage,
+                RequestId, RpcRequest,
+            },
+        },
+    },
+    transport::{Connection, ConnectionMetadata, TcpSocket},
+};
+use aptos_types::network_address::NetworkAddress;
+use futures::{
+    io::{ReadHalf, WriteHalf},
+    AsyncReadExt, SinkExt, StreamExt,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::time::Duration;
+
+/// Sends AptosNet RPC requests to a single remote peer.
+pub struct RpcClient {
+    /// Includes the protocols both sides agreed on during the handshake.
+    metadata: ConnectionMetadata,
+    message_stream: MultiplexMessageStream<ReadHalf<NoiseStream<TcpSocket>>>,
+    message_sink: MultiplexMessageSink<WriteHalf<NoiseStream<TcpSocket>>>,
+    /// Large responses are streamed back in fragments, this reassembles them.
+    stream_buffer: InboundStreamBuffer,
+    next_request_id: RequestId,
+}
+
+impl RpcClient {
+    /// Establishes a new connection to the endpoint and wraps it in a client.
+    pub async fn connect(
+        args: &CheckEndpointArgs,
+        private_key: Option<x25519::PrivateKey>,
+    ) -> Result<Self> {
+        Ok(Self::new(connect_to_endpoint(args, private_key).await?))
+    }
+
+    pub fn new(connection: Connection<NoiseStream<TcpSocket>>) -> Self {
+        let Connection { socket, metadata } = connection;
+        let (read_socket, write_socket) = socket.split();
+        Self {
+            metadata,
+            message_stream: MultiplexMessageStream::new(read_socket, MAX_FRAME_SIZE),
+            message_sink: MultiplexMessageSink::new(write_socket, MAX_FRAME_SIZE),
+            stream_buffer: InboundStreamBuffer::new(MAX_MESSAGE_SIZE / MAX_FRAME_SIZE),
+            next_request_id: 0,
+        }
+    }
+
+    pub fn remote_address(&self) -> &NetworkAddress {
+        &self.metadata.addr
+    }
+
+    /// Whether the remote peer agreed to speak the given protocol.
+    pub fn supports_protocol(&self, protocol_id: ProtocolId) -> bool {
+        self.metadata.application_protocols.contains(protocol_id)
+    }
+
+    /// Sends a single RPC request and waits for the matching response. Anything
+    /// else the remote sends in the meantime (e.g. health check pings) is ignored.
+    pub async fn send_request<TRequest: Serialize, TResponse: DeserializeOwned>(
+        &mut self,
+        protocol_id: ProtocolId,
+        request: &TRequest,
+        timeout: Duration,
+    ) -> Result<TResponse> {
+        if !self.supports_protocol(protocol_id) {
+            bail!(
+                "Peer at {} does not support protocol {}",
+                self.metadata.addr,
+                protocol_id
+            );
+        }
+
+        let raw_request = protocol_id
+            .to_bytes(request)
+            .with_context(|| format!("Failed to serialize request for {}", protocol_id))?;
+        if raw_request.len() > MAX_FRAME_SIZE {
+            bail!(
+                "Request for {} is too large to send in a single frame: {} bytes",
+                protocol_id,
+                raw_request.len()
+            );
+        }
+
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        let message = MultiplexMessage::Message(NetworkMessage::RpcRequest(RpcRequest {
+            protocol_id,
+            request_id,
+            priority: 0,
+            raw_request,
+        }));
+        self.message_sink
+            .send(&message)
+            .await
+            .with_context(|| format!("Failed to send request to {}", self.metadata.addr))?;
+
+        let raw_response = tokio::time::timeout(timeout, self.wait_for_response(request_id))
+            .await
+            .with_context(|| {
+                format!(
+                    "Timed out waiting for a {} response from {}",
+                    protocol_id, self.metadata.addr
+                )
+            })??;
+        protocol_id
+            .from_bytes(&raw_response)
+            .with_context(|| format!("Failed to deserialize response for {}", protocol_id))
+    }
+
+    async fn wait_for_response(&mut self, request_id: RequestId) -> Result<Vec<u8>> {
+        while let Some(message) = self.message_stream.next().await {
+            let message = match message
+                .with_context(|| format!("Failed to read from {}", self.metadata.addr))?
+            {
+                MultiplexMessage::Message(message) => message,
+                MultiplexMessage::Stream(StreamMessage::Header(header)) => {
+                    self.stream_buffer.new_stream(header)?;
+                    continue;
+                },
+                MultiplexMessage::Stream(StreamMessage::Fragment(fragment)) => {
+                    match self.stream_buffer.append_fragment(fragment)? {
+                        Some(message) => message,
+                        None => continue,
+                    }
+                },
+            };
+
+            match message {
+                NetworkMessage::RpcResponse(response) if response.request_id == request_id => {
+                    return Ok(response.raw_response);
+                },
+                NetworkMessage::Error(error_code) => {
+                    bail!(
+                        "Peer at {} returned an error: {:?}",
+                        self.metadata.addr,
+                        error_code
+                    );
+                },
+                _ => continue,
+            }
+        }
+
+        bail!("Connection to {} was closed", self.metadata.addr)
+    }
+}
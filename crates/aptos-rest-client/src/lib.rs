@@ -27,8 +27,9 @@ use aptos_api_types::{
     deserialize_from_string,
     mime_types::{BCS, BCS_SIGNED_TRANSACTION, BCS_VIEW_FUNCTION, JSON},
     AptosError, BcsBlock, Block, GasEstimation, HexEncodedBytes, IndexResponse, MoveModuleId,
-    TransactionData, TransactionOnChainData, TransactionsBatchSubmissionResult, UserTransaction,
-    VersionedEvent, ViewFunction, ViewRequest,
+    StateValueChunkWithProofBcs, StateValueWithProofBcs, TransactionData,
+    TransactionInfoWithProofBcs, TransactionOnChainData, TransactionsBatchSubmissionResult,
+    UserTransaction, VersionedEvent, ViewFunction, ViewRequest,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, info, sample, sample::SampleRate};
@@ -36,6 +37,8 @@ use aptos_types::{
     account_address::AccountAddress,
     account_config::{AccountResource, CoinStoreResource, NewBlockEvent, CORE_CODE_ADDRESS},
     contract_event::EventWithVersion,
+    epoch_change::EpochChangeProof,
+    state_proof::StateProof,
     state_store::state_key::StateKey,
     transaction::SignedTransaction,
     CoinType,
@@ -1429,6 +1432,79 @@ impl Client {
         Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
     }
 
+    /// Retrieves the latest ledger info, along with the epoch changes since `known_version`.
+    /// The proof is not verified here, see the light client in the SDK for that.
+    pub async fn get_state_proof(&self, known_version: u64) -> AptosResult<Response<StateProof>> {
+        let url = self.build_path(&format!("proofs/state?known_version={}", known_version))?;
+        let response = self.get_bcs(url).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    /// Retrieves a state value, or the proof of its absence, at a state checkpoint version,
+    /// which defaults to the latest signed ledger info.
+    pub async fn get_state_value_with_proof(
+        &self,
+        state_key: &StateKey,
+        version: Option<u64>,
+    ) -> AptosResult<Response<StateValueWithProofBcs>> {
+        let mut url = self.build_path("proofs/state_value")?;
+        if let Some(version) = version {
+            url.set_query(Some(format!("ledger_version={}", version).as_str()));
+        }
+        let data = json!({
+            "key": hex::encode(bcs::to_bytes(state_key)?),
+        });
+
+        let response = self.post_bcs(url, data).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    /// Retrieves a chunk of state values ordered by key hash, along with a range proof, at a
+    /// state checkpoint version, which defaults to the latest signed ledger info.
+    pub async fn get_state_values_with_range_proof(
+        &self,
+        version: Option<u64>,
+        start: u64,
+        limit: u16,
+    ) -> AptosResult<Response<StateValueChunkWithProofBcs>> {
+        let mut url = self.build_path(&format!(
+            "proofs/state_values?start={}&limit={}",
+            start, limit
+        ))?;
+        if let Some(version) = version {
+            url.query_pairs_mut()
+                .append_pair("ledger_version", &version.to_string());
+        }
+        let response = self.get_bcs(url).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    pub async fn get_transaction_info_with_proof(
+        &self,
+        version: u64,
+    ) -> AptosResult<Response<TransactionInfoWithProofBcs>> {
+        let url = self.build_path(&format!("proofs/transaction_info/{}", version))?;
+        let response = self.get_bcs(url).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    /// Retrieves the epoch ending ledger infos of `[start_epoch, end_epoch)`, where
+    /// `end_epoch` defaults to the latest epoch.
+    pub async fn get_epoch_change_proof(
+        &self,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> AptosResult<Response<EpochChangeProof>> {
+        let mut url =
+            self.build_path(&format!("proofs/epoch_change?start_epoch={}", start_epoch))?;
+        if let Some(end_epoch) = end_epoch {
+            url.query_pairs_mut()
+                .append_pair("end_epoch", &end_epoch.to_string());
+        }
+        let response = self.get_bcs(url).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
     pub async fn estimate_gas_price(&self) -> AptosResult<Response<GasEstimation>> {
         let url = self.build_path("estimate_gas_price")?;
         let response = self.inner.get(url).send().await?;
@@ -8,8 +8,8 @@
 
 use crate::{
     common::{
-        check_network, get_block_index_from_request, handle_request, native_coin, native_coin_tag,
-        with_context,
+        check_network, fungible_asset_address, get_block_index_from_request, handle_request,
+        native_coin, native_coin_fa_address, native_coin_tag, with_context,
     },
     error::{ApiError, ApiResult},
     types::{AccountBalanceRequest, AccountBalanceResponse, Amount, Currency, *},
@@ -73,6 +73,7 @@ async fn account_balance(
         request.account_identifier,
         balance_version,
         request.currencies,
+        &server_context.currencies,
     )
     .await?;
 
@@ -94,6 +95,7 @@ async fn get_balances(
     account: AccountIdentifier,
     version: u64,
     maybe_filter_currencies: Option<Vec<Currency>>,
+    supported_currencies: &HashSet<Currency>,
 ) -> ApiResult<(u64, Option<Vec<AccountAddress>>, Vec<Amount>, u64)> {
     let owner_address = account.account_address()?;
     let pool_address = account.pool_address()?;
@@ -138,7 +140,6 @@ async fn get_balances(
     }
 
     // Retrieve all account resources
-    // TODO: This will need to change for FungibleAssets, will need to lookup on a list of known FAs
     if let Ok(response) = rest_client
         .get_account_resources_at_version_bcs(owner_address, version)
         .await
@@ -238,6 +239,58 @@ async fn get_balances(
             }
         }
 
+        // Fungible assets are held in primary fungible stores, which are separate objects
+        if account.is_base_account() {
+            // APT that has been migrated to a fungible asset is still reported as APT
+            let native_fa_balance = get_fungible_asset_balance(
+                rest_client,
+                owner_address,
+                native_coin_fa_address(),
+                version,
+            )
+            .await?;
+            if let Some(native_balance) = balances
+                .iter_mut()
+                .find(|balance| balance.currency == native_coin())
+            {
+                let coin_balance = u64::from_str(&native_balance.value).unwrap_or_default();
+                let total_balance =
+                    coin_balance.checked_add(native_fa_balance).ok_or_else(|| {
+                        ApiError::InternalError(Some(format!(
+                            "Native coin balance overflowed for {}: {} + {}",
+                            owner_address, coin_balance, native_fa_balance
+                        )))
+                    })?;
+                native_balance.value = total_balance.to_string();
+            } else {
+                balances.push(Amount {
+                    value: native_fa_balance.to_string(),
+                    currency: native_coin(),
+                });
+            }
+
+            // Look up all supported fungible assets, as well as any requested ones
+            let currencies: HashSet<&Currency> = supported_currencies
+                .iter()
+                .chain(maybe_filter_currencies.iter().flatten())
+                .collect();
+            for currency in currencies {
+                if let Some(fa_address) = fungible_asset_address(currency)? {
+                    // Migrated APT is already included in the native coin balance above
+                    if fa_address == native_coin_fa_address() {
+                        continue;
+                    }
+                    let balance =
+                        get_fungible_asset_balance(rest_client, owner_address, fa_address, version)
+                            .await?;
+                    balances.push(Amount {
+                        value: balance.to_string(),
+                        currency: currency.clone(),
+                    });
+                }
+            }
+        }
+
         // Retrieves the sequence number accordingly
         // TODO: Sequence number should be 0 if it isn't retrieved probably
         let sequence_number = if let Some(sequence_number) = maybe_sequence_number {
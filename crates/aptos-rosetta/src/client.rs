@@ -11,9 +11,10 @@ use crate::{
         ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
         ConstructionPayloadsResponse, ConstructionPreprocessRequest,
         ConstructionPreprocessResponse, ConstructionSubmitRequest, ConstructionSubmitResponse,
-        Error, MetadataRequest, NetworkIdentifier, NetworkListResponse, NetworkOptionsResponse,
-        NetworkRequest, NetworkStatusResponse, Operation, PreprocessMetadata, PublicKey, Signature,
-        SignatureType, TransactionIdentifier, TransactionIdentifierResponse,
+        Currency, Error, MetadataRequest, NetworkIdentifier, NetworkListResponse,
+        NetworkOptionsResponse, NetworkRequest, NetworkStatusResponse, Operation,
+        PreprocessMetadata, PublicKey, Signature, SignatureType, TransactionIdentifier,
+        TransactionIdentifierResponse,
     },
 };
 use anyhow::anyhow;
@@ -190,6 +191,33 @@ impl RosettaClient {
         sequence_number: Option<u64>,
         max_gas: Option<u64>,
         gas_unit_price: Option<u64>,
+    ) -> anyhow::Result<TransactionIdentifier> {
+        self.transfer_currency(
+            network_identifier,
+            private_key,
+            receiver,
+            native_coin(),
+            amount,
+            expiry_time_secs,
+            sequence_number,
+            max_gas,
+            gas_unit_price,
+        )
+        .await
+    }
+
+    /// Transfers any currency supported by the server, e.g. a fungible asset
+    pub async fn transfer_currency(
+        &self,
+        network_identifier: &NetworkIdentifier,
+        private_key: &Ed25519PrivateKey,
+        receiver: AccountAddress,
+        currency: Currency,
+        amount: u64,
+        expiry_time_secs: u64,
+        sequence_number: Option<u64>,
+        max_gas: Option<u64>,
+        gas_unit_price: Option<u64>,
     ) -> anyhow::Result<TransactionIdentifier> {
         let sender = self
             .get_account_address(network_identifier.clone(), private_key)
@@ -203,14 +231,14 @@ impl RosettaClient {
                 0,
                 None,
                 AccountIdentifier::base_account(sender),
-                native_coin(),
+                currency.clone(),
                 amount,
             ),
             Operation::deposit(
                 1,
                 None,
                 AccountIdentifier::base_account(receiver),
-                native_coin(),
+                currency,
                 amount,
             ),
         ];
@@ -154,11 +154,19 @@ pub fn native_coin() -> Currency {
         symbol: DEFAULT_COIN.to_string(),
         decimals: DEFAULT_DECIMALS,
         metadata: Some(CurrencyMetadata {
-            move_type: native_coin_tag().to_string(),
+            move_type: Some(native_coin_tag().to_string()),
+            fa_address: None,
         }),
     }
 }
 
+/// Provides the address of the fungible asset metadata for APT
+///
+/// APT balances that have been migrated to fungible assets are still reported as [native_coin]
+pub fn native_coin_fa_address() -> AccountAddress {
+    AccountAddress::TEN
+}
+
 /// Provides the [TypeTag] for 0x1::aptos_coin::AptosCoin aka APT
 pub fn native_coin_tag() -> TypeTag {
     TypeTag::Struct(Box::new(StructTag {
@@ -170,8 +178,6 @@ pub fn native_coin_tag() -> TypeTag {
 }
 
 /// Tells us whether the coin is APT and errors if it's not
-///
-/// TODO: This is the function that needs to be replaced to handle more coin types
 pub fn is_native_coin(currency: &Currency) -> ApiResult<()> {
     if currency == &native_coin() {
         Ok(())
@@ -180,6 +186,53 @@ pub fn is_native_coin(currency: &Currency) -> ApiResult<()> {
     }
 }
 
+/// Retrieves the fungible asset metadata address of a currency, if it's a fungible asset
+pub fn fungible_asset_address(currency: &Currency) -> ApiResult<Option<AccountAddress>> {
+    match currency
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.fa_address.as_ref())
+    {
+        Some(fa_address) => AccountAddress::from_str(fa_address).map(Some).map_err(|_| {
+            ApiError::InvalidInput(Some(format!(
+                "Invalid fungible asset address {} for currency {}",
+                fa_address, currency.symbol
+            )))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Tells us whether the currency is APT or a fungible asset and errors if it's not
+pub fn is_native_coin_or_fungible_asset(currency: &Currency) -> ApiResult<()> {
+    if currency == &native_coin() || fungible_asset_address(currency)?.is_some() {
+        Ok(())
+    } else {
+        Err(ApiError::UnsupportedCurrency(Some(currency.symbol.clone())))
+    }
+}
+
+/// Finds the supported [Currency] for the fungible asset with the given metadata address
+///
+/// APT is always returned as [native_coin], other fungible assets must be in the supported
+/// currencies of the server.
+pub fn find_fungible_asset_currency(
+    server_context: &RosettaContext,
+    metadata_address: AccountAddress,
+) -> Option<Currency> {
+    if metadata_address == native_coin_fa_address() {
+        return Some(native_coin());
+    }
+
+    server_context
+        .currencies
+        .iter()
+        .find(|currency| {
+            matches!(fungible_asset_address(currency), Ok(Some(address)) if address == metadata_address)
+        })
+        .cloned()
+}
+
 /// Determines which block to pull for the request
 ///
 /// Inputs can give hash, index, or both
@@ -26,8 +26,8 @@
 
 use crate::{
     common::{
-        check_network, decode_bcs, decode_key, encode_bcs, get_account, handle_request,
-        native_coin, parse_currency, with_context,
+        check_network, decode_bcs, decode_key, encode_bcs, find_fungible_asset_currency,
+        get_account, handle_request, native_coin, parse_currency, with_context,
     },
     error::{ApiError, ApiResult},
     types::{InternalOperation, *},
@@ -559,9 +559,18 @@ async fn construction_parse(
                 (AccountAddress::ONE, COIN_MODULE, TRANSFER_FUNCTION) => {
                     parse_transfer_operation(sender, &type_args, &args)?
                 },
-                (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, TRANSFER_FUNCTION) => {
+                (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, TRANSFER_FUNCTION)
+                | (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, FUNGIBLE_TRANSFER_ONLY_FUNCTION) => {
                     parse_account_transfer_operation(sender, &type_args, &args)?
                 },
+                (AccountAddress::ONE, PRIMARY_FUNGIBLE_STORE_MODULE, TRANSFER_FUNCTION) => {
+                    parse_primary_fungible_store_transfer_operation(
+                        &server_context,
+                        sender,
+                        &type_args,
+                        &args,
+                    )?
+                },
                 (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, CREATE_ACCOUNT_FUNCTION) => {
                     parse_create_account_operation(sender, &type_args, &args)?
                 },
@@ -770,6 +779,48 @@ fn parse_account_transfer_operation(
     Ok(operations)
 }
 
+/// Parses 0x1::primary_fungible_store::transfer<T: key>(metadata: Object<T>, recipient: address, amount: u64)
+fn parse_primary_fungible_store_transfer_operation(
+    server_context: &RosettaContext,
+    sender: AccountAddress,
+    type_args: &[TypeTag],
+    args: &[Vec<u8>],
+) -> ApiResult<Vec<Operation>> {
+    // The type argument is only the object type, the metadata address is what determines the asset
+    if type_args.len() != 1 {
+        return Err(ApiError::TransactionParseError(Some(format!(
+            "Primary fungible store transfer should have exactly one type argument: {:?}",
+            type_args
+        ))));
+    }
+
+    let metadata_address: AccountAddress =
+        parse_function_arg("primary_fungible_store::transfer", args, 0)?;
+    let receiver: AccountAddress = parse_function_arg("primary_fungible_store::transfer", args, 1)?;
+    let amount: u64 = parse_function_arg("primary_fungible_store::transfer", args, 2)?;
+
+    let currency = find_fungible_asset_currency(server_context, metadata_address).ok_or(
+        ApiError::UnsupportedCurrency(Some(metadata_address.to_string())),
+    )?;
+
+    Ok(vec![
+        Operation::withdraw(
+            0,
+            None,
+            AccountIdentifier::base_account(sender),
+            currency.clone(),
+            amount,
+        ),
+        Operation::deposit(
+            1,
+            None,
+            AccountIdentifier::base_account(receiver),
+            currency,
+            amount,
+        ),
+    ])
+}
+
 /// Parses a specific BCS function argument to the given type
 pub fn parse_function_arg<T: DeserializeOwned>(
     name: &str,
@@ -1306,6 +1357,15 @@ async fn construction_preprocess(
     // Determine the actual operation from the collection of Rosetta [Operation]
     let internal_operation = InternalOperation::extract(&request.operations)?;
 
+    // Only transfers of currencies this server knows about can be built
+    if let InternalOperation::Transfer(transfer) = &internal_operation {
+        if !server_context.currencies.contains(&transfer.currency) {
+            return Err(ApiError::UnsupportedCurrency(Some(
+                transfer.currency.symbol.clone(),
+            )));
+        }
+    }
+
     // Provide the accounts that need public keys (there's only one supported today)
     let required_public_keys = vec![AccountIdentifier::base_account(internal_operation.sender())];
 
@@ -7,14 +7,15 @@
 
 use crate::{
     block::BlockRetriever,
-    common::{handle_request, with_context},
+    common::{handle_request, native_coin, with_context},
     error::{ApiError, ApiResult},
+    types::Currency,
 };
 use aptos_config::config::ApiConfig;
 use aptos_logger::debug;
 use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use aptos_warp_webserver::{logger, Error, WebServer};
-use std::{convert::Infallible, sync::Arc};
+use std::{collections::HashSet, convert::Infallible, sync::Arc};
 use tokio::task::JoinHandle;
 use warp::{
     http::{HeaderValue, Method, StatusCode},
@@ -43,6 +44,8 @@ pub struct RosettaContext {
     pub chain_id: ChainId,
     /// Block index cache
     pub block_cache: Option<Arc<BlockRetriever>>,
+    /// All currencies supported by the server, this always includes APT
+    pub currencies: Arc<HashSet<Currency>>,
 }
 
 impl RosettaContext {
@@ -50,11 +53,16 @@ impl RosettaContext {
         rest_client: Option<Arc<aptos_rest_client::Client>>,
         chain_id: ChainId,
         block_cache: Option<Arc<BlockRetriever>>,
+        mut currencies: HashSet<Currency>,
     ) -> Self {
+        // APT is always supported
+        currencies.insert(native_coin());
+
         RosettaContext {
             rest_client,
             chain_id,
             block_cache,
+            currencies: Arc::new(currencies),
         }
     }
 
@@ -80,12 +88,18 @@ pub fn bootstrap(
     chain_id: ChainId,
     api_config: ApiConfig,
     rest_client: Option<aptos_rest_client::Client>,
+    supported_currencies: HashSet<Currency>,
 ) -> anyhow::Result<tokio::runtime::Runtime> {
     let runtime = aptos_runtimes::spawn_named_runtime("rosetta".into(), None);
 
     debug!("Starting up Rosetta server with {:?}", api_config);
 
-    runtime.spawn(bootstrap_async(chain_id, api_config, rest_client));
+    runtime.spawn(bootstrap_async(
+        chain_id,
+        api_config,
+        rest_client,
+        supported_currencies,
+    ));
     Ok(runtime)
 }
 
@@ -94,6 +108,7 @@ pub async fn bootstrap_async(
     chain_id: ChainId,
     api_config: ApiConfig,
     rest_client: Option<aptos_rest_client::Client>,
+    supported_currencies: HashSet<Currency>,
 ) -> anyhow::Result<JoinHandle<()>> {
     debug!("Starting up Rosetta server with {:?}", api_config);
 
@@ -123,7 +138,13 @@ pub async fn bootstrap_async(
             ))
         });
 
-        let context = RosettaContext::new(rest_client.clone(), chain_id, block_cache).await;
+        let context = RosettaContext::new(
+            rest_client.clone(),
+            chain_id,
+            block_cache,
+            supported_currencies,
+        )
+        .await;
         api.serve(routes(context)).await;
     });
     Ok(handle)
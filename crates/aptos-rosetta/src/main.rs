@@ -8,10 +8,12 @@
 use aptos_config::config::{ApiConfig, DEFAULT_MAX_PAGE_SIZE};
 use aptos_logger::prelude::*;
 use aptos_node::AptosNodeArgs;
-use aptos_rosetta::bootstrap;
+use aptos_rosetta::{bootstrap, types::Currency};
 use aptos_types::chain_id::ChainId;
 use clap::Parser;
 use std::{
+    collections::HashSet,
+    fs::File,
     net::SocketAddr,
     path::PathBuf,
     sync::{
@@ -85,8 +87,13 @@ async fn main() {
 
     println!("aptos-rosetta: Starting rosetta");
     // Ensure runtime for Rosetta is up and running
-    let _rosetta = bootstrap(args.chain_id(), args.api_config(), args.rest_client())
-        .expect("aptos-rosetta: Should bootstrap rosetta server");
+    let _rosetta = bootstrap(
+        args.chain_id(),
+        args.api_config(),
+        args.rest_client(),
+        args.supported_currencies(),
+    )
+    .expect("aptos-rosetta: Should bootstrap rosetta server");
 
     println!("aptos-rosetta: Rosetta started");
     // Run until there is an interrupt
@@ -106,6 +113,9 @@ trait ServerArgs {
 
     /// Retrieve the chain id
     fn chain_id(&self) -> ChainId;
+
+    /// Retrieve the supported currencies, in addition to APT
+    fn supported_currencies(&self) -> HashSet<Currency>;
 }
 
 /// Aptos Rosetta API Server
@@ -146,6 +156,14 @@ impl ServerArgs for CommandArgs {
             CommandArgs::Online(args) => args.chain_id(),
         }
     }
+
+    fn supported_currencies(&self) -> HashSet<Currency> {
+        match self {
+            CommandArgs::OnlineRemote(args) => args.supported_currencies(),
+            CommandArgs::Offline(args) => args.supported_currencies(),
+            CommandArgs::Online(args) => args.supported_currencies(),
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -170,6 +188,12 @@ pub struct OfflineArgs {
     /// This can be configured to change performance characteristics
     #[clap(long, default_value_t = DEFAULT_MAX_PAGE_SIZE)]
     transactions_page_size: u16,
+    /// Path to a JSON file with a list of additional supported currencies
+    ///
+    /// Fungible assets must provide their metadata address in `metadata.fa_address`.
+    /// APT is always supported.
+    #[clap(long)]
+    currency_config_file: Option<PathBuf>,
 }
 
 impl ServerArgs for OfflineArgs {
@@ -192,6 +216,17 @@ impl ServerArgs for OfflineArgs {
     fn chain_id(&self) -> ChainId {
         self.chain_id
     }
+
+    fn supported_currencies(&self) -> HashSet<Currency> {
+        if let Some(ref currency_config_file) = self.currency_config_file {
+            let file = File::open(currency_config_file)
+                .expect("aptos-rosetta: Should be able to open currency config file");
+            serde_json::from_reader(file)
+                .expect("aptos-rosetta: Currency config file should be a JSON list of currencies")
+        } else {
+            HashSet::new()
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -218,6 +253,10 @@ impl ServerArgs for OnlineRemoteArgs {
     fn chain_id(&self) -> ChainId {
         self.offline_args.chain_id
     }
+
+    fn supported_currencies(&self) -> HashSet<Currency> {
+        self.offline_args.supported_currencies()
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -242,6 +281,10 @@ impl ServerArgs for OnlineLocalArgs {
     fn chain_id(&self) -> ChainId {
         self.online_args.offline_args.chain_id
     }
+
+    fn supported_currencies(&self) -> HashSet<Currency> {
+        self.online_args.offline_args.supported_currencies()
+    }
 }
 
 #[test]
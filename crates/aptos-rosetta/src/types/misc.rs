@@ -7,7 +7,7 @@ use crate::{
     types::{AccountIdentifier, Amount},
     AccountAddress, ApiResult,
 };
-use aptos_rest_client::aptos_api_types::{EntryFunctionId, ViewRequest};
+use aptos_rest_client::aptos_api_types::{EntryFunctionId, MoveType, ViewRequest};
 use aptos_types::stake_pool::StakePool;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -27,6 +27,10 @@ static STAKING_CONTRACT_AMOUNTS_FUNCTION: Lazy<EntryFunctionId> = Lazy::new(|| {
         .parse()
         .unwrap()
 });
+static PRIMARY_FUNGIBLE_STORE_BALANCE_FUNCTION: Lazy<EntryFunctionId> =
+    Lazy::new(|| "0x1::primary_fungible_store::balance".parse().unwrap());
+static FUNGIBLE_ASSET_METADATA_TYPE: Lazy<MoveType> =
+    Lazy::new(|| "0x1::fungible_asset::Metadata".parse().unwrap());
 
 /// Errors that can be returned by the API
 ///
@@ -479,6 +483,41 @@ fn parse_commission_not_yet_unlocked(staking_contract_amounts: Vec<serde_json::V
         .unwrap_or(0);
 }
 
+/// Retrieves the balance of a fungible asset in the owner's primary fungible store
+///
+/// If the primary fungible store doesn't exist, the balance is 0
+pub async fn get_fungible_asset_balance(
+    rest_client: &aptos_rest_client::Client,
+    owner_address: AccountAddress,
+    metadata_address: AccountAddress,
+    version: u64,
+) -> ApiResult<u64> {
+    let balance_response = rest_client
+        .view(
+            &ViewRequest {
+                function: PRIMARY_FUNGIBLE_STORE_BALANCE_FUNCTION.clone(),
+                type_arguments: vec![FUNGIBLE_ASSET_METADATA_TYPE.clone()],
+                arguments: vec![
+                    serde_json::Value::String(owner_address.to_string()),
+                    serde_json::Value::String(metadata_address.to_string()),
+                ],
+            },
+            Some(version),
+        )
+        .await?;
+
+    balance_response
+        .into_inner()
+        .first()
+        .and_then(|v| v.as_str().and_then(|s| s.parse::<u64>().ok()))
+        .ok_or_else(|| {
+            ApiError::InternalError(Some(format!(
+                "Failed to parse fungible asset {} balance for {}",
+                metadata_address, owner_address
+            )))
+        })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -16,6 +16,9 @@ pub const STAKING_PROXY_MODULE: &str = "staking_proxy";
 pub const STAKING_CONTRACT_MODULE: &str = "staking_contract";
 pub const VESTING_MODULE: &str = "vesting";
 pub const DELEGATION_POOL_MODULE: &str = "delegation_pool";
+pub const FUNGIBLE_ASSET_MODULE: &str = "fungible_asset";
+pub const OBJECT_MODULE: &str = "object";
+pub const PRIMARY_FUNGIBLE_STORE_MODULE: &str = "primary_fungible_store";
 
 pub const ACCOUNT_RESOURCE: &str = "Account";
 pub const APTOS_COIN_RESOURCE: &str = "AptosCoin";
@@ -27,10 +30,19 @@ pub const STORE_RESOURCE: &str = "Store";
 pub const STAKING_GROUP_UPDATE_COMMISSION_RESOURCE: &str = "StakingGroupUpdateCommissionEvent";
 pub const VESTING_RESOURCE: &str = "Vesting";
 pub const DELEGATION_POOL_RESOURCE: &str = "DelegationPool";
+pub const FUNGIBLE_STORE_RESOURCE: &str = "FungibleStore";
+pub const FUNGIBLE_ASSET_METADATA_RESOURCE: &str = "Metadata";
+pub const OBJECT_CORE_RESOURCE: &str = "ObjectCore";
+pub const OBJECT_GROUP_RESOURCE: &str = "ObjectGroup";
+pub const ADD_STAKE_EVENT: &str = "AddStakeEvent";
+pub const UNLOCK_STAKE_EVENT: &str = "UnlockStakeEvent";
 pub const WITHDRAW_STAKE_EVENT: &str = "WithdrawStakeEvent";
+pub const FUNGIBLE_ASSET_DEPOSIT_EVENT: &str = "Deposit";
+pub const FUNGIBLE_ASSET_WITHDRAW_EVENT: &str = "Withdraw";
 
 pub const CREATE_ACCOUNT_FUNCTION: &str = "create_account";
 pub const TRANSFER_FUNCTION: &str = "transfer";
+pub const FUNGIBLE_TRANSFER_ONLY_FUNCTION: &str = "fungible_transfer_only";
 
 // Staking Contract
 pub const RESET_LOCKUP_FUNCTION: &str = "reset_lockup";
@@ -230,6 +242,7 @@ pub struct AddDelegationEvent {
     pub pool_address: AccountAddress,
     pub delegator_address: AccountAddress,
     pub amount_added: u64,
+    pub add_stake_fee: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -245,3 +258,26 @@ pub struct WithdrawUndelegatedEvent {
     pub delegator_address: AccountAddress,
     pub amount_withdrawn: u64,
 }
+
+// Fungible Asset
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectCore {
+    pub guid_creation_num: u64,
+    pub owner: AccountAddress,
+    pub allow_ungated_transfer: bool,
+    pub transfer_events: EventHandle,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FungibleStore {
+    pub metadata: AccountAddress,
+    pub balance: u64,
+    pub frozen: bool,
+}
+
+/// Both the `Withdraw` and `Deposit` fungible asset events have this layout
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FungibleAssetEvent {
+    pub store: AccountAddress,
+    pub amount: u64,
+}
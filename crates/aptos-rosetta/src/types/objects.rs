@@ -6,7 +6,10 @@
 //! [Spec](https://www.rosetta-api.org/docs/api_objects.html)
 
 use crate::{
-    common::{is_native_coin, native_coin, native_coin_tag},
+    common::{
+        find_fungible_asset_currency, fungible_asset_address, is_native_coin,
+        is_native_coin_or_fungible_asset, native_coin, native_coin_tag,
+    },
     construction::{
         parse_create_stake_pool_operation, parse_delegation_pool_add_stake_operation,
         parse_delegation_pool_unlock_operation, parse_delegation_pool_withdraw_operation,
@@ -27,18 +30,23 @@ use aptos_crypto::{ed25519::Ed25519PublicKey, ValidCryptoMaterialStringExt};
 use aptos_logger::warn;
 use aptos_rest_client::aptos_api_types::{TransactionOnChainData, U64};
 use aptos_types::{
-    account_address::AccountAddress,
-    account_config::{AccountResource, CoinStoreResourceUntyped, WithdrawEvent},
+    account_address::{create_derived_object_address, AccountAddress},
+    account_config::{
+        AccountResource, CoinStoreResourceUntyped, ObjectGroupResource, WithdrawEvent,
+    },
     contract_event::{ContractEvent, FEE_STATEMENT_EVENT_TYPE},
     event::EventKey,
     fee_statement::FeeStatement,
     stake_pool::{SetOperatorEvent, StakePool},
     state_store::state_key::{inner::StateKeyInner, StateKey},
-    transaction::{EntryFunction, TransactionPayload},
+    transaction::{EntryFunction, SignedTransaction, TransactionPayload},
     write_set::{WriteOp, WriteSet},
 };
 use itertools::Itertools;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
@@ -148,7 +156,12 @@ pub struct Currency {
 
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct CurrencyMetadata {
-    pub move_type: String,
+    /// The Move coin type, for currencies that are coins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_type: Option<String>,
+    /// The address of the fungible asset metadata object, for currencies that are fungible assets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fa_address: Option<String>,
 }
 
 /// Various signing curves supported by Rosetta.  We only use [`CurveType::Edwards25519`]
@@ -904,6 +917,19 @@ impl Transaction {
         let mut operations = vec![];
         let mut operation_index: u64 = 0;
         if successful {
+            // With APT held as a fungible asset, the epilogue's gas fee burn or storage fee refund
+            // shows up as a fungible asset event, which is already covered by the fee operations
+            let epilogue_fee_change = maybe_user_txn.map(|user_txn| EpilogueFeeChange {
+                gas_payer: get_gas_payer(user_txn),
+                gas_fee: txn_info
+                    .gas_used()
+                    .saturating_mul(user_txn.gas_unit_price()),
+                storage_fee_refund: get_fee_statement_from_event(&events)
+                    .iter()
+                    .map(|fee_statement| fee_statement.storage_fee_refund())
+                    .sum(),
+            });
+
             // Parse all operations from the writeset changes in a success
             for (state_key, write_op) in &txn.changes {
                 let mut ops = parse_operations_from_write_set(
@@ -913,6 +939,7 @@ impl Transaction {
                     &events,
                     maybe_user_txn.map(|inner| inner.sender()),
                     maybe_user_txn.map(|inner| inner.payload()),
+                    epilogue_fee_change.as_ref(),
                     txn.version,
                     operation_index,
                     &txn.changes,
@@ -929,7 +956,7 @@ impl Transaction {
                     operations.push(Operation::deposit(
                         operation_index,
                         Some(OperationStatusType::Success),
-                        AccountIdentifier::base_account(user_txn.sender()),
+                        AccountIdentifier::base_account(get_gas_payer(user_txn)),
                         native_coin(),
                         event.storage_fee_refund(),
                     ));
@@ -940,6 +967,7 @@ impl Transaction {
             // Parse all failed operations from the payload
             if let Some(user_txn) = maybe_user_txn {
                 let mut ops = parse_failed_operations_from_txn_payload(
+                    server_context,
                     operation_index,
                     user_txn.sender(),
                     user_txn.payload(),
@@ -960,7 +988,7 @@ impl Transaction {
         if let Some(txn) = maybe_user_txn {
             operations.push(Operation::gas_fee(
                 operation_index,
-                txn.sender(),
+                get_gas_payer(txn),
                 txn_info.gas_used(),
                 txn.gas_unit_price(),
             ));
@@ -986,6 +1014,7 @@ impl Transaction {
 /// This case only occurs if the transaction failed, and that's because it's less accurate
 /// than just following the state changes
 fn parse_failed_operations_from_txn_payload(
+    server_context: &RosettaContext,
     operation_index: u64,
     sender: AccountAddress,
     payload: &TransactionPayload,
@@ -1013,11 +1042,35 @@ fn parse_failed_operations_from_txn_payload(
                     }
                 }
             },
-            (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, TRANSFER_FUNCTION) => {
+            (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, TRANSFER_FUNCTION)
+            | (AccountAddress::ONE, APTOS_ACCOUNT_MODULE, FUNGIBLE_TRANSFER_ONLY_FUNCTION) => {
                 // We could add a create here as well, but we don't know if it will actually happen
                 operations =
                     parse_transfer_from_txn_payload(inner, native_coin(), sender, operation_index)
             },
+            (AccountAddress::ONE, PRIMARY_FUNGIBLE_STORE_MODULE, TRANSFER_FUNCTION) => {
+                // Only put the transfer in if we can understand the currency
+                if let Some(Ok(metadata_address)) = inner
+                    .args()
+                    .first()
+                    .map(|encoded| bcs::from_bytes::<AccountAddress>(encoded))
+                {
+                    if let Some(currency) =
+                        find_fungible_asset_currency(server_context, metadata_address)
+                    {
+                        // Skip the metadata argument, the rest matches the coin transfer
+                        operations = parse_transfer_from_args(
+                            &inner.args()[1..],
+                            inner,
+                            currency,
+                            sender,
+                            operation_index,
+                        )
+                    }
+                } else {
+                    warn!("Failed to parse fungible asset transfer {:?}", inner);
+                }
+            },
             (AccountAddress::ONE, ACCOUNT_MODULE, CREATE_ACCOUNT_FUNCTION) => {
                 if let Some(Ok(address)) = inner
                     .args()
@@ -1164,10 +1217,20 @@ fn parse_transfer_from_txn_payload(
     currency: Currency,
     sender: AccountAddress,
     operation_index: u64,
+) -> Vec<Operation> {
+    parse_transfer_from_args(payload.args(), payload, currency, sender, operation_index)
+}
+
+/// Parses the receiver and amount arguments of a transfer to a Withdraw and Deposit
+fn parse_transfer_from_args(
+    args: &[Vec<u8>],
+    payload: &EntryFunction,
+    currency: Currency,
+    sender: AccountAddress,
+    operation_index: u64,
 ) -> Vec<Operation> {
     let mut operations = vec![];
 
-    let args = payload.args();
     let maybe_receiver = args
         .first()
         .map(|encoded| bcs::from_bytes::<AccountAddress>(encoded));
@@ -1209,6 +1272,7 @@ async fn parse_operations_from_write_set(
     events: &[ContractEvent],
     maybe_sender: Option<AccountAddress>,
     _maybe_payload: Option<&TransactionPayload>,
+    epilogue_fee_change: Option<&EpilogueFeeChange>,
     version: u64,
     operation_index: u64,
     changes: &WriteSet,
@@ -1267,6 +1331,18 @@ async fn parse_operations_from_write_set(
             parse_delegation_pool_resource_changes(address, data, events, operation_index, changes)
                 .await
         },
+        (AccountAddress::ONE, OBJECT_MODULE, OBJECT_GROUP_RESOURCE, 0) => {
+            parse_fungible_store_changes(
+                server_context,
+                version,
+                address,
+                data,
+                events,
+                epilogue_fee_change,
+                operation_index,
+            )
+            .await
+        },
         (AccountAddress::ONE, COIN_MODULE, COIN_STORE_RESOURCE, 1) => {
             if let Some(type_tag) = struct_tag.type_args.first() {
                 // TODO: This will need to be updated to support more coins
@@ -1736,7 +1812,7 @@ async fn parse_update_commission(
 
 /// Parses delegation pool changes to resources
 async fn parse_delegation_pool_resource_changes(
-    _owner_address: AccountAddress,
+    pool_address: AccountAddress,
     _data: &[u8],
     events: &[ContractEvent],
     mut operation_index: u64,
@@ -1755,6 +1831,55 @@ async fn parse_delegation_pool_resource_changes(
             struct_tag.module.as_str(),
             struct_tag.name.as_str(),
         ) {
+            (AccountAddress::ONE, DELEGATION_POOL_MODULE, ADD_STAKE_EVENT) => {
+                let event: AddDelegationEvent = if let Ok(event) = bcs::from_bytes(e.event_data()) {
+                    event
+                } else {
+                    warn!(
+                        "Failed to parse add delegated stake event! Skipping for pool {}",
+                        pool_address
+                    );
+                    continue;
+                };
+
+                // Events for other pools are handled with their own pool
+                if event.pool_address != pool_address {
+                    continue;
+                }
+
+                operations.push(Operation::add_delegated_stake(
+                    operation_index,
+                    Some(OperationStatusType::Success),
+                    event.delegator_address,
+                    AccountIdentifier::base_account(event.pool_address),
+                    Some(event.amount_added),
+                ));
+                operation_index += 1;
+            },
+            (AccountAddress::ONE, DELEGATION_POOL_MODULE, UNLOCK_STAKE_EVENT) => {
+                let event: UndelegationEvent = if let Ok(event) = bcs::from_bytes(e.event_data()) {
+                    event
+                } else {
+                    warn!(
+                        "Failed to parse unlock delegated stake event! Skipping for pool {}",
+                        pool_address
+                    );
+                    continue;
+                };
+
+                if event.pool_address != pool_address {
+                    continue;
+                }
+
+                operations.push(Operation::unlock_delegated_stake(
+                    operation_index,
+                    Some(OperationStatusType::Success),
+                    event.delegator_address,
+                    AccountIdentifier::base_account(event.pool_address),
+                    Some(event.amount_unlocked),
+                ));
+                operation_index += 1;
+            },
             (AccountAddress::ONE, DELEGATION_POOL_MODULE, WITHDRAW_STAKE_EVENT) => {
                 let event: WithdrawUndelegatedEvent =
                     if let Ok(event) = bcs::from_bytes(e.event_data()) {
@@ -1768,6 +1893,10 @@ async fn parse_delegation_pool_resource_changes(
                         continue;
                     };
 
+                if event.pool_address != pool_address {
+                    continue;
+                }
+
                 operations.push(Operation::withdraw_undelegated_stake(
                     operation_index,
                     Some(OperationStatusType::Success),
@@ -1835,6 +1964,192 @@ async fn parse_coinstore_changes(
     Ok(operations)
 }
 
+/// Returns the account that pays for the transaction's gas, i.e., the fee payer for
+/// sponsored transactions, and the sender otherwise
+fn get_gas_payer(user_txn: &SignedTransaction) -> AccountAddress {
+    user_txn
+        .authenticator_ref()
+        .fee_payer_address()
+        .unwrap_or_else(|| user_txn.sender())
+}
+
+/// The net change to the gas payer's APT balance made by the transaction epilogue
+///
+/// Either the gas fee minus the storage fee refund is burned, or the difference is refunded
+pub struct EpilogueFeeChange {
+    gas_payer: AccountAddress,
+    gas_fee: u64,
+    storage_fee_refund: u64,
+}
+
+impl EpilogueFeeChange {
+    fn burned(&self) -> Option<u64> {
+        (self.gas_fee > self.storage_fee_refund).then(|| self.gas_fee - self.storage_fee_refund)
+    }
+
+    fn refunded(&self) -> Option<u64> {
+        (self.storage_fee_refund > self.gas_fee).then(|| self.storage_fee_refund - self.gas_fee)
+    }
+
+    /// Removes the epilogue's burn (withdraw) or refund (deposit) from the events of the gas
+    /// payer's APT store
+    ///
+    /// The epilogue runs after the transaction's payload, so its event is the last one emitted
+    /// for the store. Earlier events are left alone, even if their amount matches.
+    fn remove_epilogue_events(
+        &self,
+        withdraws: &mut Vec<(usize, u64)>,
+        deposits: &mut Vec<(usize, u64)>,
+    ) {
+        let last_event_index = withdraws
+            .iter()
+            .chain(deposits.iter())
+            .map(|(index, _)| *index)
+            .max();
+        let (epilogue_events, epilogue_amount) = match (self.burned(), self.refunded()) {
+            (Some(burned), _) => (withdraws, burned),
+            (_, Some(refunded)) => (deposits, refunded),
+            _ => return,
+        };
+        if let Some((index, amount)) = epilogue_events.last() {
+            if Some(*index) == last_event_index && *amount == epilogue_amount {
+                epilogue_events.pop();
+            }
+        }
+    }
+}
+
+/// Parses fungible store changes in an object group, for withdraws and deposits
+///
+/// Only primary fungible stores are tracked, as those are what make up an account's balance
+async fn parse_fungible_store_changes(
+    server_context: &RosettaContext,
+    version: u64,
+    store_address: AccountAddress,
+    data: &[u8],
+    events: &[ContractEvent],
+    epilogue_fee_change: Option<&EpilogueFeeChange>,
+    mut operation_index: u64,
+) -> ApiResult<Vec<Operation>> {
+    let object_group: ObjectGroupResource = if let Ok(object_group) = bcs::from_bytes(data) {
+        object_group
+    } else {
+        warn!(
+            "Object group failed to parse for address {} at version {}",
+            store_address, version
+        );
+        return Ok(vec![]);
+    };
+
+    let mut maybe_owner = None;
+    let mut maybe_store = None;
+    for (struct_tag, bytes) in object_group.group.iter() {
+        match (
+            struct_tag.address,
+            struct_tag.module.as_str(),
+            struct_tag.name.as_str(),
+        ) {
+            (AccountAddress::ONE, OBJECT_MODULE, OBJECT_CORE_RESOURCE) => {
+                maybe_owner = bcs::from_bytes::<ObjectCore>(bytes)
+                    .ok()
+                    .map(|object_core| object_core.owner);
+            },
+            (AccountAddress::ONE, FUNGIBLE_ASSET_MODULE, FUNGIBLE_STORE_RESOURCE) => {
+                maybe_store = bcs::from_bytes::<FungibleStore>(bytes).ok();
+            },
+            _ => {},
+        }
+    }
+
+    // Skip objects that aren't fungible stores
+    let (owner, store) = if let (Some(owner), Some(store)) = (maybe_owner, maybe_store) {
+        (owner, store)
+    } else {
+        return Ok(vec![]);
+    };
+
+    // Skip secondary stores, and fungible assets that aren't supported
+    if store_address != create_derived_object_address(owner, store.metadata) {
+        return Ok(vec![]);
+    }
+    let currency =
+        if let Some(currency) = find_fungible_asset_currency(server_context, store.metadata) {
+            currency
+        } else {
+            return Ok(vec![]);
+        };
+
+    let mut withdraws =
+        get_fungible_asset_events(events, store_address, FUNGIBLE_ASSET_WITHDRAW_EVENT);
+    let mut deposits =
+        get_fungible_asset_events(events, store_address, FUNGIBLE_ASSET_DEPOSIT_EVENT);
+
+    // The epilogue's burn or refund is already accounted for by the fee operations
+    if let Some(epilogue_fee_change) = epilogue_fee_change {
+        if owner == epilogue_fee_change.gas_payer && currency == native_coin() {
+            epilogue_fee_change.remove_epilogue_events(&mut withdraws, &mut deposits);
+        }
+    }
+
+    let mut operations = vec![];
+    for (_, amount) in withdraws {
+        operations.push(Operation::withdraw(
+            operation_index,
+            Some(OperationStatusType::Success),
+            AccountIdentifier::base_account(owner),
+            currency.clone(),
+            amount,
+        ));
+        operation_index += 1;
+    }
+
+    for (_, amount) in deposits {
+        operations.push(Operation::deposit(
+            operation_index,
+            Some(OperationStatusType::Success),
+            AccountIdentifier::base_account(owner),
+            currency.clone(),
+            amount,
+        ));
+        operation_index += 1;
+    }
+
+    Ok(operations)
+}
+
+/// Pulls the event indices and amounts from the fungible asset events of a specific type for the
+/// given store
+fn get_fungible_asset_events(
+    events: &[ContractEvent],
+    store_address: AccountAddress,
+    event_name: &str,
+) -> Vec<(usize, u64)> {
+    events
+        .iter()
+        .enumerate()
+        .filter(|(_, event)| event.is_v2())
+        .filter(|(_, event)| match event.type_tag() {
+            TypeTag::Struct(struct_tag) => {
+                struct_tag.address == AccountAddress::ONE
+                    && struct_tag.module.as_str() == FUNGIBLE_ASSET_MODULE
+                    && struct_tag.name.as_str() == event_name
+            },
+            _ => false,
+        })
+        .filter_map(|(index, event)| {
+            match bcs::from_bytes::<FungibleAssetEvent>(event.event_data()) {
+                Ok(event) => Some((index, event)),
+                Err(_) => {
+                    warn!("Failed to parse fungible asset {} event!", event_name);
+                    None
+                },
+            }
+        })
+        .filter(|(_, event)| event.store == store_address)
+        .map(|(index, event)| (index, event.amount))
+        .collect()
+}
+
 /// Pulls the balance change from a withdraw or deposit event
 fn get_amount_from_event(events: &[ContractEvent], event_key: &EventKey) -> Vec<u64> {
     filter_events(events, event_key, |event_key, event| {
@@ -2201,11 +2516,22 @@ impl InternalOperation {
                 create_account.sender,
             ),
             InternalOperation::Transfer(transfer) => {
-                is_native_coin(&transfer.currency)?;
-                (
-                    aptos_stdlib::aptos_account_transfer(transfer.receiver, transfer.amount.0),
-                    transfer.sender,
-                )
+                if let Some(metadata_address) = fungible_asset_address(&transfer.currency)? {
+                    (
+                        primary_fungible_store_transfer(
+                            metadata_address,
+                            transfer.receiver,
+                            transfer.amount.0,
+                        ),
+                        transfer.sender,
+                    )
+                } else {
+                    is_native_coin(&transfer.currency)?;
+                    (
+                        aptos_stdlib::aptos_account_transfer(transfer.receiver, transfer.amount.0),
+                        transfer.sender,
+                    )
+                }
             },
             InternalOperation::SetOperator(set_operator) => {
                 if set_operator.old_operator.is_none() {
@@ -2295,6 +2621,34 @@ impl InternalOperation {
     }
 }
 
+/// Builds 0x1::primary_fungible_store::transfer<0x1::fungible_asset::Metadata>(metadata, recipient, amount)
+///
+/// This is generic over the object type, so it isn't in the generated [aptos_stdlib]
+fn primary_fungible_store_transfer(
+    metadata_address: AccountAddress,
+    receiver: AccountAddress,
+    amount: u64,
+) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(
+            AccountAddress::ONE,
+            ident_str!(PRIMARY_FUNGIBLE_STORE_MODULE).to_owned(),
+        ),
+        ident_str!(TRANSFER_FUNCTION).to_owned(),
+        vec![TypeTag::Struct(Box::new(StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!(FUNGIBLE_ASSET_MODULE).to_owned(),
+            name: ident_str!(FUNGIBLE_ASSET_METADATA_RESOURCE).to_owned(),
+            type_args: vec![],
+        }))],
+        vec![
+            bcs::to_bytes(&metadata_address).unwrap(),
+            bcs::to_bytes(&receiver).unwrap(),
+            bcs::to_bytes(&amount).unwrap(),
+        ],
+    ))
+}
+
 /// Operation to create an account
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CreateAccount {
@@ -2384,8 +2738,7 @@ impl Transfer {
         }
 
         // Check that the currency is supported
-        // TODO: in future use currency, since there's more than just 1
-        is_native_coin(&withdraw_amount.currency)?;
+        is_native_coin_or_fungible_asset(&withdraw_amount.currency)?;
 
         let withdraw_value = i128::from_str(&withdraw_amount.value)
             .map_err(|_| ApiError::InvalidTransferOperations(Some("Withdraw amount is invalid")))?;
@@ -2490,3 +2843,331 @@ pub struct UnlockDelegatedStake {
     pub pool_address: AccountAddress,
     pub amount: u64,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::native_coin_fa_address;
+    use aptos_types::{
+        chain_id::ChainId,
+        event::EventHandle,
+        transaction::{
+            authenticator::{AccountAuthenticator, TransactionAuthenticator},
+            RawTransaction,
+        },
+    };
+    use std::collections::HashSet;
+
+    const FEE_PAYER_BALANCE: u64 = 1_000_000;
+
+    fn fungible_asset_event(event_name: &str, store: AccountAddress, amount: u64) -> ContractEvent {
+        ContractEvent::new_v2_with_type_tag_str(
+            &format!("0x1::{}::{}", FUNGIBLE_ASSET_MODULE, event_name),
+            bcs::to_bytes(&FungibleAssetEvent { store, amount }).unwrap(),
+        )
+    }
+
+    fn fee_statement_event(storage_fee_refund: u64) -> ContractEvent {
+        ContractEvent::new_v2(
+            FEE_STATEMENT_EVENT_TYPE.clone(),
+            bcs::to_bytes(&FeeStatement::new(10, 5, 5, 0, storage_fee_refund)).unwrap(),
+        )
+    }
+
+    fn fungible_store_group(owner: AccountAddress, metadata: AccountAddress) -> Vec<u8> {
+        let mut object_group = ObjectGroupResource::default();
+        object_group.insert(
+            StructTag::from_str("0x1::object::ObjectCore").unwrap(),
+            bcs::to_bytes(&ObjectCore {
+                guid_creation_num: 0,
+                owner,
+                allow_ungated_transfer: false,
+                transfer_events: EventHandle::new(EventKey::new(0, owner), 0),
+            })
+            .unwrap(),
+        );
+        object_group.insert(
+            StructTag::from_str("0x1::fungible_asset::FungibleStore").unwrap(),
+            bcs::to_bytes(&FungibleStore {
+                metadata,
+                balance: FEE_PAYER_BALANCE,
+                frozen: false,
+            })
+            .unwrap(),
+        );
+        object_group.to_bytes().unwrap()
+    }
+
+    fn fungible_asset_currency(symbol: &str, fa_address: AccountAddress) -> Currency {
+        Currency {
+            symbol: symbol.to_string(),
+            decimals: 8,
+            metadata: Some(CurrencyMetadata {
+                move_type: None,
+                fa_address: Some(fa_address.to_hex_literal()),
+            }),
+        }
+    }
+
+    fn user_transaction(
+        sender: AccountAddress,
+        fee_payer: Option<AccountAddress>,
+    ) -> SignedTransaction {
+        let raw_txn = RawTransaction::new(
+            sender,
+            0,
+            aptos_stdlib::aptos_account_transfer(AccountAddress::ONE, 100),
+            1_000,
+            100,
+            0,
+            ChainId::test(),
+        );
+        match fee_payer {
+            Some(fee_payer) => SignedTransaction::new_fee_payer(
+                raw_txn,
+                AccountAuthenticator::NoAccountAuthenticator,
+                vec![],
+                vec![],
+                fee_payer,
+                AccountAuthenticator::NoAccountAuthenticator,
+            ),
+            None => SignedTransaction::new_signed_transaction(
+                raw_txn,
+                TransactionAuthenticator::single_sender(
+                    AccountAuthenticator::NoAccountAuthenticator,
+                ),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_get_gas_payer() {
+        let sender = AccountAddress::from_str("0xcafe").unwrap();
+        let fee_payer = AccountAddress::from_str("0xfee").unwrap();
+
+        // The sender pays for its own transactions
+        assert_eq!(get_gas_payer(&user_transaction(sender, None)), sender);
+
+        // The fee payer pays for sponsored transactions
+        assert_eq!(
+            get_gas_payer(&user_transaction(sender, Some(fee_payer))),
+            fee_payer
+        );
+    }
+
+    #[test]
+    fn test_remove_epilogue_events() {
+        let burn = EpilogueFeeChange {
+            gas_payer: AccountAddress::ONE,
+            gas_fee: 50,
+            storage_fee_refund: 0,
+        };
+
+        // The burn is the last event for the store, it's removed
+        let mut withdraws = vec![(0, 50), (3, 50)];
+        let mut deposits = vec![(1, 50)];
+        burn.remove_epilogue_events(&mut withdraws, &mut deposits);
+        assert_eq!(withdraws, vec![(0, 50)]);
+        assert_eq!(deposits, vec![(1, 50)]);
+
+        // A withdraw with the same amount that isn't last is kept
+        let mut withdraws = vec![(0, 50)];
+        let mut deposits = vec![(1, 50)];
+        burn.remove_epilogue_events(&mut withdraws, &mut deposits);
+        assert_eq!(withdraws, vec![(0, 50)]);
+        assert_eq!(deposits, vec![(1, 50)]);
+
+        // A last withdraw with a different amount isn't the burn
+        let mut withdraws = vec![(0, 40)];
+        burn.remove_epilogue_events(&mut withdraws, &mut vec![]);
+        assert_eq!(withdraws, vec![(0, 40)]);
+
+        // Refunds are removed from the deposits
+        let refund = EpilogueFeeChange {
+            gas_payer: AccountAddress::ONE,
+            gas_fee: 10,
+            storage_fee_refund: 30,
+        };
+        let mut withdraws = vec![(0, 20)];
+        let mut deposits = vec![(1, 20), (2, 20)];
+        refund.remove_epilogue_events(&mut withdraws, &mut deposits);
+        assert_eq!(withdraws, vec![(0, 20)]);
+        assert_eq!(deposits, vec![(1, 20)]);
+
+        // Nothing is removed if the fee is fully covered by the refund
+        let no_change = EpilogueFeeChange {
+            gas_payer: AccountAddress::ONE,
+            gas_fee: 30,
+            storage_fee_refund: 30,
+        };
+        let mut withdraws = vec![(0, 30)];
+        no_change.remove_epilogue_events(&mut withdraws, &mut vec![]);
+        assert_eq!(withdraws, vec![(0, 30)]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_fungible_store_changes() {
+        let fa_address = AccountAddress::from_str("0xfa").unwrap();
+        let fa_currency = fungible_asset_currency("FA", fa_address);
+        let server_context = RosettaContext::new(
+            None,
+            ChainId::test(),
+            None,
+            HashSet::from([fa_currency.clone()]),
+        )
+        .await;
+        let owner = AccountAddress::from_str("0xcafe").unwrap();
+        let other_store = AccountAddress::from_str("0xbeef").unwrap();
+
+        // A transfer of APT out of the gas payer's primary store, with the gas fee burned in the
+        // epilogue, shows up as a single withdraw
+        let apt_store = create_derived_object_address(owner, native_coin_fa_address());
+        let events = vec![
+            fungible_asset_event(FUNGIBLE_ASSET_WITHDRAW_EVENT, apt_store, 100),
+            fungible_asset_event(FUNGIBLE_ASSET_DEPOSIT_EVENT, other_store, 100),
+            fungible_asset_event(FUNGIBLE_ASSET_WITHDRAW_EVENT, apt_store, 50),
+            fee_statement_event(0),
+        ];
+        let epilogue_fee_change = EpilogueFeeChange {
+            gas_payer: owner,
+            gas_fee: 50,
+            storage_fee_refund: 0,
+        };
+        let operations = parse_fungible_store_changes(
+            &server_context,
+            0,
+            apt_store,
+            &fungible_store_group(owner, native_coin_fa_address()),
+            &events,
+            Some(&epilogue_fee_change),
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(operations, vec![Operation::withdraw(
+            0,
+            Some(OperationStatusType::Success),
+            AccountIdentifier::base_account(owner),
+            native_coin(),
+            100,
+        )]);
+
+        // For a sponsored transaction, the fee payer is charged for gas, so a trailing withdraw
+        // from the sender's primary store isn't the epilogue's burn
+        let fee_payer = AccountAddress::from_str("0xfee").unwrap();
+        let sponsored_fee_change = EpilogueFeeChange {
+            gas_payer: get_gas_payer(&user_transaction(owner, Some(fee_payer))),
+            gas_fee: 50,
+            storage_fee_refund: 0,
+        };
+        let operations = parse_fungible_store_changes(
+            &server_context,
+            0,
+            apt_store,
+            &fungible_store_group(owner, native_coin_fa_address()),
+            &events,
+            Some(&sponsored_fee_change),
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(operations, vec![
+            Operation::withdraw(
+                0,
+                Some(OperationStatusType::Success),
+                AccountIdentifier::base_account(owner),
+                native_coin(),
+                100,
+            ),
+            Operation::withdraw(
+                1,
+                Some(OperationStatusType::Success),
+                AccountIdentifier::base_account(owner),
+                native_coin(),
+                50,
+            ),
+        ]);
+
+        // The epilogue's burn is removed from the fee payer's primary store instead
+        let fee_payer_store = create_derived_object_address(fee_payer, native_coin_fa_address());
+        let fee_payer_events = vec![
+            fungible_asset_event(FUNGIBLE_ASSET_WITHDRAW_EVENT, fee_payer_store, 50),
+            fee_statement_event(0),
+        ];
+        let operations = parse_fungible_store_changes(
+            &server_context,
+            0,
+            fee_payer_store,
+            &fungible_store_group(fee_payer, native_coin_fa_address()),
+            &fee_payer_events,
+            Some(&sponsored_fee_change),
+            0,
+        )
+        .await
+        .unwrap();
+        assert!(operations.is_empty());
+
+        // Transfers of a supported fungible asset aren't affected by the epilogue
+        let fa_store = create_derived_object_address(owner, fa_address);
+        let events = vec![
+            fungible_asset_event(FUNGIBLE_ASSET_WITHDRAW_EVENT, fa_store, 50),
+            fungible_asset_event(FUNGIBLE_ASSET_DEPOSIT_EVENT, fa_store, 20),
+            fee_statement_event(0),
+        ];
+        let operations = parse_fungible_store_changes(
+            &server_context,
+            0,
+            fa_store,
+            &fungible_store_group(owner, fa_address),
+            &events,
+            Some(&epilogue_fee_change),
+            3,
+        )
+        .await
+        .unwrap();
+        assert_eq!(operations, vec![
+            Operation::withdraw(
+                3,
+                Some(OperationStatusType::Success),
+                AccountIdentifier::base_account(owner),
+                fa_currency.clone(),
+                50,
+            ),
+            Operation::deposit(
+                4,
+                Some(OperationStatusType::Success),
+                AccountIdentifier::base_account(owner),
+                fa_currency,
+                20,
+            ),
+        ]);
+
+        // Secondary stores and unsupported fungible assets are skipped
+        let unsupported_address = AccountAddress::from_str("0xbad").unwrap();
+        for (store_address, metadata) in [
+            (other_store, fa_address),
+            (
+                create_derived_object_address(owner, unsupported_address),
+                unsupported_address,
+            ),
+        ] {
+            let events = vec![fungible_asset_event(
+                FUNGIBLE_ASSET_DEPOSIT_EVENT,
+                store_address,
+                20,
+            )];
+            let operations = parse_fungible_store_changes(
+                &server_context,
+                0,
+                store_address,
+                &fungible_store_group(owner, metadata),
+                &events,
+                None,
+                0,
+            )
+            .await
+            .unwrap();
+            assert!(operations.is_empty());
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliResult};
+use clap::Subcommand;
+
+pub mod offline;
+
+/// Tool for building, signing and submitting transactions in separate steps
+///
+/// This allows a transaction to be built on an online machine, signed on an
+/// air-gapped machine that holds the private keys, and then submitted from an
+/// online machine again.  Multi-agent and fee payer transactions collect one
+/// signature file per signer.
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Build(offline::BuildTransaction),
+    Sign(offline::SignTransaction),
+    Combine(offline::CombineTransaction),
+    Submit(offline::SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Build(tool) => tool.execute_serialized().await,
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Combine(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
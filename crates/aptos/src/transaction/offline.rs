@@ -0,0 +1,573 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliTypedResult, EncodingOptions, EntryFunctionArguments,
+        PrivateKeyInputOptions, ProfileOptions, PromptOptions, RestOptions, SaveFile,
+        TransactionSummary,
+    },
+    utils::{explorer_transaction_link, prompt_yes_with_override, read_from_file},
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    HashValue, PrivateKey, SigningKey,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{
+        authenticator::AccountAuthenticator, RawTransaction, RawTransactionWithData,
+        SignedTransaction, TransactionPayload,
+    },
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// An unsigned transaction, along with every other account that must sign it
+///
+/// This is what gets written by `aptos transaction build` and passed around to
+/// each of the signers.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UnsignedTransaction {
+    pub raw_txn: RawTransaction,
+    pub secondary_signer_addresses: Vec<AccountAddress>,
+    pub fee_payer_address: Option<AccountAddress>,
+}
+
+impl UnsignedTransaction {
+    /// A hash of the whole unsigned transaction, used to match signatures to it
+    pub fn hash(&self) -> HashValue {
+        HashValue::sha3_256_of(&bcs::to_bytes(self).expect("Transaction must serialize"))
+    }
+
+    /// All accounts that must sign, starting with the sender
+    pub fn signers(&self) -> Vec<AccountAddress> {
+        let mut signers = vec![self.raw_txn.sender()];
+        signers.extend(self.secondary_signer_addresses.iter().copied());
+        signers.extend(self.fee_payer_address);
+        signers
+    }
+
+    /// Signs the message that every signer of this transaction must sign
+    pub fn sign(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<Ed25519Signature> {
+        let result = match self.fee_payer_address {
+            Some(fee_payer_address) => private_key.sign(&RawTransactionWithData::new_fee_payer(
+                self.raw_txn.clone(),
+                self.secondary_signer_addresses.clone(),
+                fee_payer_address,
+            )),
+            None if !self.secondary_signer_addresses.is_empty() => {
+                private_key.sign(&RawTransactionWithData::new_multi_agent(
+                    self.raw_txn.clone(),
+                    self.secondary_signer_addresses.clone(),
+                ))
+            },
+            None => private_key.sign(&self.raw_txn),
+        };
+        result.map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+
+    /// Assembles the signed transaction, expecting exactly one signature per signer
+    pub fn into_signed_transaction(
+        self,
+        signatures: Vec<TransactionSignature>,
+    ) -> CliTypedResult<SignedTransaction> {
+        let hash = self.hash();
+        let mut authenticators = BTreeMap::new();
+        for signature in signatures {
+            if signature.unsigned_transaction_hash != hash {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Signature from {} is for a different transaction",
+                    signature.signer
+                )));
+            }
+            if !self.signers().contains(&signature.signer) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "{} is not a signer of this transaction",
+                    signature.signer
+                )));
+            }
+            let authenticator =
+                AccountAuthenticator::ed25519(signature.public_key, signature.signature);
+            if authenticators
+                .insert(signature.signer, authenticator)
+                .is_some()
+            {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Duplicate signature from {}",
+                    signature.signer
+                )));
+            }
+        }
+
+        let mut take_authenticator = |signer: AccountAddress| {
+            authenticators.remove(&signer).ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Missing signature from {}", signer))
+            })
+        };
+        let sender = take_authenticator(self.raw_txn.sender())?;
+        let secondary_signers = self
+            .secondary_signer_addresses
+            .iter()
+            .map(|address| take_authenticator(*address))
+            .collect::<CliTypedResult<Vec<_>>>()?;
+
+        let signed_transaction = match self.fee_payer_address {
+            Some(fee_payer_address) => SignedTransaction::new_fee_payer(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                take_authenticator(fee_payer_address)?,
+            ),
+            None if !self.secondary_signer_addresses.is_empty() => {
+                SignedTransaction::new_multi_agent(
+                    self.raw_txn,
+                    sender,
+                    self.secondary_signer_addresses,
+                    secondary_signers,
+                )
+            },
+            None => SignedTransaction::new_single_sender(self.raw_txn, sender),
+        };
+
+        signed_transaction.verify_signature().map_err(|err| {
+            CliError::CommandArgumentError(format!("Invalid signatures: {:#}", err))
+        })?;
+        Ok(signed_transaction)
+    }
+}
+
+/// A signature from one of the signers of an [`UnsignedTransaction`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionSignature {
+    /// Ties the signature to the transaction it was made for
+    pub unsigned_transaction_hash: HashValue,
+    pub signer: AccountAddress,
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+/// What is being signed or submitted, so it can be reviewed first
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OfflineTransactionSummary {
+    /// Hash of the unsigned transaction, which every signature must match
+    pub unsigned_transaction_hash: HashValue,
+    /// Hash of the signed transaction, only known once all signatures are combined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<HashValue>,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub secondary_signers: Vec<AccountAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<AccountAddress>,
+    pub function: String,
+    pub type_args: Vec<String>,
+    /// BCS encoded arguments, as hex
+    pub args: Vec<String>,
+    pub gas_unit_price: u64,
+    pub max_gas: u64,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: ChainId,
+}
+
+impl From<&UnsignedTransaction> for OfflineTransactionSummary {
+    fn from(unsigned_transaction: &UnsignedTransaction) -> Self {
+        let raw_txn = &unsigned_transaction.raw_txn;
+        let (function, type_args, args) = match raw_txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => (
+                format!(
+                    "{}::{}",
+                    entry_function.module().short_str_lossless(),
+                    entry_function.function()
+                ),
+                entry_function
+                    .ty_args()
+                    .iter()
+                    .map(|type_arg| type_arg.to_string())
+                    .collect(),
+                entry_function.args().iter().map(hex::encode).collect(),
+            ),
+            payload => (format!("{:?}", payload), vec![], vec![]),
+        };
+
+        OfflineTransactionSummary {
+            unsigned_transaction_hash: unsigned_transaction.hash(),
+            transaction_hash: None,
+            sender: raw_txn.sender(),
+            sequence_number: raw_txn.sequence_number(),
+            secondary_signers: unsigned_transaction.secondary_signer_addresses.clone(),
+            fee_payer: unsigned_transaction.fee_payer_address,
+            function,
+            type_args,
+            args,
+            gas_unit_price: raw_txn.gas_unit_price(),
+            max_gas: raw_txn.max_gas_amount(),
+            expiration_timestamp_secs: raw_txn.expiration_timestamp_secs(),
+            chain_id: raw_txn.chain_id(),
+        }
+    }
+}
+
+fn load_bcs_file<T: for<'a> Deserialize<'a>>(path: &Path, name: &'static str) -> CliTypedResult<T> {
+    bcs::from_bytes(&read_from_file(path)?).map_err(|err| CliError::BCS(name, err))
+}
+
+/// Build an unsigned transaction and save it to a file
+///
+/// Nothing is looked up on chain, so the sequence number, gas and expiration
+/// must all be provided.  The output file is signed with `aptos transaction sign`.
+#[derive(Debug, Parser)]
+pub struct BuildTransaction {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+
+    /// Address of the account sending the transaction
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) sender_account: AccountAddress,
+
+    /// Sequence number of the sender to use for the transaction
+    #[clap(long)]
+    pub(crate) sequence_number: u64,
+
+    /// Addresses of the secondary signers, for a multi-agent transaction
+    #[clap(long, num_args = 0.., value_parser = crate::common::types::load_account_arg)]
+    pub(crate) secondary_signer_addresses: Vec<AccountAddress>,
+
+    /// Address of the account paying for gas, for a fee payer transaction
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) fee_payer_address: Option<AccountAddress>,
+
+    /// Gas unit price in Octas (10^-8 APT)
+    #[clap(long)]
+    pub(crate) gas_unit_price: u64,
+
+    /// Maximum amount of gas units to be used by the transaction
+    #[clap(long)]
+    pub(crate) max_gas: u64,
+
+    /// Time the transaction expires, as a Unix timestamp in seconds
+    ///
+    /// Leave enough time for the transaction to be carried to the signers and back.
+    #[clap(long)]
+    pub(crate) expiration_timestamp_secs: u64,
+
+    /// Chain ID of the network the transaction is for, e.g. `mainnet` or `1`
+    #[clap(long)]
+    pub(crate) chain_id: ChainId,
+
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<OfflineTransactionSummary> for BuildTransaction {
+    fn command_name(&self) -> &'static str {
+        "BuildTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<OfflineTransactionSummary> {
+        self.save_file.check_file()?;
+
+        let unsigned_transaction = UnsignedTransaction {
+            raw_txn: RawTransaction::new(
+                self.sender_account,
+                self.sequence_number,
+                TransactionPayload::EntryFunction(self.entry_function_args.try_into()?),
+                self.max_gas,
+                self.gas_unit_price,
+                self.expiration_timestamp_secs,
+                self.chain_id,
+            ),
+            secondary_signer_addresses: self.secondary_signer_addresses,
+            fee_payer_address: self.fee_payer_address,
+        };
+
+        let bytes = bcs::to_bytes(&unsigned_transaction)
+            .map_err(|err| CliError::BCS("UnsignedTransaction", err))?;
+        self.save_file
+            .save_to_file("Unsigned transaction", &bytes)?;
+        Ok(OfflineTransactionSummary::from(&unsigned_transaction))
+    }
+}
+
+/// Sign an unsigned transaction file with a local key, without going online
+///
+/// The key is taken from `--private-key`, `--private-key-file` or the profile.
+/// The signature is saved to its own file, to be combined with the signatures of
+/// any other signers by `aptos transaction combine`.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    /// Unsigned transaction file created by `aptos transaction build`
+    #[clap(long, value_parser)]
+    pub(crate) transaction_file: PathBuf,
+
+    /// Address to sign as
+    ///
+    /// Defaults to the account of the profile, or the address derived from the key
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) signer_account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<OfflineTransactionSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<OfflineTransactionSummary> {
+        self.save_file.check_file()?;
+
+        let unsigned_transaction: UnsignedTransaction =
+            load_bcs_file(&self.transaction_file, "UnsignedTransaction")?;
+        let (private_key, signer) = self.private_key_options.extract_private_key_and_address(
+            self.encoding_options.encoding,
+            &self.profile_options,
+            self.signer_account,
+        )?;
+        if !unsigned_transaction.signers().contains(&signer) {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} is not a signer of this transaction, use --signer-account to sign as another account",
+                signer
+            )));
+        }
+
+        let summary = OfflineTransactionSummary::from(&unsigned_transaction);
+        let message = format!(
+            "Do you want to sign this transaction as {}?\n{}",
+            signer,
+            serde_json::to_string_pretty(&summary)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+        );
+        prompt_yes_with_override(&message, self.save_file.prompt_options)?;
+
+        let signature = TransactionSignature {
+            unsigned_transaction_hash: summary.unsigned_transaction_hash,
+            signer,
+            public_key: private_key.public_key(),
+            signature: unsigned_transaction.sign(&private_key)?,
+        };
+        let bytes =
+            bcs::to_bytes(&signature).map_err(|err| CliError::BCS("TransactionSignature", err))?;
+        self.save_file
+            .save_to_file("Transaction signature", &bytes)?;
+        Ok(summary)
+    }
+}
+
+/// Combine the signatures of every signer into a signed transaction file
+///
+/// Signatures are checked before saving, and the output file can be submitted
+/// with `aptos transaction submit`.
+#[derive(Debug, Parser)]
+pub struct CombineTransaction {
+    /// Unsigned transaction file created by `aptos transaction build`
+    #[clap(long, value_parser)]
+    pub(crate) transaction_file: PathBuf,
+
+    /// Signature files created by `aptos transaction sign`, one for each signer
+    #[clap(long, num_args = 1.., required = true, value_parser)]
+    pub(crate) signature_files: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<OfflineTransactionSummary> for CombineTransaction {
+    fn command_name(&self) -> &'static str {
+        "CombineTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<OfflineTransactionSummary> {
+        self.save_file.check_file()?;
+
+        let unsigned_transaction: UnsignedTransaction =
+            load_bcs_file(&self.transaction_file, "UnsignedTransaction")?;
+        let signatures = self
+            .signature_files
+            .iter()
+            .map(|path| load_bcs_file(path, "TransactionSignature"))
+            .collect::<CliTypedResult<Vec<TransactionSignature>>>()?;
+
+        let mut summary = OfflineTransactionSummary::from(&unsigned_transaction);
+        let signed_transaction = unsigned_transaction.into_signed_transaction(signatures)?;
+        summary.transaction_hash = Some(signed_transaction.committed_hash());
+
+        let bytes = bcs::to_bytes(&signed_transaction)
+            .map_err(|err| CliError::BCS("SignedTransaction", err))?;
+        self.save_file.save_to_file("Signed transaction", &bytes)?;
+        Ok(summary)
+    }
+}
+
+/// Submit a signed transaction file and wait for it to be committed
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    /// Signed transaction file created by `aptos transaction combine`
+    #[clap(long, value_parser)]
+    pub(crate) transaction_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let transaction: SignedTransaction =
+            load_bcs_file(&self.transaction_file, "SignedTransaction")?;
+        transaction.verify_signature().map_err(|err| {
+            CliError::CommandArgumentError(format!("Invalid signatures: {:#}", err))
+        })?;
+
+        let message = format!(
+            "Do you want to submit transaction {} from {} with sequence number {}?",
+            transaction.committed_hash(),
+            transaction.sender(),
+            transaction.sequence_number()
+        );
+        prompt_yes_with_override(&message, self.prompt_options)?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        client
+            .submit_bcs(&transaction)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?;
+        let network = self
+            .profile_options
+            .profile()
+            .ok()
+            .and_then(|profile| profile.network);
+        eprintln!(
+            "Transaction submitted: {}",
+            explorer_transaction_link(transaction.committed_hash(), network)
+        );
+        let response = client
+            .wait_for_signed_transaction(&transaction)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?;
+
+        Ok(TransactionSummary::from(&response.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::Uniform;
+    use aptos_types::transaction::EntryFunction;
+    use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+    fn unsigned_transaction(
+        sender: AccountAddress,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        fee_payer_address: Option<AccountAddress>,
+    ) -> UnsignedTransaction {
+        UnsignedTransaction {
+            raw_txn: RawTransaction::new(
+                sender,
+                7,
+                TransactionPayload::EntryFunction(EntryFunction::new(
+                    ModuleId::new(AccountAddress::ONE, Identifier::new("test").unwrap()),
+                    Identifier::new("run").unwrap(),
+                    vec![],
+                    vec![],
+                )),
+                1000,
+                100,
+                u64::MAX,
+                ChainId::test(),
+            ),
+            secondary_signer_addresses,
+            fee_payer_address,
+        }
+    }
+
+    fn sign(
+        unsigned_transaction: &UnsignedTransaction,
+        signer: AccountAddress,
+        private_key: &Ed25519PrivateKey,
+    ) -> TransactionSignature {
+        TransactionSignature {
+            unsigned_transaction_hash: unsigned_transaction.hash(),
+            signer,
+            public_key: private_key.public_key(),
+            signature: unsigned_transaction.sign(private_key).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_combine_fee_payer_signatures() {
+        let mut rng = rand::thread_rng();
+        let keys: Vec<_> = (0..3)
+            .map(|_| Ed25519PrivateKey::generate(&mut rng))
+            .collect();
+        let (sender, secondary, fee_payer) = (
+            AccountAddress::random(),
+            AccountAddress::random(),
+            AccountAddress::random(),
+        );
+        let txn = unsigned_transaction(sender, vec![secondary], Some(fee_payer));
+
+        // Signatures can come in any order
+        let signatures = vec![
+            sign(&txn, fee_payer, &keys[2]),
+            sign(&txn, sender, &keys[0]),
+            sign(&txn, secondary, &keys[1]),
+        ];
+        let signed_txn = txn.clone().into_signed_transaction(signatures).unwrap();
+        assert_eq!(signed_txn.sender(), sender);
+
+        // A missing signer is rejected
+        let signatures = vec![
+            sign(&txn, sender, &keys[0]),
+            sign(&txn, secondary, &keys[1]),
+        ];
+        assert!(txn.clone().into_signed_transaction(signatures).is_err());
+
+        // A signature for another transaction is rejected
+        let other_txn = unsigned_transaction(sender, vec![secondary], None);
+        let signatures = vec![
+            sign(&other_txn, sender, &keys[0]),
+            sign(&txn, secondary, &keys[1]),
+            sign(&txn, fee_payer, &keys[2]),
+        ];
+        assert!(txn.clone().into_signed_transaction(signatures).is_err());
+
+        // A signature over a different message is rejected
+        let mut forged_signature = sign(&other_txn, sender, &keys[0]);
+        forged_signature.unsigned_transaction_hash = txn.hash();
+        let signatures = vec![
+            forged_signature,
+            sign(&txn, secondary, &keys[1]),
+            sign(&txn, fee_payer, &keys[2]),
+        ];
+        assert!(txn.into_signed_transaction(signatures).is_err());
+    }
+}
@@ -21,8 +21,9 @@ use anyhow::{ensure, format_err, Result};
 use aptos_config::config::DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE;
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_logger::{error, info, sample, sample::SampleRate, warn};
-use aptos_rest_client::{aptos_api_types::AptosErrorCode, error::RestError, Client as RestClient};
+use aptos_rest_client::Client as RestClient;
 use aptos_sdk::{
+    account_submitter::{get_account_sequence_number, MAX_LEDGER_LAG_SECS},
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{transaction::SignedTransaction, AccountKey, LocalAccount},
@@ -1055,13 +1056,16 @@ async fn wait_for_accounts_sequence(
             },
         }
 
-        if aptos_infallible::duration_since_epoch().as_secs() >= txn_expiration_ts_secs + 240 {
+        if aptos_infallible::duration_since_epoch().as_secs()
+            >= txn_expiration_ts_secs + MAX_LEDGER_LAG_SECS
+        {
             sample!(
                 SampleRate::Duration(Duration::from_secs(15)),
                 error!(
-                    "[{}] Client cannot catch up to needed timestamp ({}), after additional 240s, aborting",
+                    "[{}] Client cannot catch up to needed timestamp ({}), after additional {}s, aborting",
                     client.path_prefix_string(),
                     txn_expiration_ts_secs,
+                    MAX_LEDGER_LAG_SECS,
                 )
             );
             break;
@@ -1113,27 +1117,7 @@ pub async fn get_account_seq_num(
     client: &RestClient,
     address: AccountAddress,
 ) -> Result<(u64, u64)> {
-    let result = client.get_account_bcs(address).await;
-    match &result {
-        Ok(resp) => Ok((
-            resp.inner().sequence_number(),
-            Duration::from_micros(resp.state().timestamp_usecs).as_secs(),
-        )),
-        Err(e) => {
-            // if account is not present, that is equivalent to sequence_number = 0
-            if let RestError::Api(api_error) = e {
-                if let AptosErrorCode::AccountNotFound = api_error.error.error_code {
-                    return Ok((
-                        0,
-                        Duration::from_micros(api_error.state.as_ref().unwrap().timestamp_usecs)
-                            .as_secs(),
-                    ));
-                }
-            }
-            result?;
-            unreachable!()
-        },
-    }
+    get_account_sequence_number(client, address).await
 }
 
 pub async fn load_specific_account(
@@ -11,6 +11,7 @@ mod latency;
 mod minimum_peers;
 mod node_identity;
 mod state_sync_version;
+mod storage_service;
 mod tps;
 mod traits;
 mod transaction_correctness;
@@ -27,6 +28,7 @@ use self::{
     minimum_peers::{MinimumPeersChecker, MinimumPeersCheckerConfig},
     node_identity::{NodeIdentityChecker, NodeIdentityCheckerConfig},
     state_sync_version::{StateSyncVersionChecker, StateSyncVersionCheckerConfig},
+    storage_service::{StorageServiceChecker, StorageServiceCheckerConfig},
     tps::{TpsChecker, TpsCheckerConfig},
     transaction_correctness::{TransactionCorrectnessChecker, TransactionCorrectnessCheckerConfig},
 };
@@ -50,6 +52,7 @@ pub enum CheckerConfig {
     MinimumPeers(MinimumPeersCheckerConfig),
     NodeIdentity(NodeIdentityCheckerConfig),
     StateSyncVersion(StateSyncVersionCheckerConfig),
+    StorageService(StorageServiceCheckerConfig),
     Tps(TpsCheckerConfig),
     TransactionCorrectness(TransactionCorrectnessCheckerConfig),
 }
@@ -69,6 +72,7 @@ impl CheckerConfig {
             Self::MinimumPeers(config) => Ok(Box::new(MinimumPeersChecker::new(config))),
             Self::NodeIdentity(config) => Ok(Box::new(NodeIdentityChecker::new(config))),
             Self::StateSyncVersion(config) => Ok(Box::new(StateSyncVersionChecker::new(config))),
+            Self::StorageService(config) => Ok(Box::new(StorageServiceChecker::new(config))),
             Self::Tps(config) => Ok(Box::new(TpsChecker::new(config)?)),
             Self::TransactionCorrectness(config) => {
                 Ok(Box::new(TransactionCorrectnessChecker::new(config)))
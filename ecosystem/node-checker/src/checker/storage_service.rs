@@ -0,0 +1,495 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{CheckResult, Checker, CheckerError, CommonCheckerConfig};
+use crate::{
+    get_provider,
+    provider::{noise::NoiseProvider, ProviderCollection},
+};
+use anyhow::{anyhow, bail, Result};
+use aptos_network::ProtocolId;
+use aptos_network_checker::RpcClient;
+use aptos_storage_service_types::{
+    requests::{
+        DataRequest, StateValuesWithProofRequest, StorageServiceRequest,
+        TransactionsWithProofRequest,
+    },
+    responses::{DataResponse, DataSummary, StorageServerSummary},
+    StorageServiceMessage,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageServiceCheckerConfig {
+    #[serde(flatten)]
+    pub common: CommonCheckerConfig,
+
+    /// The number of requests to send for each kind of data.
+    #[serde(default = "StorageServiceCheckerConfig::default_num_samples")]
+    pub num_samples: u16,
+
+    /// The number of transactions to request in each chunk.
+    #[serde(default = "StorageServiceCheckerConfig::default_transaction_chunk_size")]
+    pub transaction_chunk_size: u64,
+
+    /// The number of state values to request in each chunk.
+    #[serde(default = "StorageServiceCheckerConfig::default_state_chunk_size")]
+    pub state_chunk_size: u64,
+
+    /// How long to wait for each response before treating it as a failure.
+    #[serde(default = "StorageServiceCheckerConfig::default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+
+    /// Whether to ask for compressed responses, like state sync does.
+    #[serde(default = "StorageServiceCheckerConfig::default_use_compression")]
+    pub use_compression: bool,
+
+    /// The target must serve data at no less than this fraction of the
+    /// throughput of the baseline node.
+    #[serde(default = "StorageServiceCheckerConfig::default_min_throughput_ratio")]
+    pub min_throughput_ratio: f64,
+
+    /// The average latency of the target may be at most this multiple of
+    /// the average latency of the baseline node.
+    #[serde(default = "StorageServiceCheckerConfig::default_max_latency_ratio")]
+    pub max_latency_ratio: f64,
+
+    /// How far the highest transaction version the target advertises may lag
+    /// behind the baseline node.
+    #[serde(default = "StorageServiceCheckerConfig::default_version_delta_tolerance")]
+    pub version_delta_tolerance: u64,
+}
+
+impl StorageServiceCheckerConfig {
+    const fn default_num_samples() -> u16 {
+        3
+    }
+
+    const fn default_transaction_chunk_size() -> u64 {
+        1000
+    }
+
+    const fn default_state_chunk_size() -> u64 {
+        1000
+    }
+
+    const fn default_request_timeout_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_use_compression() -> bool {
+        true
+    }
+
+    const fn default_min_throughput_ratio() -> f64 {
+        0.5
+    }
+
+    const fn default_max_latency_ratio() -> f64 {
+        3.0
+    }
+
+    const fn default_version_delta_tolerance() -> u64 {
+        5000
+    }
+}
+
+/// Aggregated measurements from serving the same request several times.
+#[derive(Debug, Default)]
+struct ServingStats {
+    num_items: u64,
+    num_responses: u32,
+    total_latency: Duration,
+}
+
+impl ServingStats {
+    fn average_latency(&self) -> Duration {
+        self.total_latency
+            .checked_div(self.num_responses)
+            .unwrap_or_default()
+    }
+
+    /// Items (transactions, state values) served per second.
+    fn throughput(&self) -> f64 {
+        match self.total_latency.as_secs_f64() {
+            secs if secs > 0.0 => self.num_items as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StorageServiceChecker {
+    config: StorageServiceCheckerConfig,
+}
+
+impl StorageServiceChecker {
+    pub fn new(config: StorageServiceCheckerConfig) -> Self {
+        Self { config }
+    }
+
+    async fn send_request(
+        &self,
+        client: &mut RpcClient,
+        data_request: DataRequest,
+    ) -> Result<DataResponse> {
+        let request = StorageServiceMessage::Request(StorageServiceRequest::new(
+            data_request,
+            self.config.use_compression,
+        ));
+        let response = client
+            .send_request(
+                ProtocolId::StorageServiceRpc,
+                &request,
+                Duration::from_millis(self.config.request_timeout_ms),
+            )
+            .await?;
+        match response {
+            StorageServiceMessage::Response(Ok(response)) => Ok(response.get_data_response()?),
+            StorageServiceMessage::Response(Err(error)) => {
+                bail!("The storage service returned an error: {}", error)
+            },
+            StorageServiceMessage::Request(_) => {
+                bail!("The storage service sent a request instead of a response")
+            },
+        }
+    }
+
+    async fn get_storage_server_summary(
+        &self,
+        client: &mut RpcClient,
+    ) -> Result<StorageServerSummary> {
+        match self
+            .send_request(client, DataRequest::GetStorageServerSummary)
+            .await?
+        {
+            DataResponse::StorageServerSummary(summary) => Ok(summary),
+            response => bail!("Unexpected response type: {}", response.get_label()),
+        }
+    }
+
+    /// Sends the same request a few times, recording how much data came back
+    /// and how long it took.
+    async fn measure_serving(
+        &self,
+        client: &mut RpcClient,
+        data_request: &DataRequest,
+    ) -> Result<ServingStats> {
+        let mut stats = ServingStats::default();
+        for _ in 0..self.config.num_samples {
+            let start = Instant::now();
+            let response = self.send_request(client, data_request.clone()).await?;
+            stats.total_latency += start.elapsed();
+            stats.num_responses += 1;
+            stats.num_items += match response {
+                DataResponse::TransactionsWithProof(transaction_list) => {
+                    transaction_list.transactions.len() as u64
+                },
+                DataResponse::StateValueChunkWithProof(state_value_chunk) => {
+                    state_value_chunk.raw_values.len() as u64
+                },
+                response => bail!("Unexpected response type: {}", response.get_label()),
+            };
+        }
+        Ok(stats)
+    }
+
+    /// Request the most recent chunk of transactions the node can prove.
+    fn build_transactions_request(&self, data_summary: &DataSummary) -> Option<DataRequest> {
+        let transactions = data_summary.transactions.as_ref()?;
+        let synced_version = data_summary
+            .synced_ledger_info
+            .as_ref()?
+            .ledger_info()
+            .version();
+        let end_version = transactions.highest().min(synced_version);
+        let start_version = end_version
+            .saturating_sub(self.config.transaction_chunk_size.saturating_sub(1))
+            .max(transactions.lowest());
+        if start_version > end_version {
+            return None;
+        }
+
+        Some(DataRequest::GetTransactionsWithProof(
+            TransactionsWithProofRequest {
+                proof_version: end_version,
+                start_version,
+                end_version,
+                include_events: false,
+            },
+        ))
+    }
+
+    /// Request the first chunk of state values at the most recent state version.
+    fn build_state_values_request(&self, data_summary: &DataSummary) -> Option<DataRequest> {
+        let states = data_summary.states.as_ref()?;
+        Some(DataRequest::GetStateValuesWithProof(
+            StateValuesWithProofRequest {
+                version: states.highest(),
+                start_index: 0,
+                end_index: self.config.state_chunk_size.saturating_sub(1),
+            },
+        ))
+    }
+
+    fn build_data_summary_result(
+        &self,
+        target_data_summary: &DataSummary,
+        baseline_data_summary: &DataSummary,
+    ) -> CheckResult {
+        let (target_transactions, target_states) = match (
+            &target_data_summary.transactions,
+            &target_data_summary.states,
+        ) {
+            (Some(transactions), Some(states)) => (transactions, states),
+            _ => {
+                return Self::build_result(
+                    "Storage service is not advertising data".to_string(),
+                    0,
+                    format!(
+                        "Your node's storage service summary is missing a transaction \
+                            or state range (transactions: {:?}, states: {:?}), so peers \
+                            will not request any data from it.",
+                        target_data_summary.transactions, target_data_summary.states
+                    ),
+                )
+            },
+        };
+
+        let baseline_highest_version = baseline_data_summary
+            .transactions
+            .as_ref()
+            .map(|transactions| transactions.highest())
+            .unwrap_or_default();
+        // We convert to i64 to avoid potential overflow if the target is ahead of the baseline.
+        let delta_from_baseline =
+            baseline_highest_version as i64 - target_transactions.highest() as i64;
+        if delta_from_baseline > self.config.version_delta_tolerance as i64 {
+            Self::build_result(
+                "Advertised data is lagging".to_string(),
+                50,
+                format!(
+                    "Your node advertises transactions up to version {}, which is {} versions \
+                    behind the baseline node, more than the allowed lag of {}. Peers will \
+                    prefer other nodes for recent data.",
+                    target_transactions.highest(),
+                    delta_from_baseline,
+                    self.config.version_delta_tolerance
+                ),
+            )
+        } else {
+            Self::build_result(
+                "Storage service is advertising data".to_string(),
+                100,
+                format!(
+                    "Your node advertises transactions {}..={} and states {}..={}, which is \
+                    within the allowed lag of {} versions of the baseline node (highest \
+                    version {}).",
+                    target_transactions.lowest(),
+                    target_transactions.highest(),
+                    target_states.lowest(),
+                    target_states.highest(),
+                    self.config.version_delta_tolerance,
+                    baseline_highest_version
+                ),
+            )
+        }
+    }
+
+    fn build_serving_result(
+        &self,
+        data_name: &str,
+        target_stats: Result<ServingStats>,
+        baseline_stats: &ServingStats,
+    ) -> CheckResult {
+        let target_stats = match target_stats {
+            Ok(target_stats) => target_stats,
+            Err(err) => {
+                return Self::build_result(
+                    format!("Failed to serve {}", data_name),
+                    0,
+                    format!(
+                        "Your node failed to serve {} over the storage service: {:#}",
+                        data_name, err
+                    ),
+                )
+            },
+        };
+        if target_stats.num_items == 0 {
+            return Self::build_result(
+                format!("Served no {}", data_name),
+                0,
+                format!(
+                    "Your node responded to {} requests for {} but returned no data.",
+                    target_stats.num_responses, data_name
+                ),
+            );
+        }
+
+        let target_throughput = target_stats.throughput();
+        let baseline_throughput = baseline_stats.throughput();
+        let target_latency = target_stats.average_latency();
+        let baseline_latency = baseline_stats.average_latency();
+        let stats_explanation = format!(
+            "Your node served {:.0} {} per second with an average latency of {}ms. \
+            The baseline node served {:.0} per second with an average latency of {}ms.",
+            target_throughput,
+            data_name,
+            target_latency.as_millis(),
+            baseline_throughput,
+            baseline_latency.as_millis()
+        );
+
+        if target_throughput < baseline_throughput * self.config.min_throughput_ratio {
+            Self::build_result(
+                format!("Throughput serving {} is too low", data_name),
+                50,
+                format!(
+                    "{} The minimum allowed is {} of the baseline throughput.",
+                    stats_explanation, self.config.min_throughput_ratio
+                ),
+            )
+        } else if target_latency.as_secs_f64()
+            > baseline_latency.as_secs_f64() * self.config.max_latency_ratio
+        {
+            Self::build_result(
+                format!("Latency serving {} is too high", data_name),
+                50,
+                format!(
+                    "{} The maximum allowed is {}x the baseline latency.",
+                    stats_explanation, self.config.max_latency_ratio
+                ),
+            )
+        } else {
+            Self::build_result(
+                format!("Serving {} successfully", data_name),
+                100,
+                stats_explanation,
+            )
+        }
+    }
+
+    /// Measure the baseline and the target serving the same kind of data. Each
+    /// node is asked for data it advertises, so the chunks are equally sized but
+    /// not necessarily identical.
+    async fn check_serving(
+        &self,
+        data_name: &'static str,
+        build_request: fn(&Self, &DataSummary) -> Option<DataRequest>,
+        target_client: &mut RpcClient,
+        target_summary: &StorageServerSummary,
+        baseline_client: &mut RpcClient,
+        baseline_summary: &StorageServerSummary,
+    ) -> Result<CheckResult, CheckerError> {
+        let baseline_request =
+            build_request(self, &baseline_summary.data_summary).ok_or_else(|| {
+                CheckerError::MissingDataError(
+                    data_name,
+                    anyhow!("The baseline node is not advertising any {}", data_name),
+                )
+            })?;
+        let baseline_stats = self
+            .measure_serving(baseline_client, &baseline_request)
+            .await
+            .map_err(|err| CheckerError::RetryableEndpointError("storage_service", err))?;
+
+        let target_stats = match build_request(self, &target_summary.data_summary) {
+            Some(target_request) => self.measure_serving(target_client, &target_request).await,
+            None => Err(anyhow!("Your node is not advertising any {}", data_name)),
+        };
+
+        Ok(self.build_serving_result(data_name, target_stats, &baseline_stats))
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for StorageServiceChecker {
+    /// Assert that the target node can actually serve data to peers over the
+    /// storage service, comparing the data it advertises, its throughput and
+    /// its latency to the baseline node.
+    async fn check(
+        &self,
+        providers: &ProviderCollection,
+    ) -> Result<Vec<CheckResult>, CheckerError> {
+        let baseline_noise_provider = get_provider!(
+            providers.baseline_noise_provider,
+            self.config.common.required,
+            NoiseProvider
+        );
+        let target_noise_provider = get_provider!(
+            providers.target_noise_provider,
+            self.config.common.required,
+            NoiseProvider
+        );
+
+        // Problems with the baseline are errors, problems with the target are results.
+        let mut baseline_client = baseline_noise_provider
+            .build_rpc_client()
+            .await
+            .map_err(|err| CheckerError::RetryableEndpointError("storage_service", err))?;
+        let baseline_summary = self
+            .get_storage_server_summary(&mut baseline_client)
+            .await
+            .map_err(|err| CheckerError::RetryableEndpointError("storage_service", err))?;
+
+        let mut target_client = match target_noise_provider.build_rpc_client().await {
+            Ok(client) => client,
+            Err(err) => {
+                return Ok(vec![Self::build_result(
+                    "Failed to connect to the storage service".to_string(),
+                    0,
+                    format!(
+                        "{:#}. NHC could not establish a noise connection to your node \
+                        ({}) to query its storage service.",
+                        err, target_noise_provider.network_address
+                    ),
+                )]);
+            },
+        };
+        let target_summary = match self.get_storage_server_summary(&mut target_client).await {
+            Ok(summary) => summary,
+            Err(err) => {
+                return Ok(vec![Self::build_result(
+                    "Failed to fetch the storage server summary".to_string(),
+                    0,
+                    format!(
+                        "There was an error requesting the storage server summary from \
+                        your node, so peers cannot learn what data it can serve: {:#}",
+                        err
+                    ),
+                )]);
+            },
+        };
+
+        let mut results = vec![self.build_data_summary_result(
+            &target_summary.data_summary,
+            &baseline_summary.data_summary,
+        )];
+        results.push(
+            self.check_serving(
+                "transactions",
+                Self::build_transactions_request,
+                &mut target_client,
+                &target_summary,
+                &mut baseline_client,
+                &baseline_summary,
+            )
+            .await?,
+        );
+        results.push(
+            self.check_serving(
+                "state values",
+                Self::build_state_values_request,
+                &mut target_client,
+                &target_summary,
+                &mut baseline_client,
+                &baseline_summary,
+            )
+            .await?,
+        );
+
+        Ok(results)
+    }
+}
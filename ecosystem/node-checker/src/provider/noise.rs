@@ -1,8 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-//! There is only one noise based Checker right now, so this Provider is a bit light
-//! on features, it just makes it possible to make a noise connection.
+//! This Provider is a bit light on features, it just makes it possible to make a
+//! noise connection, either to check that it works or to send RPCs over it.
 
 use super::{
     api_index::ApiIndexProvider,
@@ -13,6 +13,7 @@ use anyhow::Result;
 use aptos_network_checker::{
     args::{CheckEndpointArgs, HandshakeArgs, NodeAddressArgs},
     check_endpoint::check_endpoint,
+    RpcClient,
 };
 use aptos_sdk::types::{chain_id::ChainId, network_address::NetworkAddress};
 use async_trait::async_trait;
@@ -65,6 +66,18 @@ impl NoiseProvider {
         )
         .await
     }
+
+    /// Establish a connection and return a client that can send RPCs over it.
+    pub async fn build_rpc_client(&self) -> Result<RpcClient> {
+        RpcClient::connect(
+            &CheckEndpointArgs {
+                node_address_args: self.provide().await?,
+                handshake_args: self.config.handshake_args.clone(),
+            },
+            None,
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -12,7 +12,7 @@ use crate::{
 use aptos_consensus_types::common::TransactionSummary;
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, transaction::use_case::UseCaseKey};
 use rand::seq::SliceRandom;
 use std::{
     cmp::Ordering,
@@ -64,6 +64,7 @@ impl PriorityIndex {
             address: txn.get_sender(),
             sequence_number: txn.sequence_info,
             hash: txn.get_committed_hash(),
+            use_case: txn.use_case.clone(),
         }
     }
 
@@ -84,6 +85,8 @@ pub struct OrderedQueueKey {
     pub address: AccountAddress,
     pub sequence_number: SequenceInfo,
     pub hash: HashValue,
+    /// Not part of the ordering, determined by the transaction like the hash.
+    pub use_case: UseCaseKey,
 }
 
 impl PartialOrd for OrderedQueueKey {
@@ -0,0 +1,100 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fairness lanes used to form the transaction batches pulled by consensus.
+use crate::{
+    core_mempool::index::OrderedQueueKey,
+    shared_mempool::use_case_history::{OTHERS_USE_CASE_NAME, PLATFORM_USE_CASE_NAME},
+};
+use aptos_config::config::MempoolFairnessConfig;
+use aptos_types::{account_address::AccountAddress, transaction::use_case::UseCaseKey};
+use std::collections::HashMap;
+
+pub(crate) const LOW_FEE_LANE_LABEL: &str = "low_fee";
+pub(crate) const DEFAULT_LANE_LABEL: &str = "default";
+
+/// The use cases that are always tracked, regardless of the recent history.
+pub(crate) fn default_tracked_use_cases() -> HashMap<UseCaseKey, String> {
+    HashMap::from([
+        (UseCaseKey::Platform, PLATFORM_USE_CASE_NAME.to_string()),
+        (UseCaseKey::Others, OTHERS_USE_CASE_NAME.to_string()),
+    ])
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum LaneFilter {
+    /// Transactions of a use case tracked by name, see `UseCaseHistory`.
+    TrackedUseCase(String),
+    /// Transactions calling entry functions of the contract at the address.
+    Contract(AccountAddress),
+    /// Transactions with a gas ranking score at or below the threshold.
+    LowFee(u64),
+    /// All transactions.
+    All,
+}
+
+/// A lane fills up to `max_batch_percentage` of a batch with the transactions passing its filter,
+/// in the order of the priority index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Lane {
+    pub label: String,
+    pub filter: LaneFilter,
+    pub max_batch_percentage: u8,
+}
+
+impl Lane {
+    /// Returns the lanes to fill a batch with, in order. The last one takes all transactions, up
+    /// to the whole batch.
+    pub(crate) fn from_config(config: &MempoolFairnessConfig) -> Vec<Lane> {
+        let mut lanes: Vec<_> = config
+            .priority_lanes
+            .iter()
+            .map(|lane| Lane {
+                label: lane.use_case.clone(),
+                filter: match AccountAddress::from_hex_literal(&lane.use_case) {
+                    Ok(address) => LaneFilter::Contract(address),
+                    Err(_) => LaneFilter::TrackedUseCase(lane.use_case.clone()),
+                },
+                max_batch_percentage: lane.max_batch_percentage,
+            })
+            .collect();
+        if config.low_fee_reserved_batch_percentage > 0 {
+            lanes.push(Lane {
+                label: LOW_FEE_LANE_LABEL.to_string(),
+                filter: LaneFilter::LowFee(config.low_fee_max_gas_unit_price),
+                max_batch_percentage: config.low_fee_reserved_batch_percentage,
+            });
+        }
+        lanes.push(Lane {
+            label: DEFAULT_LANE_LABEL.to_string(),
+            filter: LaneFilter::All,
+            max_batch_percentage: 100,
+        });
+        lanes
+    }
+
+    /// The maximum number of transactions the lane adds to a batch of `max_txns`. Lanes with a
+    /// non-zero share get at least one transaction.
+    pub(crate) fn max_txns(&self, max_txns: u64) -> u64 {
+        if self.max_batch_percentage == 0 {
+            return 0;
+        }
+        std::cmp::max(max_txns * self.max_batch_percentage as u64 / 100, 1)
+    }
+
+    /// Returns true iff the transaction belongs to the lane.
+    pub(crate) fn matches(
+        &self,
+        key: &OrderedQueueKey,
+        tracked_use_cases: &HashMap<UseCaseKey, String>,
+    ) -> bool {
+        match &self.filter {
+            LaneFilter::TrackedUseCase(name) => tracked_use_cases
+                .get(&key.use_case)
+                .map_or(false, |tracked_name| tracked_name == name),
+            LaneFilter::Contract(address) => key.use_case == UseCaseKey::ContractAddress(*address),
+            LaneFilter::LowFee(max_gas_unit_price) => key.gas_ranking_score <= *max_gas_unit_price,
+            LaneFilter::All => true,
+        }
+    }
+}
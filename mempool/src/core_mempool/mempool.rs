@@ -7,10 +7,12 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        lanes::{default_tracked_use_cases, Lane},
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
         transaction_store::{sender_bucket, TransactionStore},
     },
     counters,
+    journal::JournalChanges,
     logging::{LogEntry, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
@@ -38,6 +40,38 @@ pub struct Mempool {
     transactions: TransactionStore,
 
     pub system_transaction_timeout: Duration,
+
+    // The lanes to fill the batches pulled by consensus with, in order.
+    lanes: Vec<Lane>,
+    max_txns_per_sender_per_batch: Option<u64>,
+    // The use cases tracked from recent blocks, which the priority lanes can refer to by name.
+    tracked_use_cases: HashMap<UseCaseKey, String>,
+}
+
+/// The state of a batch being formed, shared by all the lanes.
+struct BatchState<'a> {
+    max_txns: u64,
+    exclude_transactions: &'a BTreeMap<TransactionSummary, TransactionInProgress>,
+    inserted: HashSet<(AccountAddress, u64)>,
+    result: Vec<(AccountAddress, u64)>,
+    txns_per_sender: HashMap<AccountAddress, u64>,
+    txn_walked: usize,
+}
+
+impl BatchState<'_> {
+    fn is_full(&self) -> bool {
+        self.result.len() as u64 == self.max_txns
+    }
+
+    fn is_filled_to(&self, len: u64) -> bool {
+        self.is_full() || self.result.len() as u64 >= len
+    }
+
+    fn insert(&mut self, txn: (AccountAddress, u64)) {
+        self.inserted.insert(txn);
+        self.result.push(txn);
+        *self.txns_per_sender.entry(txn.0).or_insert(0) += 1;
+    }
 }
 
 impl Mempool {
@@ -47,9 +81,20 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            lanes: Lane::from_config(&config.mempool.fairness),
+            max_txns_per_sender_per_batch: config.mempool.fairness.max_txns_per_sender_per_batch,
+            tracked_use_cases: default_tracked_use_cases(),
         }
     }
 
+    /// Updates the use cases tracked from recent blocks, see `UseCaseHistory`.
+    pub(crate) fn update_tracked_use_cases(
+        &mut self,
+        tracked_use_cases: HashMap<UseCaseKey, String>,
+    ) {
+        self.tracked_use_cases = tracked_use_cases;
+    }
+
     /// This function will be called once the transaction has been stored.
     pub(crate) fn commit_transaction(&mut self, sender: &AccountAddress, sequence_number: u64) {
         self.transactions
@@ -385,7 +430,6 @@ impl Mempool {
     ///                          exclude_transactions. Should only be true for Quorum Store.
     /// `exclude_transactions` - transactions that were sent to Consensus but were not committed yet
     ///  mempool should filter out such transactions.
-    #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn get_batch(
         &self,
         max_txns: u64,
@@ -395,57 +439,29 @@ impl Mempool {
     ) -> Vec<SignedTransaction> {
         let start_time = Instant::now();
         let exclude_size = exclude_transactions.len();
-        let mut inserted = HashSet::new();
 
         let gas_end_time = start_time.elapsed();
 
-        let mut result = vec![];
-        // Helper DS. Helps to mitigate scenarios where account submits several transactions
-        // with increasing gas price (e.g. user submits transactions with sequence number 1, 2
-        // and gas_price 1, 10 respectively)
-        // Later txn has higher gas price and will be observed first in priority index iterator,
-        // but can't be executed before first txn. Once observed, such txn will be saved in
-        // `skipped` DS and rechecked once it's ancestor becomes available
-        let mut skipped = HashSet::new();
+        let mut state = BatchState {
+            max_txns,
+            exclude_transactions: &exclude_transactions,
+            inserted: HashSet::new(),
+            result: vec![],
+            txns_per_sender: HashMap::new(),
+            txn_walked: 0,
+        };
         let mut total_bytes = 0;
-        let mut txn_walked = 0usize;
-        // iterate over the queue of transactions based on gas price
-        'main: for txn in self.transactions.iter_queue() {
-            txn_walked += 1;
-            let txn_ptr = TxnPointer::from(txn);
-
-            // TODO: removed gas upgraded logic. double check if it's needed
-            if exclude_transactions.contains_key(&txn_ptr) {
-                continue;
-            }
-            let tx_seq = txn.sequence_number.transaction_sequence_number;
-            let txn_in_sequence = tx_seq > 0
-                && Self::txn_was_chosen(txn.address, tx_seq - 1, &inserted, &exclude_transactions);
-            let account_sequence_number = self.transactions.get_sequence_number(&txn.address);
-            // include transaction if it's "next" for given account or
-            // we've already sent its ancestor to Consensus.
-            if txn_in_sequence || account_sequence_number == Some(&tx_seq) {
-                inserted.insert((txn.address, tx_seq));
-                result.push((txn.address, tx_seq));
-                if (result.len() as u64) == max_txns {
-                    break;
-                }
-
-                // check if we can now include some transactions
-                // that were skipped before for given account
-                let mut skipped_txn = (txn.address, tx_seq + 1);
-                while skipped.remove(&skipped_txn) {
-                    inserted.insert(skipped_txn);
-                    result.push(skipped_txn);
-                    if (result.len() as u64) == max_txns {
-                        break 'main;
-                    }
-                    skipped_txn = (skipped_txn.0, skipped_txn.1 + 1);
-                }
-            } else {
-                skipped.insert((txn.address, tx_seq));
+        let mut lane_sizes = Vec::with_capacity(self.lanes.len());
+        for lane in &self.lanes {
+            let lane_start = state.result.len();
+            if !state.is_full() {
+                self.fill_lane(lane, &mut state);
             }
+            lane_sizes.push(state.result.len() - lane_start);
         }
+        let BatchState {
+            result, txn_walked, ..
+        } = state;
         let result_size = result.len();
         let result_end_time = start_time.elapsed();
         let result_time = result_end_time.saturating_sub(gas_end_time);
@@ -517,6 +533,15 @@ impl Mempool {
             block.clear();
         }
 
+        // Attribute the transactions that made it into the block to the lanes, in order
+        let mut block_remaining = block.len();
+        for (lane, lane_size) in self.lanes.iter().zip(lane_sizes) {
+            let lane_block_size = std::cmp::min(lane_size, block_remaining);
+            block_remaining -= lane_block_size;
+            counters::CORE_MEMPOOL_LANE_TXNS_PULLED
+                .with_label_values(&[lane.label.as_str()])
+                .inc_by(lane_block_size as u64);
+        }
         counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, block.len());
         counters::MEMPOOL_SERVICE_BYTES_GET_BLOCK.observe(total_bytes as f64);
         for transaction in &block {
@@ -525,6 +550,79 @@ impl Mempool {
         block
     }
 
+    /// Adds the transactions in the lane to the batch, in the order of the priority index, up to
+    /// the lane's share of the batch.
+    fn fill_lane(&self, lane: &Lane, state: &mut BatchState) {
+        let lane_end = state.result.len() as u64 + lane.max_txns(state.max_txns);
+        if state.is_filled_to(lane_end) {
+            return;
+        }
+
+        // Helper DS. Helps to mitigate scenarios where account submits several transactions
+        // with increasing gas price (e.g. user submits transactions with sequence number 1, 2
+        // and gas_price 1, 10 respectively)
+        // Later txn has higher gas price and will be observed first in priority index iterator,
+        // but can't be executed before first txn. Once observed, such txn will be saved in
+        // `skipped` DS and rechecked once it's ancestor becomes available
+        let mut skipped = HashSet::new();
+        // iterate over the queue of transactions based on gas price
+        'main: for txn in self.transactions.iter_queue() {
+            state.txn_walked += 1;
+            let txn_ptr = TxnPointer::from(txn);
+
+            // TODO: removed gas upgraded logic. double check if it's needed
+            if state.exclude_transactions.contains_key(&txn_ptr) {
+                continue;
+            }
+            let tx_seq = txn.sequence_number.transaction_sequence_number;
+            // skip transactions already added by a previous lane, or not in this lane
+            if state.inserted.contains(&(txn.address, tx_seq))
+                || !lane.matches(txn, &self.tracked_use_cases)
+            {
+                continue;
+            }
+            if !self.sender_below_cap(state, &txn.address) {
+                counters::CORE_MEMPOOL_SENDER_QUOTA_SKIPPED_TXNS.inc();
+                continue;
+            }
+            let txn_in_sequence = tx_seq > 0
+                && Self::txn_was_chosen(
+                    txn.address,
+                    tx_seq - 1,
+                    &state.inserted,
+                    state.exclude_transactions,
+                );
+            let account_sequence_number = self.transactions.get_sequence_number(&txn.address);
+            // include transaction if it's "next" for given account or
+            // we've already sent its ancestor to Consensus.
+            if txn_in_sequence || account_sequence_number == Some(&tx_seq) {
+                state.insert((txn.address, tx_seq));
+                if state.is_filled_to(lane_end) {
+                    break;
+                }
+
+                // check if we can now include some transactions
+                // that were skipped before for given account
+                let mut skipped_txn = (txn.address, tx_seq + 1);
+                while self.sender_below_cap(state, &skipped_txn.0) && skipped.remove(&skipped_txn) {
+                    state.insert(skipped_txn);
+                    if state.is_filled_to(lane_end) {
+                        break 'main;
+                    }
+                    skipped_txn = (skipped_txn.0, skipped_txn.1 + 1);
+                }
+            } else {
+                skipped.insert((txn.address, tx_seq));
+            }
+        }
+    }
+
+    fn sender_below_cap(&self, state: &BatchState, sender: &AccountAddress) -> bool {
+        self.max_txns_per_sender_per_batch.map_or(true, |cap| {
+            state.txns_per_sender.get(sender).copied().unwrap_or(0) < cap
+        })
+    }
+
     /// Periodic core mempool garbage collection.
     /// Removes all expired transactions and clears expired entries in metrics
     /// cache and sequence number cache.
@@ -588,6 +686,11 @@ impl Mempool {
         self.transactions.gen_snapshot()
     }
 
+    /// Returns the changes to mempool since the last call, to be written to the journal.
+    pub(crate) fn take_journal_changes(&mut self) -> JournalChanges {
+        self.transactions.take_journal_changes()
+    }
+
     #[cfg(test)]
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod lanes;
 mod mempool;
 pub mod transaction;
 mod transaction_store;
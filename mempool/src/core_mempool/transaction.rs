@@ -4,7 +4,13 @@
 
 use crate::{core_mempool::TXN_INDEX_ESTIMATED_BYTES, counters, network::BroadcastPeerPriority};
 use aptos_crypto::HashValue;
-use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        use_case::{UseCaseAwareTransaction, UseCaseKey},
+        SignedTransaction,
+    },
+};
 use serde::{Deserialize, Serialize};
 use std::{
     mem::size_of,
@@ -27,6 +33,8 @@ pub struct MempoolTransaction {
     pub was_parked: bool,
     // The priority of this node for the sender of this transaction.
     pub priority_of_sender: Option<BroadcastPeerPriority>,
+    // Parsed once on insertion, used to assign the transaction to fairness lanes.
+    pub use_case: UseCaseKey,
 }
 
 impl MempoolTransaction {
@@ -45,6 +53,7 @@ impl MempoolTransaction {
                 transaction_sequence_number: txn.sequence_number(),
                 account_sequence_number: seqno,
             },
+            use_case: txn.parse_use_case(),
             txn,
             expiration_time,
             ranking_score,
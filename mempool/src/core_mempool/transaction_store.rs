@@ -12,6 +12,7 @@ use crate::{
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
     },
     counters::{self, BROADCAST_BATCHED_LABEL, BROADCAST_READY_LABEL, CONSENSUS_READY_LABEL},
+    journal::JournalChanges,
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
@@ -79,6 +80,8 @@ pub struct TransactionStore {
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
     // estimated size in bytes
     size_bytes: usize,
+    // Changes not written to the journal yet, if journaling is enabled.
+    journal_changes: Option<JournalChanges>,
 
     // configuration
     capacity: usize,
@@ -117,6 +120,7 @@ impl TransactionStore {
             hash_index: HashMap::new(),
             // estimated size in bytes
             size_bytes: 0,
+            journal_changes: config.persistence.enabled.then(JournalChanges::new),
 
             // configuration
             capacity: config.capacity,
@@ -299,6 +303,9 @@ impl TransactionStore {
                 .insert(txn.get_committed_hash(), (txn.get_sender(), txn_seq_num));
             self.sequence_numbers.insert(txn.get_sender(), acc_seq_num);
             self.size_bytes += txn.get_estimated_bytes();
+            if let Some(journal_changes) = &mut self.journal_changes {
+                journal_changes.insert((address, txn_seq_num), Some(txn.txn.clone()));
+            }
             txns.insert(txn_seq_num, txn);
             self.track_indices();
         }
@@ -637,6 +644,15 @@ impl TransactionStore {
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
         self.size_bytes -= txn.get_estimated_bytes();
+        if let Some(journal_changes) = &mut self.journal_changes {
+            journal_changes.insert(
+                (
+                    txn.get_sender(),
+                    txn.sequence_info.transaction_sequence_number,
+                ),
+                None,
+            );
+        }
 
         // Remove account datastructures if there are no more transactions for the account.
         let address = &txn.get_sender();
@@ -907,6 +923,14 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Returns the changes to be written to the journal since the last call, see `JournalChanges`.
+    pub(crate) fn take_journal_changes(&mut self) -> JournalChanges {
+        self.journal_changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
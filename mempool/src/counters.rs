@@ -44,6 +44,13 @@ pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
 pub const GC_ACTIVE_TXN_LABEL: &str = "active";
 pub const GC_PARKED_TXN_LABEL: &str = "parked";
 
+// Mempool journal operation labels
+pub const JOURNAL_WRITTEN_LABEL: &str = "written";
+pub const JOURNAL_DELETED_LABEL: &str = "deleted";
+pub const JOURNAL_RESTORED_LABEL: &str = "restored";
+pub const JOURNAL_EXPIRED_LABEL: &str = "expired";
+pub const JOURNAL_REJECTED_LABEL: &str = "rejected";
+
 // Mempool service request type labels
 pub const GET_BLOCK_LABEL: &str = "get_block";
 pub const GET_BLOCK_LOCK_LABEL: &str = "get_block_lock";
@@ -316,6 +323,35 @@ pub static CORE_MEMPOOL_TXN_CONSENSUS_PULLED_BY_BUCKET: Lazy<HistogramVec> = Laz
     .unwrap()
 });
 
+/// Counter of the transactions pulled by consensus, by the fairness lane they were pulled in
+pub static CORE_MEMPOOL_LANE_TXNS_PULLED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_lane_txns_pulled",
+        "Number of txns pulled from core mempool by consensus, by fairness lane",
+        &["lane"]
+    )
+    .unwrap()
+});
+
+/// Counter of the times a transaction was passed over because its sender hit the per batch quota
+pub static CORE_MEMPOOL_SENDER_QUOTA_SKIPPED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_core_mempool_sender_quota_skipped_txns",
+        "Number of times a txn was skipped in a batch because its sender hit the per batch quota"
+    )
+    .unwrap()
+});
+
+/// Counter of the transactions written to, deleted from and restored from the mempool journal
+pub static MEMPOOL_JOURNAL_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_mempool_journal_txns",
+        "Number of txns written to, deleted from and restored from the mempool journal",
+        &["operation"]
+    )
+    .unwrap()
+});
+
 pub static CORE_MEMPOOL_PARKING_LOT_EVICTED_COUNT: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         "aptos_core_mempool_parking_lot_evicted_count",
@@ -680,6 +716,15 @@ pub static DB_ERROR: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for number of transactions rejected by the mempool transaction filter
+pub static MEMPOOL_FILTERED_TRANSACTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_mempool_filtered_transactions_count",
+        "Number of transactions rejected by the mempool transaction filter"
+    )
+    .unwrap()
+});
+
 /// Counter for the current number of active upstream peers mempool can
 /// broadcast to, summed across each of its networks
 static ACTIVE_UPSTREAM_PEERS_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
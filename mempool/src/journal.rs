@@ -0,0 +1,130 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A RocksDB backed journal of the mempool contents, so pending transactions survive node
+//! restarts. Core mempool records its changes as transactions are inserted and removed, and the
+//! journal is written periodically with the changes since the last write, see `journal_job`. It
+//! is read once on startup, see `restore_from_journal`.
+
+use anyhow::{ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName, Options, SchemaBatch, DB,
+};
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use std::{collections::HashMap, path::Path, time::Instant};
+
+/// The name of the mempool db file
+pub const MEMPOOL_DB_NAME: &str = "mempool_db";
+
+/// The name of the transaction column family
+const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
+
+/// Changes to the journal, by sender and sequence number: the transaction to write, or `None` if
+/// it is to be deleted.
+pub(crate) type JournalChanges = HashMap<(AccountAddress, u64), Option<SignedTransaction>>;
+
+pub(crate) struct MempoolJournal {
+    db: DB,
+}
+
+impl MempoolJournal {
+    pub(crate) fn new<P: AsRef<Path>>(db_root_path: P) -> Self {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let path = db_root_path.as_ref().join(MEMPOOL_DB_NAME);
+        let instant = Instant::now();
+        let db = DB::open(path.clone(), "mempool", vec![TRANSACTION_CF_NAME], &options)
+            .unwrap_or_else(|error| {
+                panic!(
+                    "Failed to open/create the mempool database at: {:?}. Error: {:?}",
+                    path, error
+                )
+            });
+        info!(
+            "Opened the mempool database at: {:?}, in {:?} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self { db }
+    }
+
+    /// Returns all the journaled transactions, ordered by sender and sequence number.
+    pub(crate) fn get_all(&self) -> Result<Vec<SignedTransaction>> {
+        let mut iter = self.db.iter::<TransactionSchema>()?;
+        iter.seek_to_first();
+        iter.map(|res| Ok(res?.1)).collect()
+    }
+
+    /// Writes the changes atomically.
+    pub(crate) fn write(&self, changes: &JournalChanges) -> Result<()> {
+        let batch = SchemaBatch::new();
+        for ((sender, sequence_number), txn) in changes {
+            let key = TransactionKey {
+                sender: *sender,
+                sequence_number: *sequence_number,
+            };
+            match txn {
+                Some(txn) => batch.put::<TransactionSchema>(&key, txn)?,
+                None => batch.delete::<TransactionSchema>(&key)?,
+            }
+        }
+        self.db.write_schemas(batch)?;
+        Ok(())
+    }
+}
+
+/// Transactions are keyed by sender and then sequence number (big endian), so the transactions
+/// of an account are restored in order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct TransactionKey {
+    sender: AccountAddress,
+    sequence_number: u64,
+}
+
+// This defines a physical storage schema for the journaled transactions.
+//
+// |<-----------key------------>|<---value--->|
+// | sender | sequence number   | transaction |
+define_schema!(
+    TransactionSchema,
+    TransactionKey,
+    SignedTransaction,
+    TRANSACTION_CF_NAME
+);
+
+impl KeyCodec<TransactionSchema> for TransactionKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded_key = self.sender.to_vec();
+        encoded_key.extend_from_slice(&self.sequence_number.to_be_bytes());
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == AccountAddress::LENGTH + 8,
+            "Unexpected key length: {}",
+            data.len()
+        );
+        let (sender, sequence_number) = data.split_at(AccountAddress::LENGTH);
+        Ok(TransactionKey {
+            sender: AccountAddress::try_from(sender)?,
+            sequence_number: u64::from_be_bytes(sequence_number.try_into()?),
+        })
+    }
+}
+
+impl ValueCodec<TransactionSchema> for SignedTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
@@ -71,6 +71,7 @@ pub use tests::{fuzzing, mocks};
 
 mod core_mempool;
 pub mod counters;
+mod journal;
 mod logging;
 mod shared_mempool;
 pub(crate) mod thread_pool;
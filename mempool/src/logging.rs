@@ -173,6 +173,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    Journal,
 }
 
 #[derive(Clone, Copy, Serialize)]
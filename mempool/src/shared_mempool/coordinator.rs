@@ -7,6 +7,7 @@ use super::types::MempoolClientRequest;
 use crate::{
     core_mempool::{CoreMempool, TimelineState},
     counters,
+    journal::{JournalChanges, MempoolJournal},
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
@@ -455,6 +456,42 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Periodically writes the changes to core mempool to the journal, so they can be restored after
+/// a restart. `pending` are changes to write on top of the ones recorded by core mempool.
+pub(crate) async fn journal_job(
+    mempool: Arc<Mutex<CoreMempool>>,
+    journal: Arc<MempoolJournal>,
+    mut pending: JournalChanges,
+    flush_interval_ms: u64,
+) {
+    let mut interval = IntervalStream::new(interval(Duration::from_millis(flush_interval_ms)));
+    while let Some(_interval) = interval.next().await {
+        // Later changes to a transaction override the pending ones
+        let changes = mempool.lock().take_journal_changes();
+        pending.extend(changes);
+        if pending.is_empty() {
+            continue;
+        }
+        if let Err(e) = journal.write(&pending) {
+            // The changes are retried on the next flush
+            error!(
+                LogSchema::new(LogEntry::Journal).error(&e),
+                "Failed to write the mempool journal"
+            );
+            continue;
+        }
+
+        let num_written = pending.values().filter(|txn| txn.is_some()).count();
+        counters::MEMPOOL_JOURNAL_TXNS
+            .with_label_values(&[counters::JOURNAL_WRITTEN_LABEL])
+            .inc_by(num_written as u64);
+        counters::MEMPOOL_JOURNAL_TXNS
+            .with_label_values(&[counters::JOURNAL_DELETED_LABEL])
+            .inc_by((pending.len() - num_written) as u64);
+        pending.clear();
+    }
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
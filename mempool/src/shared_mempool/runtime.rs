@@ -4,14 +4,16 @@
 
 use crate::{
     core_mempool::CoreMempool,
+    journal::MempoolJournal,
     network::MempoolSyncMsg,
     shared_mempool::{
-        coordinator::{coordinator, gc_coordinator, snapshot_job},
+        coordinator::{coordinator, gc_coordinator, journal_job, snapshot_job},
+        tasks::restore_from_journal,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
 };
-use aptos_config::config::{NodeConfig, NodeType};
+use aptos_config::config::{transaction_filter_type::LiveFilter, NodeConfig, NodeType};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::Level;
@@ -46,6 +48,7 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     validator: Arc<RwLock<TransactionValidator>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filter: Arc<LiveFilter>,
 ) where
     TransactionValidator: TransactionValidation + 'static,
     ConfigProvider: OnChainConfigProvider,
@@ -60,8 +63,21 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
             validator,
             subscribers,
             node_type,
+            transaction_filter,
         );
 
+    // Restore the journaled transactions before anything else touches mempool
+    if config.mempool.persistence.enabled {
+        let journal = Arc::new(MempoolJournal::new(config.storage.dir()));
+        let pending = restore_from_journal(&smp, &journal);
+        executor.spawn(journal_job(
+            mempool.clone(),
+            journal,
+            pending,
+            config.mempool.persistence.flush_interval_ms,
+        ));
+    }
+
     executor.spawn(coordinator(
         smp,
         executor.clone(),
@@ -97,6 +113,7 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filter: Arc<LiveFilter>,
 ) -> Runtime {
     let runtime = aptos_runtimes::spawn_named_runtime("shared-mem".into(), None);
     let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
@@ -118,6 +135,7 @@ pub fn bootstrap(
         vm_validator,
         vec![],
         peers_and_metadata,
+        transaction_filter,
     );
     runtime
 }
@@ -7,6 +7,7 @@ use super::types::MempoolMessageId;
 use crate::{
     core_mempool::{CoreMempool, TimelineState},
     counters,
+    journal::{JournalChanges, MempoolJournal},
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
@@ -20,7 +21,7 @@ use crate::{
     QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
 };
 use anyhow::Result;
-use aptos_config::network_id::PeerNetworkId;
+use aptos_config::{config::transaction_filter_type::LiveFilter, network_id::PeerNetworkId};
 use aptos_consensus_types::common::RejectedTransactionSummary;
 use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
@@ -151,6 +152,96 @@ pub(crate) async fn process_client_transaction_submission<NetworkClient, Transac
     }
 }
 
+/// The number of journaled transactions validated and added to mempool at a time on restore.
+const JOURNAL_RESTORE_BATCH_SIZE: usize = 1_000;
+
+/// Restores the journaled transactions to mempool on startup. The transactions are validated
+/// again, as if submitted by a client, and the expired ones are dropped. Restoring stops once
+/// mempool is full. Returns the deletion of all the journaled transactions: the restored ones are
+/// written back with the changes recorded by core mempool, which override these.
+pub(crate) fn restore_from_journal<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    journal: &MempoolJournal,
+) -> JournalChanges
+where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let transactions = match journal.get_all() {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            // Start with an empty mempool, the journal is overwritten on the next flush
+            error!(
+                LogSchema::new(LogEntry::Journal).error(&e),
+                "Failed to read the mempool journal"
+            );
+            return JournalChanges::new();
+        },
+    };
+    let journaled: JournalChanges = transactions
+        .iter()
+        .map(|txn| ((txn.sender(), txn.sequence_number()), None))
+        .collect();
+
+    let now_secs = aptos_infallible::duration_since_epoch().as_secs();
+    let (transactions, expired): (Vec<_>, Vec<_>) = transactions
+        .into_iter()
+        .partition(|txn| txn.expiration_timestamp_secs() > now_secs);
+    counters::MEMPOOL_JOURNAL_TXNS
+        .with_label_values(&[counters::JOURNAL_EXPIRED_LABEL])
+        .inc_by(expired.len() as u64);
+
+    let ineligible_for_broadcast =
+        smp.network_interface.is_validator() && !smp.broadcast_within_validator_network();
+    let timeline_state = if ineligible_for_broadcast {
+        TimelineState::NonQualified
+    } else {
+        TimelineState::NotReady
+    };
+    let (mut num_restored, mut num_rejected) = (0, 0);
+    for chunk in transactions.chunks(JOURNAL_RESTORE_BATCH_SIZE) {
+        let statuses = process_incoming_transactions(
+            smp,
+            chunk
+                .iter()
+                .map(|txn| (txn.clone(), None, Some(BroadcastPeerPriority::Primary)))
+                .collect(),
+            timeline_state,
+            false,
+        );
+        let mut mempool_is_full = false;
+        for (_, (status, _)) in &statuses {
+            match status.code {
+                MempoolStatusCode::Accepted => num_restored += 1,
+                MempoolStatusCode::MempoolIsFull => {
+                    mempool_is_full = true;
+                    num_rejected += 1;
+                },
+                _ => num_rejected += 1,
+            }
+        }
+        if mempool_is_full {
+            break;
+        }
+    }
+    counters::MEMPOOL_JOURNAL_TXNS
+        .with_label_values(&[counters::JOURNAL_RESTORED_LABEL])
+        .inc_by(num_restored);
+    counters::MEMPOOL_JOURNAL_TXNS
+        .with_label_values(&[counters::JOURNAL_REJECTED_LABEL])
+        .inc_by(num_rejected);
+    info!(
+        LogSchema::new(LogEntry::Journal),
+        "Restored {} transactions from the mempool journal ({} expired, {} rejected, {} left out)",
+        num_restored,
+        expired.len(),
+        num_rejected,
+        journaled.len() - expired.len() - (num_restored + num_rejected) as usize,
+    );
+
+    journaled
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -285,6 +376,12 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject any transactions that are denied by the mempool transaction filter
+    let transactions = filter_transactions(&smp.transaction_filter, transactions, &mut statuses);
+    if transactions.is_empty() {
+        return statuses;
+    }
+
     let start_storage_read = Instant::now();
     let state_view = smp
         .db
@@ -351,6 +448,50 @@ where
     statuses
 }
 
+/// Removes the transactions that are denied by the given filter, and adds
+/// a rejection status for each of them to the given statuses.
+fn filter_transactions(
+    filter: &LiveFilter,
+    transactions: Vec<(
+        SignedTransaction,
+        Option<u64>,
+        Option<BroadcastPeerPriority>,
+    )>,
+    statuses: &mut Vec<(SignedTransaction, (MempoolStatus, Option<StatusCode>))>,
+) -> Vec<(
+    SignedTransaction,
+    Option<u64>,
+    Option<BroadcastPeerPriority>,
+)> {
+    // Special case for no filter to avoid unnecessary iteration through all transactions
+    if filter.is_empty() {
+        return transactions;
+    }
+
+    // There is no block on admission, so we use HashValue::zero() for the block ID (block ID
+    // based filters are rejected by the config sanitizer and the admin service) and the
+    // current time as the timestamp.
+    let timestamp_usecs = aptos_infallible::duration_since_epoch().as_micros() as u64;
+    transactions
+        .into_iter()
+        .filter_map(|(txn, ready_time_at_sender, priority)| {
+            if filter.allows(HashValue::zero(), timestamp_usecs, &txn) {
+                Some((txn, ready_time_at_sender, priority))
+            } else {
+                counters::MEMPOOL_FILTERED_TRANSACTIONS.inc();
+                statuses.push((
+                    txn,
+                    (
+                        MempoolStatus::new(MempoolStatusCode::RejectedByFilter),
+                        None,
+                    ),
+                ));
+                None
+            }
+        })
+        .collect()
+}
+
 /// Perfoms VM validation on the transactions and inserts those that passes
 /// validation into the mempool.
 #[cfg(not(feature = "consensus-only-perf-test"))]
@@ -618,6 +759,7 @@ pub(crate) fn process_committed_transactions(
         );
         pool.commit_transaction(&transaction.sender, transaction.sequence_number);
     }
+    pool.update_tracked_use_cases(tracking_usecases);
 
     if block_timestamp_usecs > 0 {
         pool.gc_by_expiration_time(block_timestamp);
@@ -9,6 +9,10 @@ use std::{
     collections::{BinaryHeap, HashMap, VecDeque},
 };
 
+/// The names of the use cases that are always tracked.
+pub(crate) const PLATFORM_USE_CASE_NAME: &str = "entry_platform";
+pub(crate) const OTHERS_USE_CASE_NAME: &str = "non_entry";
+
 pub(crate) struct UseCaseHistory {
     window_size: usize,
     num_top_to_track: usize,
@@ -57,8 +61,8 @@ impl UseCaseHistory {
 
     pub fn compute_tracking_set(&self) -> HashMap<UseCaseKey, String> {
         let mut result = HashMap::new();
-        result.insert(UseCaseKey::Platform, "entry_platform".to_string());
-        result.insert(UseCaseKey::Others, "non_entry".to_string());
+        result.insert(UseCaseKey::Platform, PLATFORM_USE_CASE_NAME.to_string());
+        result.insert(UseCaseKey::Others, OTHERS_USE_CASE_NAME.to_string());
 
         let mut max_heap: BinaryHeap<UseCaseByCount> = self
             .total
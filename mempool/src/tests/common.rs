@@ -15,7 +15,8 @@ use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
     mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, Script, SignedTransaction, TransactionArgument},
+    move_utils::MemberId,
+    transaction::{EntryFunction, RawTransaction, Script, SignedTransaction, TransactionArgument},
 };
 use once_cell::sync::Lazy;
 use rand::{rngs::StdRng, SeedableRng};
@@ -157,6 +158,32 @@ impl TestTransaction {
             exp_timestamp_secs,
             ChainId::test(),
         );
+        Self::sign(raw_txn)
+    }
+
+    /// Makes a transaction calling the entry function, e.g., "0x1::coin::transfer", without
+    /// arguments.
+    pub(crate) fn make_signed_entry_function_transaction(
+        &self,
+        function: &str,
+    ) -> SignedTransaction {
+        let MemberId {
+            module_id,
+            member_id,
+        } = function.parse().unwrap();
+        let raw_txn = RawTransaction::new_entry_function(
+            self.address,
+            self.sequence_number,
+            EntryFunction::new(module_id, member_id, vec![], vec![]),
+            100,
+            self.gas_price,
+            u64::MAX,
+            ChainId::test(),
+        );
+        Self::sign(raw_txn)
+    }
+
+    fn sign(raw_txn: RawTransaction) -> SignedTransaction {
         let mut seed: [u8; 32] = [0u8; 32];
         seed[..4].copy_from_slice(&[1, 2, 3, 4]);
         let mut rng: StdRng = StdRng::from_seed(seed);
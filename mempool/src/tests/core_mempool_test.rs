@@ -10,7 +10,7 @@ use crate::{
         setup_mempool_with_broadcast_buckets, txn_bytes_len, TestTransaction,
     },
 };
-use aptos_config::config::{MempoolConfig, NodeConfig};
+use aptos_config::config::{MempoolConfig, NodeConfig, PriorityLaneConfig};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
 use aptos_types::{
@@ -1311,3 +1311,86 @@ fn test_include_gas_upgraded() {
     });
     assert_eq!(batch.len(), 0);
 }
+
+#[test]
+fn test_get_batch_sender_quota() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.fairness.max_txns_per_sender_per_batch = Some(2);
+    let mut pool = CoreMempool::new(&config);
+
+    // A busy sender paying more, and a quiet one
+    let mut busy_txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 0, 10),
+        TestTransaction::new(0, 1, 10),
+        TestTransaction::new(0, 2, 10),
+        TestTransaction::new(0, 3, 10),
+    ]);
+    let quiet_txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 0, 1)]);
+
+    // Only the first two transactions of the busy sender make it into the batch
+    let batch = pool.get_batch(10, 10240, true, btreemap![]);
+    busy_txns.truncate(2);
+    assert_eq!(batch, [busy_txns, quiet_txns].concat());
+}
+
+#[test]
+fn test_get_batch_low_fee_lane() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.fairness.low_fee_max_gas_unit_price = 1;
+    config.mempool.fairness.low_fee_reserved_batch_percentage = 50;
+    let mut pool = CoreMempool::new(&config);
+
+    let high_fee_txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 0, 10),
+        TestTransaction::new(0, 1, 10),
+        TestTransaction::new(0, 2, 10),
+        TestTransaction::new(0, 3, 10),
+    ]);
+    let low_fee_txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(1, 0, 1),
+        TestTransaction::new(2, 0, 1),
+        TestTransaction::new(3, 0, 1),
+    ]);
+
+    // Half of the batch is reserved for the low-fee transactions
+    let batch = pool.get_batch(4, 10240, true, btreemap![]);
+    assert_eq!(batch.len(), 4);
+    assert_eq!(batch[..2], low_fee_txns[..2]);
+    assert_eq!(batch[2..], high_fee_txns[..2]);
+
+    // The low-fee lane doesn't limit what the low-fee transactions can get otherwise
+    let batch = pool.get_batch(10, 10240, true, btreemap![]);
+    assert_eq!(batch.len(), 7);
+}
+
+#[test]
+fn test_get_batch_priority_lane() {
+    let contract = AccountAddress::random();
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.fairness.priority_lanes = vec![PriorityLaneConfig {
+        use_case: contract.to_hex_literal(),
+        max_batch_percentage: 25,
+    }];
+    let mut pool = CoreMempool::new(&config);
+
+    let other_txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 0, 10),
+        TestTransaction::new(0, 1, 10),
+        TestTransaction::new(0, 2, 10),
+        TestTransaction::new(0, 3, 10),
+    ]);
+    let function = format!("{}::dapp::play", contract.to_hex_literal());
+    let dapp_txns: Vec<_> = (1..3)
+        .map(|address| {
+            TestTransaction::new(address, 0, 1).make_signed_entry_function_transaction(&function)
+        })
+        .collect();
+    for txn in &dapp_txns {
+        add_signed_txn(&mut pool, txn.clone()).unwrap();
+    }
+
+    // A quarter of the batch goes to the priority lane first, even though it pays less
+    let batch = pool.get_batch(4, 10240, true, btreemap![]);
+    assert_eq!(batch[0], dapp_txns[0]);
+    assert_eq!(batch[1..], other_txns[..3]);
+}
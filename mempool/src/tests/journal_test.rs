@@ -0,0 +1,62 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    core_mempool::CoreMempool,
+    journal::{JournalChanges, MempoolJournal},
+    tests::common::{add_txns_to_mempool, setup_mempool, TestTransaction},
+};
+use aptos_config::config::NodeConfig;
+use aptos_temppath::TempPath;
+
+fn setup_journaled_mempool() -> CoreMempool {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0];
+    config.mempool.persistence.enabled = true;
+    CoreMempool::new(&config)
+}
+
+#[test]
+fn test_journal_changes() {
+    let path = TempPath::new();
+    path.create_as_dir().unwrap();
+    let journal = MempoolJournal::new(path.path());
+    let mut pool = setup_journaled_mempool();
+    let mut txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(1, 0, 1),
+        TestTransaction::new(0, 0, 1),
+        TestTransaction::new(0, 1, 1),
+    ]);
+
+    // All transactions are recorded on insertion, once
+    let changes = pool.take_journal_changes();
+    let expected: JournalChanges = txns
+        .iter()
+        .map(|txn| ((txn.sender(), txn.sequence_number()), Some(txn.clone())))
+        .collect();
+    assert_eq!(changes, expected);
+    journal.write(&changes).unwrap();
+    assert!(pool.take_journal_changes().is_empty());
+
+    // Committed transactions are deleted from the journal
+    let address = TestTransaction::get_address(0);
+    pool.commit_transaction(&address, 0);
+    let changes = pool.take_journal_changes();
+    assert_eq!(changes, JournalChanges::from([((address, 0), None)]));
+    journal.write(&changes).unwrap();
+
+    // The journal survives a restart, and is read by sender and sequence number
+    drop(journal);
+    let journal = MempoolJournal::new(path.path());
+    txns.retain(|txn| (txn.sender(), txn.sequence_number()) != (address, 0));
+    txns.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    assert_eq!(journal.get_all().unwrap(), txns);
+}
+
+#[test]
+fn test_journal_changes_disabled() {
+    let (mut pool, _) = setup_mempool();
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    pool.commit_transaction(&TestTransaction::get_address(0), 0);
+    assert!(pool.take_journal_changes().is_empty());
+}
@@ -11,7 +11,10 @@ use crate::{
 use anyhow::{format_err, Result};
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{NetworkConfig, NodeConfig},
+    config::{
+        transaction_filter_type::{Filter, LiveFilter},
+        NetworkConfig, NodeConfig,
+    },
     network_id::NetworkId,
 };
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
@@ -60,11 +63,18 @@ impl MockSharedMempool {
     /// Returns the runtime on which the shared mempool is running
     /// and the channel through which shared mempool receives client events.
     pub fn new() -> Self {
+        Self::new_with_transaction_filter(Filter::empty())
+    }
+
+    /// Creates a mock of a running instance of shared mempool that
+    /// rejects the transactions denied by the given filter on admission.
+    pub fn new_with_transaction_filter(transaction_filter: Filter) -> Self {
         // Create the shared mempool
         let (ac_client, mempool, quorum_store_sender, mempool_notifier) = Self::start(
             &Handle::current(),
             &DbReaderWriter::new(MockDbReaderWriter),
             MockVMValidator,
+            transaction_filter,
         );
         Self {
             _runtime: Some(Handle::current()),
@@ -94,7 +104,7 @@ impl MockSharedMempool {
     ) -> Self {
         let handle = Handle::current();
         let (ac_client, mempool, quorum_store_sender, mempool_notifier) =
-            Self::start(&handle, db, validator);
+            Self::start(&handle, db, validator, Filter::empty());
         Self {
             _runtime: None,
             _handle: Some(handle),
@@ -109,6 +119,7 @@ impl MockSharedMempool {
         handle: &Handle,
         db: &DbReaderWriter,
         validator: V,
+        transaction_filter: Filter,
     ) -> (
         MempoolClientSender,
         Arc<Mutex<CoreMempool>>,
@@ -117,6 +128,7 @@ impl MockSharedMempool {
     ) {
         let mut config = NodeConfig::generate_random_config();
         config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
+        config.mempool.transaction_filter = transaction_filter;
 
         let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
         let (network_reqs_tx, _network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
@@ -169,6 +181,7 @@ impl MockSharedMempool {
             Arc::new(RwLock::new(validator)),
             vec![],
             peers_and_metadata,
+            Arc::new(LiveFilter::new(config.mempool.transaction_filter.clone())),
         );
 
         (ac_client, mempool, quorum_store_sender, mempool_notifier)
@@ -9,6 +9,8 @@ mod core_mempool_test;
 #[cfg(test)]
 mod integration_tests;
 #[cfg(test)]
+mod journal_test;
+#[cfg(test)]
 mod multi_node_test;
 #[cfg(test)]
 mod node;
@@ -3,17 +3,39 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::sender_bucket,
+    core_mempool::{sender_bucket, CoreMempool},
+    journal::{JournalChanges, MempoolJournal},
     mocks::MockSharedMempool,
-    network::BroadcastPeerPriority,
+    network::{BroadcastPeerPriority, MempoolSyncMsg},
+    shared_mempool::{tasks::restore_from_journal, types::SharedMempool},
     tests::common::{batch_add_signed_txn, TestTransaction},
-    QuorumStoreRequest,
+    MempoolClientRequest, QuorumStoreRequest,
+};
+use aptos_config::{
+    config::{
+        transaction_filter_type::{Filter, LiveFilter},
+        MempoolConfig, NodeConfig, NodeType,
+    },
+    network_id::NetworkId,
 };
-use aptos_config::config::MempoolConfig;
 use aptos_consensus_types::common::RejectedTransactionSummary;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_mempool_notifications::MempoolNotificationSender;
-use aptos_types::{transaction::Transaction, vm_status::DiscardedVMStatus};
+use aptos_network::{
+    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    protocols::wire::handshake::v1::ProtocolId::MempoolDirectSend,
+};
+use aptos_storage_interface::mock::MockDbReaderWriter;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    mempool_status::MempoolStatusCode,
+    transaction::{SignedTransaction, Transaction},
+    vm_status::DiscardedVMStatus,
+};
+use aptos_vm_validator::mocks::mock_vm_validator::{MockVMValidator, INVALID_SIG_TEST_ADD};
 use futures::{channel::oneshot, sink::SinkExt};
+use std::{collections::HashMap, sync::Arc};
 use tokio::time::timeout;
 
 #[tokio::test]
@@ -152,3 +174,127 @@ async fn test_mempool_notify_committed_txns() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_transaction_filter_rejects_denied_txns() {
+    let denied_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let allowed_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    let smp = MockSharedMempool::new_with_transaction_filter(
+        Filter::empty().add_deny_sender(denied_txn.sender()),
+    );
+
+    // Submit both transactions through the client
+    let mut ac_client = smp.ac_client.clone();
+    let mut statuses = vec![];
+    for txn in [denied_txn.clone(), allowed_txn] {
+        let (callback, callback_rcv) = oneshot::channel();
+        ac_client
+            .send(MempoolClientRequest::SubmitTransaction(txn, callback))
+            .await
+            .unwrap();
+        let (status, _) = callback_rcv.await.unwrap().unwrap();
+        statuses.push(status.code);
+    }
+
+    // Only the transaction from the denied sender is rejected by the filter
+    assert_eq!(statuses[0], MempoolStatusCode::RejectedByFilter);
+    assert_ne!(statuses[1], MempoolStatusCode::RejectedByFilter);
+    assert!(smp
+        .mempool
+        .lock()
+        .get_by_hash(denied_txn.committed_hash())
+        .is_none());
+}
+
+fn new_shared_mempool(
+    capacity: usize,
+) -> SharedMempool<NetworkClient<MempoolSyncMsg>, MockVMValidator> {
+    let mut config = NodeConfig::default();
+    config.mempool.capacity = capacity;
+    let network_client = NetworkClient::new(
+        vec![MempoolDirectSend],
+        vec![],
+        HashMap::new(),
+        PeersAndMetadata::new(&[NetworkId::Validator]),
+    );
+    SharedMempool::new(
+        Arc::new(Mutex::new(CoreMempool::new(&config))),
+        config.mempool.clone(),
+        network_client,
+        Arc::new(MockDbReaderWriter),
+        Arc::new(RwLock::new(MockVMValidator)),
+        vec![],
+        NodeType::extract_from_config(&config),
+        Arc::new(LiveFilter::new(config.mempool.transaction_filter.clone())),
+    )
+}
+
+fn write_journal(path: &TempPath, txns: &[SignedTransaction]) -> MempoolJournal {
+    path.create_as_dir().unwrap();
+    let journal = MempoolJournal::new(path.path());
+    let changes: JournalChanges = txns
+        .iter()
+        .map(|txn| ((txn.sender(), txn.sequence_number()), Some(txn.clone())))
+        .collect();
+    journal.write(&changes).unwrap();
+    journal
+}
+
+#[test]
+fn test_restore_from_journal() {
+    let valid_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let expired_txn = TestTransaction::new(1, 0, 1).make_signed_transaction_with_expiration_time(1);
+    let rejected_txn =
+        TestTransaction::new_with_address(INVALID_SIG_TEST_ADD, 0, 1).make_signed_transaction();
+    let txns = vec![valid_txn.clone(), expired_txn.clone(), rejected_txn.clone()];
+    let path = TempPath::new();
+    let journal = write_journal(&path, &txns);
+
+    let smp = new_shared_mempool(MempoolConfig::default().capacity);
+    let pending = restore_from_journal(&smp, &journal);
+
+    // All the journaled transactions are deleted, the restored one is written back later on
+    let expected: JournalChanges = txns
+        .iter()
+        .map(|txn| ((txn.sender(), txn.sequence_number()), None))
+        .collect();
+    assert_eq!(pending, expected);
+
+    // Only the valid transaction is restored, the expired one is dropped and the one failing
+    // validation is rejected
+    let pool = smp.mempool.lock();
+    assert!(pool.get_by_hash(valid_txn.committed_hash()).is_some());
+    assert!(pool.get_by_hash(expired_txn.committed_hash()).is_none());
+    assert!(pool.get_by_hash(rejected_txn.committed_hash()).is_none());
+}
+
+#[test]
+fn test_restore_from_journal_stops_when_full() {
+    // A full first batch of transactions that aren't ready, as their sequence numbers are ahead
+    // of the accounts'. Only the first two fit in mempool, in its parking lot.
+    let mut txns: Vec<_> = (0..1_000)
+        .map(|_| {
+            TestTransaction::new_with_address(AccountAddress::random(), 1, 1)
+                .make_signed_transaction()
+        })
+        .collect();
+    // A ready transaction, last in the journal, which would be restored by evicting a parked
+    // transaction if the restore went on after mempool got full.
+    let ready_txn = TestTransaction::new_with_address(AccountAddress::new([0xFF; 32]), 0, 1)
+        .make_signed_transaction();
+    txns.push(ready_txn.clone());
+    let path = TempPath::new();
+    let journal = write_journal(&path, &txns);
+
+    let smp = new_shared_mempool(2);
+    let pending = restore_from_journal(&smp, &journal);
+    assert_eq!(pending.len(), txns.len());
+
+    let pool = smp.mempool.lock();
+    let num_restored = txns
+        .iter()
+        .filter(|txn| pool.get_by_hash(txn.committed_hash()).is_some())
+        .count();
+    assert_eq!(num_restored, 2);
+    assert!(pool.get_by_hash(ready_txn.committed_hash()).is_none());
+}
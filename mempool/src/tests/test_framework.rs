@@ -13,7 +13,7 @@ use crate::{
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::NodeConfig,
+    config::{transaction_filter_type::LiveFilter, NodeConfig},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
@@ -653,6 +653,7 @@ fn setup_mempool(
         vm_validator,
         vec![sender],
         peers_and_metadata,
+        Arc::new(LiveFilter::new(config.mempool.transaction_filter.clone())),
     );
 
     (
@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::protocols::wire::handshake::v1::ProtocolId;
+use crate::{peer::ProtocolClass, protocols::wire::handshake::v1::ProtocolId};
 use aptos_config::network_id::NetworkContext;
 use aptos_metrics_core::{
     exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge,
@@ -415,6 +415,54 @@ pub static PENDING_PEER_MANAGER_DIAL_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Time outbound messages spend queued before being scheduled for the wire
+pub static APTOS_NETWORK_OUTBOUND_SCHEDULER_QUEUEING_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_outbound_scheduler_queueing_delay_seconds",
+        "Time outbound messages spend queued in the scheduler in seconds",
+        &["role_type", "network_id", "protocol_class"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Observes the queueing delay of an outbound message
+pub fn observe_outbound_scheduler_queueing_delay(
+    network_context: &NetworkContext,
+    protocol_class: ProtocolClass,
+    queueing_delay_secs: f64,
+) {
+    APTOS_NETWORK_OUTBOUND_SCHEDULER_QUEUEING_DELAY
+        .with_label_values(&[
+            network_context.role().as_str(),
+            network_context.network_id().as_str(),
+            protocol_class.get_label(),
+        ])
+        .observe(queueing_delay_secs);
+}
+
+/// Counter of outbound messages dropped by the scheduler (because the queue was full)
+pub static APTOS_NETWORK_OUTBOUND_SCHEDULER_DROPPED_MESSAGES: Lazy<IntCounterVec> =
+    Lazy::new(|| {
+        register_int_counter_vec!(
+            "aptos_network_outbound_scheduler_dropped_messages",
+            "Number of outbound messages dropped by the scheduler",
+            &["role_type", "network_id", "protocol_class"],
+        )
+        .unwrap()
+    });
+
+pub fn outbound_scheduler_dropped_messages(
+    network_context: &NetworkContext,
+    protocol_class: ProtocolClass,
+) -> IntCounter {
+    APTOS_NETWORK_OUTBOUND_SCHEDULER_DROPPED_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        protocol_class.get_label(),
+    ])
+}
+
 /// Counter of messages pending in queue to be sent out on the wire.
 pub static PENDING_WIRE_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
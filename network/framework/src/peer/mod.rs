@@ -36,7 +36,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::network_id::{NetworkContext, PeerNetworkId};
+use aptos_config::{
+    config::OutboundSchedulerConfig,
+    network_id::{NetworkContext, PeerNetworkId},
+};
 use aptos_logger::prelude::*;
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
@@ -46,7 +49,7 @@ use futures::{
     channel::oneshot,
     io::{AsyncRead, AsyncWrite},
     stream::StreamExt,
-    SinkExt,
+    FutureExt, SinkExt,
 };
 use futures_util::stream::select;
 use serde::Serialize;
@@ -56,9 +59,18 @@ use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod scheduler;
 #[cfg(test)]
 mod test;
 
+use scheduler::OutboundScheduler;
+pub use scheduler::{OutboundMessage, ProtocolClass};
+
+/// The size of the channel between the outbound scheduler and the writer task.
+/// This is small, so that messages wait in the scheduler (where they can be
+/// prioritized), and not in the channel (where they are sent in FIFO order).
+const MULTIPLEX_MESSAGE_CHANNEL_SIZE: usize = 1;
+
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
 
@@ -130,6 +142,8 @@ pub struct Peer<TSocket> {
     max_message_size: usize,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// The outbound message scheduler configuration
+    outbound_scheduler_config: OutboundSchedulerConfig,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -152,6 +166,7 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_scheduler_config: OutboundSchedulerConfig,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -185,6 +200,7 @@ where
             max_frame_size,
             max_message_size,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
+            outbound_scheduler_config,
         }
     }
 
@@ -222,6 +238,7 @@ where
             writer,
             self.max_frame_size,
             self.max_message_size,
+            self.outbound_scheduler_config,
         );
 
         // Start main Peer event loop.
@@ -316,7 +333,9 @@ where
     // 1. The first channel is used to send outbound NetworkMessages to the task
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
-    // them and immediately closes the connection.
+    // them and immediately closes the connection. Outbound messages are prioritized (by protocol
+    // class) using the outbound scheduler before they are written.
+    #[allow(clippy::too_many_arguments)]
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
@@ -325,12 +344,13 @@ where
         mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_scheduler_config: OutboundSchedulerConfig,
     ) -> (
-        aptos_channel::Sender<(), NetworkMessage>,
+        aptos_channel::Sender<(), OutboundMessage>,
         oneshot::Sender<()>,
     ) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (aptos_channel::Sender<(), NetworkMessage>, _) =
+        let (write_reqs_tx, mut write_reqs_rx): (aptos_channel::Sender<(), OutboundMessage>, _) =
             aptos_channel::new(
                 QueueStyle::KLAST,
                 1024,
@@ -338,7 +358,10 @@ where
             );
         let (close_tx, mut close_rx) = oneshot::channel();
 
-        let (mut msg_tx, msg_rx) = aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_MESSAGE);
+        let (mut msg_tx, msg_rx) = aptos_channels::new(
+            MULTIPLEX_MESSAGE_CHANNEL_SIZE,
+            &counters::PENDING_MULTIPLEX_MESSAGE,
+        );
         let (stream_msg_tx, stream_msg_rx) =
             aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
 
@@ -408,11 +431,37 @@ where
                 },
             }
         };
-        // the task ends when the write_reqs_tx is dropped
+        // the task ends when the write_reqs_tx is dropped (and all queued messages are sent)
         let multiplex_task = async move {
             let mut outbound_stream =
                 OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
-            while let Some(message) = write_reqs_rx.next().await {
+            let mut outbound_scheduler =
+                OutboundScheduler::new(network_context, outbound_scheduler_config);
+            let mut write_reqs_closed = false;
+            loop {
+                // Wait for the next outbound message (if none are queued)
+                if outbound_scheduler.is_empty() {
+                    match write_reqs_rx.next().await {
+                        Some(message) => outbound_scheduler.enqueue(message),
+                        None => break,
+                    }
+                }
+
+                // Queue all other pending outbound messages (without waiting)
+                while !write_reqs_closed {
+                    match write_reqs_rx.next().now_or_never() {
+                        Some(Some(message)) => outbound_scheduler.enqueue(message),
+                        Some(None) => write_reqs_closed = true,
+                        None => break,
+                    }
+                }
+
+                // Send the next scheduled message
+                let message = match outbound_scheduler.dequeue() {
+                    Some(outbound_message) => outbound_message.message,
+                    None => continue,
+                };
+
                 // either channel full would block the other one
                 let result = if outbound_stream.should_stream(&message) {
                     outbound_stream.stream_message(message).await
@@ -430,6 +479,10 @@ where
                         remote_peer_id.short_str(),
                     );
                 }
+
+                if write_reqs_closed && outbound_scheduler.is_empty() {
+                    break;
+                }
             }
         };
         executor.spawn(writer_task);
@@ -553,7 +606,7 @@ where
     fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
+        write_reqs_tx: &mut aptos_channel::Sender<(), OutboundMessage>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -575,7 +628,9 @@ where
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
                     let message = NetworkMessage::Error(error_code);
 
-                    write_reqs_tx.push((), message)?;
+                    // Error messages are small, so they're sent with the highest priority
+                    write_reqs_tx
+                        .push((), OutboundMessage::new(ProtocolClass::Consensus, message))?;
                     return Err(err.into());
                 },
                 ReadError::IoError(_) => {
@@ -595,7 +650,7 @@ where
     fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
+        write_reqs_tx: &mut aptos_channel::Sender<(), OutboundMessage>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -615,7 +670,9 @@ where
                     raw_msg: Vec::from(message.mdata.as_ref()),
                 });
 
-                match write_reqs_tx.push((), message) {
+                match write_reqs_tx
+                    .push((), OutboundMessage::from_protocol_id(protocol_id, message))
+                {
                     Ok(_) => {
                         self.update_outbound_direct_send_metrics(protocol_id, message_len as u64);
                     },
@@ -674,7 +731,7 @@ where
 
     async fn do_shutdown(
         mut self,
-        write_req_tx: aptos_channel::Sender<(), NetworkMessage>,
+        write_req_tx: aptos_channel::Sender<(), OutboundMessage>,
         writer_close_tx: oneshot::Sender<()>,
         reason: DisconnectReason,
     ) {
@@ -0,0 +1,398 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The outbound scheduler determines the order in which outbound messages are
+//! written to a single peer connection.
+//!
+//! Without the scheduler, all outbound messages share a single FIFO queue, so
+//! large messages (e.g., storage service responses) can delay latency sensitive
+//! messages (e.g., consensus votes) on the same connection. Instead, messages
+//! are queued per [`ProtocolClass`] and dequeued using deficit round-robin:
+//! classes are visited in priority order, and on each visit a class may send
+//! messages up to its (configurable) byte budget. Unused budget carries over
+//! only while the class has messages queued, so messages larger than the budget
+//! are sent once enough budget has accumulated.
+
+use crate::{counters, protocols::wire::messaging::v1::NetworkMessage, ProtocolId};
+use aptos_config::{config::OutboundSchedulerConfig, network_id::NetworkContext};
+use std::{collections::VecDeque, time::Instant};
+
+/// The number of protocol classes
+const NUM_PROTOCOL_CLASSES: usize = 4;
+
+/// The class of a protocol (used to prioritize outbound messages).
+/// Classes are ordered by priority (highest first).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProtocolClass {
+    Consensus = 0,
+    Mempool = 1,
+    StateSync = 2,
+    Netbench = 3,
+}
+
+impl ProtocolClass {
+    /// Returns the protocol class for the given protocol
+    pub fn from_protocol_id(protocol_id: ProtocolId) -> Self {
+        use ProtocolId::*;
+        match protocol_id {
+            ConsensusRpcBcs
+            | ConsensusDirectSendBcs
+            | ConsensusDirectSendJson
+            | ConsensusRpcJson
+            | ConsensusRpcCompressed
+            | ConsensusDirectSendCompressed
+            | DKGDirectSendCompressed
+            | DKGDirectSendBcs
+            | DKGDirectSendJson
+            | DKGRpcCompressed
+            | DKGRpcBcs
+            | DKGRpcJson
+            | JWKConsensusDirectSendCompressed
+            | JWKConsensusDirectSendBcs
+            | JWKConsensusDirectSendJson
+            | JWKConsensusRpcCompressed
+            | JWKConsensusRpcBcs
+            | JWKConsensusRpcJson
+            | ConsensusObserver
+            | ConsensusObserverRpc
+            | HealthCheckerRpc
+            | DiscoveryDirectSend => ProtocolClass::Consensus,
+            MempoolDirectSend
+            | MempoolRpc
+            | MempoolDirectSendZstd
+            | MempoolDirectSendZstdDictionary => ProtocolClass::Mempool,
+            StateSyncDirectSend
+            | StorageServiceRpc
+            | StorageServiceRpcZstd
+            | StorageServiceRpcZstdDictionary
+            | PeerMonitoringServiceRpc => ProtocolClass::StateSync,
+            NetbenchDirectSend | NetbenchRpc => ProtocolClass::Netbench,
+        }
+    }
+
+    /// Returns a summary label for the protocol class
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ProtocolClass::Consensus => "consensus",
+            ProtocolClass::Mempool => "mempool",
+            ProtocolClass::StateSync => "state_sync",
+            ProtocolClass::Netbench => "netbench",
+        }
+    }
+
+    /// Returns the byte budget (per round) for the protocol class
+    fn get_byte_budget(&self, config: &OutboundSchedulerConfig) -> u64 {
+        let byte_budget = match self {
+            ProtocolClass::Consensus => config.consensus_byte_budget,
+            ProtocolClass::Mempool => config.mempool_byte_budget,
+            ProtocolClass::StateSync => config.state_sync_byte_budget,
+            ProtocolClass::Netbench => config.netbench_byte_budget,
+        };
+        byte_budget.max(1) // Every class must be able to make progress
+    }
+}
+
+/// An outbound message (queued to be written to the wire)
+#[derive(Debug)]
+pub struct OutboundMessage {
+    pub protocol_class: ProtocolClass,
+    pub message: NetworkMessage,
+    enqueue_time: Instant,
+}
+
+impl OutboundMessage {
+    pub fn new(protocol_class: ProtocolClass, message: NetworkMessage) -> Self {
+        Self {
+            protocol_class,
+            message,
+            enqueue_time: Instant::now(),
+        }
+    }
+
+    pub fn from_protocol_id(protocol_id: ProtocolId, message: NetworkMessage) -> Self {
+        Self::new(ProtocolClass::from_protocol_id(protocol_id), message)
+    }
+
+    /// Returns the number of bytes charged against the class byte budget
+    fn size(&self) -> u64 {
+        self.message.data_len() as u64
+    }
+}
+
+/// A queue of outbound messages for a single protocol class
+struct ClassQueue {
+    messages: VecDeque<OutboundMessage>,
+    byte_budget: u64,
+    deficit: u64,
+}
+
+impl ClassQueue {
+    fn new(byte_budget: u64) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            byte_budget,
+            deficit: 0,
+        }
+    }
+
+    /// Returns the number of bytes the queue must still accumulate
+    /// before the next message can be sent (if any).
+    fn missing_bytes(&self) -> Option<u64> {
+        self.messages
+            .front()
+            .map(|message| message.size().saturating_sub(self.deficit))
+    }
+}
+
+/// A deficit round-robin scheduler for outbound messages to a single peer
+pub struct OutboundScheduler {
+    network_context: NetworkContext,
+    queues: [ClassQueue; NUM_PROTOCOL_CLASSES],
+    max_queued_messages_per_class: usize,
+
+    // The class currently being served, and whether or
+    // not the class has received its budget for this visit.
+    active_class_index: usize,
+    active_class_budget_granted: bool,
+}
+
+impl OutboundScheduler {
+    pub fn new(network_context: NetworkContext, config: OutboundSchedulerConfig) -> Self {
+        let queues = [
+            ProtocolClass::Consensus,
+            ProtocolClass::Mempool,
+            ProtocolClass::StateSync,
+            ProtocolClass::Netbench,
+        ]
+        .map(|protocol_class| ClassQueue::new(protocol_class.get_byte_budget(&config)));
+
+        Self {
+            network_context,
+            queues,
+            max_queued_messages_per_class: config.max_queued_messages_per_class.max(1),
+            active_class_index: 0,
+            active_class_budget_granted: false,
+        }
+    }
+
+    /// Returns true iff there are no queued messages
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(|queue| queue.messages.is_empty())
+    }
+
+    /// Enqueues the given message. If the queue for the message class
+    /// is full, the oldest message in the queue is dropped.
+    pub fn enqueue(&mut self, message: OutboundMessage) {
+        let protocol_class = message.protocol_class;
+        let queue = &mut self.queues[protocol_class as usize];
+        if queue.messages.len() >= self.max_queued_messages_per_class {
+            queue.messages.pop_front();
+            counters::outbound_scheduler_dropped_messages(&self.network_context, protocol_class)
+                .inc();
+        }
+        queue.messages.push_back(message);
+    }
+
+    /// Dequeues the next message to send (if any)
+    pub fn dequeue(&mut self) -> Option<OutboundMessage> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // Visit the classes in round-robin order until one can send a message.
+        // After two full rounds, every non-empty class has been granted budget,
+        // so if no message fits, we fast-forward the deficits instead of looping.
+        let mut num_visits = 0;
+        loop {
+            if let Some(message) = self.dequeue_from_active_class() {
+                counters::observe_outbound_scheduler_queueing_delay(
+                    &self.network_context,
+                    message.protocol_class,
+                    message.enqueue_time.elapsed().as_secs_f64(),
+                );
+                return Some(message);
+            }
+
+            num_visits += 1;
+            if num_visits >= 2 * NUM_PROTOCOL_CLASSES {
+                self.fast_forward_deficits();
+                num_visits = 0;
+            }
+
+            self.active_class_index = (self.active_class_index + 1) % NUM_PROTOCOL_CLASSES;
+            self.active_class_budget_granted = false;
+        }
+    }
+
+    /// Dequeues the next message from the active class (if the
+    /// class has messages and enough budget to send the next one).
+    fn dequeue_from_active_class(&mut self) -> Option<OutboundMessage> {
+        let queue = &mut self.queues[self.active_class_index];
+        let message_size = match queue.messages.front() {
+            Some(message) => message.size(),
+            None => {
+                queue.deficit = 0; // Budget doesn't carry over for idle classes
+                return None;
+            },
+        };
+
+        // Grant the budget for this visit
+        if !self.active_class_budget_granted {
+            queue.deficit = queue.deficit.saturating_add(queue.byte_budget);
+            self.active_class_budget_granted = true;
+        }
+
+        // Send the message if the class has enough budget
+        if message_size > queue.deficit {
+            return None;
+        }
+        queue.deficit -= message_size;
+        let message = queue.messages.pop_front();
+        if queue.messages.is_empty() {
+            queue.deficit = 0;
+        }
+        message
+    }
+
+    /// Skips the rounds in which no class could send a message, i.e., grants
+    /// each non-empty class the budget it would have accumulated in those rounds.
+    fn fast_forward_deficits(&mut self) {
+        let num_rounds_to_skip = self
+            .queues
+            .iter()
+            .filter_map(|queue| {
+                queue
+                    .missing_bytes()
+                    .map(|missing_bytes| missing_bytes.div_ceil(queue.byte_budget))
+            })
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(1); // The next round grants the final budget
+
+        for queue in self.queues.iter_mut() {
+            if !queue.messages.is_empty() {
+                let skipped_budget = queue.byte_budget.saturating_mul(num_rounds_to_skip);
+                queue.deficit = queue.deficit.saturating_add(skipped_budget);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocols::wire::messaging::v1::{DirectSendMsg, Priority};
+
+    #[test]
+    fn test_consensus_is_not_blocked_by_state_sync() {
+        let mut scheduler = create_scheduler(1000, 100, 100, 100);
+
+        // Enqueue many large state sync messages, followed by a consensus message
+        for _ in 0..10 {
+            scheduler.enqueue(create_message(ProtocolId::StorageServiceRpc, 1000));
+        }
+        scheduler.enqueue(create_message(ProtocolId::ConsensusDirectSendBcs, 10));
+
+        // Verify the consensus message is sent first
+        let message = scheduler.dequeue().unwrap();
+        assert_eq!(message.protocol_class, ProtocolClass::Consensus);
+
+        // Verify all state sync messages are eventually sent
+        for _ in 0..10 {
+            let message = scheduler.dequeue().unwrap();
+            assert_eq!(message.protocol_class, ProtocolClass::StateSync);
+        }
+        assert!(scheduler.is_empty());
+        assert!(scheduler.dequeue().is_none());
+    }
+
+    #[test]
+    fn test_weighted_byte_budgets() {
+        let mut scheduler = create_scheduler(300, 200, 100, 100);
+
+        // Enqueue many equally sized messages for all classes
+        let protocol_ids = [
+            ProtocolId::ConsensusRpcBcs,
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::StateSyncDirectSend,
+            ProtocolId::NetbenchDirectSend,
+        ];
+        for protocol_id in protocol_ids {
+            for _ in 0..100 {
+                scheduler.enqueue(create_message(protocol_id, 100));
+            }
+        }
+
+        // Dequeue several rounds of messages
+        let mut num_messages_per_class = [0; NUM_PROTOCOL_CLASSES];
+        for _ in 0..70 {
+            let message = scheduler.dequeue().unwrap();
+            num_messages_per_class[message.protocol_class as usize] += 1;
+        }
+
+        // Verify the messages were sent in proportion to the byte budgets
+        assert_eq!(num_messages_per_class, [30, 20, 10, 10]);
+    }
+
+    #[test]
+    fn test_messages_larger_than_budget() {
+        let mut scheduler = create_scheduler(100, 1, 1, 1);
+
+        // Enqueue a message much larger than the class budget
+        scheduler.enqueue(create_message(ProtocolId::StorageServiceRpc, 10_000_000));
+        scheduler.enqueue(create_message(ProtocolId::NetbenchRpc, 5_000));
+
+        // Verify the messages are eventually sent (smallest missing budget first)
+        let message = scheduler.dequeue().unwrap();
+        assert_eq!(message.protocol_class, ProtocolClass::Netbench);
+        let message = scheduler.dequeue().unwrap();
+        assert_eq!(message.protocol_class, ProtocolClass::StateSync);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest() {
+        let config = OutboundSchedulerConfig {
+            max_queued_messages_per_class: 2,
+            ..Default::default()
+        };
+        let mut scheduler = OutboundScheduler::new(NetworkContext::mock(), config);
+
+        // Enqueue more messages than the queue can hold
+        for data_len in 1..=3 {
+            scheduler.enqueue(create_message(ProtocolId::MempoolDirectSend, data_len));
+        }
+
+        // Verify the oldest message was dropped
+        assert_eq!(scheduler.dequeue().unwrap().message.data_len(), 2);
+        assert_eq!(scheduler.dequeue().unwrap().message.data_len(), 3);
+        assert!(scheduler.dequeue().is_none());
+    }
+
+    /// Creates a scheduler with the given byte budgets
+    fn create_scheduler(
+        consensus_byte_budget: u64,
+        mempool_byte_budget: u64,
+        state_sync_byte_budget: u64,
+        netbench_byte_budget: u64,
+    ) -> OutboundScheduler {
+        let config = OutboundSchedulerConfig {
+            consensus_byte_budget,
+            mempool_byte_budget,
+            state_sync_byte_budget,
+            netbench_byte_budget,
+            ..Default::default()
+        };
+        OutboundScheduler::new(NetworkContext::mock(), config)
+    }
+
+    /// Creates a direct send message with the given data length
+    fn create_message(protocol_id: ProtocolId, data_len: usize) -> OutboundMessage {
+        let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: Priority::default(),
+            raw_msg: vec![0; data_len],
+        });
+        OutboundMessage::from_protocol_id(protocol_id, message)
+    }
+}
@@ -25,7 +25,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{OutboundSchedulerConfig, PeerRole},
+    network_id::NetworkContext,
+};
 use aptos_logger::info;
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::ConnectionOrigin;
@@ -97,6 +100,7 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        OutboundSchedulerConfig::default(),
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -15,7 +15,11 @@
 
 use crate::counters::{start_serialization_timer, DESERIALIZATION_LABEL, SERIALIZATION_LABEL};
 use anyhow::anyhow;
-use aptos_compression::client::CompressionClient;
+use aptos_compression::{
+    client::CompressionClient,
+    dictionary::{MEMPOOL_DICTIONARY_ID, STORAGE_SERVICE_DICTIONARY_ID},
+    CompressionCodec,
+};
 use aptos_config::{config::MAX_APPLICATION_MESSAGE_SIZE, network_id::NetworkId};
 use aptos_types::chain_id::ChainId;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -73,12 +77,16 @@ pub enum ProtocolId {
     JWKConsensusRpcJson = 26,
     ConsensusObserver = 27,
     ConsensusObserverRpc = 28,
+    MempoolDirectSendZstd = 29,
+    StorageServiceRpcZstd = 30,
+    MempoolDirectSendZstdDictionary = 31,
+    StorageServiceRpcZstdDictionary = 32,
 }
 
 /// The encoding types for Protocols
 enum Encoding {
     Bcs(usize),
-    CompressedBcs(usize),
+    CompressedBcs(CompressionCodec, usize),
     Json,
 }
 
@@ -115,6 +123,10 @@ impl ProtocolId {
             JWKConsensusRpcJson => "JWKConsensusRpcJson",
             ConsensusObserver => "ConsensusObserver",
             ConsensusObserverRpc => "ConsensusObserverRpc",
+            MempoolDirectSendZstd => "MempoolDirectSendZstd",
+            StorageServiceRpcZstd => "StorageServiceRpcZstd",
+            MempoolDirectSendZstdDictionary => "MempoolDirectSendZstdDictionary",
+            StorageServiceRpcZstdDictionary => "StorageServiceRpcZstdDictionary",
         }
     }
 
@@ -150,23 +162,59 @@ impl ProtocolId {
             ProtocolId::JWKConsensusRpcJson,
             ProtocolId::ConsensusObserver,
             ProtocolId::ConsensusObserverRpc,
+            ProtocolId::MempoolDirectSendZstd,
+            ProtocolId::StorageServiceRpcZstd,
+            ProtocolId::MempoolDirectSendZstdDictionary,
+            ProtocolId::StorageServiceRpcZstdDictionary,
         ]
     }
 
+    /// Returns the ID of the zstd dictionary used by the protocol (if any).
+    /// Dictionary IDs are fixed per protocol, so advertising the protocol
+    /// during the handshake also advertises the dictionary. As a result,
+    /// nodes should only advertise the protocol if the dictionary is registered.
+    pub fn compression_dictionary_id(self) -> Option<u32> {
+        match self {
+            ProtocolId::MempoolDirectSendZstdDictionary => Some(MEMPOOL_DICTIONARY_ID),
+            ProtocolId::StorageServiceRpcZstdDictionary => Some(STORAGE_SERVICE_DICTIONARY_ID),
+            _ => None,
+        }
+    }
+
     /// Specifies how to encode messages for a given `ProtocolId`
     fn encoding(self) -> Encoding {
         match self {
             ProtocolId::ConsensusDirectSendJson | ProtocolId::ConsensusRpcJson => Encoding::Json,
             ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                Encoding::CompressedBcs(RECURSION_LIMIT)
+                Encoding::CompressedBcs(CompressionCodec::Lz4, RECURSION_LIMIT)
+            },
+            ProtocolId::ConsensusObserver => {
+                Encoding::CompressedBcs(CompressionCodec::Lz4, RECURSION_LIMIT)
             },
-            ProtocolId::ConsensusObserver => Encoding::CompressedBcs(RECURSION_LIMIT),
             ProtocolId::DKGDirectSendCompressed | ProtocolId::DKGRpcCompressed => {
-                Encoding::CompressedBcs(RECURSION_LIMIT)
+                Encoding::CompressedBcs(CompressionCodec::Lz4, RECURSION_LIMIT)
             },
             ProtocolId::JWKConsensusDirectSendCompressed
-            | ProtocolId::JWKConsensusRpcCompressed => Encoding::CompressedBcs(RECURSION_LIMIT),
-            ProtocolId::MempoolDirectSend => Encoding::CompressedBcs(USER_INPUT_RECURSION_LIMIT),
+            | ProtocolId::JWKConsensusRpcCompressed => {
+                Encoding::CompressedBcs(CompressionCodec::Lz4, RECURSION_LIMIT)
+            },
+            ProtocolId::MempoolDirectSend => {
+                Encoding::CompressedBcs(CompressionCodec::Lz4, USER_INPUT_RECURSION_LIMIT)
+            },
+            ProtocolId::MempoolDirectSendZstd => {
+                Encoding::CompressedBcs(CompressionCodec::Zstd, USER_INPUT_RECURSION_LIMIT)
+            },
+            ProtocolId::StorageServiceRpcZstd => {
+                Encoding::CompressedBcs(CompressionCodec::Zstd, RECURSION_LIMIT)
+            },
+            ProtocolId::MempoolDirectSendZstdDictionary => Encoding::CompressedBcs(
+                CompressionCodec::ZstdWithDictionary(MEMPOOL_DICTIONARY_ID),
+                USER_INPUT_RECURSION_LIMIT,
+            ),
+            ProtocolId::StorageServiceRpcZstdDictionary => Encoding::CompressedBcs(
+                CompressionCodec::ZstdWithDictionary(STORAGE_SERVICE_DICTIONARY_ID),
+                RECURSION_LIMIT,
+            ),
             ProtocolId::MempoolRpc => Encoding::Bcs(USER_INPUT_RECURSION_LIMIT),
             _ => Encoding::Bcs(RECURSION_LIMIT),
         }
@@ -179,7 +227,12 @@ impl ProtocolId {
                 CompressionClient::Consensus
             },
             ProtocolId::ConsensusObserver => CompressionClient::ConsensusObserver,
-            ProtocolId::MempoolDirectSend => CompressionClient::Mempool,
+            ProtocolId::MempoolDirectSend
+            | ProtocolId::MempoolDirectSendZstd
+            | ProtocolId::MempoolDirectSendZstdDictionary => CompressionClient::Mempool,
+            ProtocolId::StorageServiceRpcZstd | ProtocolId::StorageServiceRpcZstdDictionary => {
+                CompressionClient::StateSync
+            },
             ProtocolId::DKGDirectSendCompressed | ProtocolId::DKGRpcCompressed => {
                 CompressionClient::DKG
             },
@@ -201,11 +254,12 @@ impl ProtocolId {
         // Serialize the message
         let result = match self.encoding() {
             Encoding::Bcs(limit) => self.bcs_encode(value, limit),
-            Encoding::CompressedBcs(limit) => {
+            Encoding::CompressedBcs(codec, limit) => {
                 let compression_client = self.get_compression_client();
                 let bcs_bytes = self.bcs_encode(value, limit)?;
-                aptos_compression::compress(
+                aptos_compression::compress_with_codec(
                     bcs_bytes,
+                    codec,
                     compression_client,
                     MAX_APPLICATION_MESSAGE_SIZE,
                 )
@@ -231,10 +285,11 @@ impl ProtocolId {
         // Deserialize the message
         let result = match self.encoding() {
             Encoding::Bcs(limit) => self.bcs_decode(bytes, limit),
-            Encoding::CompressedBcs(limit) => {
+            Encoding::CompressedBcs(codec, limit) => {
                 let compression_client = self.get_compression_client();
-                let raw_bytes = aptos_compression::decompress(
+                let raw_bytes = aptos_compression::decompress_with_codec(
                     &bytes.to_vec(),
+                    codec,
                     compression_client,
                     MAX_APPLICATION_MESSAGE_SIZE,
                 )
@@ -36,6 +36,81 @@ fn test_as_u8_serde_equiv() {
     }
 }
 
+#[test]
+fn test_protocol_encodings() {
+    // Register the compression dictionaries
+    register_test_dictionaries();
+
+    // Verify that all protocols can encode and decode messages
+    let message: Vec<u64> = (0..1000).map(|value| value % 10).collect();
+    for protocol in ProtocolId::all() {
+        let bytes = protocol.to_bytes(&message).unwrap();
+        assert_eq!(protocol.from_bytes::<Vec<u64>>(&bytes).unwrap(), message);
+    }
+
+    // Verify that the zstd protocols can't decode LZ4 compressed messages (and vice versa)
+    let lz4_bytes = ProtocolId::MempoolDirectSend.to_bytes(&message).unwrap();
+    let zstd_bytes = ProtocolId::MempoolDirectSendZstd
+        .to_bytes(&message)
+        .unwrap();
+    assert_ne!(lz4_bytes, zstd_bytes);
+    assert!(ProtocolId::MempoolDirectSendZstd
+        .from_bytes::<Vec<u64>>(&lz4_bytes)
+        .is_err());
+    assert!(ProtocolId::MempoolDirectSend
+        .from_bytes::<Vec<u64>>(&zstd_bytes)
+        .is_err());
+
+    // Verify that the dictionary protocols can't decode zstd compressed messages (and vice versa)
+    let dictionary_bytes = ProtocolId::MempoolDirectSendZstdDictionary
+        .to_bytes(&message)
+        .unwrap();
+    assert!(ProtocolId::MempoolDirectSendZstdDictionary
+        .from_bytes::<Vec<u64>>(&zstd_bytes)
+        .is_err());
+    assert!(ProtocolId::MempoolDirectSendZstd
+        .from_bytes::<Vec<u64>>(&dictionary_bytes)
+        .is_err());
+
+    // Verify that the dictionary protocols can't decode each other's messages
+    assert!(ProtocolId::StorageServiceRpcZstdDictionary
+        .from_bytes::<Vec<u64>>(&dictionary_bytes)
+        .is_err());
+}
+
+#[test]
+fn test_compression_dictionary_ids() {
+    // Verify that only the dictionary protocols have dictionary IDs
+    for protocol in ProtocolId::all() {
+        let dictionary_id = protocol.compression_dictionary_id();
+        match protocol {
+            ProtocolId::MempoolDirectSendZstdDictionary => {
+                assert_eq!(dictionary_id, Some(MEMPOOL_DICTIONARY_ID))
+            },
+            ProtocolId::StorageServiceRpcZstdDictionary => {
+                assert_eq!(dictionary_id, Some(STORAGE_SERVICE_DICTIONARY_ID))
+            },
+            _ => assert_eq!(dictionary_id, None),
+        }
+    }
+}
+
+/// Trains and registers the compression dictionaries used by the protocols
+fn register_test_dictionaries() {
+    let samples: Vec<_> = (0..1000u64)
+        .map(|index| {
+            let message: Vec<u64> = (0..100).map(|value| (value + index) % 10).collect();
+            bcs::to_bytes(&message).unwrap()
+        })
+        .collect();
+    for dictionary_id in [MEMPOOL_DICTIONARY_ID, STORAGE_SERVICE_DICTIONARY_ID] {
+        let dictionary =
+            aptos_compression::dictionary::train_dictionary(&samples, 4 * 1024, dictionary_id)
+                .unwrap();
+        aptos_compression::dictionary::register_dictionary(&dictionary).unwrap();
+    }
+}
+
 #[test]
 fn represents_same_network() {
     let mut handshake_msg = HandshakeMsg::new_for_testing();
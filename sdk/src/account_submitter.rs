@@ -0,0 +1,727 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pipelines many transactions from a single account.
+//!
+//! The submitter assigns sequence numbers locally, submits the transactions in batches of up
+//! to what mempool accepts per account, and waits for each batch by polling the account's
+//! sequence number, the same way the transaction emitter does. Transactions that expired or
+//! were rejected for a stale sequence number get re-signed with fresh sequence numbers and
+//! resubmitted.
+
+use crate::{
+    crypto::HashValue,
+    move_types::vm_status::StatusCode,
+    rest_client::{
+        aptos_api_types::{
+            AptosErrorCode, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+        },
+        error::RestError,
+        Client as ApiClient,
+    },
+    transaction_builder::TransactionFactory,
+    types::{
+        account_address::AccountAddress,
+        transaction::{ExecutionStatus, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use anyhow::{bail, ensure, Context, Result};
+use async_trait::async_trait;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How long to keep waiting after the expiration time has passed on the local clock, in case
+/// the fullnode lags behind, before giving up on a batch.
+pub const MAX_LEDGER_LAG_SECS: u64 = 240;
+
+/// The max number of transactions that can be fetched in a single page.
+const MAX_TRANSACTIONS_PAGE_SIZE: u64 = 100;
+
+pub struct SubmitterOptions {
+    /// The max number of transactions in flight at once. Mempool rejects transactions beyond
+    /// its per-account capacity, which is 100 by default. Values below 1 are treated as 1.
+    pub max_in_flight: usize,
+
+    /// How often to poll the account's sequence number while waiting for commitment.
+    pub poll_interval: Duration,
+
+    /// The max number of times a transaction gets resubmitted after expiring or being
+    /// rejected for a stale sequence number.
+    pub max_resubmissions: usize,
+}
+
+impl Default for SubmitterOptions {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 100,
+            poll_interval: Duration::from_millis(500),
+            max_resubmissions: 3,
+        }
+    }
+}
+
+/// A transaction committed on behalf of one of the payloads.
+#[derive(Clone, Debug)]
+pub struct CommittedTransaction {
+    /// Index of the payload the transaction was built from.
+    pub index: usize,
+    pub sequence_number: u64,
+    pub version: u64,
+    pub hash: HashValue,
+    /// Whether the transaction was executed successfully, or aborted.
+    pub status: ExecutionStatus,
+}
+
+/// The fullnode requests the submitter makes, so that it can run against a mock in tests.
+#[async_trait]
+pub trait SubmitterClient: Sync {
+    /// Submits the transactions, and returns the failures of the rejected ones.
+    async fn submit_batch(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<Vec<TransactionsBatchSingleSubmissionFailure>>;
+
+    /// Returns the sequence number of the account, along with the ledger timestamp in seconds.
+    async fn get_sequence_number(&self, address: AccountAddress) -> Result<(u64, u64)>;
+
+    /// Returns up to `limit` transactions of the account, starting at sequence number `start`.
+    async fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start: u64,
+        limit: u16,
+    ) -> Result<Vec<TransactionOnChainData>>;
+}
+
+#[async_trait]
+impl SubmitterClient for ApiClient {
+    async fn submit_batch(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<Vec<TransactionsBatchSingleSubmissionFailure>> {
+        Ok(self
+            .submit_batch_bcs(txns)
+            .await
+            .context("Failed to submit transactions")?
+            .into_inner()
+            .transaction_failures)
+    }
+
+    async fn get_sequence_number(&self, address: AccountAddress) -> Result<(u64, u64)> {
+        get_account_sequence_number(self, address).await
+    }
+
+    async fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start: u64,
+        limit: u16,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        Ok(self
+            .get_account_transactions_bcs(address, Some(start), Some(limit))
+            .await
+            .context("Failed to get account transactions")?
+            .into_inner())
+    }
+}
+
+pub struct AccountSubmitter<'a, C: SubmitterClient = ApiClient> {
+    api_client: &'a C,
+    account: &'a LocalAccount,
+    transaction_factory: TransactionFactory,
+    options: SubmitterOptions,
+}
+
+impl<'a, C: SubmitterClient> AccountSubmitter<'a, C> {
+    pub fn new(
+        api_client: &'a C,
+        account: &'a LocalAccount,
+        transaction_factory: TransactionFactory,
+    ) -> Self {
+        Self {
+            api_client,
+            account,
+            transaction_factory,
+            options: SubmitterOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, mut options: SubmitterOptions) -> Self {
+        // At least one transaction has to be in flight for the submission to make progress.
+        options.max_in_flight = options.max_in_flight.max(1);
+        self.options = options;
+        self
+    }
+
+    /// Resets the account's local sequence number to the one on chain, and returns it.
+    pub async fn sync_sequence_number(&self) -> Result<u64> {
+        let (sequence_number, _) = self
+            .api_client
+            .get_sequence_number(self.account.address())
+            .await?;
+        self.account.set_sequence_number(sequence_number);
+        Ok(sequence_number)
+    }
+
+    /// Submits a transaction for each of the payloads, in order, and waits for all of them to
+    /// be committed. Returns the committed transactions ordered by payload index.
+    ///
+    /// The account's local sequence number is expected to be in sync with the chain, see
+    /// [`Self::sync_sequence_number`], and is kept in sync as the batches get committed.
+    pub async fn submit_and_wait(
+        &self,
+        payloads: Vec<TransactionPayload>,
+    ) -> Result<Vec<CommittedTransaction>> {
+        let mut pending: VecDeque<_> = payloads.into_iter().enumerate().collect();
+        let mut resubmissions = BTreeMap::new();
+        let mut committed = Vec::with_capacity(pending.len());
+
+        while !pending.is_empty() {
+            let batch: Vec<_> = pending
+                .drain(..pending.len().min(self.options.max_in_flight))
+                .collect();
+            let txns: Vec<_> = batch
+                .iter()
+                .map(|(_, payload)| {
+                    self.account.sign_with_transaction_builder(
+                        self.transaction_factory.payload(payload.clone()),
+                    )
+                })
+                .collect();
+
+            let start_seq_num = txns[0].sequence_number();
+            let num_to_wait_for = match self.submit_batch(&batch, &txns).await {
+                Ok(num_to_wait_for) => num_to_wait_for,
+                Err(err) => {
+                    // None of the transactions is known to be accepted, so the sequence numbers
+                    // used to sign them are given back.
+                    self.account.set_sequence_number(start_seq_num);
+                    return Err(err);
+                },
+            };
+            let end_seq_num = start_seq_num + num_to_wait_for as u64;
+            let expiration_timestamp_secs = txns
+                .iter()
+                .map(SignedTransaction::expiration_timestamp_secs)
+                .max()
+                .unwrap_or_default();
+
+            let on_chain_seq_num = if num_to_wait_for > 0 {
+                self.wait_for_sequence_number(end_seq_num, expiration_timestamp_secs)
+                    .await?
+            } else {
+                self.api_client
+                    .get_sequence_number(self.account.address())
+                    .await?
+                    .0
+            };
+            let mut committed_in_batch = self
+                .get_committed_transactions(&txns, start_seq_num, on_chain_seq_num.min(end_seq_num))
+                .await?;
+
+            // Everything not committed gets requeued in order, ahead of the remaining payloads,
+            // e.g., the transactions that expired, or whose sequence numbers got used by another
+            // client of the same account.
+            for ((index, payload), txn) in batch.into_iter().zip(&txns).rev() {
+                match committed_in_batch.remove(&txn.sequence_number()) {
+                    Some(mut committed_txn) => {
+                        committed_txn.index = index;
+                        committed.push(committed_txn);
+                    },
+                    None => {
+                        let count = resubmissions.entry(index).or_insert(0);
+                        *count += 1;
+                        if *count > self.options.max_resubmissions {
+                            bail!(
+                                "Transaction for payload {} was not committed after {} resubmissions",
+                                index,
+                                self.options.max_resubmissions,
+                            );
+                        }
+                        pending.push_front((index, payload));
+                    },
+                }
+            }
+            self.account.set_sequence_number(on_chain_seq_num);
+        }
+
+        committed.sort_by_key(|txn| txn.index);
+        Ok(committed)
+    }
+
+    /// Submits the batch, and returns the number of transactions up to the last accepted one,
+    /// which need to be waited for. Rejections for stale sequence numbers are expected and
+    /// resolved by resyncing, any other rejection fails the submission.
+    async fn submit_batch(
+        &self,
+        batch: &[(usize, TransactionPayload)],
+        txns: &[SignedTransaction],
+    ) -> Result<usize> {
+        let failures = self.api_client.submit_batch(txns).await?;
+
+        let mut rejected = vec![false; txns.len()];
+        for failure in failures {
+            ensure!(
+                failure.transaction_index < txns.len(),
+                "Submission failure for transaction {} out of {} submitted: {}",
+                failure.transaction_index,
+                txns.len(),
+                failure.error,
+            );
+            let status_code = failure
+                .error
+                .vm_error_code
+                .and_then(|code| StatusCode::try_from(code).ok());
+            match status_code {
+                Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD)
+                | Some(StatusCode::SEQUENCE_NUMBER_TOO_NEW) => {},
+                _ => bail!(
+                    "Transaction for payload {} was rejected: {}",
+                    batch[failure.transaction_index].0,
+                    failure.error,
+                ),
+            }
+            rejected[failure.transaction_index] = true;
+        }
+        Ok(rejected
+            .iter()
+            .rposition(|rejected| !rejected)
+            .map_or(0, |index| index + 1))
+    }
+
+    /// Waits until the account's sequence number reaches `end_seq_num`, or the transactions
+    /// expire, and returns the latest sequence number fetched.
+    async fn wait_for_sequence_number(
+        &self,
+        end_seq_num: u64,
+        expiration_timestamp_secs: u64,
+    ) -> Result<u64> {
+        loop {
+            let (sequence_number, ledger_timestamp_secs) = self
+                .api_client
+                .get_sequence_number(self.account.address())
+                .await?;
+            if sequence_number >= end_seq_num || ledger_timestamp_secs > expiration_timestamp_secs {
+                return Ok(sequence_number);
+            }
+            let now_secs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            if now_secs >= expiration_timestamp_secs + MAX_LEDGER_LAG_SECS {
+                bail!(
+                    "Ledger timestamp {} did not catch up to the expiration timestamp {}",
+                    ledger_timestamp_secs,
+                    expiration_timestamp_secs,
+                );
+            }
+            tokio::time::sleep(self.options.poll_interval).await;
+        }
+    }
+
+    /// Fetches the account's transactions committed in `[start_seq_num, end_seq_num)`, keyed by
+    /// sequence number, keeping only those matching the submitted ones.
+    async fn get_committed_transactions(
+        &self,
+        txns: &[SignedTransaction],
+        start_seq_num: u64,
+        end_seq_num: u64,
+    ) -> Result<BTreeMap<u64, CommittedTransaction>> {
+        let mut committed = BTreeMap::new();
+        let mut start = start_seq_num;
+        while start < end_seq_num {
+            let limit = (end_seq_num - start).min(MAX_TRANSACTIONS_PAGE_SIZE);
+            let on_chain_txns = self
+                .api_client
+                .get_account_transactions(self.account.address(), start, limit as u16)
+                .await?;
+            if on_chain_txns.is_empty() {
+                break;
+            }
+            start += on_chain_txns.len() as u64;
+
+            for on_chain_txn in on_chain_txns {
+                let hash = on_chain_txn.info.transaction_hash();
+                if let Some(txn) = txns.iter().find(|txn| txn.committed_hash() == hash) {
+                    committed.insert(txn.sequence_number(), CommittedTransaction {
+                        index: 0,
+                        sequence_number: txn.sequence_number(),
+                        version: on_chain_txn.version,
+                        hash,
+                        status: on_chain_txn.info.status().clone(),
+                    });
+                }
+            }
+        }
+        Ok(committed)
+    }
+}
+
+/// Returns the sequence number of the account, along with the ledger timestamp in seconds. An
+/// account that doesn't exist yet has sequence number 0.
+pub async fn get_account_sequence_number(
+    api_client: &ApiClient,
+    address: AccountAddress,
+) -> Result<(u64, u64)> {
+    let result = api_client.get_account_bcs(address).await;
+    match &result {
+        Ok(resp) => Ok((
+            resp.inner().sequence_number(),
+            Duration::from_micros(resp.state().timestamp_usecs).as_secs(),
+        )),
+        Err(e) => {
+            // if account is not present, that is equivalent to sequence_number = 0
+            if let RestError::Api(api_error) = e {
+                if let AptosErrorCode::AccountNotFound = api_error.error.error_code {
+                    return Ok((
+                        0,
+                        Duration::from_micros(api_error.state.as_ref().unwrap().timestamp_usecs)
+                            .as_secs(),
+                    ));
+                }
+            }
+            result?;
+            unreachable!()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rest_client::aptos_api_types::AptosError,
+        transaction_builder::aptos_stdlib,
+        types::{
+            chain_id::ChainId,
+            transaction::{Transaction, TransactionInfo},
+            write_set::WriteSet,
+        },
+    };
+    use std::sync::Mutex;
+
+    /// How the fake fullnode handles a submission.
+    enum Submission {
+        /// Commits the transactions right away, rejecting the ones with sequence numbers that
+        /// don't follow the account's.
+        Commit,
+        /// Accepts the transactions, but lets them expire without committing them.
+        Expire,
+        /// Rejects the first transaction with the status code.
+        Reject(StatusCode),
+        /// Reports a failure for a transaction that wasn't submitted.
+        InvalidIndex,
+    }
+
+    /// A fullnode serving a single account.
+    #[derive(Default)]
+    struct FakeChain {
+        /// The transactions of the account, in sequence number order.
+        committed: Vec<TransactionOnChainData>,
+        ledger_timestamp_secs: u64,
+        /// How the next submissions are handled, committing everything once exhausted.
+        submissions: VecDeque<Submission>,
+        /// The `(start, limit)` of each request for the account's transactions.
+        transaction_requests: Vec<(u64, u16)>,
+    }
+
+    impl FakeChain {
+        fn sequence_number(&self) -> u64 {
+            self.committed.len() as u64
+        }
+
+        fn commit(&mut self, txn: SignedTransaction) {
+            let info = TransactionInfo::new(
+                txn.committed_hash(),
+                HashValue::zero(),
+                HashValue::zero(),
+                None,
+                0, /* gas_used */
+                ExecutionStatus::Success,
+            );
+            self.committed.push(TransactionOnChainData {
+                version: 100 + self.committed.len() as u64,
+                transaction: Transaction::UserTransaction(txn),
+                info,
+                events: vec![],
+                accumulator_root_hash: HashValue::zero(),
+                changes: WriteSet::default(),
+            });
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeClient(Mutex<FakeChain>);
+
+    impl FakeClient {
+        fn with_submissions(submissions: Vec<Submission>) -> Self {
+            Self(Mutex::new(FakeChain {
+                submissions: submissions.into(),
+                ..Default::default()
+            }))
+        }
+
+        /// Commits transactions using the next sequence numbers of the account on behalf of
+        /// another client.
+        fn commit_from_other_client(&self, count: usize) {
+            let other = LocalAccount::generate(&mut rand::rngs::OsRng);
+            let mut chain = self.0.lock().unwrap();
+            for _ in 0..count {
+                other.set_sequence_number(chain.sequence_number());
+                chain.commit(transfer(
+                    &other,
+                    &TransactionFactory::new(ChainId::test()),
+                    1,
+                ));
+            }
+        }
+
+        fn transaction_requests(&self) -> Vec<(u64, u16)> {
+            self.0.lock().unwrap().transaction_requests.clone()
+        }
+    }
+
+    fn failure(
+        transaction_index: usize,
+        status_code: StatusCode,
+    ) -> TransactionsBatchSingleSubmissionFailure {
+        TransactionsBatchSingleSubmissionFailure {
+            error: AptosError::new_with_vm_status(
+                "Transaction rejected",
+                AptosErrorCode::VmError,
+                status_code,
+            ),
+            transaction_index,
+        }
+    }
+
+    #[async_trait]
+    impl SubmitterClient for FakeClient {
+        async fn submit_batch(
+            &self,
+            txns: &[SignedTransaction],
+        ) -> Result<Vec<TransactionsBatchSingleSubmissionFailure>> {
+            let mut chain = self.0.lock().unwrap();
+            let mut failures = vec![];
+            match chain.submissions.pop_front().unwrap_or(Submission::Commit) {
+                Submission::Commit => {
+                    chain.ledger_timestamp_secs = 0;
+                    for (index, txn) in txns.iter().enumerate() {
+                        let sequence_number = chain.sequence_number();
+                        if txn.sequence_number() == sequence_number {
+                            chain.commit(txn.clone());
+                        } else if txn.sequence_number() < sequence_number {
+                            failures.push(failure(index, StatusCode::SEQUENCE_NUMBER_TOO_OLD));
+                        } else {
+                            failures.push(failure(index, StatusCode::SEQUENCE_NUMBER_TOO_NEW));
+                        }
+                    }
+                },
+                Submission::Expire => {
+                    chain.ledger_timestamp_secs = txns
+                        .iter()
+                        .map(SignedTransaction::expiration_timestamp_secs)
+                        .max()
+                        .unwrap()
+                        + 1;
+                },
+                Submission::Reject(status_code) => failures.push(failure(0, status_code)),
+                Submission::InvalidIndex => {
+                    failures.push(failure(txns.len(), StatusCode::SEQUENCE_NUMBER_TOO_OLD))
+                },
+            }
+            Ok(failures)
+        }
+
+        async fn get_sequence_number(&self, _address: AccountAddress) -> Result<(u64, u64)> {
+            let chain = self.0.lock().unwrap();
+            Ok((chain.sequence_number(), chain.ledger_timestamp_secs))
+        }
+
+        async fn get_account_transactions(
+            &self,
+            _address: AccountAddress,
+            start: u64,
+            limit: u16,
+        ) -> Result<Vec<TransactionOnChainData>> {
+            let mut chain = self.0.lock().unwrap();
+            chain.transaction_requests.push((start, limit));
+            let start = (start as usize).min(chain.committed.len());
+            let end = (start + limit as usize).min(chain.committed.len());
+            Ok(chain.committed[start..end].to_vec())
+        }
+    }
+
+    fn transfer(
+        account: &LocalAccount,
+        transaction_factory: &TransactionFactory,
+        amount: u64,
+    ) -> SignedTransaction {
+        account.sign_with_transaction_builder(transaction_factory.payload(
+            aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, amount),
+        ))
+    }
+
+    fn payloads(count: u64) -> Vec<TransactionPayload> {
+        (0..count)
+            .map(|amount| aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, amount))
+            .collect()
+    }
+
+    fn options(max_in_flight: usize) -> SubmitterOptions {
+        SubmitterOptions {
+            max_in_flight,
+            poll_interval: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    async fn submit(
+        client: &FakeClient,
+        account: &LocalAccount,
+        options: SubmitterOptions,
+        num_payloads: u64,
+    ) -> Result<Vec<CommittedTransaction>> {
+        AccountSubmitter::new(client, account, TransactionFactory::new(ChainId::test()))
+            .with_options(options)
+            .submit_and_wait(payloads(num_payloads))
+            .await
+    }
+
+    fn sequence_numbers(committed: &[CommittedTransaction]) -> Vec<(usize, u64)> {
+        committed
+            .iter()
+            .map(|txn| (txn.index, txn.sequence_number))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_submit_in_batches() {
+        let client = FakeClient::default();
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        let committed = submit(&client, &account, options(2), 5).await.unwrap();
+        assert_eq!(sequence_numbers(&committed), vec![
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (3, 3),
+            (4, 4)
+        ]);
+        assert_eq!(account.sequence_number(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_submit_with_zero_max_in_flight() {
+        let client = FakeClient::default();
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        // The transactions are submitted one at a time.
+        let committed = submit(&client, &account, options(0), 3).await.unwrap();
+        assert_eq!(sequence_numbers(&committed), vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(client.transaction_requests(), vec![(0, 1), (1, 1), (2, 1)]);
+        assert_eq!(account.sequence_number(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resync_on_sequence_number_too_old() {
+        let client = FakeClient::default();
+        client.commit_from_other_client(2);
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        // The first two transactions are rejected, while the third one takes the next sequence
+        // number. The rejected ones are resubmitted after it.
+        let committed = submit(&client, &account, options(10), 3).await.unwrap();
+        assert_eq!(sequence_numbers(&committed), vec![(0, 3), (1, 4), (2, 2)]);
+        assert_eq!(account.sequence_number(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_resync_on_sequence_number_too_new() {
+        let client = FakeClient::default();
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+        account.set_sequence_number(10);
+
+        let committed = submit(&client, &account, options(10), 3).await.unwrap();
+        assert_eq!(sequence_numbers(&committed), vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(account.sequence_number(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_requeue_after_expiry() {
+        let client = FakeClient::with_submissions(vec![Submission::Commit, Submission::Expire]);
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        // The second batch expires, and is requeued ahead of the third one.
+        let committed = submit(&client, &account, options(2), 6).await.unwrap();
+        assert_eq!(sequence_numbers(&committed), vec![
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (3, 3),
+            (4, 4),
+            (5, 5)
+        ]);
+        assert_eq!(account.sequence_number(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_bail_after_max_resubmissions() {
+        let client = FakeClient::with_submissions(vec![
+            Submission::Expire,
+            Submission::Expire,
+            Submission::Expire,
+        ]);
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+        let options = SubmitterOptions {
+            max_resubmissions: 2,
+            ..options(10)
+        };
+
+        let err = submit(&client, &account, options, 3).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("was not committed after 2 resubmissions"));
+    }
+
+    #[tokio::test]
+    async fn test_get_committed_transactions_in_pages() {
+        let client = FakeClient::default();
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        let committed = submit(&client, &account, options(250), 250).await.unwrap();
+        assert_eq!(committed.len(), 250);
+        assert!(committed
+            .iter()
+            .enumerate()
+            .all(|(index, txn)| txn.index == index && txn.version == 100 + index as u64));
+        assert_eq!(client.transaction_requests(), vec![
+            (0, 100),
+            (100, 100),
+            (200, 50)
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_reset_sequence_number_on_rejection() {
+        let client = FakeClient::with_submissions(vec![
+            Submission::Commit,
+            Submission::Reject(StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE),
+        ]);
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        let err = submit(&client, &account, options(2), 4).await.unwrap_err();
+        assert!(err.to_string().contains("was rejected"));
+        assert_eq!(account.sequence_number(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reject_invalid_failure_index() {
+        let client = FakeClient::with_submissions(vec![Submission::InvalidIndex]);
+        let account = LocalAccount::generate(&mut rand::rngs::OsRng);
+
+        let err = submit(&client, &account, options(2), 2).await.unwrap_err();
+        assert!(err.to_string().contains("out of 2 submitted"));
+        assert_eq!(account.sequence_number(), 0);
+    }
+}
@@ -8,7 +8,9 @@
 //!
 //! This SDK provides all the necessary components for building on top of the Aptos Blockchain. Some of the important modules are:
 //!
+//! * `account_submitter` - Pipelines many transactions from one account, managing its sequence number
 //! * `crypto` - Types used for signing and verifying
+//! * `light_client` - Verifies ledger infos, state values and transaction infos returned by a fullnode
 //! * `move_types` - Includes types used when interacting with the Move VM
 //! * `rest_client` - The Aptos API Client, used for sending requests to the Aptos Blockchain.
 //! * `transaction_builder` - Includes helpers for constructing transactions
@@ -22,8 +24,12 @@
 
 pub use bcs;
 
+pub mod account_submitter;
+
 pub mod coin_client;
 
+pub mod light_client;
+
 pub mod crypto {
     pub use aptos_crypto::*;
 }
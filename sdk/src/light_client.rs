@@ -0,0 +1,635 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client that verifies the data returned by a fullnode instead of trusting it.
+//!
+//! Starting from a trusted waypoint, the client ratchets through the epoch changes, verifying
+//! the aggregate signatures of each epoch's validator set, up to the latest ledger info. State
+//! values and transaction infos can then be verified against the root hashes committed to by
+//! that ledger info, or by a later ledger info of the same epoch, as returned along with the
+//! proofs by the fullnode.
+
+use crate::{
+    crypto::hash::CryptoHash,
+    rest_client::Client as ApiClient,
+    types::{
+        epoch_change::EpochChangeProof,
+        ledger_info::LedgerInfoWithSignatures,
+        proof::{SparseMerkleProof, TransactionInfoWithProof},
+        state_proof::StateProof,
+        state_store::{state_key::StateKey, state_value::StateValue},
+        transaction::{TransactionInfo, Version},
+        trusted_state::{TrustedState, TrustedStateChange},
+        waypoint::Waypoint,
+    },
+};
+use anyhow::{bail, format_err, Context, Result};
+
+#[derive(Clone, Debug)]
+pub struct LightClient {
+    trusted_state: TrustedState,
+    /// The latest verified ledger info, if any has been verified yet.
+    ledger_info: Option<LedgerInfoWithSignatures>,
+}
+
+impl LightClient {
+    /// Creates a light client trusting the given epoch waypoint, e.g., the genesis waypoint.
+    pub fn new(waypoint: Waypoint) -> Self {
+        Self {
+            trusted_state: TrustedState::from_epoch_waypoint(waypoint),
+            ledger_info: None,
+        }
+    }
+
+    pub fn trusted_state(&self) -> &TrustedState {
+        &self.trusted_state
+    }
+
+    pub fn version(&self) -> Version {
+        self.trusted_state.version()
+    }
+
+    pub fn ledger_info(&self) -> Option<&LedgerInfoWithSignatures> {
+        self.ledger_info.as_ref()
+    }
+
+    /// Verifies the state proof against the trusted state, and ratchets the trusted state
+    /// forward to the newly verified ledger info. Returns whether the trusted state changed.
+    pub fn verify_and_ratchet(&mut self, state_proof: &StateProof) -> Result<bool> {
+        let latest_li = state_proof.latest_ledger_info_w_sigs();
+        match self.trusted_state.verify_and_ratchet(state_proof)? {
+            TrustedStateChange::Epoch {
+                new_state,
+                latest_epoch_change_li,
+            } => {
+                // If there are more epoch changes than the proof holds, we only got as far as
+                // the last included epoch change.
+                let verified_li = if new_state.version() == latest_li.ledger_info().version() {
+                    latest_li
+                } else {
+                    latest_epoch_change_li
+                };
+                self.ledger_info = Some(verified_li.clone());
+                self.trusted_state = new_state;
+                Ok(true)
+            },
+            TrustedStateChange::Version { new_state } => {
+                self.ledger_info = Some(latest_li.clone());
+                self.trusted_state = new_state;
+                Ok(true)
+            },
+            TrustedStateChange::NoChange => {
+                // The latest ledger info matches the trusted waypoint.
+                if self.ledger_info.is_none() {
+                    self.ledger_info = Some(latest_li.clone());
+                }
+                Ok(false)
+            },
+        }
+    }
+
+    /// Fetches state proofs from the fullnode until the trusted state has caught up with the
+    /// latest ledger info, and returns the verified ledger info.
+    pub async fn sync(&mut self, api_client: &ApiClient) -> Result<&LedgerInfoWithSignatures> {
+        loop {
+            let state_proof = api_client
+                .get_state_proof(self.version())
+                .await
+                .context("Failed to get state proof")?
+                .into_inner();
+            let changed = self
+                .verify_and_ratchet(&state_proof)
+                .context("Failed to verify state proof")?;
+            if !state_proof.epoch_changes().more {
+                break;
+            }
+            if !changed {
+                bail!("State proof indicates more epoch changes, but made no progress");
+            }
+        }
+        self.ledger_info
+            .as_ref()
+            .ok_or_else(|| format_err!("No ledger info verified"))
+    }
+
+    /// Verifies a ledger info signed by the validators of the trusted epoch, and ratchets the
+    /// trusted state forward to it, so that proofs relative to it can be verified. A ledger
+    /// info of a later epoch can only be verified after syncing to that epoch.
+    pub fn verify_ledger_info(&mut self, ledger_info: &LedgerInfoWithSignatures) -> Result<()> {
+        let state_proof =
+            StateProof::new(ledger_info.clone(), EpochChangeProof::new(vec![], false));
+        self.verify_and_ratchet(&state_proof)
+            .context("Failed to verify ledger info against the trusted state, try syncing first")?;
+        Ok(())
+    }
+
+    /// Fetches the state value of the state key, or the proof of its absence, at the latest
+    /// signed ledger info, and verifies it.
+    pub async fn get_verified_state_value(
+        &mut self,
+        api_client: &ApiClient,
+        state_key: &StateKey,
+    ) -> Result<Option<StateValue>> {
+        let response = api_client
+            .get_state_value_with_proof(state_key, None)
+            .await
+            .context("Failed to get state value with proof")?
+            .into_inner();
+        self.verify_ledger_info(&response.ledger_info_with_signatures)?;
+        self.verify_state_value(
+            response.version,
+            state_key,
+            response.state_value.as_ref(),
+            &response.proof,
+            &response.transaction_info_with_proof,
+        )?;
+        Ok(response.state_value)
+    }
+
+    /// Fetches the transaction info at `version`, along with its proof against the latest
+    /// signed ledger info, and verifies it.
+    pub async fn get_verified_transaction_info(
+        &mut self,
+        api_client: &ApiClient,
+        version: Version,
+    ) -> Result<TransactionInfo> {
+        let response = api_client
+            .get_transaction_info_with_proof(version)
+            .await
+            .context("Failed to get transaction info with proof")?
+            .into_inner();
+        self.verify_ledger_info(&response.ledger_info_with_signatures)?;
+        self.verify_transaction_info(version, &response.transaction_info_with_proof)?;
+        Ok(response.transaction_info_with_proof.transaction_info)
+    }
+
+    /// Verifies that the transaction info at `version` is part of the ledger committed to by
+    /// the latest verified ledger info.
+    pub fn verify_transaction_info(
+        &self,
+        version: Version,
+        txn_info_with_proof: &TransactionInfoWithProof,
+    ) -> Result<()> {
+        txn_info_with_proof.verify(self.verified_ledger_info()?.ledger_info(), version)
+    }
+
+    /// Verifies the value (or the absence of it, if `None`) of the state key at `version`.
+    ///
+    /// The state root is taken from the transaction info at `version`, which must be a state
+    /// checkpoint, e.g., the version of a ledger info.
+    pub fn verify_state_value(
+        &self,
+        version: Version,
+        state_key: &StateKey,
+        state_value: Option<&StateValue>,
+        proof: &SparseMerkleProof,
+        txn_info_with_proof: &TransactionInfoWithProof,
+    ) -> Result<()> {
+        self.verify_transaction_info(version, txn_info_with_proof)?;
+        let state_root = txn_info_with_proof
+            .transaction_info()
+            .state_checkpoint_hash()
+            .ok_or_else(|| format_err!("Version {} is not a state checkpoint", version))?;
+        proof.verify(state_root, state_key.hash(), state_value)
+    }
+
+    fn verified_ledger_info(&self) -> Result<&LedgerInfoWithSignatures> {
+        self.ledger_info
+            .as_ref()
+            .ok_or_else(|| format_err!("No ledger info verified yet, sync first"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::HashValue,
+        types::{
+            aggregate_signature::{AggregateSignature, PartialSignatures},
+            block_info::BlockInfo,
+            epoch_state::EpochState,
+            ledger_info::LedgerInfo,
+            proof::{
+                accumulator::InMemoryTransactionAccumulator, SparseMerkleInternalNode,
+                SparseMerkleLeafNode, TransactionAccumulatorProof,
+            },
+            transaction::ExecutionStatus,
+            validator_signer::ValidatorSigner,
+            validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+        },
+    };
+    use std::sync::Arc;
+
+    /// The validators of an epoch, all with the same voting power.
+    struct Validators {
+        signers: Vec<ValidatorSigner>,
+        verifier: Arc<ValidatorVerifier>,
+    }
+
+    impl Validators {
+        fn new(seed: u8) -> Self {
+            let signers = (0..4)
+                .map(|i| ValidatorSigner::random([seed * 4 + i; 32]))
+                .collect::<Vec<_>>();
+            let verifier = Arc::new(ValidatorVerifier::new(
+                signers
+                    .iter()
+                    .map(|signer| {
+                        ValidatorConsensusInfo::new(signer.author(), signer.public_key(), 1)
+                    })
+                    .collect(),
+            ));
+            Self { signers, verifier }
+        }
+
+        fn epoch_state(&self, epoch: u64) -> EpochState {
+            EpochState {
+                epoch,
+                verifier: self.verifier.clone(),
+            }
+        }
+
+        fn sign(&self, ledger_info: LedgerInfo) -> LedgerInfoWithSignatures {
+            let partial_sigs = PartialSignatures::new(
+                self.signers
+                    .iter()
+                    .map(|signer| (signer.author(), signer.sign(&ledger_info).unwrap()))
+                    .collect(),
+            );
+            let signature = self
+                .verifier
+                .aggregate_signatures(partial_sigs.signatures_iter())
+                .unwrap();
+            LedgerInfoWithSignatures::new(ledger_info, signature)
+        }
+    }
+
+    fn ledger_info(
+        epoch: u64,
+        version: Version,
+        accumulator_root_hash: HashValue,
+        next_epoch_state: Option<EpochState>,
+    ) -> LedgerInfo {
+        LedgerInfo::new(
+            BlockInfo::new(
+                epoch,
+                0, /* round */
+                HashValue::zero(),
+                accumulator_root_hash,
+                version,
+                0, /* timestamp_usecs */
+                next_epoch_state,
+            ),
+            HashValue::zero(),
+        )
+    }
+
+    /// A chain starting with a genesis ledger info that hands over to the validators of epoch
+    /// 1, which in turn hand over to the validators of epoch 2 at version 10.
+    struct TestChain {
+        epoch_1: Validators,
+        epoch_2: Validators,
+        genesis: LedgerInfoWithSignatures,
+        epoch_1_change: LedgerInfoWithSignatures,
+    }
+
+    impl TestChain {
+        fn new() -> Self {
+            let epoch_1 = Validators::new(1);
+            let epoch_2 = Validators::new(2);
+            let genesis = LedgerInfoWithSignatures::new(
+                ledger_info(0, 0, HashValue::zero(), Some(epoch_1.epoch_state(1))),
+                AggregateSignature::empty(),
+            );
+            let epoch_1_change = epoch_1.sign(ledger_info(
+                1,
+                10,
+                HashValue::zero(),
+                Some(epoch_2.epoch_state(2)),
+            ));
+            Self {
+                epoch_1,
+                epoch_2,
+                genesis,
+                epoch_1_change,
+            }
+        }
+
+        fn light_client(&self) -> LightClient {
+            LightClient::new(Waypoint::new_epoch_boundary(self.genesis.ledger_info()).unwrap())
+        }
+
+        /// A light client synced through both epoch changes to the given ledger info of epoch 2.
+        fn synced_light_client(&self, latest_li: LedgerInfoWithSignatures) -> LightClient {
+            let mut client = self.light_client();
+            client
+                .verify_and_ratchet(&StateProof::new(
+                    latest_li,
+                    EpochChangeProof::new(
+                        vec![self.genesis.clone(), self.epoch_1_change.clone()],
+                        false,
+                    ),
+                ))
+                .unwrap();
+            client
+        }
+    }
+
+    #[test]
+    fn test_ratchet_across_epoch_changes() {
+        let chain = TestChain::new();
+        let latest_li = chain
+            .epoch_2
+            .sign(ledger_info(2, 15, HashValue::zero(), None));
+        let mut client = chain.light_client();
+
+        // The first proof only holds the genesis epoch change, so the client only gets as far
+        // as epoch 1.
+        assert!(client
+            .verify_and_ratchet(&StateProof::new(
+                latest_li.clone(),
+                EpochChangeProof::new(vec![chain.genesis.clone()], true),
+            ))
+            .unwrap());
+        assert_eq!(client.version(), 0);
+        assert_eq!(client.ledger_info(), Some(&chain.genesis));
+        assert!(client.verify_ledger_info(&latest_li).is_err());
+
+        // The next proof takes it into epoch 2, up to the latest ledger info.
+        assert!(client
+            .verify_and_ratchet(&StateProof::new(
+                latest_li.clone(),
+                EpochChangeProof::new(vec![chain.epoch_1_change.clone()], false),
+            ))
+            .unwrap());
+        assert_eq!(client.version(), 15);
+        assert_eq!(client.ledger_info(), Some(&latest_li));
+
+        // Ledger infos of the same epoch are verified without epoch changes.
+        let newer_li = chain
+            .epoch_2
+            .sign(ledger_info(2, 20, HashValue::zero(), None));
+        client.verify_ledger_info(&newer_li).unwrap();
+        assert_eq!(client.version(), 20);
+        assert_eq!(client.ledger_info(), Some(&newer_li));
+
+        // Verifying the same ledger info again doesn't change anything, while older ones are
+        // rejected as stale.
+        assert!(!client
+            .verify_and_ratchet(&StateProof::new(
+                newer_li,
+                EpochChangeProof::new(vec![], false)
+            ))
+            .unwrap());
+        assert!(client.verify_ledger_info(&latest_li).is_err());
+        assert_eq!(client.version(), 20);
+    }
+
+    #[test]
+    fn test_reject_bad_signatures() {
+        let chain = TestChain::new();
+
+        // An epoch change signed by the validators of the next epoch instead of the current one.
+        let forged_epoch_change = chain
+            .epoch_2
+            .sign(chain.epoch_1_change.ledger_info().clone());
+        let latest_li = chain
+            .epoch_2
+            .sign(ledger_info(2, 15, HashValue::zero(), None));
+        let mut client = chain.light_client();
+        assert!(client
+            .verify_and_ratchet(&StateProof::new(
+                latest_li.clone(),
+                EpochChangeProof::new(vec![chain.genesis.clone(), forged_epoch_change], false),
+            ))
+            .is_err());
+        assert_eq!(client.version(), 0);
+        assert!(client.ledger_info().is_none());
+
+        let mut client = chain.synced_light_client(latest_li);
+
+        // A ledger info signed by the validators of the previous epoch.
+        let stale_validators_li = chain
+            .epoch_1
+            .sign(ledger_info(2, 20, HashValue::zero(), None));
+        assert!(client.verify_ledger_info(&stale_validators_li).is_err());
+
+        // A ledger info tampered with after being signed.
+        let signed_li = chain
+            .epoch_2
+            .sign(ledger_info(2, 20, HashValue::zero(), None));
+        let tampered_li = LedgerInfoWithSignatures::new(
+            ledger_info(2, 20, HashValue::random(), None),
+            signed_li.signatures().clone(),
+        );
+        assert!(client.verify_ledger_info(&tampered_li).is_err());
+
+        // A ledger info without enough signatures.
+        let unsigned_li = LedgerInfoWithSignatures::new(
+            ledger_info(2, 20, HashValue::zero(), None),
+            AggregateSignature::empty(),
+        );
+        assert!(client.verify_ledger_info(&unsigned_li).is_err());
+
+        assert_eq!(client.version(), 15);
+        client.verify_ledger_info(&signed_li).unwrap();
+        assert_eq!(client.version(), 20);
+    }
+
+    /// The state at version 15 consists of a single state value and a sibling subtree, and the
+    /// accumulator of a ledger info at that version has its transaction info as the last leaf.
+    struct StateFixture {
+        state_key: StateKey,
+        state_value: StateValue,
+        proof: SparseMerkleProof,
+        txn_info_with_proof: TransactionInfoWithProof,
+        ledger_info: LedgerInfo,
+    }
+
+    impl StateFixture {
+        const VERSION: Version = 15;
+
+        fn new() -> Self {
+            let state_key = StateKey::raw(b"light_client_test");
+            let state_value = StateValue::from(b"value".to_vec());
+            let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+            let sibling = HashValue::random();
+            let state_root = if state_key.hash().bit(0) {
+                SparseMerkleInternalNode::new(sibling, leaf.hash()).hash()
+            } else {
+                SparseMerkleInternalNode::new(leaf.hash(), sibling).hash()
+            };
+            let proof = SparseMerkleProof::new(Some(leaf), vec![sibling]);
+
+            let txn_info = TransactionInfo::new(
+                HashValue::random(),
+                HashValue::random(),
+                HashValue::random(),
+                Some(state_root),
+                0, /* gas_used */
+                ExecutionStatus::Success,
+            );
+            let mut leaves = (0..Self::VERSION)
+                .map(|_| HashValue::random())
+                .collect::<Vec<_>>();
+            leaves.push(txn_info.hash());
+            let root_hash = |leaves: &[HashValue]| {
+                InMemoryTransactionAccumulator::from_leaves(leaves).root_hash()
+            };
+            // The siblings of the last of the 16 leaves, from the bottom to the root.
+            let txn_info_with_proof = TransactionInfoWithProof::new(
+                TransactionAccumulatorProof::new(vec![
+                    leaves[14],
+                    root_hash(&leaves[12..14]),
+                    root_hash(&leaves[8..12]),
+                    root_hash(&leaves[0..8]),
+                ]),
+                txn_info,
+            );
+
+            Self {
+                state_key,
+                state_value,
+                proof,
+                txn_info_with_proof,
+                ledger_info: ledger_info(2, Self::VERSION, root_hash(&leaves), None),
+            }
+        }
+
+        fn verify(
+            &self,
+            client: &LightClient,
+            state_value: Option<&StateValue>,
+            proof: &SparseMerkleProof,
+            txn_info_with_proof: &TransactionInfoWithProof,
+        ) -> Result<()> {
+            client.verify_state_value(
+                Self::VERSION,
+                &self.state_key,
+                state_value,
+                proof,
+                txn_info_with_proof,
+            )
+        }
+    }
+
+    #[test]
+    fn test_verify_state_value() {
+        let chain = TestChain::new();
+        let fixture = StateFixture::new();
+        let client = chain.synced_light_client(chain.epoch_2.sign(fixture.ledger_info.clone()));
+
+        fixture
+            .verify(
+                &client,
+                Some(&fixture.state_value),
+                &fixture.proof,
+                &fixture.txn_info_with_proof,
+            )
+            .unwrap();
+        client
+            .verify_transaction_info(StateFixture::VERSION, &fixture.txn_info_with_proof)
+            .unwrap();
+
+        // A different value, or claiming the key doesn't exist.
+        let other_value = StateValue::from(b"other".to_vec());
+        assert!(fixture
+            .verify(
+                &client,
+                Some(&other_value),
+                &fixture.proof,
+                &fixture.txn_info_with_proof
+            )
+            .is_err());
+        assert!(fixture
+            .verify(&client, None, &fixture.proof, &fixture.txn_info_with_proof)
+            .is_err());
+
+        // Tampered sparse Merkle proofs.
+        let tampered_sibling =
+            SparseMerkleProof::new(fixture.proof.leaf(), vec![HashValue::random()]);
+        let extra_sibling = SparseMerkleProof::new(fixture.proof.leaf(), vec![
+            HashValue::random(),
+            fixture.proof.siblings()[0],
+        ]);
+        let tampered_leaf = SparseMerkleProof::new(
+            Some(SparseMerkleLeafNode::new(
+                fixture.state_key.hash(),
+                other_value.hash(),
+            )),
+            fixture.proof.siblings().to_vec(),
+        );
+        for proof in [tampered_sibling, extra_sibling, tampered_leaf] {
+            assert!(fixture
+                .verify(
+                    &client,
+                    Some(&fixture.state_value),
+                    &proof,
+                    &fixture.txn_info_with_proof
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_reject_tampered_accumulator_proof() {
+        let chain = TestChain::new();
+        let fixture = StateFixture::new();
+        let client = chain.synced_light_client(chain.epoch_2.sign(fixture.ledger_info.clone()));
+        let txn_info = fixture.txn_info_with_proof.transaction_info().clone();
+        let siblings = fixture
+            .txn_info_with_proof
+            .ledger_info_to_transaction_info_proof()
+            .siblings()
+            .to_vec();
+
+        // A tampered sibling, or a transaction info that isn't in the accumulator.
+        let tampered_sibling = TransactionInfoWithProof::new(
+            TransactionAccumulatorProof::new(vec![HashValue::random()]),
+            txn_info.clone(),
+        );
+        let other_txn_info = TransactionInfoWithProof::new(
+            TransactionAccumulatorProof::new(siblings.clone()),
+            TransactionInfo::new(
+                HashValue::random(),
+                HashValue::random(),
+                HashValue::random(),
+                txn_info.state_checkpoint_hash(),
+                0, /* gas_used */
+                ExecutionStatus::Success,
+            ),
+        );
+        for txn_info_with_proof in [tampered_sibling, other_txn_info] {
+            assert!(client
+                .verify_transaction_info(StateFixture::VERSION, &txn_info_with_proof)
+                .is_err());
+            assert!(fixture
+                .verify(
+                    &client,
+                    Some(&fixture.state_value),
+                    &fixture.proof,
+                    &txn_info_with_proof
+                )
+                .is_err());
+        }
+
+        // The right proof at the wrong version, and a version past the verified ledger info.
+        assert!(client
+            .verify_transaction_info(StateFixture::VERSION - 1, &fixture.txn_info_with_proof)
+            .is_err());
+        assert!(client
+            .verify_transaction_info(StateFixture::VERSION + 1, &fixture.txn_info_with_proof)
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_before_sync() {
+        let chain = TestChain::new();
+        let fixture = StateFixture::new();
+        let client = chain.light_client();
+        assert!(client
+            .verify_transaction_info(StateFixture::VERSION, &fixture.txn_info_with_proof)
+            .is_err());
+    }
+}
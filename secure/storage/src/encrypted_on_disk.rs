@@ -0,0 +1,453 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CryptoKVStorage, Error, GetResponse, KVStorage};
+use aptos_temppath::TempPath;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use ring::{
+    aead::{self, Aad, LessSafeKey, UnboundKey},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{Read, Write},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The current version of the encrypted file format
+const FORMAT_VERSION: u32 = 1;
+/// The number of PBKDF2 iterations used to derive a key from a passphrase
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const AES_KEY_SIZE: usize = 32;
+const AES_NONCE_SIZE: usize = 12;
+const SALT_SIZE: usize = 32;
+/// Domain separators used as associated data, so that ciphertexts can't be swapped
+const DATA_KEY_AAD: &[u8] = b"APTOS::EncryptedOnDiskStorage::DataKey";
+const DATA_AAD: &[u8] = b"APTOS::EncryptedOnDiskStorage::Data";
+
+/// The source of the key encryption key (KEK), which protects the data stored on disk.
+#[derive(Clone)]
+pub enum EncryptionKeySource {
+    /// A passphrase, from which the KEK is derived using PBKDF2
+    Passphrase(String),
+    /// A raw 32-byte KEK (e.g., provisioned by the operator or an external KMS)
+    KeyEncryptionKey(Vec<u8>),
+}
+
+/// EncryptedOnDiskStorage is a key value store that is persisted to the local filesystem (like
+/// OnDiskStorage), but encrypted at rest using AES-256-GCM. The data is encrypted with a random
+/// data key, which is in turn encrypted (wrapped) with the key encryption key (see
+/// `EncryptionKeySource`). Any modification to the file on disk is detected when it is read,
+/// as decryption fails. Note: like OnDiskStorage, this offers no permission checks and is
+/// intended for single threads (or must be wrapped by a Arc<RwLock<>>).
+///
+/// Threat model: the storage protects the confidentiality and integrity of the data against
+/// an attacker with access to the disk (but not to the key encryption key). Every write
+/// increments a counter that is bound to the ciphertext, and the storage rejects any file
+/// older than the latest one it has read or written. This detects rollbacks (e.g., to an
+/// older copy of the safety data) while the node is running. A rollback performed while the
+/// node is stopped can't be detected without trusted external state, and must be prevented
+/// by the operator (e.g., by restricting access to the disk and its backups).
+pub struct EncryptedOnDiskStorage {
+    file_path: PathBuf,
+    temp_path: TempPath,
+    time_service: TimeService,
+    key_header: KeyHeader,
+    data_key: LessSafeKey,
+    /// The counter of the latest file read or written by the storage
+    counter: AtomicU64,
+}
+
+/// The encrypted file stored on disk
+#[derive(Deserialize, Serialize)]
+struct EncryptedFile {
+    #[serde(flatten)]
+    key_header: KeyHeader,
+    /// Incremented on every write, and bound to the data (to detect rollbacks)
+    counter: u64,
+    data: Ciphertext,
+}
+
+/// Describes how to derive the key encryption key, and holds the wrapped data key
+#[derive(Clone, Deserialize, Serialize)]
+struct KeyHeader {
+    version: u32,
+    kdf: KeyDerivation,
+    wrapped_data_key: Ciphertext,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+enum KeyDerivation {
+    /// The KEK is provided directly
+    None,
+    /// The KEK is derived from a passphrase using PBKDF2-HMAC-SHA256
+    Pbkdf2HmacSha256 {
+        #[serde(
+            serialize_with = "crate::to_base64",
+            deserialize_with = "crate::from_base64"
+        )]
+        salt: Vec<u8>,
+        iterations: u32,
+    },
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Ciphertext {
+    #[serde(
+        serialize_with = "crate::to_base64",
+        deserialize_with = "crate::from_base64"
+    )]
+    nonce: Vec<u8>,
+    #[serde(
+        serialize_with = "crate::to_base64",
+        deserialize_with = "crate::from_base64"
+    )]
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedOnDiskStorage {
+    /// Opens (or creates) the encrypted storage at the given path
+    pub fn new(file_path: PathBuf, key_source: EncryptionKeySource) -> Result<Self, Error> {
+        Self::new_with_time_service(file_path, key_source, None, TimeService::real())
+    }
+
+    /// Opens (or creates) the encrypted storage at the given path. If the storage can't be
+    /// decrypted using the current key source, the previous key source is used, and the
+    /// storage is then re-encrypted using the current key source. This allows operators to
+    /// rotate the key encryption key by simply updating the config.
+    pub fn new_with_previous_key_source(
+        file_path: PathBuf,
+        key_source: EncryptionKeySource,
+        previous_key_source: EncryptionKeySource,
+    ) -> Result<Self, Error> {
+        Self::new_with_time_service(
+            file_path,
+            key_source,
+            Some(previous_key_source),
+            TimeService::real(),
+        )
+    }
+
+    fn new_with_time_service(
+        file_path: PathBuf,
+        key_source: EncryptionKeySource,
+        previous_key_source: Option<EncryptionKeySource>,
+        time_service: TimeService,
+    ) -> Result<Self, Error> {
+        // The parent will be one when only a filename is supplied. Therefore use the current
+        // working directory provided by PathBuf::new().
+        let file_dir = file_path
+            .parent()
+            .map_or(PathBuf::new(), |p| p.to_path_buf());
+        let temp_path = TempPath::new_with_temp_dir(file_dir);
+
+        // If the storage doesn't exist yet, create it (with a new data key)
+        let encrypted_file = read_encrypted_file(&file_path)?;
+        let Some(encrypted_file) = encrypted_file else {
+            let (key_header, data_key) = new_data_key(&key_source)?;
+            let storage = Self {
+                file_path,
+                temp_path,
+                time_service,
+                key_header,
+                data_key,
+                counter: AtomicU64::new(0),
+            };
+            storage.write(&HashMap::new())?;
+            return Ok(storage);
+        };
+
+        // Otherwise, unwrap the data key (falling back to the previous key source)
+        let key_header = encrypted_file.key_header;
+        let counter = AtomicU64::new(encrypted_file.counter);
+        match (
+            unwrap_data_key(&key_header, &key_source),
+            previous_key_source,
+        ) {
+            (Ok(data_key), _) => {
+                let storage = Self {
+                    file_path,
+                    temp_path,
+                    time_service,
+                    key_header,
+                    data_key,
+                    counter,
+                };
+                storage.read()?; // Verify the data (and its counter) before using the storage
+                Ok(storage)
+            },
+            (Err(_), Some(previous_key_source)) => {
+                let data_key = unwrap_data_key(&key_header, &previous_key_source)?;
+                let mut storage = Self {
+                    file_path,
+                    temp_path,
+                    time_service,
+                    key_header,
+                    data_key,
+                    counter,
+                };
+                storage.rotate_encryption_key(key_source)?;
+                Ok(storage)
+            },
+            (Err(error), None) => Err(error),
+        }
+    }
+
+    /// Rotates the encryption keys: a new data key is generated (and wrapped using the
+    /// given key encryption key), and all data is re-encrypted.
+    pub fn rotate_encryption_key(&mut self, key_source: EncryptionKeySource) -> Result<(), Error> {
+        let data = self.read()?;
+        let (key_header, data_key) = new_data_key(&key_source)?;
+        self.key_header = key_header;
+        self.data_key = data_key;
+        self.write(&data)
+    }
+
+    fn read(&self) -> Result<HashMap<String, Value>, Error> {
+        let encrypted_file = read_encrypted_file(&self.file_path)?.ok_or_else(|| {
+            Error::DecryptionError(format!("Storage file is empty: {:?}", self.file_path))
+        })?;
+
+        // Note: the associated data binds the ciphertext to the expected key header and counter
+        let contents = open(
+            &self.data_key,
+            &encrypted_file.data,
+            &data_aad(&self.key_header, encrypted_file.counter)?,
+        )?;
+
+        // Reject files older than the latest one seen by the storage
+        let latest_counter = self.counter.load(Ordering::SeqCst);
+        if encrypted_file.counter < latest_counter {
+            return Err(Error::DecryptionError(format!(
+                "The storage was rolled back! File counter: {}, latest counter: {}",
+                encrypted_file.counter, latest_counter
+            )));
+        }
+        self.counter.store(encrypted_file.counter, Ordering::SeqCst);
+
+        let data = serde_json::from_slice(&contents)?;
+        Ok(data)
+    }
+
+    fn write(&self, data: &HashMap<String, Value>) -> Result<(), Error> {
+        let counter = self
+            .counter
+            .load(Ordering::SeqCst)
+            .checked_add(1)
+            .ok_or_else(|| Error::InternalError("The storage counter overflowed!".into()))?;
+        let contents = serde_json::to_vec(data)?;
+        let encrypted_file = EncryptedFile {
+            key_header: self.key_header.clone(),
+            counter,
+            data: seal(
+                &self.data_key,
+                contents,
+                &data_aad(&self.key_header, counter)?,
+            )?,
+        };
+        let mut file = File::create(self.temp_path.path())?;
+        file.write_all(&serde_json::to_vec(&encrypted_file)?)?;
+        file.sync_all()?;
+        fs::rename(&self.temp_path, &self.file_path)?;
+        self.counter.store(counter, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl KVStorage for EncryptedOnDiskStorage {
+    fn available(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get<V: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<V>, Error> {
+        let mut data = self.read()?;
+        data.remove(key)
+            .ok_or_else(|| Error::KeyNotSet(key.to_string()))
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.into()))
+    }
+
+    fn set<V: Serialize>(&mut self, key: &str, value: V) -> Result<(), Error> {
+        let now = self.time_service.now_secs();
+        let mut data = self.read()?;
+        data.insert(
+            key.to_string(),
+            serde_json::to_value(&GetResponse::new(value, now))?,
+        );
+        self.write(&data)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        self.write(&HashMap::new())
+    }
+}
+
+impl CryptoKVStorage for EncryptedOnDiskStorage {}
+
+/// Reads the encrypted file at the given path (if it exists and is non-empty)
+fn read_encrypted_file(file_path: &Path) -> Result<Option<EncryptedFile>, Error> {
+    if !file_path.exists() {
+        return Ok(None);
+    }
+
+    let mut file = File::open(file_path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    if contents.is_empty() {
+        return Ok(None);
+    }
+
+    let encrypted_file: EncryptedFile = serde_json::from_str(&contents)?;
+    if encrypted_file.key_header.version != FORMAT_VERSION {
+        return Err(Error::DecryptionError(format!(
+            "Unsupported storage format version: {}",
+            encrypted_file.key_header.version
+        )));
+    }
+    Ok(Some(encrypted_file))
+}
+
+/// Generates a new data key, and wraps it using the given key source
+fn new_data_key(key_source: &EncryptionKeySource) -> Result<(KeyHeader, LessSafeKey), Error> {
+    let kdf = match key_source {
+        EncryptionKeySource::Passphrase(_) => KeyDerivation::Pbkdf2HmacSha256 {
+            salt: random_bytes(SALT_SIZE)?,
+            iterations: PBKDF2_ITERATIONS,
+        },
+        EncryptionKeySource::KeyEncryptionKey(_) => KeyDerivation::None,
+    };
+    let key_encryption_key = derive_key_encryption_key(key_source, &kdf)?;
+
+    let data_key_bytes = random_bytes(AES_KEY_SIZE)?;
+    let wrapped_data_key = seal(
+        &key_encryption_key,
+        data_key_bytes.clone(),
+        &data_key_aad(&kdf)?,
+    )?;
+    let key_header = KeyHeader {
+        version: FORMAT_VERSION,
+        kdf,
+        wrapped_data_key,
+    };
+    Ok((key_header, aes_key(&data_key_bytes)?))
+}
+
+/// Unwraps the data key in the header using the given key source
+fn unwrap_data_key(
+    key_header: &KeyHeader,
+    key_source: &EncryptionKeySource,
+) -> Result<LessSafeKey, Error> {
+    let key_encryption_key = derive_key_encryption_key(key_source, &key_header.kdf)?;
+    let data_key_bytes = open(
+        &key_encryption_key,
+        &key_header.wrapped_data_key,
+        &data_key_aad(&key_header.kdf)?,
+    )?;
+    aes_key(&data_key_bytes)
+}
+
+/// Derives the key encryption key from the key source
+fn derive_key_encryption_key(
+    key_source: &EncryptionKeySource,
+    kdf: &KeyDerivation,
+) -> Result<LessSafeKey, Error> {
+    match (key_source, kdf) {
+        (EncryptionKeySource::KeyEncryptionKey(key), KeyDerivation::None) => aes_key(key),
+        (
+            EncryptionKeySource::Passphrase(passphrase),
+            KeyDerivation::Pbkdf2HmacSha256 { salt, iterations },
+        ) => {
+            let iterations = NonZeroU32::new(*iterations).ok_or_else(|| {
+                Error::DecryptionError("The number of PBKDF2 iterations must be non-zero!".into())
+            })?;
+            let mut key = [0u8; AES_KEY_SIZE];
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                iterations,
+                salt,
+                passphrase.as_bytes(),
+                &mut key,
+            );
+            aes_key(&key)
+        },
+        _ => Err(Error::DecryptionError(
+            "The key source does not match the key derivation of the storage!".into(),
+        )),
+    }
+}
+
+/// The associated data for the wrapped data key (i.e., the key derivation parameters)
+fn data_key_aad(kdf: &KeyDerivation) -> Result<Vec<u8>, Error> {
+    let mut aad = DATA_KEY_AAD.to_vec();
+    aad.extend(serde_json::to_vec(kdf)?);
+    Ok(aad)
+}
+
+/// The associated data for the encrypted data (i.e., the entire key header and the counter)
+fn data_aad(key_header: &KeyHeader, counter: u64) -> Result<Vec<u8>, Error> {
+    let mut aad = DATA_AAD.to_vec();
+    aad.extend(serde_json::to_vec(key_header)?);
+    aad.extend(counter.to_le_bytes());
+    Ok(aad)
+}
+
+fn aes_key(key: &[u8]) -> Result<LessSafeKey, Error> {
+    UnboundKey::new(&aead::AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| {
+            Error::InternalError(format!(
+                "Invalid encryption key length! Expected {} bytes, got {}",
+                AES_KEY_SIZE,
+                key.len()
+            ))
+        })
+}
+
+/// Encrypts the plaintext using a random nonce
+fn seal(key: &LessSafeKey, mut plaintext: Vec<u8>, aad: &[u8]) -> Result<Ciphertext, Error> {
+    let nonce = random_bytes(AES_NONCE_SIZE)?;
+    key.seal_in_place_append_tag(
+        aead::Nonce::try_assume_unique_for_key(&nonce)
+            .map_err(|_| Error::InternalError("Invalid nonce length!".into()))?,
+        Aad::from(aad),
+        &mut plaintext,
+    )
+    .map_err(|_| Error::InternalError("Failed to encrypt the data!".into()))?;
+    Ok(Ciphertext {
+        nonce,
+        ciphertext: plaintext,
+    })
+}
+
+/// Decrypts the ciphertext, and verifies its integrity
+fn open(key: &LessSafeKey, ciphertext: &Ciphertext, aad: &[u8]) -> Result<Vec<u8>, Error> {
+    let nonce = aead::Nonce::try_assume_unique_for_key(&ciphertext.nonce)
+        .map_err(|_| Error::DecryptionError("Invalid nonce length!".into()))?;
+    let mut in_out = ciphertext.ciphertext.clone();
+    let plaintext_len = key
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| {
+            Error::DecryptionError(
+                "Failed to decrypt the data! The key is incorrect, or the data was tampered with."
+                    .into(),
+            )
+        })?
+        .len();
+    in_out.truncate(plaintext_len);
+    Ok(in_out)
+}
+
+fn random_bytes(length: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0u8; length];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| Error::EntropyError("Failed to generate random bytes!".into()))?;
+    Ok(bytes)
+}
@@ -8,6 +8,8 @@ use thiserror::Error;
 
 #[derive(Debug, Deserialize, Error, PartialEq, Eq, Serialize)]
 pub enum Error {
+    #[error("Decryption error: {0}")]
+    DecryptionError(String),
     #[error("Entropy error: {0}")]
     EntropyError(String),
     #[error("Internal error: {0}")]
@@ -6,6 +6,7 @@
 
 mod crypto_kv_storage;
 mod crypto_storage;
+mod encrypted_on_disk;
 mod error;
 mod in_memory;
 mod kv_storage;
@@ -18,6 +19,7 @@ mod vault;
 pub use crate::{
     crypto_kv_storage::CryptoKVStorage,
     crypto_storage::{CryptoStorage, PublicKeyResponse},
+    encrypted_on_disk::{EncryptedOnDiskStorage, EncryptionKeySource},
     error::Error,
     in_memory::InMemoryStorage,
     kv_storage::{GetResponse, KVStorage},
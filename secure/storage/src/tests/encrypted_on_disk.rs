@@ -0,0 +1,145 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::suite, EncryptedOnDiskStorage, EncryptionKeySource, Error, KVStorage, Storage};
+use aptos_temppath::TempPath;
+use std::fs;
+
+const U64_KEY: &str = "U64_Key";
+
+#[test]
+fn encrypted_on_disk() {
+    let path_buf = TempPath::new().path().to_path_buf();
+    let storage = EncryptedOnDiskStorage::new(path_buf, passphrase("passphrase")).unwrap();
+    let mut storage = Storage::from(storage);
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn encrypted_on_disk_with_key_encryption_key() {
+    let path_buf = TempPath::new().path().to_path_buf();
+    let storage = EncryptedOnDiskStorage::new(path_buf, key_encryption_key(1)).unwrap();
+    let mut storage = Storage::from(storage);
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn test_data_is_encrypted_at_rest() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), passphrase("secret")).unwrap();
+    storage.set(U64_KEY, 123_456_789u64).unwrap();
+
+    // Verify the key and value aren't stored in plaintext
+    let contents = fs::read_to_string(&path_buf).unwrap();
+    assert!(!contents.contains(U64_KEY));
+    assert!(!contents.contains("123456789"));
+
+    // Verify the data can be read after re-opening the storage
+    let storage = EncryptedOnDiskStorage::new(path_buf.clone(), passphrase("secret")).unwrap();
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, 123_456_789);
+
+    // Verify the storage can't be opened with the wrong passphrase or key source
+    assert!(matches!(
+        EncryptedOnDiskStorage::new(path_buf.clone(), passphrase("wrong")),
+        Err(Error::DecryptionError(_))
+    ));
+    assert!(matches!(
+        EncryptedOnDiskStorage::new(path_buf, key_encryption_key(1)),
+        Err(Error::DecryptionError(_))
+    ));
+}
+
+#[test]
+fn test_tampering_is_detected() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), key_encryption_key(2)).unwrap();
+    storage.set(U64_KEY, 10u64).unwrap();
+
+    // Flip a bit in the encrypted data
+    let mut encrypted_file: serde_json::Value =
+        serde_json::from_slice(&fs::read(&path_buf).unwrap()).unwrap();
+    let ciphertext = encrypted_file["data"]["ciphertext"].as_str().unwrap();
+    let mut ciphertext = base64::decode(ciphertext).unwrap();
+    ciphertext[0] ^= 1;
+    encrypted_file["data"]["ciphertext"] = base64::encode(ciphertext).into();
+    fs::write(&path_buf, serde_json::to_vec(&encrypted_file).unwrap()).unwrap();
+
+    // Verify the modification is detected
+    assert!(matches!(
+        storage.get::<u64>(U64_KEY),
+        Err(Error::DecryptionError(_))
+    ));
+    assert!(matches!(
+        storage.set(U64_KEY, 11u64),
+        Err(Error::DecryptionError(_))
+    ));
+}
+
+#[test]
+fn test_rollback_is_detected() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), key_encryption_key(4)).unwrap();
+    storage.set(U64_KEY, 10u64).unwrap();
+    let old_contents = fs::read(&path_buf).unwrap();
+    storage.set(U64_KEY, 11u64).unwrap();
+
+    // Verify that replacing the file with an older copy is detected
+    fs::write(&path_buf, &old_contents).unwrap();
+    assert!(matches!(
+        storage.get::<u64>(U64_KEY),
+        Err(Error::DecryptionError(_))
+    ));
+    assert!(matches!(
+        storage.set(U64_KEY, 12u64),
+        Err(Error::DecryptionError(_))
+    ));
+
+    // Verify that the counter can't be modified to hide the rollback
+    let mut encrypted_file: serde_json::Value = serde_json::from_slice(&old_contents).unwrap();
+    encrypted_file["counter"] = (encrypted_file["counter"].as_u64().unwrap() + 1).into();
+    fs::write(&path_buf, serde_json::to_vec(&encrypted_file).unwrap()).unwrap();
+    assert!(matches!(
+        storage.get::<u64>(U64_KEY),
+        Err(Error::DecryptionError(_))
+    ));
+}
+
+#[test]
+fn test_encryption_key_rotation() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), passphrase("old")).unwrap();
+    storage.set(U64_KEY, 10u64).unwrap();
+
+    // Rotate the key explicitly, and verify the old key no longer works
+    storage
+        .rotate_encryption_key(key_encryption_key(3))
+        .unwrap();
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, 10);
+    assert!(EncryptedOnDiskStorage::new(path_buf.clone(), passphrase("old")).is_err());
+
+    // Rotate the key on startup (using the previous key source)
+    let storage = EncryptedOnDiskStorage::new_with_previous_key_source(
+        path_buf.clone(),
+        passphrase("new"),
+        key_encryption_key(3),
+    )
+    .unwrap();
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, 10);
+
+    // Verify the storage is now encrypted with the new key only
+    assert!(EncryptedOnDiskStorage::new(path_buf.clone(), key_encryption_key(3)).is_err());
+    let storage = EncryptedOnDiskStorage::new(path_buf, passphrase("new")).unwrap();
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, 10);
+}
+
+fn passphrase(passphrase: &str) -> EncryptionKeySource {
+    EncryptionKeySource::Passphrase(passphrase.into())
+}
+
+fn key_encryption_key(byte: u8) -> EncryptionKeySource {
+    EncryptionKeySource::KeyEncryptionKey(vec![byte; 32])
+}
@@ -28,7 +28,7 @@ use aptos_infallible::Mutex;
 use aptos_logger::{info, sample, sample::SampleRate, trace, warn};
 use aptos_network::{
     application::{interface::NetworkClient, storage::PeersAndMetadata},
-    protocols::network::RpcError,
+    protocols::{network::RpcError, wire::handshake::v1::ProtocolId},
 };
 use aptos_storage_interface::DbReader;
 use aptos_storage_service_client::StorageServiceClient;
@@ -698,6 +698,14 @@ impl AptosDataClient {
         T: TryFrom<StorageServiceResponse, Error = E> + Send + 'static,
         E: Into<Error>,
     {
+        // If the peer compresses messages at the network layer (i.e., zstd
+        // was negotiated during the handshake), avoid compressing the data twice.
+        let request = if request.use_compression && self.peer_supports_zstd(peer) {
+            StorageServiceRequest::new(request.data_request, false)
+        } else {
+            request
+        };
+
         // Start the timer for the request
         let timer = start_request_timer(&metrics::REQUEST_LATENCIES, &request.get_label(), peer);
 
@@ -882,6 +890,17 @@ impl AptosDataClient {
             .await
     }
 
+    /// Returns true iff the peer supports a zstd storage service protocol
+    fn peer_supports_zstd(&self, peer: PeerNetworkId) -> bool {
+        self.get_peers_and_metadata()
+            .get_metadata_for_peer(peer)
+            .map(|peer_metadata| {
+                peer_metadata.supports_protocol(ProtocolId::StorageServiceRpcZstdDictionary)
+                    || peer_metadata.supports_protocol(ProtocolId::StorageServiceRpcZstd)
+            })
+            .unwrap_or(false)
+    }
+
     /// Updates the metrics for the responses received via the data client
     fn update_received_response_metrics(
         &self,
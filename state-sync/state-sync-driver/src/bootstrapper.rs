@@ -746,7 +746,8 @@ impl<
                 Error::UnexpectedError("No higher epoch ending version known!".into())
             })?;
         let data_stream = match self.get_bootstrapping_mode() {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::RestoreFromBackup => {
                 self.streaming_client
                     .get_all_transaction_outputs(
                         next_version,
@@ -1174,7 +1175,8 @@ impl<
 
         // Execute/apply and commit the transactions/outputs
         let num_transactions_or_outputs = match bootstrapping_mode {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
                     utils::apply_transaction_outputs(
                         &mut self.storage_synchronizer,
@@ -1373,7 +1375,8 @@ impl<
     ) -> Result<Option<LedgerInfoWithSignatures>, Error> {
         // Calculate the payload end version
         let num_versions = match self.get_bootstrapping_mode() {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
                     transaction_outputs_with_proof
                         .transactions_and_outputs
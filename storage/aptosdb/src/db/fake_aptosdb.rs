@@ -598,6 +598,10 @@ impl DbReader for FakeAptosDB {
         self.inner.get_first_write_set_version()
     }
 
+    fn get_first_state_value_version(&self) -> Result<Option<Version>> {
+        self.inner.get_first_state_value_version()
+    }
+
     fn get_transaction_outputs(
         &self,
         start_version: Version,
@@ -222,6 +222,56 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Creates a physical checkpoint of this DB in directory `cp_path` while it keeps serving
+    /// reads and writes, and returns the committed version the checkpoint opens at.
+    ///
+    /// The state merkle db, whose snapshots are committed asynchronously and trail the ledger, goes
+    /// first. The ledger metadata db that follows fixes the committed version, and data in the
+    /// other sub dbs past it is truncated when the checkpoint is opened. Indexer dbs are not
+    /// included.
+    pub fn create_online_checkpoint(&self, cp_path: impl AsRef<Path>) -> Result<Version> {
+        let start = Instant::now();
+        let cp_path = cp_path.as_ref();
+        let sharding = self.ledger_db.enable_storage_sharding();
+
+        info!(
+            sharding = sharding,
+            "Creating online checkpoint for AptosDB."
+        );
+
+        ensure!(
+            !cp_path.exists(),
+            "Checkpoint dir {cp_path:?} already exists."
+        );
+        std::fs::create_dir_all(cp_path)?;
+        self.state_store
+            .state_merkle_db
+            .create_checkpoint_at(cp_path)?;
+        self.ledger_db.create_checkpoint_at(cp_path)?;
+        if self.state_kv_db.enabled_sharding() {
+            self.state_kv_db.create_checkpoint_at(cp_path)?;
+        }
+
+        let rocksdb_configs = RocksdbConfigs {
+            enable_storage_sharding: sharding,
+            ..Default::default()
+        };
+        let version = LedgerDb::new(cp_path, rocksdb_configs, /*readonly=*/ true)?
+            .metadata_db()
+            .get_synced_version()?
+            .ok_or_else(|| {
+                AptosDbError::Other("No committed version in checkpoint.".to_string())
+            })?;
+
+        info!(
+            cp_path = cp_path,
+            version = version,
+            time_ms = %start.elapsed().as_millis(),
+            "Made online AptosDB checkpoint."
+        );
+        Ok(version)
+    }
+
     pub fn commit_genesis_ledger_info(&self, genesis_li: &LedgerInfoWithSignatures) -> Result<()> {
         let ledger_metadata_db = self.ledger_db.metadata_db();
         let current_epoch = ledger_metadata_db
@@ -1,29 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{
-    db::AptosDB,
-    db_debugger::ShardingConfig,
-    schema::{
-        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
-        epoch_by_version::EpochByVersionSchema,
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
-    },
-    state_merkle_db::StateMerkleDb,
-    state_store::StateStore,
-    utils::truncation_helper::{
-        find_closest_node_version_at_or_before, get_current_version_in_state_merkle_db,
-        get_state_kv_commit_progress, truncate_state_merkle_db,
-    },
-};
-use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
-use aptos_jellyfish_merkle::node_type::NodeKey;
-use aptos_schemadb::{SchemaBatch, DB};
-use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
-use aptos_types::transaction::Version;
-use claims::assert_le;
+use crate::{db::AptosDB, db_debugger::ShardingConfig, utils::truncation_helper::truncate_db};
+use aptos_storage_interface::{db_ensure as ensure, Result};
 use clap::Parser;
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{fs, path::PathBuf};
 
 #[derive(Parser)]
 #[clap(about = "Delete all data after the provided version.")]
@@ -72,151 +53,16 @@ impl Cmd {
             println!("Opted out backup creation!.");
         }
 
-        let rocksdb_config = RocksdbConfigs {
-            enable_storage_sharding: self.sharding_config.enable_storage_sharding,
-            ..Default::default()
-        };
-        let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
-            &StorageDirPaths::from_path(&self.db_dir),
-            rocksdb_config,
-            /*readonly=*/ false,
-            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        println!("Starting db truncation...");
+        let version = truncate_db(
+            &self.db_dir,
+            self.target_version,
+            self.sharding_config.enable_storage_sharding,
         )?;
-
-        let ledger_db = Arc::new(ledger_db);
-        let state_merkle_db = Arc::new(state_merkle_db);
-        let state_kv_db = Arc::new(state_kv_db);
-        let overall_version = ledger_db
-            .metadata_db()
-            .get_synced_version()
-            .expect("DB read failed.")
-            .expect("Overall commit progress must exist.");
-        let ledger_db_version = ledger_db
-            .metadata_db()
-            .get_ledger_commit_progress()
-            .expect("Current version of ledger db must exist.");
-        let state_kv_db_version = get_state_kv_commit_progress(&state_kv_db)?
-            .expect("Current version of state kv db must exist.");
-        let state_merkle_db_version = get_current_version_in_state_merkle_db(&state_merkle_db)?
-            .expect("Current version of state merkle db must exist.");
-
-        let mut target_version = self.target_version;
-
-        assert_le!(overall_version, ledger_db_version);
-        assert_le!(overall_version, state_kv_db_version);
-        assert_le!(state_merkle_db_version, overall_version);
-        assert_le!(target_version, overall_version);
-
-        println!(
-            "overall_version: {}, ledger_db_version: {}, state_kv_db_version: {}, state_merkle_db_version: {}, target_version: {}",
-            overall_version, ledger_db_version, state_kv_db_version, state_merkle_db_version, target_version,
-        );
-
-        if ledger_db.metadata_db().get_usage(target_version).is_err() {
-            println!(
-                "Unable to truncate to version {}, since there is no VersionData on that version.",
-                target_version
-            );
-            println!(
-                "Trying to fallback to the largest valid version before version {}.",
-                target_version,
-            );
-            target_version = ledger_db
-                .metadata_db()
-                .get_usage_before_or_at(target_version)?
-                .0;
-        }
-
-        // TODO(grao): We are using a brute force implementation for now. We might be able to make
-        // it faster, since our data is append only.
-        if target_version < state_merkle_db_version {
-            let state_merkle_target_version = Self::find_tree_root_at_or_before(
-                &ledger_db.metadata_db_arc(),
-                &state_merkle_db,
-                target_version,
-            )?
-            .unwrap_or_else(|| {
-                panic!(
-                    "Could not find a valid root before or at version {}, maybe it was pruned?",
-                    target_version
-                )
-            });
-
-            println!(
-                "Starting state merkle db truncation... target_version: {}",
-                state_merkle_target_version
-            );
-            truncate_state_merkle_db(&state_merkle_db, state_merkle_target_version)?;
-            println!("Done!");
-        }
-
-        println!("Starting ledger db and state kv db truncation...");
-        let batch = SchemaBatch::new();
-        batch.put::<DbMetadataSchema>(
-            &DbMetadataKey::OverallCommitProgress,
-            &DbMetadataValue::Version(target_version),
-        )?;
-        ledger_db.metadata_db().write_schemas(batch)?;
-
-        StateStore::sync_commit_progress(
-            Arc::clone(&ledger_db),
-            Arc::clone(&state_kv_db),
-            /*crash_if_difference_is_too_large=*/ false,
-        );
-        println!("Done!");
-
-        if let Some(state_merkle_db_version) =
-            get_current_version_in_state_merkle_db(&state_merkle_db)?
-        {
-            if state_merkle_db_version < target_version {
-                println!(
-                    "Trying to catch up state merkle db, by replaying write set in ledger db."
-                );
-                let version = StateStore::catch_up_state_merkle_db(
-                    Arc::clone(&ledger_db),
-                    Arc::clone(&state_merkle_db),
-                    Arc::clone(&state_kv_db),
-                )?;
-                println!("Done! current_version: {:?}", version);
-            }
-        }
+        println!("Done! Truncated to version {}.", version);
 
         Ok(())
     }
-
-    fn find_tree_root_at_or_before(
-        ledger_metadata_db: &DB,
-        state_merkle_db: &StateMerkleDb,
-        version: Version,
-    ) -> Result<Option<Version>> {
-        match find_closest_node_version_at_or_before(state_merkle_db, version)? {
-            Some(closest_version) => {
-                if Self::root_exists_at_version(state_merkle_db, closest_version)? {
-                    return Ok(Some(closest_version));
-                }
-                let mut iter = ledger_metadata_db.iter::<EpochByVersionSchema>()?;
-                iter.seek_for_prev(&version)?;
-                match iter.next().transpose()? {
-                    Some((closest_epoch_version, _)) => {
-                        if Self::root_exists_at_version(state_merkle_db, closest_epoch_version)? {
-                            Ok(Some(closest_epoch_version))
-                        } else {
-                            Ok(None)
-                        }
-                    },
-                    None => Ok(None),
-                }
-            },
-            None => Ok(None),
-        }
-    }
-
-    fn root_exists_at_version(state_merkle_db: &StateMerkleDb, version: Version) -> Result<bool> {
-        Ok(state_merkle_db
-            .metadata_db()
-            .get::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(version))?
-            .is_some())
-    }
 }
 
 #[cfg(test)]
@@ -229,7 +75,8 @@ mod test {
             AptosDB,
         },
         schema::{
-            epoch_by_version::EpochByVersionSchema, ledger_info::LedgerInfoSchema,
+            epoch_by_version::EpochByVersionSchema,
+            jellyfish_merkle_node::JellyfishMerkleNodeSchema, ledger_info::LedgerInfoSchema,
             stale_node_index::StaleNodeIndexSchema,
             stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
             stale_state_value_index::StaleStateValueIndexSchema,
@@ -241,9 +88,11 @@ mod test {
         },
         utils::truncation_helper::num_frozen_nodes_in_accumulator,
     };
+    use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
     use aptos_storage_interface::DbReader;
     use aptos_temppath::TempPath;
     use proptest::prelude::*;
+    use std::sync::Arc;
 
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(1))]
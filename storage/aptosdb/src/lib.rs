@@ -20,6 +20,7 @@ pub mod db;
 pub mod get_restore_handler;
 pub mod metrics;
 pub(crate) mod rocksdb_property_reporter;
+pub mod rolling_checkpointer;
 pub mod schema;
 pub mod state_restore;
 pub mod utils;
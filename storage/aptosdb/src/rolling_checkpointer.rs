@@ -0,0 +1,321 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{utils::truncation_helper::truncate_db, AptosDB};
+use aptos_config::config::RollingCheckpointConfig;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::{db_ensure as ensure, Result};
+use aptos_types::transaction::Version;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const CHECKPOINT_DIR_PREFIX: &str = "checkpoint_";
+const IN_PROGRESS_DIR_PREFIX: &str = "in_progress_";
+
+/// A checkpoint kept by the [`RollingCheckpointer`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CheckpointInfo {
+    /// The committed version the checkpoint opens at.
+    pub version: Version,
+    /// The root dir of the checkpoint, which can be opened as an `AptosDB`.
+    pub path: PathBuf,
+}
+
+struct Checkpoints {
+    db: Arc<AptosDB>,
+    checkpoint_dir: PathBuf,
+    num_checkpoints_to_keep: usize,
+    // Serializes the periodic and the on demand checkpoints, as well as the retention.
+    lock: Mutex<()>,
+}
+
+impl Checkpoints {
+    fn create_checkpoint(&self, target_version: Option<Version>) -> Result<CheckpointInfo> {
+        let _lock = self.lock.lock();
+
+        fs::create_dir_all(&self.checkpoint_dir)?;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards.")
+            .as_nanos();
+        let in_progress_path = self
+            .checkpoint_dir
+            .join(format!("{IN_PROGRESS_DIR_PREFIX}{nanos}"));
+        let version = match self
+            .db
+            .create_online_checkpoint(&in_progress_path)
+            .and_then(|version| match target_version {
+                Some(target_version) => {
+                    self.truncate_checkpoint(&in_progress_path, version, target_version)
+                },
+                None => Ok(version),
+            }) {
+            Ok(version) => version,
+            Err(e) => {
+                fs::remove_dir_all(&in_progress_path).unwrap_or(());
+                return Err(e);
+            },
+        };
+
+        // The DB doesn't necessarily move between two checkpoints, keep the earlier one if so.
+        let path = self
+            .checkpoint_dir
+            .join(format!("{CHECKPOINT_DIR_PREFIX}{version}"));
+        if path.exists() {
+            fs::remove_dir_all(&in_progress_path)?;
+        } else {
+            fs::rename(&in_progress_path, &path)?;
+        }
+
+        self.remove_old_checkpoints()?;
+        Ok(CheckpointInfo { version, path })
+    }
+
+    // Truncates the fresh checkpoint at `path`, taken at `version`, to `target_version`.
+    fn truncate_checkpoint(
+        &self,
+        path: &Path,
+        version: Version,
+        target_version: Version,
+    ) -> Result<Version> {
+        ensure!(
+            target_version <= version,
+            "Can't checkpoint at version {}, the latest committed version is {}.",
+            target_version,
+            version,
+        );
+        if target_version == version {
+            return Ok(version);
+        }
+        truncate_db(
+            path,
+            target_version,
+            self.db.ledger_db.enable_storage_sharding(),
+        )
+    }
+
+    fn remove_old_checkpoints(&self) -> Result<()> {
+        let checkpoints = list_checkpoints(&self.checkpoint_dir)?;
+        let num_to_remove = checkpoints
+            .len()
+            .saturating_sub(self.num_checkpoints_to_keep);
+        for checkpoint in &checkpoints[..num_to_remove] {
+            info!(
+                version = checkpoint.version,
+                "Removing checkpoint at {:?}.", checkpoint.path
+            );
+            fs::remove_dir_all(&checkpoint.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Takes checkpoints of a running `AptosDB` under a checkpoint dir, periodically if enabled in
+/// the config, and on demand. Only the most recent `num_checkpoints_to_keep` checkpoints are
+/// kept.
+pub struct RollingCheckpointer {
+    checkpoints: Arc<Checkpoints>,
+    sender: Option<Mutex<mpsc::Sender<()>>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl RollingCheckpointer {
+    pub fn new(
+        db: Arc<AptosDB>,
+        config: &RollingCheckpointConfig,
+        checkpoint_dir: PathBuf,
+    ) -> Self {
+        // Leftovers of checkpoints that were interrupted, e.g. by a restart.
+        if let Ok(entries) = fs::read_dir(&checkpoint_dir) {
+            for entry in entries.flatten() {
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(IN_PROGRESS_DIR_PREFIX)
+                {
+                    fs::remove_dir_all(entry.path()).unwrap_or(());
+                }
+            }
+        }
+
+        let checkpoints = Arc::new(Checkpoints {
+            db,
+            checkpoint_dir,
+            // Never remove the checkpoint that was just created (the config sanitizer rejects 0)
+            num_checkpoints_to_keep: config.num_checkpoints_to_keep.max(1),
+            lock: Mutex::new(()),
+        });
+
+        let (sender, join_handle) = if config.enable {
+            let (send, recv) = mpsc::channel();
+            let interval = Duration::from_secs(config.interval_secs);
+            let checkpoints = checkpoints.clone();
+            let join_handle = thread::Builder::new()
+                .name("db_checkpoint".into())
+                .spawn(move || loop {
+                    match recv.recv_timeout(interval) {
+                        Ok(_) => break,
+                        Err(mpsc::RecvTimeoutError::Timeout) => (),
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                    match checkpoints.create_checkpoint(None) {
+                        Ok(checkpoint) => info!(
+                            version = checkpoint.version,
+                            "Created rolling checkpoint at {:?}.", checkpoint.path
+                        ),
+                        Err(e) => error!(
+                            error = ?e,
+                            "Failed to create rolling checkpoint."
+                        ),
+                    }
+                })
+                .expect("Failed to spawn the checkpoint thread.");
+            (Some(Mutex::new(send)), Some(join_handle))
+        } else {
+            (None, None)
+        };
+
+        Self {
+            checkpoints,
+            sender,
+            join_handle,
+        }
+    }
+
+    /// Creates a checkpoint of the DB now and removes the ones out of retention. The checkpoint
+    /// is at the latest committed version, or else truncated to `target_version`, falling back
+    /// to the largest version before it the DB can be truncated to.
+    pub fn create_checkpoint(&self, target_version: Option<Version>) -> Result<CheckpointInfo> {
+        self.checkpoints.create_checkpoint(target_version)
+    }
+
+    /// Returns the existing checkpoints, in ascending order of version.
+    pub fn list_checkpoints(&self) -> Result<Vec<CheckpointInfo>> {
+        list_checkpoints(&self.checkpoints.checkpoint_dir)
+    }
+}
+
+impl Drop for RollingCheckpointer {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            // Notify the checkpoint thread to exit
+            sender.lock().send(()).unwrap_or(());
+        }
+        if let Some(join_handle) = self.join_handle.take() {
+            join_handle
+                .join()
+                .expect("Checkpoint thread should join peacefully.");
+        }
+    }
+}
+
+fn list_checkpoints(checkpoint_dir: &Path) -> Result<Vec<CheckpointInfo>> {
+    if !checkpoint_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut checkpoints = Vec::new();
+    for entry in fs::read_dir(checkpoint_dir)? {
+        let entry = entry?;
+        let version = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix(CHECKPOINT_DIR_PREFIX))
+            .and_then(|version| version.parse::<Version>().ok());
+        if let Some(version) = version {
+            checkpoints.push(CheckpointInfo {
+                version,
+                path: entry.path(),
+            });
+        }
+    }
+    checkpoints.sort_by_key(|checkpoint| checkpoint.version);
+    Ok(checkpoints)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::test_helper::{arb_blocks_to_commit_with_block_nums, update_in_memory_state};
+    use aptos_config::config::DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD;
+    use aptos_storage_interface::DbReader;
+    use aptos_temppath::TempPath;
+    use proptest::prelude::*;
+
+    fn open_db(path: &Path, sharding: bool) -> AptosDB {
+        if sharding {
+            AptosDB::new_for_test_with_sharding(path, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD)
+        } else {
+            AptosDB::new_for_test(path)
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1))]
+
+        #[test]
+        fn test_rolling_checkpoints(input in arb_blocks_to_commit_with_block_nums(1, 10)) {
+            let (blocks, sharding) = input;
+            let tmp_dir = TempPath::new();
+            let db = Arc::new(open_db(tmp_dir.path(), sharding));
+            let config = RollingCheckpointConfig {
+                num_checkpoints_to_keep: 2,
+                ..Default::default()
+            };
+            let checkpoint_dir = TempPath::new();
+            let checkpointer =
+                RollingCheckpointer::new(db.clone(), &config, checkpoint_dir.path().to_path_buf());
+
+            let mut in_memory_state = db.state_store.buffered_state().lock().current_state().clone();
+            let mut version = 0;
+            let mut checkpoint_versions = vec![];
+            for (txns_to_commit, ledger_info_with_sigs) in blocks.iter() {
+                update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+                db.save_transactions_for_test(
+                    txns_to_commit,
+                    version,
+                    version.checked_sub(1),
+                    Some(ledger_info_with_sigs),
+                    true, /* sync_commit */
+                    in_memory_state.clone(),
+                )
+                .unwrap();
+                version += txns_to_commit.len() as u64;
+
+                let checkpoint = checkpointer.create_checkpoint(None).unwrap();
+                prop_assert_eq!(checkpoint.version, version - 1);
+                checkpoint_versions.push(checkpoint.version);
+
+                let cp_db = open_db(&checkpoint.path, sharding);
+                prop_assert_eq!(cp_db.expect_synced_version(), version - 1);
+                prop_assert_eq!(
+                    &cp_db.get_latest_ledger_info().unwrap(),
+                    ledger_info_with_sigs
+                );
+            }
+
+            let listed: Vec<_> = checkpointer
+                .list_checkpoints()
+                .unwrap()
+                .into_iter()
+                .map(|checkpoint| checkpoint.version)
+                .collect();
+            let num_removed = checkpoint_versions.len().saturating_sub(2);
+            prop_assert_eq!(listed, checkpoint_versions[num_removed..].to_vec());
+
+            // Checkpoints can be truncated to an earlier version, but not a future one.
+            let target_version = blocks[0].0.len() as u64 - 1;
+            let checkpoint = checkpointer.create_checkpoint(Some(target_version)).unwrap();
+            prop_assert!(checkpoint.version <= target_version);
+            let cp_db = open_db(&checkpoint.path, sharding);
+            prop_assert_eq!(cp_db.expect_synced_version(), checkpoint.version);
+            prop_assert!(checkpointer.create_checkpoint(Some(version)).is_err());
+        }
+    }
+}
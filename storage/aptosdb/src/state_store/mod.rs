@@ -1178,7 +1178,7 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
                 .internal_indexer_db
                 .as_ref()
                 .unwrap()
-                .statekeys_enabled()
+                .state_snapshot_restore_enabled()
         {
             let keys = node_batch.iter().map(|(key, _)| key.0.clone()).collect();
             self.internal_indexer_db
@@ -1222,6 +1222,12 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
                         &MetadataValue::Version(version - 1),
                     )?;
                 }
+                if internal_indexer_db.resource_by_type_enabled() {
+                    batch.put::<InternalIndexerMetadataSchema>(
+                        &MetadataKey::ResourceByTypeVersion,
+                        &MetadataValue::Version(version - 1),
+                    )?;
+                }
                 internal_indexer_db
                     .get_inner_db_ref()
                     .write_schemas(batch)?;
@@ -1244,7 +1250,7 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
                 .internal_indexer_db
                 .as_ref()
                 .unwrap()
-                .statekeys_enabled()
+                .state_snapshot_restore_enabled()
         {
             let progress_opt = self
                 .internal_indexer_db
@@ -4,6 +4,7 @@
 #![allow(dead_code)]
 
 use crate::{
+    db::AptosDB,
     ledger_db::{LedgerDb, LedgerDbSchemaBatches},
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -25,23 +26,25 @@ use crate::{
     },
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
-    state_store::MAX_COMMIT_PROGRESS_DIFFERENCE,
+    state_store::{StateStore, MAX_COMMIT_PROGRESS_DIFFERENCE},
     transaction_store::TransactionStore,
     utils::get_progress,
 };
+use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
 use aptos_jellyfish_merkle::{node_type::NodeKey, StaleNodeIndex};
 use aptos_logger::info;
 use aptos_schemadb::{
     schema::{Schema, SeekKeyCodec},
     SchemaBatch, DB,
 };
-use aptos_storage_interface::Result;
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
 use aptos_types::{proof::position::Position, transaction::Version};
-use claims::assert_ge;
+use claims::{assert_ge, assert_le};
 use rayon::prelude::*;
 use status_line::StatusLine;
 use std::{
     fmt::{Display, Formatter},
+    path::Path,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc,
@@ -212,6 +215,158 @@ pub(crate) fn find_closest_node_version_at_or_before(
     Ok(iter.next().transpose()?.map(|item| item.0.version()))
 }
 
+/// Deletes all data after `target_version` from the (closed) DB at `db_root_path`, and returns
+/// the version the DB got truncated to. That's the largest version at or before `target_version`
+/// with `VersionData`.
+pub(crate) fn truncate_db(
+    db_root_path: &Path,
+    target_version: Version,
+    enable_storage_sharding: bool,
+) -> Result<Version> {
+    let rocksdb_config = RocksdbConfigs {
+        enable_storage_sharding,
+        ..Default::default()
+    };
+    let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+        &StorageDirPaths::from_path(db_root_path),
+        rocksdb_config,
+        /*readonly=*/ false,
+        /*max_num_nodes_per_lru_cache_shard=*/ 0,
+    )?;
+
+    let ledger_db = Arc::new(ledger_db);
+    let state_merkle_db = Arc::new(state_merkle_db);
+    let state_kv_db = Arc::new(state_kv_db);
+    let overall_version = ledger_db
+        .metadata_db()
+        .get_synced_version()?
+        .ok_or_else(|| AptosDbError::Other("Overall commit progress must exist.".to_string()))?;
+    let ledger_db_version = ledger_db.metadata_db().get_ledger_commit_progress()?;
+    let state_kv_db_version = get_state_kv_commit_progress(&state_kv_db)?.ok_or_else(|| {
+        AptosDbError::Other("Current version of state kv db must exist.".to_string())
+    })?;
+    let state_merkle_db_version = get_current_version_in_state_merkle_db(&state_merkle_db)?
+        .ok_or_else(|| {
+            AptosDbError::Other("Current version of state merkle db must exist.".to_string())
+        })?;
+
+    assert_le!(overall_version, ledger_db_version);
+    assert_le!(overall_version, state_kv_db_version);
+    assert_le!(state_merkle_db_version, overall_version);
+    ensure!(
+        target_version <= overall_version,
+        "Target version {} is newer than the committed version {}.",
+        target_version,
+        overall_version,
+    );
+
+    info!(
+        overall_version = overall_version,
+        ledger_db_version = ledger_db_version,
+        state_kv_db_version = state_kv_db_version,
+        state_merkle_db_version = state_merkle_db_version,
+        target_version = target_version,
+        "Truncating DB."
+    );
+
+    let mut target_version = target_version;
+    if ledger_db.metadata_db().get_usage(target_version).is_err() {
+        target_version = ledger_db
+            .metadata_db()
+            .get_usage_before_or_at(target_version)?
+            .0;
+        info!(
+            target_version = target_version,
+            "No VersionData at the target version, falling back to the largest valid version before it."
+        );
+    }
+
+    // TODO(grao): We are using a brute force implementation for now. We might be able to make
+    // it faster, since our data is append only.
+    if target_version < state_merkle_db_version {
+        let state_merkle_target_version = find_tree_root_at_or_before(
+            &ledger_db.metadata_db_arc(),
+            &state_merkle_db,
+            target_version,
+        )?
+        .ok_or_else(|| {
+            AptosDbError::Other(format!(
+                "Could not find a valid root before or at version {}, maybe it was pruned?",
+                target_version
+            ))
+        })?;
+
+        info!(
+            target_version = state_merkle_target_version,
+            "Truncating state merkle db."
+        );
+        truncate_state_merkle_db(&state_merkle_db, state_merkle_target_version)?;
+    }
+
+    info!("Truncating ledger db and state kv db.");
+    let batch = SchemaBatch::new();
+    batch.put::<DbMetadataSchema>(
+        &DbMetadataKey::OverallCommitProgress,
+        &DbMetadataValue::Version(target_version),
+    )?;
+    ledger_db.metadata_db().write_schemas(batch)?;
+
+    StateStore::sync_commit_progress(
+        Arc::clone(&ledger_db),
+        Arc::clone(&state_kv_db),
+        /*crash_if_difference_is_too_large=*/ false,
+    );
+
+    if let Some(state_merkle_db_version) = get_current_version_in_state_merkle_db(&state_merkle_db)?
+    {
+        if state_merkle_db_version < target_version {
+            info!("Catching up state merkle db, by replaying write set in ledger db.");
+            let version = StateStore::catch_up_state_merkle_db(
+                Arc::clone(&ledger_db),
+                Arc::clone(&state_merkle_db),
+                Arc::clone(&state_kv_db),
+            )?;
+            info!(current_version = ?version, "Caught up state merkle db.");
+        }
+    }
+
+    Ok(target_version)
+}
+
+fn find_tree_root_at_or_before(
+    ledger_metadata_db: &DB,
+    state_merkle_db: &StateMerkleDb,
+    version: Version,
+) -> Result<Option<Version>> {
+    match find_closest_node_version_at_or_before(state_merkle_db, version)? {
+        Some(closest_version) => {
+            if root_exists_at_version(state_merkle_db, closest_version)? {
+                return Ok(Some(closest_version));
+            }
+            let mut iter = ledger_metadata_db.iter::<EpochByVersionSchema>()?;
+            iter.seek_for_prev(&version)?;
+            match iter.next().transpose()? {
+                Some((closest_epoch_version, _)) => {
+                    if root_exists_at_version(state_merkle_db, closest_epoch_version)? {
+                        Ok(Some(closest_epoch_version))
+                    } else {
+                        Ok(None)
+                    }
+                },
+                None => Ok(None),
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+fn root_exists_at_version(state_merkle_db: &StateMerkleDb, version: Version) -> Result<bool> {
+    Ok(state_merkle_db
+        .metadata_db()
+        .get::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(version))?
+        .is_some())
+}
+
 pub(crate) fn num_frozen_nodes_in_accumulator(num_leaves: u64) -> u64 {
     2 * num_leaves - num_leaves.count_ones() as u64
 }
@@ -0,0 +1,395 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::state_snapshot::{
+        backup::get_version_for_epoch_ending,
+        manifest::{StateSnapshotDiffBackup, StateSnapshotDiffChunk},
+    },
+    metadata::Metadata,
+    metrics::backup::BACKUP_TIMER,
+    storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
+    utils::{
+        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+        storage_ext::BackupStorageExt, stream::StreamX, GlobalBackupOpt,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_logger::prelude::*;
+use aptos_metrics_core::TimerHelper;
+use aptos_types::{
+    contract_event::ContractEvent,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::TransactionInfoWithProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::{TransactionWrite, WriteSet},
+};
+use bytes::{BufMut, BytesMut};
+use clap::Parser;
+use futures::{stream, TryStreamExt};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::{collections::BTreeMap, convert::TryInto, str::FromStr, sync::Arc};
+use tokio::io::AsyncWriteExt;
+
+/// The changes since the base snapshot, keyed and ordered by the hash of the state key.
+/// `None` means the key was deleted.
+type StateChanges = BTreeMap<HashValue, (StateKey, Option<StateValue>)>;
+
+#[derive(Parser)]
+pub struct StateSnapshotDiffBackupOpt {
+    #[clap(
+        long = "state-snapshot-epoch",
+        help = "Epoch at the end of which a differential state snapshot is to be taken."
+    )]
+    pub epoch: u64,
+    #[clap(
+        long = "base-state-manifest",
+        help = "Manifest of the snapshot to take the difference against, either a full state \
+        snapshot or another differential one. It must be taken at or before the epoch above."
+    )]
+    pub base_manifest: FileHandle,
+    #[clap(
+        long,
+        default_value_t = Self::DEFAULT_LEAVES_PER_CHUNK,
+        help = "Number of state items in the resulting state each chunk covers. A range proof is \
+        stored per chunk, and the restore holds the state items of a chunk in memory."
+    )]
+    pub leaves_per_chunk: usize,
+}
+
+impl StateSnapshotDiffBackupOpt {
+    pub const DEFAULT_LEAVES_PER_CHUNK: usize = 100_000;
+}
+
+/// Both the full and the differential state snapshot manifests carry these.
+#[derive(Deserialize)]
+struct BaseManifest {
+    version: Version,
+    epoch: u64,
+}
+
+/// Takes a differential state snapshot: only the state items that changed since the base
+/// snapshot are stored, derived from the write sets of the transactions in between.
+/// To make the resulting state verifiable on restore, the state items at the target version are
+/// partitioned into chunks by index, and a range proof is stored for each chunk.
+pub struct StateSnapshotDiffBackupController {
+    epoch: u64,
+    base_manifest: FileHandle,
+    leaves_per_chunk: usize,
+    version: Option<Version>, // initialize before using
+    client: Arc<BackupServiceClient>,
+    storage: Arc<dyn BackupStorage>,
+    concurrent_data_requests: usize,
+}
+
+impl StateSnapshotDiffBackupController {
+    pub fn new(
+        opt: StateSnapshotDiffBackupOpt,
+        global_opt: GlobalBackupOpt,
+        client: Arc<BackupServiceClient>,
+        storage: Arc<dyn BackupStorage>,
+    ) -> Self {
+        Self {
+            epoch: opt.epoch,
+            base_manifest: opt.base_manifest,
+            leaves_per_chunk: opt.leaves_per_chunk,
+            version: None,
+            client,
+            storage,
+            concurrent_data_requests: global_opt.concurrent_data_requests,
+        }
+    }
+
+    pub async fn run(self) -> Result<FileHandle> {
+        info!(
+            "Differential state snapshot backup started, for epoch {}, base {}.",
+            self.epoch, self.base_manifest
+        );
+        let ret = self
+            .run_impl()
+            .await
+            .map_err(|e| anyhow!("Differential state snapshot backup failed: {}", e))?;
+        info!(
+            "Differential state snapshot backup succeeded. Manifest: {}",
+            ret
+        );
+        Ok(ret)
+    }
+
+    async fn run_impl(mut self) -> Result<FileHandle> {
+        ensure!(
+            self.leaves_per_chunk > 0,
+            "leaves_per_chunk must be positive."
+        );
+        let base: BaseManifest = self.storage.load_json_file(&self.base_manifest).await?;
+        ensure!(
+            base.epoch <= self.epoch,
+            "Base snapshot is taken in epoch {}, after epoch {}.",
+            base.epoch,
+            self.epoch,
+        );
+        self.version = Some(get_version_for_epoch_ending(&self.client, self.epoch).await?);
+        let backup_handle = self
+            .storage
+            .create_backup_with_random_suffix(&self.backup_name())
+            .await?;
+
+        let changes = self.get_changes(base.version).await?;
+        info!(
+            base_version = base.version,
+            version = self.version(),
+            num_changes = changes.len(),
+            "Collected state changes."
+        );
+
+        let num_leaves = self.client.get_state_item_count(self.version()).await?;
+        ensure!(num_leaves > 0, "State is empty.");
+        let leaves_per_chunk = self.leaves_per_chunk;
+        let chunk_ranges: Vec<_> = (0..num_leaves)
+            .step_by(leaves_per_chunk)
+            .map(|first_idx| {
+                (
+                    first_idx,
+                    std::cmp::min(first_idx + leaves_per_chunk, num_leaves) - 1,
+                )
+            })
+            .collect();
+        let con = self.concurrent_data_requests;
+        let last_keys: Vec<HashValue> = stream::iter(
+            chunk_ranges
+                .iter()
+                .map(|(_, last_idx)| self.get_key_at_index(*last_idx)),
+        )
+        .buffered_x(con * 2, con)
+        .try_collect()
+        .await?;
+
+        // Split the changes by the key ranges of the chunks. Deletions beyond the last key (if
+        // any) go to the last chunk, the rest of the changes must have been consumed by then.
+        let mut changes = changes.into_iter().peekable();
+        let mut chunk_changes = Vec::with_capacity(last_keys.len());
+        for last_key in &last_keys {
+            let mut this_chunk = StateChanges::new();
+            while let Some((key, _)) = changes.peek() {
+                if key > last_key {
+                    break;
+                }
+                let (key, change) = changes.next().unwrap();
+                this_chunk.insert(key, change);
+            }
+            chunk_changes.push(this_chunk);
+        }
+        for (key, change) in changes {
+            ensure!(
+                change.1.is_none(),
+                "State item {:x} changed but is beyond the last key in the state.",
+                key
+            );
+            chunk_changes.last_mut().unwrap().insert(key, change);
+        }
+
+        let chunks: Vec<StateSnapshotDiffChunk> = stream::iter(
+            chunk_ranges
+                .into_iter()
+                .zip(last_keys)
+                .zip(chunk_changes)
+                .map(|(((first_idx, last_idx), last_key), changes)| {
+                    self.write_chunk(&backup_handle, first_idx, last_idx, last_key, changes)
+                }),
+        )
+        .buffered_x(con * 2, con)
+        .try_collect()
+        .await?;
+
+        self.write_manifest(&backup_handle, base.version, chunks)
+            .await
+    }
+
+    /// Replays the write sets of all transactions after the base version, up to and including the
+    /// target version, keeping only the last write to each key.
+    async fn get_changes(&self, base_version: Version) -> Result<StateChanges> {
+        const BATCH_SIZE: usize = if cfg!(test) { 2 } else { 100_000 };
+        let _timer = BACKUP_TIMER.timer_with(&["state_snapshot_diff_get_changes"]);
+
+        let mut changes = StateChanges::new();
+        let mut next_version = base_version + 1;
+        while next_version <= self.version() {
+            let num_transactions =
+                std::cmp::min(BATCH_SIZE as u64, self.version() + 1 - next_version) as usize;
+            let mut input = self
+                .client
+                .get_transactions(next_version, num_transactions)
+                .await?;
+            let mut count = 0;
+            while let Some(record_bytes) = input.read_record_bytes().await? {
+                let (_txn, _txn_info, _events, write_set): (
+                    Transaction,
+                    TransactionInfo,
+                    Vec<ContractEvent>,
+                    WriteSet,
+                ) = bcs::from_bytes(&record_bytes)?;
+                for (key, op) in write_set {
+                    changes.insert(key.hash(), (key, op.as_state_value()));
+                }
+                count += 1;
+            }
+            ensure!(
+                count == num_transactions,
+                "expecting {} transactions, got {}",
+                num_transactions,
+                count
+            );
+            next_version += num_transactions as u64;
+        }
+        Ok(changes)
+    }
+
+    async fn get_key_at_index(&self, idx: usize) -> Result<HashValue> {
+        let record_bytes = self
+            .client
+            .get_state_snapshot_chunk(self.version(), idx, 1)
+            .await?
+            .read_record_bytes()
+            .await?
+            .ok_or_else(|| anyhow!("State item {} not found.", idx))?;
+        let (key, _): (StateKey, StateValue) = bcs::from_bytes(&record_bytes)?;
+        Ok(key.hash())
+    }
+
+    async fn write_chunk(
+        &self,
+        backup_handle: &BackupHandleRef,
+        first_idx: usize,
+        last_idx: usize,
+        last_key: HashValue,
+        changes: StateChanges,
+    ) -> Result<StateSnapshotDiffChunk> {
+        let _timer = BACKUP_TIMER.timer_with(&["state_snapshot_diff_write_chunk"]);
+
+        let changes_handle = if changes.is_empty() {
+            None
+        } else {
+            let mut buf = BytesMut::new();
+            for record in changes.into_values() {
+                let bytes = bcs::to_bytes(&record)?;
+                buf.put_slice(&(bytes.len() as u32).to_be_bytes());
+                buf.put_slice(&bytes);
+            }
+            let (changes_handle, mut changes_file) = self
+                .storage
+                .create_for_write(backup_handle, &Self::chunk_name(first_idx))
+                .await?;
+            changes_file.write_all(&buf).await?;
+            changes_file.shutdown().await?;
+            Some(changes_handle)
+        };
+
+        let (proof_handle, mut proof_file) = self
+            .storage
+            .create_for_write(backup_handle, &Self::chunk_proof_name(first_idx, last_idx))
+            .await?;
+        tokio::io::copy(
+            &mut self
+                .client
+                .get_account_range_proof(last_key, self.version())
+                .await?,
+            &mut proof_file,
+        )
+        .await?;
+        proof_file.shutdown().await?;
+
+        info!(last_idx = last_idx, "Chunk written.");
+        Ok(StateSnapshotDiffChunk {
+            first_idx,
+            last_idx,
+            last_key,
+            changes: changes_handle,
+            proof: proof_handle,
+        })
+    }
+
+    async fn write_manifest(
+        &self,
+        backup_handle: &BackupHandleRef,
+        base_version: Version,
+        chunks: Vec<StateSnapshotDiffChunk>,
+    ) -> Result<FileHandle> {
+        let proof_bytes = self.client.get_state_root_proof(self.version()).await?;
+        let (txn_info, _): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            bcs::from_bytes(&proof_bytes)?;
+
+        let (proof_handle, mut proof_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::proof_name())
+            .await?;
+        proof_file.write_all(&proof_bytes).await?;
+        proof_file.shutdown().await?;
+
+        let manifest = StateSnapshotDiffBackup {
+            version: self.version(),
+            epoch: self.epoch,
+            base_version,
+            base_manifest: self.base_manifest.clone(),
+            root_hash: txn_info.transaction_info().ensure_state_checkpoint_hash()?,
+            chunks,
+            proof: proof_handle,
+        };
+
+        let (manifest_handle, mut manifest_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::manifest_name())
+            .await?;
+        manifest_file
+            .write_all(&serde_json::to_vec(&manifest)?)
+            .await?;
+        manifest_file.shutdown().await?;
+
+        let metadata = Metadata::new_state_snapshot_diff_backup(
+            self.epoch,
+            self.version(),
+            base_version,
+            self.base_manifest.clone(),
+            manifest_handle.clone(),
+        );
+        self.storage
+            .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+            .await?;
+
+        Ok(manifest_handle)
+    }
+}
+
+impl StateSnapshotDiffBackupController {
+    fn version(&self) -> Version {
+        self.version.unwrap()
+    }
+
+    fn backup_name(&self) -> String {
+        format!("state_diff_epoch_{}_ver_{}", self.epoch, self.version())
+    }
+
+    fn manifest_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("state_diff.manifest").unwrap());
+        &NAME
+    }
+
+    fn proof_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("state_diff.proof").unwrap());
+        &NAME
+    }
+
+    fn chunk_name(first_idx: usize) -> ShellSafeName {
+        format!("{}-.diff", first_idx).try_into().unwrap()
+    }
+
+    fn chunk_proof_name(first_idx: usize, last_idx: usize) -> ShellSafeName {
+        format!("{}-{}.proof", first_idx, last_idx)
+            .try_into()
+            .unwrap()
+    }
+}
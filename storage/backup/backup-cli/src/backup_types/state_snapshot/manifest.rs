@@ -50,3 +50,46 @@ pub struct StateSnapshotBackup {
     /// limits the requirement on such `EpochStateBackup` to no older than the same epoch.
     pub proof: FileHandle,
 }
+
+/// A chunk of a differential state snapshot manifest. Chunks partition the leaves of the state at
+/// the version of the backup by index, and each carries the changes since the base snapshot that
+/// fall into the key range (`last_key` of the previous chunk, `last_key`].
+#[derive(Deserialize, Serialize)]
+pub struct StateSnapshotDiffChunk {
+    /// index of the first account in this chunk over all accounts in the resulting state.
+    pub first_idx: usize,
+    /// index of the last account in this chunk over all accounts in the resulting state.
+    pub last_idx: usize,
+    /// key of the last account in this chunk.
+    pub last_key: HashValue,
+    /// Repeated `len(record) + record` where `record` is BCS serialized tuple
+    /// `(key, Option<state_value>)`, `None` meaning the key is deleted since the base snapshot.
+    /// Absent if nothing changed in the key range of this chunk.
+    pub changes: Option<FileHandle>,
+    /// BCS serialized `SparseMerkleRangeProof` that proves the leaves in this chunk, after
+    /// applying the changes on top of the base snapshot, add up to the root hash indicated in the
+    /// backup (`StateSnapshotDiffBackup::root_hash`).
+    pub proof: FileHandle,
+}
+
+/// Differential state snapshot backup manifest, representing the changes to the state between the
+/// version of a base snapshot (either a full one or another differential one) and the specified
+/// version.
+#[derive(Deserialize, Serialize)]
+pub struct StateSnapshotDiffBackup {
+    /// Version at which this state snapshot is taken.
+    pub version: Version,
+    /// Epoch in which this state snapshot is taken.
+    pub epoch: u64,
+    /// Version of the base snapshot.
+    pub base_version: Version,
+    /// Manifest of the base snapshot.
+    pub base_manifest: FileHandle,
+    /// Hash of the state tree root.
+    pub root_hash: HashValue,
+    /// All changes in chunks.
+    pub chunks: Vec<StateSnapshotDiffChunk>,
+    /// BCS serialized `Tuple(TransactionInfoWithProof, LedgerInfoWithSignatures)`, same as
+    /// `StateSnapshotBackup::proof`.
+    pub proof: FileHandle,
+}
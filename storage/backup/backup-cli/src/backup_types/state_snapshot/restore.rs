@@ -4,7 +4,8 @@
 
 use crate::{
     backup_types::{
-        epoch_ending::restore::EpochHistory, state_snapshot::manifest::StateSnapshotBackup,
+        epoch_ending::restore::EpochHistory,
+        state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotDiffBackup},
     },
     metrics::{
         restore::{
@@ -23,9 +24,11 @@ use crate::{
     },
 };
 use anyhow::{anyhow, ensure, Result};
-use aptos_db::state_restore::StateSnapshotRestoreMode;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_db::state_restore::{StateSnapshotRestore, StateSnapshotRestoreMode};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
+use aptos_push_metrics::IntGauge;
 use aptos_storage_interface::StateSnapshotReceiver;
 use aptos_types::{
     access_path::Path,
@@ -40,16 +43,28 @@ use aptos_types::{
     vm::configs::aptos_prod_verifier_config,
 };
 use clap::Parser;
-use futures::{stream, TryStreamExt};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
 use move_binary_format::CompiledModule;
 use move_bytecode_verifier::verify_module_with_config;
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 use tokio::time::Instant;
 
+type Receiver = Arc<Mutex<Option<StateSnapshotRestore<StateKey, StateValue>>>>;
+
 #[derive(Parser)]
 pub struct StateSnapshotRestoreOpt {
     #[clap(long = "state-manifest")]
     pub manifest_handle: FileHandle,
+    #[clap(
+        long = "state-diff-manifest",
+        help = "Differential state snapshots to apply on top of the one specified by \
+        `--state-manifest`, oldest first. Each must be taken against the previous one. If \
+        specified, the state is restored to the version of the last one."
+    )]
+    pub diff_manifest_handles: Vec<FileHandle>,
     #[clap(long = "state-into-version")]
     pub version: Version,
     #[clap(long)]
@@ -64,6 +79,7 @@ pub struct StateSnapshotRestoreController {
     /// State snapshot restores to this version.
     version: Version,
     manifest_handle: FileHandle,
+    diff_manifest_handles: Vec<FileHandle>,
     /// Global "target_version" for the entire restore process, if `version` is newer than this,
     /// nothing will be done, otherwise, this has no effect.
     target_version: Version,
@@ -85,6 +101,7 @@ impl StateSnapshotRestoreController {
             run_mode: global_opt.run_mode,
             version: opt.version,
             manifest_handle: opt.manifest_handle,
+            diff_manifest_handles: opt.diff_manifest_handles,
             target_version: global_opt.target_version,
             epoch_history,
             concurrent_downloads: global_opt.concurrent_downloads,
@@ -122,16 +139,42 @@ impl StateSnapshotRestoreController {
 
         let manifest: StateSnapshotBackup =
             self.storage.load_json_file(&self.manifest_handle).await?;
+        let mut diffs: Vec<StateSnapshotDiffBackup> = vec![];
+        for handle in &self.diff_manifest_handles {
+            let diff: StateSnapshotDiffBackup = self.storage.load_json_file(handle).await?;
+            let base_version = diffs.last().map_or(manifest.version, |d| d.version);
+            ensure!(
+                diff.base_version == base_version,
+                "Differential state snapshot {} is taken against version {}, expected {}.",
+                handle,
+                diff.base_version,
+                base_version,
+            );
+            diffs.push(diff);
+        }
+        if let Some(diff) = diffs.last() {
+            ensure!(
+                diff.version == self.version,
+                "The last differential state snapshot is at version {}, restoring into version {}.",
+                diff.version,
+                self.version,
+            );
+        }
+        // Only the resulting state needs to be proven, the ones in between are never restored.
+        let (target_version, target_root_hash, target_proof) = match diffs.last() {
+            Some(diff) => (diff.version, diff.root_hash, &diff.proof),
+            None => (manifest.version, manifest.root_hash, &manifest.proof),
+        };
         let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
-            self.storage.load_bcs_file(&manifest.proof).await?;
-        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+            self.storage.load_bcs_file(target_proof).await?;
+        txn_info_with_proof.verify(li.ledger_info(), target_version)?;
         let state_root_hash = txn_info_with_proof
             .transaction_info()
             .ensure_state_checkpoint_hash()?;
         ensure!(
-            state_root_hash == manifest.root_hash,
+            state_root_hash == target_root_hash,
             "Root hash mismatch with that in proof. root hash: {}, expected: {}",
-            manifest.root_hash,
+            target_root_hash,
             state_root_hash,
         );
         if let Some(epoch_history) = self.epoch_history.as_ref() {
@@ -140,7 +183,7 @@ impl StateSnapshotRestoreController {
 
         let receiver = Arc::new(Mutex::new(Some(self.run_mode.get_state_restore_receiver(
             self.version,
-            manifest.root_hash,
+            target_root_hash,
             self.restore_mode,
         )?)));
 
@@ -159,10 +202,23 @@ impl StateSnapshotRestoreController {
         };
 
         ver_gauge.set(self.version as i64);
+        let resume_point_opt = receiver.lock().as_mut().unwrap().previous_key_hash()?;
+        if !diffs.is_empty() {
+            return self
+                .run_with_diffs(
+                    manifest,
+                    diffs,
+                    receiver,
+                    resume_point_opt,
+                    tgt_leaf_idx,
+                    leaf_idx,
+                )
+                .await;
+        }
+
         tgt_leaf_idx.set(manifest.chunks.last().map_or(0, |c| c.last_idx as i64));
         let total_chunks = manifest.chunks.len();
 
-        let resume_point_opt = receiver.lock().as_mut().unwrap().previous_key_hash()?;
         let chunks = if let Some(resume_point) = resume_point_opt {
             manifest
                 .chunks
@@ -232,6 +288,150 @@ impl StateSnapshotRestoreController {
         Ok(())
     }
 
+    /// Restores the state resulting from applying `diffs` on top of the full snapshot `base`, by
+    /// merging the state items in the base with the changes on the fly. The merged state is then
+    /// fed to the receiver in the chunks of the last diff, whose range proofs are against the
+    /// resulting state. The base and every diff are read chunk by chunk in key hash order, so
+    /// only a few chunks of each are held in memory at a time.
+    async fn run_with_diffs(
+        &self,
+        base: StateSnapshotBackup,
+        mut diffs: Vec<StateSnapshotDiffBackup>,
+        receiver: Receiver,
+        resume_point_opt: Option<HashValue>,
+        tgt_leaf_idx: &IntGauge,
+        leaf_idx: &IntGauge,
+    ) -> Result<()> {
+        let target = diffs.pop().expect("Checked by the caller.");
+        tgt_leaf_idx.set(target.chunks.last().map_or(0, |c| c.last_idx as i64));
+
+        let storage = self.storage.clone();
+        let base_chunks = stream::iter(
+            base.chunks
+                .into_iter()
+                .filter(move |chunk| resume_point_opt.map_or(true, |p| chunk.last_key > p))
+                .map(move |chunk| {
+                    let storage = storage.clone();
+                    async move {
+                        tokio::spawn(async move {
+                            let leaves = Self::read_state_value(&storage, chunk.blobs).await?;
+                            Result::<_>::Ok(
+                                leaves
+                                    .into_iter()
+                                    .map(|(key, value)| (key.hash(), (key, value)))
+                                    .collect::<Vec<_>>(),
+                            )
+                        })
+                        .await?
+                    }
+                }),
+        );
+        let con = self.concurrent_downloads;
+        let changes = diffs
+            .iter()
+            .chain(std::iter::once(&target))
+            .map(|diff| {
+                // Changes beyond the last key of a diff are in its last chunk.
+                let num_chunks = diff.chunks.len();
+                let handles: Vec<FileHandle> = diff
+                    .chunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, chunk)| {
+                        *idx + 1 == num_chunks
+                            || resume_point_opt.map_or(true, |p| chunk.last_key > p)
+                    })
+                    .filter_map(|(_, chunk)| chunk.changes.clone())
+                    .collect();
+                let storage = self.storage.clone();
+                let chunks = stream::iter(handles.into_iter().map(move |handle| {
+                    let storage = storage.clone();
+                    async move {
+                        tokio::spawn(
+                            async move { Self::read_state_changes(&storage, &handle).await },
+                        )
+                        .await?
+                    }
+                }));
+                SortedChunks::new(chunks.buffered_x(2, 1).boxed(), resume_point_opt)
+            })
+            .collect();
+        let mut merger = StateDiffMerger {
+            base: SortedChunks::new(
+                base_chunks.buffered_x(con * 2, con).boxed(),
+                resume_point_opt,
+            ),
+            changes,
+        };
+
+        let total_chunks = target.chunks.len();
+        let chunks: Vec<_> = target
+            .chunks
+            .into_iter()
+            .skip_while(|chunk| resume_point_opt.map_or(false, |p| chunk.last_key <= p))
+            .collect();
+        if chunks.len() < total_chunks {
+            info!(
+                chunks_to_add = chunks.len(),
+                total_chunks = total_chunks,
+                "Resumed state snapshot restore."
+            )
+        };
+        let chunks_to_add = chunks.len();
+        let start_idx = chunks.first().map_or(0, |chunk| chunk.first_idx);
+
+        let start = Instant::now();
+        for (chunk_idx, chunk) in chunks.into_iter().enumerate() {
+            let _timer = OTHER_TIMERS_SECONDS
+                .with_label_values(&["add_state_chunk"])
+                .start_timer();
+            let mut blobs = merger.take_until(chunk.last_key).await?;
+            // A resumed restore can start in the middle of a chunk.
+            ensure!(
+                (chunk_idx == 0 && resume_point_opt.is_some())
+                    || blobs.len() == chunk.last_idx + 1 - chunk.first_idx,
+                "Expecting {} state items in chunk {}-{}, got {}.",
+                chunk.last_idx + 1 - chunk.first_idx,
+                chunk.first_idx,
+                chunk.last_idx,
+                blobs.len(),
+            );
+            let proof = self.storage.load_bcs_file(&chunk.proof).await?;
+            if self.validate_modules {
+                blobs = tokio::task::spawn_blocking(move || {
+                    Self::validate_modules(&blobs);
+                    blobs
+                })
+                .await?;
+            }
+            let receiver = receiver.clone();
+            tokio::task::spawn_blocking(move || {
+                receiver.lock().as_mut().unwrap().add_chunk(blobs, proof)
+            })
+            .await??;
+            leaf_idx.set(chunk.last_idx as i64);
+            info!(
+                chunk = chunk_idx,
+                chunks_to_add = chunks_to_add,
+                last_idx = chunk.last_idx,
+                values_per_second = ((chunk.last_idx + 1 - start_idx) as f64
+                    / start.elapsed().as_secs_f64()) as u64,
+                "State chunk added.",
+            );
+        }
+        ensure!(
+            merger
+                .take_until(HashValue::new([0xFF; HashValue::LENGTH]))
+                .await?
+                .is_empty(),
+            "State items found beyond the last chunk.",
+        );
+
+        tokio::task::spawn_blocking(move || receiver.lock().take().unwrap().finish()).await??;
+        self.run_mode.finish();
+        Ok(())
+    }
+
     fn validate_modules(blob: &[(StateKey, StateValue)]) {
         // TODO: Instead of using default features, fetch them from the the state.
         let features = Features::default();
@@ -266,4 +466,124 @@ impl StateSnapshotRestoreController {
 
         Ok(chunk)
     }
+
+    async fn read_state_changes(
+        storage: &Arc<dyn BackupStorage>,
+        file_handle: &FileHandle,
+    ) -> Result<Vec<(HashValue, (StateKey, Option<StateValue>))>> {
+        let mut file = storage.open_for_read(file_handle).await?;
+
+        let mut changes = vec![];
+
+        while let Some(record_bytes) = file.read_record_bytes().await? {
+            let (key, value): (StateKey, Option<StateValue>) = bcs::from_bytes(&record_bytes)?;
+            changes.push((key.hash(), (key, value)));
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Items in key hash order, read chunk by chunk.
+struct SortedChunks<T> {
+    chunks: BoxStream<'static, Result<Vec<(HashValue, T)>>>,
+    items: VecDeque<(HashValue, T)>,
+    /// Items up to and including this have been restored already.
+    resume_point: Option<HashValue>,
+}
+
+impl<T> SortedChunks<T> {
+    fn new(
+        chunks: BoxStream<'static, Result<Vec<(HashValue, T)>>>,
+        resume_point: Option<HashValue>,
+    ) -> Self {
+        Self {
+            chunks,
+            items: VecDeque::new(),
+            resume_point,
+        }
+    }
+
+    /// Returns the key hash of the next item, if any, reading the next chunk if needed.
+    async fn peek(&mut self) -> Result<Option<HashValue>> {
+        while self.items.is_empty() {
+            match self.chunks.try_next().await? {
+                Some(chunk) => {
+                    let resume_point = self.resume_point;
+                    self.items.extend(
+                        chunk
+                            .into_iter()
+                            .filter(|(hash, _)| resume_point.map_or(true, |p| *hash > p)),
+                    )
+                },
+                None => break,
+            }
+        }
+        Ok(self.items.front().map(|(hash, _)| *hash))
+    }
+
+    fn pop(&mut self) -> Option<(HashValue, T)> {
+        self.items.pop_front()
+    }
+}
+
+/// Merges the state items of a full snapshot, in key hash order, with the changes since in a
+/// series of differential snapshots, oldest first.
+struct StateDiffMerger {
+    base: SortedChunks<(StateKey, StateValue)>,
+    changes: Vec<SortedChunks<(StateKey, Option<StateValue>)>>,
+}
+
+impl StateDiffMerger {
+    /// Returns the key hash of the next change over all diffs, if any.
+    async fn peek_change(&mut self) -> Result<Option<HashValue>> {
+        let mut next = None;
+        for diff in &mut self.changes {
+            if let Some(hash) = diff.peek().await? {
+                next = Some(next.map_or(hash, |next: HashValue| next.min(hash)));
+            }
+        }
+        Ok(next)
+    }
+
+    /// Takes the change to `hash`, which must be the next one. Later changes to the same key
+    /// override earlier ones.
+    fn pop_change(&mut self, hash: HashValue) -> (StateKey, Option<StateValue>) {
+        let mut change = None;
+        for diff in &mut self.changes {
+            if diff.items.front().map(|(h, _)| *h) == Some(hash) {
+                change = diff.pop().map(|(_, change)| change);
+            }
+        }
+        change.expect("Peeked.")
+    }
+
+    /// Returns the merged state items with key hashes up to and including `last_key`.
+    async fn take_until(&mut self, last_key: HashValue) -> Result<Vec<(StateKey, StateValue)>> {
+        let mut leaves = vec![];
+        loop {
+            let base_key = self.base.peek().await?.filter(|hash| *hash <= last_key);
+            let change_key = self.peek_change().await?.filter(|hash| *hash <= last_key);
+            match (base_key, change_key) {
+                (None, None) => break,
+                (base_key, Some(change_key))
+                    if base_key.map_or(true, |base_key| change_key <= base_key) =>
+                {
+                    let (key, value) = self.pop_change(change_key);
+                    if base_key == Some(change_key) {
+                        // Overwritten or deleted.
+                        self.base.pop();
+                    }
+                    if let Some(value) = value {
+                        leaves.push((key, value));
+                    }
+                },
+                _ => {
+                    let (_, (key, value)) = self.base.pop().expect("Peeked.");
+                    leaves.push((key, value));
+                },
+            }
+        }
+        Ok(leaves)
+    }
 }
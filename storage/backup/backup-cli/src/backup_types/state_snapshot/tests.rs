@@ -5,6 +5,7 @@
 use crate::{
     backup_types::state_snapshot::{
         backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        diff_backup::{StateSnapshotDiffBackupController, StateSnapshotDiffBackupOpt},
         restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
     },
     storage::{local_fs::LocalFs, BackupStorage},
@@ -18,6 +19,7 @@ use crate::{
 use aptos_db::{state_restore::StateSnapshotRestoreMode, AptosDB};
 use aptos_storage_interface::DbReader;
 use aptos_temppath::TempPath;
+use aptos_types::transaction::Version;
 use std::{convert::TryInto, sync::Arc};
 use tokio::time::Duration;
 
@@ -77,6 +79,7 @@ fn end_to_end() {
         StateSnapshotRestoreController::new(
             StateSnapshotRestoreOpt {
                 manifest_handle,
+                diff_manifest_handles: vec![],
                 version,
                 validate_modules: false,
                 restore_mode: StateSnapshotRestoreMode::Default,
@@ -111,3 +114,126 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+fn epoch_ending_version(db: &AptosDB, epoch: u64) -> Version {
+    db.get_epoch_ending_ledger_infos(epoch, epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap()
+        .ledger_info()
+        .version()
+}
+
+#[test]
+fn diff_end_to_end() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let epoch = src_db
+        .get_latest_ledger_info()
+        .unwrap()
+        .ledger_info()
+        .next_block_epoch()
+        - 1;
+    let base_epoch = epoch / 2;
+    let mid_epoch = (base_epoch + epoch) / 2;
+    let version = epoch_ending_version(&src_db, epoch);
+    let state_root_hash = src_db
+        .get_transactions(version, 1, version, false)
+        .unwrap()
+        .proof
+        .transaction_infos
+        .pop()
+        .unwrap()
+        .state_checkpoint_hash()
+        .unwrap();
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let global_backup_opt = GlobalBackupOpt {
+        max_chunk_size: 500,
+        concurrent_data_requests: 2,
+    };
+    let base_manifest = rt
+        .block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt { epoch: base_epoch },
+                global_backup_opt.clone(),
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+    // Chain two differential snapshots, with chunks small enough so there are many of them.
+    let mut diff_manifest_handles = vec![];
+    let mut base_manifest_of_next = base_manifest.clone();
+    for epoch in [mid_epoch, epoch] {
+        let diff_manifest = rt
+            .block_on(
+                StateSnapshotDiffBackupController::new(
+                    StateSnapshotDiffBackupOpt {
+                        epoch,
+                        base_manifest: base_manifest_of_next,
+                        leaves_per_chunk: 7,
+                    },
+                    global_backup_opt.clone(),
+                    Arc::clone(&client),
+                    Arc::clone(&store),
+                )
+                .run(),
+            )
+            .unwrap();
+        diff_manifest_handles.push(diff_manifest.clone());
+        base_manifest_of_next = diff_manifest;
+    }
+
+    let restore = |version| {
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle: base_manifest.clone(),
+                diff_manifest_handles: diff_manifest_handles.clone(),
+                version,
+                validate_modules: false,
+                restore_mode: StateSnapshotRestoreMode::Default,
+            },
+            GlobalRestoreOpt {
+                dry_run: false,
+                db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                enable_state_indices: false,
+            }
+            .try_into()
+            .unwrap(),
+            Arc::clone(&store),
+            None, /* epoch_history */
+        )
+        .run()
+    };
+    // The state can only be restored into the version of the last diff.
+    assert!(rt.block_on(restore(version - 1)).is_err());
+    rt.block_on(restore(version)).unwrap();
+
+    let tgt_db = AptosDB::new_readonly_for_test(&tgt_db_dir);
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(version + 1)
+            .unwrap()
+            .unwrap(),
+        (version, state_root_hash)
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -134,6 +134,7 @@ fn test_end_to_end_impl(d: TestData) {
             StateSnapshotRestoreController::new(
                 StateSnapshotRestoreOpt {
                     manifest_handle: state_snapshot_manifest.unwrap(),
+                    diff_manifest_handles: vec![],
                     version,
                     validate_modules: false,
                     restore_mode: StateSnapshotRestoreMode::Default,
@@ -5,13 +5,17 @@
 use crate::{
     backup_types::{
         epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
-        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        state_snapshot::{
+            backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            diff_backup::{StateSnapshotDiffBackupController, StateSnapshotDiffBackupOpt},
+        },
         transaction::backup::{TransactionBackupController, TransactionBackupOpt},
     },
     metadata,
     metadata::{cache::MetadataCacheOpt, view::MetadataView, CompactionTimestampsMeta, Metadata},
     metrics::backup::{
-        EPOCH_ENDING_EPOCH, HEARTBEAT_TS, STATE_SNAPSHOT_EPOCH, TRANSACTION_VERSION,
+        EPOCH_ENDING_EPOCH, HEARTBEAT_TS, STATE_SNAPSHOT_DIFF_EPOCH, STATE_SNAPSHOT_EPOCH,
+        TRANSACTION_VERSION,
     },
     storage::{BackupStorage, FileHandle},
     utils::{
@@ -56,6 +60,17 @@ pub struct BackupCoordinatorOpt {
         is already at 19, then snapshot at 15 will be taken instead of at 10 (not at 18)."
     )]
     pub state_snapshot_interval_epochs: usize,
+    #[clap(
+        long,
+        help = "If set, frequency (in number of epochs) to take differential state snapshots in \
+        between the full ones. Each is taken against the latest state snapshot in the backup \
+        storage, full or differential, and records only the state items changed since, so it's \
+        much cheaper than a full snapshot. On restore, the chain of differential snapshots is \
+        applied on top of the full snapshot it leads back to. Note that versions of the backup \
+        tools older than this one can't read the metadata of a backup storage holding \
+        differential snapshots."
+    )]
+    pub state_snapshot_diff_interval_epochs: Option<usize>,
     // Defaulting to 1M, which converts to a 20 minutes delay of a transaction showing up in a backup,
     // from a 1K TPS chain, and a few minutes replay time.
     #[clap(
@@ -76,7 +91,9 @@ pub struct BackupCoordinatorOpt {
 impl BackupCoordinatorOpt {
     fn validate(&self) -> Result<()> {
         ensure!(
-            self.state_snapshot_interval_epochs > 0 && self.transaction_batch_size > 0,
+            self.state_snapshot_interval_epochs > 0
+                && self.state_snapshot_diff_interval_epochs != Some(0)
+                && self.transaction_batch_size > 0,
             "Backup interval and batch size must be greater than 0."
         );
         Ok(())
@@ -89,6 +106,7 @@ pub struct BackupCoordinator {
     global_opt: GlobalBackupOpt,
     metadata_cache_opt: MetadataCacheOpt,
     state_snapshot_interval_epochs: usize,
+    state_snapshot_diff_interval_epochs: Option<usize>,
     transaction_batch_size: usize,
     concurrent_downloads: usize,
 }
@@ -107,6 +125,7 @@ impl BackupCoordinator {
             global_opt,
             metadata_cache_opt: opt.metadata_cache_opt,
             state_snapshot_interval_epochs: opt.state_snapshot_interval_epochs,
+            state_snapshot_diff_interval_epochs: opt.state_snapshot_diff_interval_epochs,
             transaction_batch_size: opt.transaction_batch_size,
             concurrent_downloads: opt.concurrent_downloads.get(),
         }
@@ -159,13 +178,23 @@ impl BackupCoordinator {
             )
             .boxed_local();
 
-        info!("Backup coordinator started.");
-        let mut all_work = stream::select_all(vec![
+        let mut work_streams = vec![
             watch_db_state,
             backup_epoch_endings,
             backup_state_snapshots,
             backup_transactions,
-        ]);
+        ];
+        if self.state_snapshot_diff_interval_epochs.is_some() {
+            // The initial state doesn't matter, the latest snapshot in the storage is checked
+            // before taking each differential one.
+            work_streams.push(
+                self.backup_work_stream(None, &rx2, Self::backup_state_snapshot_diff)
+                    .boxed_local(),
+            );
+        }
+
+        info!("Backup coordinator started.");
+        let mut all_work = stream::select_all(work_streams);
 
         loop {
             all_work
@@ -267,6 +296,55 @@ impl BackupCoordinator {
         Ok(Some(epoch))
     }
 
+    async fn backup_state_snapshot_diff(
+        &self,
+        last_diff_epoch_in_backup: Option<u64>,
+        db_state: DbState,
+    ) -> Result<Option<u64>> {
+        let interval = self
+            .state_snapshot_diff_interval_epochs
+            .expect("Only scheduled if set.");
+        if let Some(epoch) = last_diff_epoch_in_backup {
+            STATE_SNAPSHOT_DIFF_EPOCH.set(epoch as i64);
+        }
+        let epoch = get_next_snapshot(last_diff_epoch_in_backup, db_state, interval);
+
+        // <= because db_state.epoch is still open
+        if db_state.epoch <= epoch {
+            // wait for the next db_state update
+            return Ok(last_diff_epoch_in_backup);
+        }
+
+        let base = metadata::cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?
+        .select_state_snapshot_chain(Version::MAX)?;
+        let base = match base {
+            Some(base) if base.epoch() < epoch => base,
+            // Nothing to take the difference against yet, or a snapshot as recent is already
+            // there.
+            _ => return Ok(last_diff_epoch_in_backup),
+        };
+
+        StateSnapshotDiffBackupController::new(
+            StateSnapshotDiffBackupOpt {
+                epoch,
+                base_manifest: base.manifest().clone(),
+                leaves_per_chunk: StateSnapshotDiffBackupOpt::DEFAULT_LEAVES_PER_CHUNK,
+            },
+            self.global_opt.clone(),
+            Arc::clone(&self.client),
+            Arc::clone(&self.storage),
+        )
+        .run()
+        .await?;
+
+        Ok(Some(epoch))
+    }
+
     async fn backup_transactions(
         &self,
         mut last_transaction_version_in_backup: Option<Version>,
@@ -448,6 +526,15 @@ impl BackupCompactor {
                 .await?;
             new_files.insert(file_handle);
         }
+        for range in metaview.compact_state_diff_backups(self.state_snapshot_file_compact_factor)? {
+            let (state_range, file_name) =
+                Metadata::compact_state_snapshot_diff_backup_range(range.to_vec())?;
+            let file_handle = self
+                .storage
+                .save_metadata_lines(&file_name, state_range.as_slice())
+                .await?;
+            new_files.insert(file_handle);
+        }
 
         // Move expired files to the metadata backup folder
         let (to_move, compaction_meta) =
@@ -178,6 +178,7 @@ impl ReplayVerifyCoordinator {
                 StateSnapshotRestoreController::new(
                     StateSnapshotRestoreOpt {
                         manifest_handle: backup.manifest,
+                        diff_manifest_handles: vec![],
                         version: backup.version,
                         validate_modules: self.validate_modules,
                         restore_mode: Default::default(),
@@ -161,9 +161,9 @@ impl RestoreCoordinator {
                     // already restored the kv snapshot, no need to restore again
                     None
                 } else {
-                    let snapshot = metadata_view.select_state_snapshot(ver)?;
+                    let snapshot = metadata_view.select_state_snapshot_chain(ver)?;
                     ensure!(
-                        snapshot.is_some() && snapshot.as_ref().unwrap().version == ver,
+                        snapshot.is_some() && snapshot.as_ref().unwrap().version() == ver,
                         "cannot find in-progress state snapshot {}",
                         ver
                     );
@@ -176,36 +176,36 @@ impl RestoreCoordinator {
                     "DB should be empty if no in-progress state snapshot found"
                 );
                 metadata_view
-                    .select_state_snapshot(std::cmp::min(lhs, max_txn_ver))
+                    .select_state_snapshot_chain(std::cmp::min(lhs, max_txn_ver))
                     .expect("Cannot find any snapshot before ledger history start version")
             },
         };
 
         let tree_snapshot = if let Some((latest_tree_version, _)) = latest_tree_version {
-            let snapshot = metadata_view.select_state_snapshot(latest_tree_version)?;
+            let snapshot = metadata_view.select_state_snapshot_chain(latest_tree_version)?;
 
             ensure!(
-                snapshot.is_some() && snapshot.as_ref().unwrap().version == latest_tree_version,
+                snapshot.is_some() && snapshot.as_ref().unwrap().version() == latest_tree_version,
                 "cannot find tree snapshot {}",
                 latest_tree_version
             );
             snapshot.unwrap()
         } else {
             metadata_view
-                .select_state_snapshot(target_version)?
+                .select_state_snapshot_chain(target_version)?
                 .expect("Cannot find tree snapshot before target version")
         };
 
         let mut do_phase_1 = if let Some(kv_snapshot) = kv_snapshot.as_ref() {
             // if we have a kv snapshot, we need to restore the state between lhs and rs
             // if the version are equal, we don't need to restore phase 1. we can directly restore a snapshot with both tree and KV, and then replay txn till the target_version
-            kv_snapshot.version < tree_snapshot.version
+            kv_snapshot.version() < tree_snapshot.version()
         } else {
             // if we don't have a kv snapshot, we need to restore the state between db_next_version and rs
-            db_next_version < tree_snapshot.version
+            db_next_version < tree_snapshot.version()
         };
         let txn_start_version = if let Some(kv_snapshot) = kv_snapshot.as_ref() {
-            kv_snapshot.version
+            kv_snapshot.version()
         } else {
             db_next_version
         };
@@ -237,18 +237,20 @@ impl RestoreCoordinator {
         if do_phase_1 {
             info!(
                 "Start restoring DB from version {} to tree snapshot version {}",
-                txn_start_version, tree_snapshot.version,
+                txn_start_version,
+                tree_snapshot.version(),
             );
 
             // phase 1.a: restore the kv snapshot
             if kv_snapshot.is_some() {
                 let kv_snapshot = kv_snapshot.clone().unwrap();
-                info!("Start restoring KV snapshot at {}", kv_snapshot.version);
+                info!("Start restoring KV snapshot at {}", kv_snapshot.version());
 
                 StateSnapshotRestoreController::new(
                     StateSnapshotRestoreOpt {
-                        manifest_handle: kv_snapshot.manifest,
-                        version: kv_snapshot.version,
+                        manifest_handle: kv_snapshot.base.manifest.clone(),
+                        diff_manifest_handles: kv_snapshot.diff_manifests(),
+                        version: kv_snapshot.version(),
                         validate_modules: false,
                         restore_mode: StateSnapshotRestoreMode::KvOnly,
                     },
@@ -264,7 +266,7 @@ impl RestoreCoordinator {
             let txn_manifests = transaction_backups
                 .iter()
                 .filter(|e| {
-                    e.first_version <= tree_snapshot.version && e.last_version >= db_next_version
+                    e.first_version <= tree_snapshot.version() && e.last_version >= db_next_version
                 })
                 .map(|e| e.manifest.clone())
                 .collect();
@@ -282,11 +284,11 @@ impl RestoreCoordinator {
             // We should replay kv to include the version of tree snapshot so that we can get correct storage usage at that version
             // while restore tree only snapshots
             let kv_replay_version = if let Some(kv_snapshot) = kv_snapshot.as_ref() {
-                kv_snapshot.version + 1
+                kv_snapshot.version() + 1
             } else {
                 db_next_version
             };
-            transaction_restore_opt.target_version = tree_snapshot.version;
+            transaction_restore_opt.target_version = tree_snapshot.version();
             TransactionRestoreBatchController::new(
                 transaction_restore_opt,
                 Arc::clone(&self.storage),
@@ -300,7 +302,7 @@ impl RestoreCoordinator {
             .run()
             .await?;
             // update the expected version for the first phase restore
-            db_next_version = tree_snapshot.version;
+            db_next_version = tree_snapshot.version();
         }
 
         // Phase 2: restore the full tree snapshot and replay till the target version
@@ -329,12 +331,14 @@ impl RestoreCoordinator {
                 if let Some(restore_mode) = restore_mode_opt {
                     info!(
                         "Start restoring tree snapshot at {} with db_next_version {}",
-                        tree_snapshot.version, db_next_version
+                        tree_snapshot.version(),
+                        db_next_version
                     );
                     StateSnapshotRestoreController::new(
                         StateSnapshotRestoreOpt {
-                            manifest_handle: tree_snapshot.manifest.clone(),
-                            version: tree_snapshot.version,
+                            manifest_handle: tree_snapshot.base.manifest.clone(),
+                            diff_manifest_handles: tree_snapshot.diff_manifests(),
+                            version: tree_snapshot.version(),
                             validate_modules: false,
                             restore_mode,
                         },
@@ -347,7 +351,7 @@ impl RestoreCoordinator {
                 }
 
                 replay_version = Some((
-                    tree_snapshot.version + 1,
+                    tree_snapshot.version() + 1,
                     false, /*replay entire txn including update tree and KV*/
                 ));
             }
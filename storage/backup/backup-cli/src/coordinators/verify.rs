@@ -91,7 +91,7 @@ impl VerifyCoordinator {
         .await?;
         let ver_max = Version::max_value();
         let state_snapshot =
-            metadata_view.select_state_snapshot(self.state_snapshot_before_version)?;
+            metadata_view.select_state_snapshot_chain(self.state_snapshot_before_version)?;
         let transactions =
             metadata_view.select_transaction_backups(self.start_version, self.end_version)?;
         let epoch_endings = metadata_view.select_epoch_ending_backups(ver_max)?;
@@ -123,14 +123,16 @@ impl VerifyCoordinator {
 
         if let Some(backup) = state_snapshot {
             info!(
-                epoch = backup.epoch,
-                version = backup.version,
+                epoch = backup.epoch(),
+                version = backup.version(),
+                num_diffs = backup.diffs.len(),
                 "State snapshot selected for verification."
             );
             StateSnapshotRestoreController::new(
                 StateSnapshotRestoreOpt {
-                    manifest_handle: backup.manifest,
-                    version: backup.version,
+                    manifest_handle: backup.base.manifest.clone(),
+                    diff_manifest_handles: backup.diff_manifests(),
+                    version: backup.version(),
                     validate_modules: self.validate_modules,
                     restore_mode: StateSnapshotRestoreMode::Default,
                 },
@@ -240,9 +240,39 @@ impl<R: AsyncRead + Send + Unpin> LoadMetadataLines for R {
         self.read_to_string(&mut buf)
             .await
             .err_notes((file!(), line!(), &buf))?;
-        Ok(buf
-            .lines()
-            .map(serde_json::from_str::<Metadata>)
-            .collect::<Result<_, serde_json::error::Error>>()?)
+        let mut metadata_vec = Vec::new();
+        for line in buf.lines() {
+            match Metadata::from_text_line(line)? {
+                Some(metadata) => metadata_vec.push(metadata),
+                None => warn!(line = line, "Skipping metadata of unknown kind."),
+            }
+        }
+        Ok(metadata_vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::StateSnapshotBackupMeta;
+
+    #[tokio::test]
+    async fn test_load_metadata_lines_skips_unknown_kinds() {
+        let known = Metadata::new_state_snapshot_backup(1, 100, "manifest".to_string());
+        let lines = format!(
+            "{}{}\n",
+            known.to_text_line().unwrap().as_ref(),
+            r#"{"FutureBackup":{"version":100}}"#,
+        );
+        let metadata_vec = lines.as_bytes().load_metadata_lines().await.unwrap();
+        assert_eq!(metadata_vec.len(), 1);
+        assert!(matches!(
+            metadata_vec[0],
+            Metadata::StateSnapshotBackup(StateSnapshotBackupMeta { version: 100, .. })
+        ));
+
+        // Malformed lines of a known kind are still errors.
+        let lines = r#"{"StateSnapshotBackup":{"version":100}}"#;
+        assert!(lines.as_bytes().load_metadata_lines().await.is_err());
     }
 }
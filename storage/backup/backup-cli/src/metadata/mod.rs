@@ -17,12 +17,23 @@ use std::{cmp::Ordering, collections::HashMap, convert::TryInto};
 pub(crate) enum Metadata {
     EpochEndingBackup(EpochEndingBackupMeta),
     StateSnapshotBackup(StateSnapshotBackupMeta),
+    StateSnapshotDiffBackup(StateSnapshotDiffBackupMeta),
     TransactionBackup(TransactionBackupMeta),
     Identity(IdentityMeta),
     CompactionTimestamps(CompactionTimestampsMeta),
 }
 
 impl Metadata {
+    // The serialized names of the variants.
+    const KNOWN_KINDS: &'static [&'static str] = &[
+        "EpochEndingBackup",
+        "StateSnapshotBackup",
+        "StateSnapshotDiffBackup",
+        "TransactionBackup",
+        "Identity",
+        "CompactionTimestamps",
+    ];
+
     pub fn new_epoch_ending_backup(
         first_epoch: u64,
         last_epoch: u64,
@@ -47,6 +58,22 @@ impl Metadata {
         })
     }
 
+    pub fn new_state_snapshot_diff_backup(
+        epoch: u64,
+        version: Version,
+        base_version: Version,
+        base_manifest: FileHandle,
+        manifest: FileHandle,
+    ) -> Self {
+        Self::StateSnapshotDiffBackup(StateSnapshotDiffBackupMeta {
+            epoch,
+            version,
+            base_version,
+            base_manifest,
+            manifest,
+        })
+    }
+
     pub fn new_transaction_backup(
         first_version: Version,
         last_version: Version,
@@ -112,6 +139,25 @@ impl Metadata {
         Ok((res, name.parse()?))
     }
 
+    pub fn compact_state_snapshot_diff_backup_range(
+        backup_metas: Vec<StateSnapshotDiffBackupMeta>,
+    ) -> Result<(Vec<TextLine>, ShellSafeName)> {
+        ensure!(
+            !backup_metas.is_empty(),
+            "compacting an empty metadata vector"
+        );
+        let name = format!(
+            "state_snapshot_diff_compacted_epoch_{}_{}.meta",
+            backup_metas[0].epoch,
+            backup_metas[backup_metas.len() - 1].epoch
+        );
+        let res: Vec<TextLine> = backup_metas
+            .into_iter()
+            .map(|e| Metadata::StateSnapshotDiffBackup(e).to_text_line())
+            .collect::<Result<_>>()?;
+        Ok((res, name.parse()?))
+    }
+
     pub fn compact_transaction_backup_range(
         backup_metas: Vec<TransactionBackupMeta>,
     ) -> Result<(Vec<TextLine>, ShellSafeName)> {
@@ -156,6 +202,9 @@ impl Metadata {
                 format!("epoch_ending_{}-{}.meta", e.first_epoch, e.last_epoch)
             },
             Self::StateSnapshotBackup(s) => format!("state_snapshot_ver_{}.meta", s.version),
+            Self::StateSnapshotDiffBackup(s) => {
+                format!("state_snapshot_diff_ver_{}.meta", s.version)
+            },
             Self::TransactionBackup(t) => {
                 format!("transaction_{}-{}.meta", t.first_version, t.last_version)
             },
@@ -171,6 +220,28 @@ impl Metadata {
     pub fn to_text_line(&self) -> Result<TextLine> {
         TextLine::new(&serde_json::to_string(self)?)
     }
+
+    /// Parses a metadata line. Returns `None` for the kinds of metadata unknown to this version,
+    /// e.g. ones added by newer versions, so that the rest of the backup can still be used.
+    pub fn from_text_line(line: &str) -> Result<Option<Self>> {
+        match serde_json::from_str::<Self>(line) {
+            Ok(metadata) => Ok(Some(metadata)),
+            Err(err) => {
+                let value: serde_json::Value = serde_json::from_str(line)?;
+                match value.as_object() {
+                    Some(object)
+                        if object.len() == 1
+                            && object
+                                .keys()
+                                .all(|kind| !Self::KNOWN_KINDS.contains(&kind.as_str())) =>
+                    {
+                        Ok(None)
+                    },
+                    _ => Err(err.into()),
+                }
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
@@ -189,6 +260,15 @@ pub struct StateSnapshotBackupMeta {
     pub manifest: FileHandle,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
+pub struct StateSnapshotDiffBackupMeta {
+    pub epoch: u64,
+    pub version: Version,
+    pub base_version: Version,
+    pub base_manifest: FileHandle,
+    pub manifest: FileHandle,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
 pub struct TransactionBackupMeta {
     pub first_version: Version,
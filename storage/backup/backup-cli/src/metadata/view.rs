@@ -5,7 +5,7 @@
 use crate::{
     metadata::{
         CompactionTimestampsMeta, EpochEndingBackupMeta, IdentityMeta, Metadata,
-        StateSnapshotBackupMeta, TransactionBackupMeta,
+        StateSnapshotBackupMeta, StateSnapshotDiffBackupMeta, TransactionBackupMeta,
     },
     metrics::backup::COMPACTED_TXN_VERSION,
     storage::FileHandle,
@@ -20,6 +20,7 @@ use std::{fmt, str::FromStr};
 pub struct MetadataView {
     epoch_ending_backups: Vec<EpochEndingBackupMeta>,
     state_snapshot_backups: Vec<StateSnapshotBackupMeta>,
+    state_snapshot_diff_backups: Vec<StateSnapshotDiffBackupMeta>,
     transaction_backups: Vec<TransactionBackupMeta>,
     _identity: Option<IdentityMeta>,
     // The compaction timestamps of the file handles producing this view
@@ -30,6 +31,7 @@ impl MetadataView {
     pub(crate) fn new(metadata_vec: Vec<Metadata>, file_handles: Vec<FileHandle>) -> Self {
         let mut epoch_ending_backups = Vec::new();
         let mut state_snapshot_backups = Vec::new();
+        let mut state_snapshot_diff_backups = Vec::new();
         let mut transaction_backups = Vec::new();
         let mut identity = None;
         let mut compaction_timestamps = Vec::new();
@@ -38,6 +40,7 @@ impl MetadataView {
             match meta {
                 Metadata::EpochEndingBackup(e) => epoch_ending_backups.push(e),
                 Metadata::StateSnapshotBackup(s) => state_snapshot_backups.push(s),
+                Metadata::StateSnapshotDiffBackup(s) => state_snapshot_diff_backups.push(s),
                 Metadata::TransactionBackup(t) => transaction_backups.push(t),
                 Metadata::Identity(i) => identity = Some(i),
                 Metadata::CompactionTimestamps(t) => compaction_timestamps.push(t),
@@ -47,6 +50,8 @@ impl MetadataView {
         epoch_ending_backups.dedup();
         state_snapshot_backups.sort_unstable();
         state_snapshot_backups.dedup();
+        state_snapshot_diff_backups.sort_unstable();
+        state_snapshot_diff_backups.dedup();
         transaction_backups.sort_unstable();
         transaction_backups.dedup();
 
@@ -72,6 +77,7 @@ impl MetadataView {
         Self {
             epoch_ending_backups,
             state_snapshot_backups,
+            state_snapshot_diff_backups,
             transaction_backups,
             _identity: identity,
             compaction_timestamps: compaction_meta_opt,
@@ -122,6 +128,66 @@ impl MetadataView {
             .cloned())
     }
 
+    /// Selects the latest state snapshot at or before `target_version`, which can be either a full
+    /// snapshot or a differential one whose chain of base snapshots leads to a full snapshot.
+    pub fn select_state_snapshot_chain(
+        &self,
+        target_version: Version,
+    ) -> Result<Option<StateSnapshotChain>> {
+        let full = self.select_state_snapshot(target_version)?;
+        for diff in self.state_snapshot_diff_backups.iter().sorted().rev() {
+            if diff.version > target_version {
+                continue;
+            }
+            if full
+                .as_ref()
+                .map_or(false, |full| diff.version <= full.version)
+            {
+                break;
+            }
+            if let Some(chain) = self.resolve_state_snapshot_chain(diff) {
+                return Ok(Some(chain));
+            }
+        }
+
+        Ok(full.map(|base| StateSnapshotChain {
+            base,
+            diffs: Vec::new(),
+        }))
+    }
+
+    /// Follows the base snapshots of `diff` back to a full snapshot. Returns None if any of them
+    /// is missing.
+    fn resolve_state_snapshot_chain(
+        &self,
+        diff: &StateSnapshotDiffBackupMeta,
+    ) -> Option<StateSnapshotChain> {
+        let mut diffs = vec![diff.clone()];
+        loop {
+            let base_manifest = &diffs.last().expect("Not empty.").base_manifest;
+            if let Some(base) = self
+                .state_snapshot_backups
+                .iter()
+                .find(|s| &s.manifest == base_manifest)
+            {
+                diffs.reverse();
+                return Some(StateSnapshotChain {
+                    base: base.clone(),
+                    diffs,
+                });
+            }
+            let base = self
+                .state_snapshot_diff_backups
+                .iter()
+                .find(|s| &s.manifest == base_manifest)?;
+            // Guard against a cycle among corrupted metadata.
+            if diffs.len() > self.state_snapshot_diff_backups.len() {
+                return None;
+            }
+            diffs.push(base.clone());
+        }
+    }
+
     pub fn expect_state_snapshot(&self, version: Version) -> Result<StateSnapshotBackupMeta> {
         self.state_snapshot_backups
             .iter()
@@ -239,6 +305,13 @@ impl MetadataView {
         Self::compact_backups(&self.state_snapshot_backups, compaction_cnt)
     }
 
+    pub fn compact_state_diff_backups(
+        &mut self,
+        compaction_cnt: usize,
+    ) -> Result<Vec<&[StateSnapshotDiffBackupMeta]>> {
+        Self::compact_backups(&self.state_snapshot_diff_backups, compaction_cnt)
+    }
+
     pub fn get_file_handles(&self) -> Vec<FileHandle> {
         self.select_latest_compaction_timestamps()
             .as_ref()
@@ -247,6 +320,40 @@ impl MetadataView {
     }
 }
 
+/// A full state snapshot, and the differential snapshots to apply on top of it in order to get to
+/// the state at `version()`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateSnapshotChain {
+    pub base: StateSnapshotBackupMeta,
+    pub diffs: Vec<StateSnapshotDiffBackupMeta>,
+}
+
+impl StateSnapshotChain {
+    pub fn epoch(&self) -> u64 {
+        self.diffs.last().map_or(self.base.epoch, |diff| diff.epoch)
+    }
+
+    pub fn version(&self) -> Version {
+        self.diffs
+            .last()
+            .map_or(self.base.version, |diff| diff.version)
+    }
+
+    /// Manifest of the newest snapshot in the chain.
+    pub fn manifest(&self) -> &FileHandle {
+        self.diffs
+            .last()
+            .map_or(&self.base.manifest, |diff| &diff.manifest)
+    }
+
+    pub fn diff_manifests(&self) -> Vec<FileHandle> {
+        self.diffs
+            .iter()
+            .map(|diff| diff.manifest.clone())
+            .collect()
+    }
+}
+
 pub struct BackupStorageState {
     pub latest_epoch_ending_epoch: Option<u64>,
     pub latest_state_snapshot_epoch: Option<u64>,
@@ -32,6 +32,14 @@ pub static STATE_SNAPSHOT_EPOCH: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static STATE_SNAPSHOT_DIFF_EPOCH: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_db_backup_coordinator_state_snapshot_diff_epoch",
+        "The epoch at the end of which the latest differential state snapshot was taken."
+    )
+    .unwrap()
+});
+
 pub static TRANSACTION_VERSION: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
         "aptos_db_backup_coordinator_transaction_version",
@@ -0,0 +1,521 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::s3::config::{S3Config, S3Credentials};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_logger::warn;
+use bytes::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Method, StatusCode, Url};
+use sha2_0_10_6::{Digest, Sha256};
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SIGNING_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const SERVICE_NAME: &str = "s3";
+
+/// A minimal client for the S3 HTTP API, covering what the backup storage needs. Requests are
+/// signed with AWS Signature Version 4, and failed requests are retried with exponential backoff
+/// if the failure is transient (connection errors, throttling and server errors).
+pub(super) struct S3Client {
+    http_client: reqwest::Client,
+    endpoint: Url,
+    bucket: String,
+    region: String,
+    credentials: S3Credentials,
+    max_retries: usize,
+    retry_base_delay: Duration,
+}
+
+impl S3Client {
+    pub fn new(config: &S3Config) -> Result<Self> {
+        ensure!(!config.bucket.is_empty(), "S3 bucket is not configured.");
+        let endpoint = Url::parse(&config.endpoint())?;
+        ensure!(
+            endpoint.host_str().is_some(),
+            "S3 endpoint has no host: {}",
+            endpoint
+        );
+
+        Ok(Self {
+            http_client: reqwest::Client::new(),
+            endpoint,
+            bucket: config.bucket.clone(),
+            region: config.region.clone(),
+            credentials: config.credentials()?,
+            max_retries: config.max_retries,
+            retry_base_delay: Duration::from_millis(config.retry_base_delay_ms),
+        })
+    }
+
+    pub async fn put_object(&self, key: &str, body: Bytes) -> Result<()> {
+        self.send(Method::PUT, Some(key), vec![], vec![], body)
+            .await?;
+        Ok(())
+    }
+
+    /// Reads the bytes in the (inclusive) range `[start, end]` of the object. Returns fewer bytes
+    /// than requested if the object ends within the range, and no bytes if it ends before it.
+    pub async fn get_object_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        let headers = vec![("range", format!("bytes={}-{}", start, end))];
+        let response = self
+            .send_allowing(Method::GET, Some(key), vec![], headers, Bytes::new(), &[
+                StatusCode::RANGE_NOT_SATISFIABLE,
+            ])
+            .await?;
+        match response.status() {
+            StatusCode::RANGE_NOT_SATISFIABLE => Ok(Bytes::new()),
+            StatusCode::PARTIAL_CONTENT => Ok(response.bytes().await?),
+            // The whole object was returned (e.g. the range was ignored for an empty object)
+            _ => {
+                let bytes = response.bytes().await?;
+                Ok(bytes.slice(std::cmp::min(start as usize, bytes.len())..))
+            },
+        }
+    }
+
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let response = self
+            .send(
+                Method::POST,
+                Some(key),
+                vec![("uploads", String::new())],
+                vec![],
+                Bytes::new(),
+            )
+            .await?;
+        let body = response.text().await?;
+        xml_values(&body, "UploadId")
+            .into_iter()
+            .next()
+            .ok_or_else(|| format_err!("No UploadId in CreateMultipartUpload response: {}", body))
+    }
+
+    /// Uploads a part of a multipart upload and returns its ETag.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<String> {
+        let query = vec![
+            ("partNumber", part_number.to_string()),
+            ("uploadId", upload_id.to_string()),
+        ];
+        let response = self
+            .send(Method::PUT, Some(key), query, vec![], body)
+            .await?;
+        response
+            .headers()
+            .get("etag")
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| format_err!("No ETag in UploadPart response for {}", key))
+    }
+
+    /// Completes a multipart upload, given the ETags of all parts in order.
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(idx, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    idx + 1,
+                    xml_escape(etag)
+                )
+            })
+            .collect();
+        let body = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            parts
+        );
+        let response = self
+            .send(
+                Method::POST,
+                Some(key),
+                vec![("uploadId", upload_id.to_string())],
+                vec![],
+                Bytes::from(body),
+            )
+            .await?;
+        // S3 can report a failure in the body of a 200 response to this request.
+        let body = response.text().await?;
+        if body.contains("<Error>") {
+            bail!("Failed to complete multipart upload of {}: {}", key, body);
+        }
+        Ok(())
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.send(
+            Method::DELETE,
+            Some(key),
+            vec![("uploadId", upload_id.to_string())],
+            vec![],
+            Bytes::new(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Lists the keys of all objects starting with `prefix`.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut query = vec![
+                ("list-type", "2".to_string()),
+                ("prefix", prefix.to_string()),
+            ];
+            if let Some(token) = continuation_token.take() {
+                query.push(("continuation-token", token));
+            }
+            let response = self
+                .send(Method::GET, None, query, vec![], Bytes::new())
+                .await?;
+            let body = response.text().await?;
+            keys.extend(xml_values(&body, "Key"));
+
+            let is_truncated = xml_values(&body, "IsTruncated")
+                .first()
+                .map_or(false, |value| value == "true");
+            if !is_truncated {
+                break;
+            }
+            continuation_token = Some(
+                xml_values(&body, "NextContinuationToken")
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| format_err!("Truncated listing without continuation token."))?,
+            );
+        }
+        Ok(keys)
+    }
+
+    pub async fn copy_object(&self, source_key: &str, destination_key: &str) -> Result<()> {
+        let copy_source = format!("/{}/{}", self.bucket, uri_encode(source_key, false));
+        let response = self
+            .send(
+                Method::PUT,
+                Some(destination_key),
+                vec![],
+                vec![("x-amz-copy-source", copy_source)],
+                Bytes::new(),
+            )
+            .await?;
+        // S3 can report a failure in the body of a 200 response to this request.
+        let body = response.text().await?;
+        if body.contains("<Error>") {
+            bail!(
+                "Failed to copy {} to {}: {}",
+                source_key,
+                destination_key,
+                body
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.send(Method::DELETE, Some(key), vec![], vec![], Bytes::new())
+            .await?;
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: Option<&str>,
+        query: Vec<(&'static str, String)>,
+        headers: Vec<(&'static str, String)>,
+        body: Bytes,
+    ) -> Result<reqwest::Response> {
+        self.send_allowing(method, key, query, headers, body, &[])
+            .await
+    }
+
+    /// Sends a signed request, retrying on transient failures. Responses with a status code other
+    /// than success or one of `allowed_statuses` are turned into errors carrying the response body.
+    async fn send_allowing(
+        &self,
+        method: Method,
+        key: Option<&str>,
+        query: Vec<(&'static str, String)>,
+        headers: Vec<(&'static str, String)>,
+        body: Bytes,
+        allowed_statuses: &[StatusCode],
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let request =
+                self.build_request(method.clone(), key, &query, &headers, body.clone())?;
+            let error = match self.http_client.execute(request).await {
+                Ok(response)
+                    if response.status().is_success()
+                        || allowed_statuses.contains(&response.status()) =>
+                {
+                    return Ok(response);
+                },
+                Ok(response) => {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    let error = format_err!(
+                        "S3 {} request for {:?} failed with status {}: {}",
+                        method,
+                        key,
+                        status,
+                        body
+                    );
+                    if !is_retryable(status) {
+                        return Err(error);
+                    }
+                    error
+                },
+                Err(error) => format_err!("S3 {} request for {:?} failed: {}", method, key, error),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(error);
+            }
+            let delay = self.retry_base_delay * 2u32.saturating_pow(attempt as u32);
+            warn!(
+                attempt = attempt,
+                delay_ms = delay.as_millis() as u64,
+                error = %error,
+                "S3 request failed, retrying."
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn build_request(
+        &self,
+        method: Method,
+        key: Option<&str>,
+        query: &[(&'static str, String)],
+        headers: &[(&'static str, String)],
+        body: Bytes,
+    ) -> Result<reqwest::Request> {
+        let mut path = format!(
+            "{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            uri_encode(&self.bucket, true)
+        );
+        if let Some(key) = key {
+            path.push('/');
+            path.push_str(&uri_encode(key, false));
+        }
+
+        let canonical_query = canonical_query(query);
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+        url.set_query(
+            if canonical_query.is_empty() {
+                None
+            } else {
+                Some(&canonical_query)
+            },
+        );
+
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", self.endpoint.host_str().unwrap_or_default(), port),
+            None => self.endpoint.host_str().unwrap_or_default().to_string(),
+        };
+
+        // All headers are signed, so they must be sorted by (lowercase) name
+        let mut signed_headers: Vec<(String, String)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+            .collect();
+        signed_headers.push(("host".to_string(), host));
+        signed_headers.push(("x-amz-content-sha256".to_string(), payload_hash.clone()));
+        signed_headers.push(("x-amz-date".to_string(), amz_date.clone()));
+        if let Some(session_token) = &self.credentials.session_token {
+            signed_headers.push(("x-amz-security-token".to_string(), session_token.clone()));
+        }
+        signed_headers.sort();
+
+        let authorization = CanonicalRequest {
+            method: method.as_str(),
+            uri: &path,
+            query: &canonical_query,
+            headers: &signed_headers,
+            payload_hash: &payload_hash,
+        }
+        .authorization(&self.credentials, &self.region, &amz_date);
+
+        let mut builder = self
+            .http_client
+            .request(method, url)
+            .header("authorization", authorization);
+        for (name, value) in signed_headers {
+            // The host header is set by the HTTP client itself
+            if name != "host" {
+                builder = builder.header(name, value);
+            }
+        }
+        Ok(builder.body(body).build()?)
+    }
+}
+
+/// The parts of a request covered by its AWS Signature Version 4.
+pub(super) struct CanonicalRequest<'a> {
+    pub method: &'a str,
+    /// The URI-encoded path.
+    pub uri: &'a str,
+    /// See `canonical_query`.
+    pub query: &'a str,
+    /// All signed headers, with lowercase names and trimmed values, sorted by name.
+    pub headers: &'a [(String, String)],
+    /// The hex encoded SHA-256 hash of the body.
+    pub payload_hash: &'a str,
+}
+
+impl<'a> CanonicalRequest<'a> {
+    /// Returns the value of the `Authorization` header of the request sent at `amz_date`
+    /// (formatted as `%Y%m%dT%H%M%SZ`).
+    pub fn authorization(
+        &self,
+        credentials: &S3Credentials,
+        region: &str,
+        amz_date: &str,
+    ) -> String {
+        let canonical_headers: String = self
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let signed_header_names = self
+            .headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            self.method,
+            self.uri,
+            self.query,
+            canonical_headers,
+            signed_header_names,
+            self.payload_hash
+        );
+
+        let date_stamp = &amz_date[..std::cmp::min(8, amz_date.len())];
+        let scope = format!("{}/{}/{}/aws4_request", date_stamp, region, SERVICE_NAME);
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            SIGNING_ALGORITHM,
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = [
+            date_stamp.as_bytes(),
+            region.as_bytes(),
+            SERVICE_NAME.as_bytes(),
+            b"aws4_request",
+        ]
+        .iter()
+        .fold(
+            format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+            |key, data| hmac_sha256(&key, data),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            SIGNING_ALGORITHM, credentials.access_key_id, scope, signed_header_names, signature
+        )
+    }
+}
+
+/// Returns the URI-encoded query parameters, sorted by name, as specified for AWS Signature
+/// Version 4. This is also the query string sent with the request.
+pub(super) fn canonical_query(query: &[(&str, String)]) -> String {
+    let mut query: Vec<(String, String)> = query
+        .iter()
+        .map(|(name, value)| (uri_encode(name, true), uri_encode(value, true)))
+        .collect();
+    query.sort();
+    query
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// URI-encodes `input` as specified for AWS Signature Version 4.
+pub(super) fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            },
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Extracts the (unescaped) text of all elements named `tag` from an XML document. The S3
+/// responses we parse are flat enough that this doesn't need a full XML parser.
+pub(super) fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        match rest.find(&close) {
+            Some(end) => {
+                values.push(xml_unescape(&rest[..end]));
+                rest = &rest[end + close.len()..];
+            },
+            None => break,
+        }
+    }
+    values
+}
+
+pub(super) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
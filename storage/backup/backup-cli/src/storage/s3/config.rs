@@ -0,0 +1,115 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::error_notes::ErrorNotes;
+use anyhow::{format_err, Result};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+const DEFAULT_REGION: &str = "us-east-1";
+const DEFAULT_PART_SIZE_BYTES: usize = 64 * 1024 * 1024; // 64 MiB
+const DEFAULT_MAX_RETRIES: usize = 5;
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 200;
+
+const ACCESS_KEY_ID_ENV_VAR: &str = "AWS_ACCESS_KEY_ID";
+const SECRET_ACCESS_KEY_ENV_VAR: &str = "AWS_SECRET_ACCESS_KEY";
+const SESSION_TOKEN_ENV_VAR: &str = "AWS_SESSION_TOKEN";
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+    /// Name of the bucket holding the backups.
+    pub bucket: String,
+    /// Optional key prefix (without leading or trailing slashes) under which all files are stored.
+    pub prefix: Option<String>,
+    /// Region of the bucket, used for request signing.
+    pub region: String,
+    /// Endpoint of the S3-compatible service, e.g. "http://127.0.0.1:9000" for a local MinIO.
+    /// Defaults to "https://s3.{region}.amazonaws.com". Objects are always addressed in path
+    /// style, i.e. "{endpoint}/{bucket}/{key}".
+    pub endpoint: Option<String>,
+    /// Access key ID. Falls back to the `AWS_ACCESS_KEY_ID` environment variable.
+    pub access_key_id: Option<String>,
+    /// Secret access key. Falls back to the `AWS_SECRET_ACCESS_KEY` environment variable.
+    pub secret_access_key: Option<String>,
+    /// Optional session token. Falls back to the `AWS_SESSION_TOKEN` environment variable.
+    pub session_token: Option<String>,
+    /// Size of each part of a multipart upload, and of each ranged read. S3 requires all parts but
+    /// the last one of a multipart upload to be at least 5 MiB, so smaller sizes are rejected.
+    pub part_size_bytes: usize,
+    /// Number of times a failed request is retried before giving up.
+    pub max_retries: usize,
+    /// Delay before the first retry, doubled on each subsequent retry.
+    pub retry_base_delay_ms: u64,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            bucket: String::new(),
+            prefix: None,
+            region: DEFAULT_REGION.to_string(),
+            endpoint: None,
+            access_key_id: None,
+            secret_access_key: None,
+            session_token: None,
+            part_size_bytes: DEFAULT_PART_SIZE_BYTES,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+        }
+    }
+}
+
+impl S3Config {
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or_default();
+        let mut file = tokio::fs::File::open(path).await.err_notes(path_str)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await.err_notes(path_str)?;
+
+        Ok(serde_yaml::from_slice(&content)?)
+    }
+
+    pub fn load_from_str(content: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", self.region))
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    pub fn credentials(&self) -> Result<S3Credentials> {
+        let access_key_id = self
+            .access_key_id
+            .clone()
+            .or_else(|| std::env::var(ACCESS_KEY_ID_ENV_VAR).ok())
+            .ok_or_else(|| format_err!("S3 access key ID is not configured."))?;
+        let secret_access_key = self
+            .secret_access_key
+            .clone()
+            .or_else(|| std::env::var(SECRET_ACCESS_KEY_ENV_VAR).ok())
+            .ok_or_else(|| format_err!("S3 secret access key is not configured."))?;
+        let session_token = self
+            .session_token
+            .clone()
+            .or_else(|| std::env::var(SESSION_TOKEN_ENV_VAR).ok());
+
+        Ok(S3Credentials {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
@@ -0,0 +1,373 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod config;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    s3::{client::S3Client, config::S3Config},
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use anyhow::{ensure, format_err, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use clap::Parser;
+use futures::{ready, Future};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream, ReadBuf},
+    task::JoinHandle,
+};
+
+/// Size of the in-memory pipe between a reader / writer and the task talking to S3.
+const PIPE_BUFFER_SIZE: usize = 1024 * 1024;
+/// S3 rejects multipart uploads with a part other than the last one smaller than 5 MiB. Tests
+/// use tiny parts against the mock server to cover files spanning many parts.
+const MIN_PART_SIZE_BYTES: usize = if cfg!(test) { 1 } else { 5 * 1024 * 1024 };
+
+#[derive(Parser, Clone, Debug, Serialize, Deserialize)]
+pub struct S3Opt {
+    #[clap(long = "config", help = "Config file for the S3 backup store.")]
+    config: PathBuf,
+}
+
+impl FromStr for S3Opt {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(S3Opt {
+            config: PathBuf::from(s),
+        })
+    }
+}
+
+/// A BackupStorage that talks to S3 (or an S3-compatible service like MinIO) directly over HTTP,
+/// without shelling out to a CLI. Files are streamed with multipart uploads and ranged reads, so
+/// neither side needs to hold a whole file in memory.
+/// see `S3Config`.
+pub struct S3 {
+    client: Arc<S3Client>,
+    prefix: Option<String>,
+    part_size_bytes: usize,
+}
+
+impl S3 {
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+
+    pub fn new(config: S3Config) -> Result<Self> {
+        ensure!(
+            config.part_size_bytes >= MIN_PART_SIZE_BYTES,
+            "S3 part size must be at least {} bytes, got {}.",
+            MIN_PART_SIZE_BYTES,
+            config.part_size_bytes,
+        );
+        Ok(Self {
+            client: Arc::new(S3Client::new(&config)?),
+            prefix: config
+                .prefix
+                .as_ref()
+                .map(|prefix| prefix.trim_matches('/').to_string())
+                .filter(|prefix| !prefix.is_empty()),
+            part_size_bytes: config.part_size_bytes,
+        })
+    }
+
+    pub async fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        let config = S3Config::load_from_file(&opt.config).await?;
+
+        Self::new(config)
+    }
+
+    /// File handles are relative to the configured prefix, so a backup can be moved around in the
+    /// bucket (or to another bucket) without invalidating its manifests.
+    fn key(&self, file_handle: &FileHandleRef) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}/{}", prefix, file_handle),
+            None => file_handle.to_string(),
+        }
+    }
+
+    fn file_handle(&self, key: &str) -> FileHandle {
+        match &self.prefix {
+            Some(prefix) => key
+                .strip_prefix(prefix.as_str())
+                .and_then(|handle| handle.strip_prefix('/'))
+                .unwrap_or(key)
+                .to_string(),
+            None => key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // There are no directories in S3, objects are created on write.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = format!("{}/{}", backup_handle, name.as_ref());
+        let (writer, reader) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+        let upload = tokio::spawn(upload(
+            self.client.clone(),
+            self.key(&file_handle),
+            self.part_size_bytes,
+            reader,
+        ));
+        Ok((file_handle, Box::new(S3DataSink::new(writer, upload))))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let (writer, reader) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+        let download = tokio::spawn(download(
+            self.client.clone(),
+            self.key(file_handle),
+            self.part_size_bytes,
+            writer,
+        ));
+        Ok(Box::new(S3DataSource::new(reader, download)))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let dir = self.key(&format!("{}/", Self::METADATA_DIR));
+        Ok(self
+            .client
+            .list_objects(&dir)
+            .await?
+            .iter()
+            .filter(|key| !key.ends_with('/'))
+            .map(|key| self.file_handle(key))
+            .collect())
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        // extract the file name from the file_handle
+        let name = Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))?;
+        let source = self.key(file_handle);
+        let destination = self.key(&format!("{}/{}", Self::METADATA_BACKUP_DIR, name));
+        // S3 has no rename, so copy then delete.
+        self.client.copy_object(&source, &destination).await?;
+        self.client.delete_object(&source).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let file_handle = format!("{}/{}", Self::METADATA_DIR, name.as_ref());
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        self.client
+            .put_object(&self.key(&file_handle), Bytes::from(content))
+            .await?;
+        Ok(file_handle)
+    }
+}
+
+/// Reads up to `part_size` bytes, returning fewer only if the stream ends.
+async fn read_part(reader: &mut DuplexStream, part_size: usize) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(part_size);
+    reader.take(part_size as u64).read_to_end(&mut buf).await?;
+    Ok(buf)
+}
+
+/// Uploads everything written to the other end of `reader`. Small files are uploaded with a single
+/// request, larger ones with a multipart upload which is aborted on failure.
+async fn upload(
+    client: Arc<S3Client>,
+    key: String,
+    part_size: usize,
+    mut reader: DuplexStream,
+) -> Result<()> {
+    let first_part = read_part(&mut reader, part_size).await?;
+    if first_part.len() < part_size {
+        return client.put_object(&key, Bytes::from(first_part)).await;
+    }
+
+    let upload_id = client.create_multipart_upload(&key).await?;
+    let res: Result<()> = async {
+        let mut etags = Vec::new();
+        let mut part = first_part;
+        while !part.is_empty() {
+            etags.push(
+                client
+                    .upload_part(&key, &upload_id, etags.len() + 1, Bytes::from(part))
+                    .await?,
+            );
+            part = read_part(&mut reader, part_size).await?;
+        }
+        client
+            .complete_multipart_upload(&key, &upload_id, &etags)
+            .await
+    }
+    .await;
+
+    if res.is_err() {
+        // Best effort, so the incomplete parts don't linger in the bucket.
+        let _ = client.abort_multipart_upload(&key, &upload_id).await;
+    }
+    res
+}
+
+/// Downloads the object with ranged reads of `part_size` bytes into `writer`.
+async fn download(
+    client: Arc<S3Client>,
+    key: String,
+    part_size: usize,
+    mut writer: DuplexStream,
+) -> Result<()> {
+    let mut offset = 0u64;
+    loop {
+        let bytes = client
+            .get_object_range(&key, offset, offset + part_size as u64 - 1)
+            .await?;
+        if bytes.is_empty() {
+            break;
+        }
+        writer.write_all(&bytes).await?;
+        if bytes.len() < part_size {
+            break;
+        }
+        offset += bytes.len() as u64;
+    }
+    writer.shutdown().await?;
+    Ok(())
+}
+
+fn join_result(
+    res: std::result::Result<Result<()>, tokio::task::JoinError>,
+) -> Result<(), tokio::io::Error> {
+    res.map_err(|e| tokio::io::Error::new(tokio::io::ErrorKind::Other, e))?
+        .map_err(|e| tokio::io::Error::new(tokio::io::ErrorKind::Other, e))
+}
+
+/// Feeds written data to the upload task, and surfaces the result of the upload on shutdown.
+struct S3DataSink {
+    writer: DuplexStream,
+    upload: Option<JoinHandle<Result<()>>>,
+    writer_shut_down: bool,
+}
+
+impl S3DataSink {
+    fn new(writer: DuplexStream, upload: JoinHandle<Result<()>>) -> Self {
+        Self {
+            writer,
+            upload: Some(upload),
+            writer_shut_down: false,
+        }
+    }
+}
+
+impl AsyncWrite for S3DataSink {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, tokio::io::Error>> {
+        if self.writer_shut_down {
+            Poll::Ready(Err(tokio::io::ErrorKind::BrokenPipe.into()))
+        } else {
+            Pin::new(&mut self.writer).poll_write(cx, buf)
+        }
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), tokio::io::Error>> {
+        if self.writer_shut_down {
+            Poll::Ready(Err(tokio::io::ErrorKind::BrokenPipe.into()))
+        } else {
+            Pin::new(&mut self.writer).poll_flush(cx)
+        }
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), tokio::io::Error>> {
+        if !self.writer_shut_down {
+            ready!(Pin::new(&mut self.writer).poll_shutdown(cx))?;
+            // pipe shutdown successful, wait for the upload to finish
+            self.writer_shut_down = true;
+        }
+
+        match self.upload.as_mut() {
+            Some(upload) => {
+                let res = ready!(Pin::new(upload).poll(cx));
+                self.upload = None;
+                Poll::Ready(join_result(res))
+            },
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+/// Reads data fed by the download task, and surfaces the result of the download on EOF.
+struct S3DataSource {
+    reader: DuplexStream,
+    download: Option<JoinHandle<Result<()>>>,
+}
+
+impl S3DataSource {
+    fn new(reader: DuplexStream, download: JoinHandle<Result<()>>) -> Self {
+        Self {
+            reader,
+            download: Some(download),
+        }
+    }
+}
+
+impl AsyncRead for S3DataSource {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<tokio::io::Result<()>> {
+        let filled_before_poll = buf.filled().len();
+        ready!(Pin::new(&mut self.reader).poll_read(cx, buf))?;
+        if buf.filled().len() > filled_before_poll {
+            return Poll::Ready(Ok(()));
+        }
+
+        // hit EOF, which is only legit if the download succeeded
+        match self.download.as_mut() {
+            Some(download) => {
+                let res = ready!(Pin::new(download).poll(cx));
+                self.download = None;
+                Poll::Ready(join_result(res))
+            },
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
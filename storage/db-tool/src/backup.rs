@@ -6,7 +6,10 @@ use anyhow::Result;
 use aptos_backup_cli::{
     backup_types::{
         epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
-        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        state_snapshot::{
+            backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            diff_backup::{StateSnapshotDiffBackupController, StateSnapshotDiffBackupOpt},
+        },
         transaction::backup::{TransactionBackupController, TransactionBackupOpt},
     },
     coordinators::{
@@ -98,6 +101,15 @@ enum BackupType {
         #[clap[flatten]]
         storage: DBToolStorageOpt,
     },
+    /// Backs up the state items changed since the latest state snapshot in the backup storage.
+    /// Versions of the backup tools older than this one can't read the metadata of a backup
+    /// storage holding differential snapshots.
+    StateSnapshotDiff {
+        #[clap(flatten)]
+        opt: StateSnapshotDiffBackupOpt,
+        #[clap[flatten]]
+        storage: DBToolStorageOpt,
+    },
     Transaction {
         #[clap(flatten)]
         opt: TransactionBackupOpt,
@@ -193,6 +205,16 @@ impl Command {
                         .run()
                         .await?;
                     },
+                    BackupType::StateSnapshotDiff { opt, storage } => {
+                        StateSnapshotDiffBackupController::new(
+                            opt,
+                            global_opt,
+                            client,
+                            storage.init_storage().await?,
+                        )
+                        .run()
+                        .await?;
+                    },
                     BackupType::Transaction { opt, storage } => {
                         TransactionBackupController::new(
                             opt,
@@ -0,0 +1,694 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use aptos_backup_cli::utils::RocksdbOpt;
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::hash::CryptoHash;
+use aptos_db::AptosDB;
+use aptos_logger::info;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_storage_interface::{
+    state_view::{DbStateView, DbStateViewAtVersion},
+    DbReader, MAX_REQUEST_LIMIT,
+};
+use aptos_types::{
+    access_path::Path,
+    contract_event::ContractEvent,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    transaction::{
+        EntryFunction, MultisigTransactionPayload, Transaction, TransactionInfo,
+        TransactionPayload, Version,
+    },
+    write_set::{TransactionWrite, WriteOp, WriteOpKind, WriteSet},
+};
+use arrow::{
+    array::{ArrayRef, BinaryBuilder, StringBuilder, UInt32Builder, UInt64Builder},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use clap::{Parser, ValueEnum};
+use move_core_types::language_storage::StructTag;
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Export the ledger data in a local DB to Parquet files, partitioned by version range.
+///
+/// Each table is written to its own directory under the output directory, with one file per
+/// partition, named after the (inclusive) range of versions it contains, e.g.,
+/// `events/00000000000000000000-00000000000000999999.parquet`.
+#[derive(Parser)]
+pub struct Opt {
+    #[clap(long = "db-dir", value_parser)]
+    db_dir: PathBuf,
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+    #[clap(
+        long,
+        default_value_t = 0,
+        help = "The first transaction version to export."
+    )]
+    start_version: Version,
+    #[clap(
+        long,
+        help = "The last transaction version to export (inclusive). \
+        [Defaults to the latest version in the DB]"
+    )]
+    end_version: Option<Version>,
+    #[clap(
+        long,
+        default_value_t = 1_000_000,
+        help = "The number of versions in each Parquet file."
+    )]
+    versions_per_file: u64,
+    #[clap(
+        long,
+        default_value_t = 10_000,
+        help = "The number of versions read from the DB (and buffered in memory) at a time."
+    )]
+    versions_per_batch: u64,
+    #[clap(
+        long,
+        value_enum,
+        num_args = 1..,
+        help = "The tables to export. [Defaults to all tables]"
+    )]
+    tables: Vec<Table>,
+    #[clap(
+        long,
+        help = "Do not decode Move values (event data, resources and entry function arguments) \
+        to JSON."
+    )]
+    skip_decoding: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Table {
+    Transactions,
+    TransactionInfos,
+    Events,
+    WriteSets,
+}
+
+impl Table {
+    fn name(&self) -> &'static str {
+        match self {
+            Table::Transactions => "transactions",
+            Table::TransactionInfos => "transaction_infos",
+            Table::Events => "events",
+            Table::WriteSets => "write_sets",
+        }
+    }
+}
+
+impl Opt {
+    pub fn run(self) -> Result<()> {
+        ensure!(self.versions_per_file > 0, "versions_per_file must be > 0.");
+        ensure!(
+            self.versions_per_batch > 0 && self.versions_per_batch <= MAX_REQUEST_LIMIT,
+            "versions_per_batch must be in [1, {}].",
+            MAX_REQUEST_LIMIT
+        );
+
+        let db: Arc<dyn DbReader> = Arc::new(AptosDB::open(
+            StorageDirPaths::from_path(&self.db_dir),
+            true,                        /* read_only */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+            self.rocksdb_opt.into(),
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?);
+
+        let latest_version = db.get_latest_ledger_info_version()?;
+        let end_version = self.end_version.unwrap_or(latest_version);
+        ensure!(
+            end_version <= latest_version,
+            "end_version {} is beyond the latest version in the DB {}.",
+            end_version,
+            latest_version
+        );
+        ensure!(
+            self.start_version <= end_version,
+            "start_version {} is beyond end_version {}.",
+            self.start_version,
+            end_version
+        );
+
+        let tables = if self.tables.is_empty() {
+            Table::value_variants().to_vec()
+        } else {
+            self.tables.clone()
+        };
+
+        // Move values are decoded against the latest exported state. Module upgrades are
+        // required to be layout compatible, so this also decodes values from older versions.
+        let state_view = db.state_view_at_version(Some(end_version))?;
+        let decoder = Decoder {
+            annotator: (!self.skip_decoding).then(|| AptosValueAnnotator::new(&state_view)),
+            num_failures: 0,
+        };
+
+        let mut exporter = Exporter {
+            db,
+            output_dir: self.output_dir.clone(),
+            tables,
+            decoder,
+        };
+
+        let mut partition_start = self.start_version;
+        while partition_start <= end_version {
+            let partition_end = std::cmp::min(
+                (partition_start / self.versions_per_file + 1) * self.versions_per_file - 1,
+                end_version,
+            );
+            exporter.export_partition(partition_start, partition_end, self.versions_per_batch)?;
+            partition_start = partition_end + 1;
+        }
+
+        info!(
+            start_version = self.start_version,
+            end_version = end_version,
+            num_decoding_failures = exporter.decoder.num_failures,
+            "Export finished."
+        );
+        Ok(())
+    }
+}
+
+struct Exporter<'a> {
+    db: Arc<dyn DbReader>,
+    output_dir: PathBuf,
+    tables: Vec<Table>,
+    decoder: Decoder<'a>,
+}
+
+impl<'a> Exporter<'a> {
+    /// Exports the versions in [first_version, last_version] to one file per table.
+    fn export_partition(
+        &mut self,
+        first_version: Version,
+        last_version: Version,
+        versions_per_batch: u64,
+    ) -> Result<()> {
+        let mut writers = self
+            .tables
+            .iter()
+            .map(|table| {
+                let dir = self.output_dir.join(table.name());
+                fs::create_dir_all(&dir)?;
+                let path = dir.join(format!(
+                    "{:020}-{:020}.parquet",
+                    first_version, last_version
+                ));
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                let writer =
+                    ArrowWriter::try_new(File::create(path)?, schema(*table), Some(props))?;
+                Ok((*table, writer))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut batch_start = first_version;
+        while batch_start <= last_version {
+            let limit = std::cmp::min(versions_per_batch, last_version - batch_start + 1);
+            for (table, writer) in writers.iter_mut() {
+                let batch = self.read_batch(*table, batch_start, limit)?;
+                writer.write(&batch)?;
+            }
+            batch_start += limit;
+        }
+
+        for (_, writer) in writers {
+            writer.close()?;
+        }
+        info!(
+            first_version = first_version,
+            last_version = last_version,
+            "Exported partition."
+        );
+        Ok(())
+    }
+
+    fn read_batch(
+        &mut self,
+        table: Table,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<RecordBatch> {
+        let versions = start_version..start_version + limit;
+        match table {
+            Table::Transactions => {
+                let mut builder = TransactionsBuilder::default();
+                for (version, txn) in
+                    versions.zip(self.db.get_transaction_iterator(start_version, limit)?)
+                {
+                    builder.append(version, &txn?, &mut self.decoder);
+                }
+                builder.finish()
+            },
+            Table::TransactionInfos => {
+                let mut builder = TransactionInfosBuilder::default();
+                for (version, txn_info) in versions.zip(
+                    self.db
+                        .get_transaction_info_iterator(start_version, limit)?,
+                ) {
+                    builder.append(version, &txn_info?);
+                }
+                builder.finish()
+            },
+            Table::Events => {
+                let mut builder = EventsBuilder::default();
+                for (version, events) in
+                    versions.zip(self.db.get_events_iterator(start_version, limit)?)
+                {
+                    for (index, event) in events?.iter().enumerate() {
+                        builder.append(version, index as u32, event, &mut self.decoder);
+                    }
+                }
+                builder.finish()
+            },
+            Table::WriteSets => {
+                let mut builder = WriteSetsBuilder::default();
+                for (version, write_set) in
+                    versions.zip(self.db.get_write_set_iterator(start_version, limit)?)
+                {
+                    builder.append(version, &write_set?, &mut self.decoder);
+                }
+                builder.finish()
+            },
+        }
+    }
+}
+
+/// Decodes Move values to JSON, if decoding is enabled. Values that fail to decode (e.g.,
+/// because their module can't be found) are exported undecoded.
+struct Decoder<'a> {
+    annotator: Option<AptosValueAnnotator<'a, DbStateView>>,
+    num_failures: u64,
+}
+
+impl<'a> Decoder<'a> {
+    fn decode<T: serde::Serialize>(
+        &mut self,
+        f: impl FnOnce(&AptosValueAnnotator<'a, DbStateView>) -> Result<T>,
+    ) -> Option<String> {
+        let annotator = self.annotator.as_ref()?;
+        match f(annotator).and_then(|value| Ok(serde_json::to_string(&value)?)) {
+            Ok(json) => Some(json),
+            Err(_) => {
+                self.num_failures += 1;
+                None
+            },
+        }
+    }
+
+    fn decode_entry_function(&mut self, entry_function: &EntryFunction) -> Option<String> {
+        self.decode(|annotator| {
+            annotator.view_function_arguments(
+                entry_function.module(),
+                entry_function.function(),
+                entry_function.ty_args(),
+                entry_function.args(),
+            )
+        })
+    }
+
+    fn decode_event(&mut self, event: &ContractEvent) -> Option<String> {
+        self.decode(|annotator| annotator.view_value(event.type_tag(), event.event_data()))
+    }
+
+    fn decode_state_value(&mut self, path: &Path, bytes: &[u8]) -> Option<String> {
+        match path {
+            Path::Resource(struct_tag) => {
+                self.decode(|annotator| annotator.view_resource(struct_tag, bytes))
+            },
+            Path::ResourceGroup(_) => self.decode(|annotator| {
+                let group: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(bytes)?;
+                group
+                    .iter()
+                    .map(|(tag, blob)| {
+                        Ok((
+                            tag.to_canonical_string(),
+                            annotator.view_resource(tag, blob)?,
+                        ))
+                    })
+                    .collect::<Result<BTreeMap<_, _>>>()
+            }),
+            Path::Code(_) => None,
+        }
+    }
+}
+
+fn schema(table: Table) -> SchemaRef {
+    let fields = match table {
+        Table::Transactions => vec![
+            Field::new("version", DataType::UInt64, false),
+            Field::new("hash", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("sender", DataType::Utf8, true),
+            Field::new("sequence_number", DataType::UInt64, true),
+            Field::new("max_gas_amount", DataType::UInt64, true),
+            Field::new("gas_unit_price", DataType::UInt64, true),
+            Field::new("expiration_timestamp_secs", DataType::UInt64, true),
+            Field::new("entry_function", DataType::Utf8, true),
+            Field::new("entry_function_args", DataType::Utf8, true),
+            Field::new("bcs", DataType::Binary, false),
+        ],
+        Table::TransactionInfos => vec![
+            Field::new("version", DataType::UInt64, false),
+            Field::new("transaction_hash", DataType::Utf8, false),
+            Field::new("state_change_hash", DataType::Utf8, false),
+            Field::new("event_root_hash", DataType::Utf8, false),
+            Field::new("state_checkpoint_hash", DataType::Utf8, true),
+            Field::new("gas_used", DataType::UInt64, false),
+            Field::new("status", DataType::Utf8, false),
+        ],
+        Table::Events => vec![
+            Field::new("version", DataType::UInt64, false),
+            Field::new("event_index", DataType::UInt32, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("event_key", DataType::Utf8, true),
+            Field::new("sequence_number", DataType::UInt64, true),
+            Field::new("data", DataType::Utf8, true),
+            Field::new("data_bcs", DataType::Binary, false),
+        ],
+        Table::WriteSets => vec![
+            Field::new("version", DataType::UInt64, false),
+            Field::new("write_index", DataType::UInt32, false),
+            Field::new("write_type", DataType::Utf8, false),
+            Field::new("state_key_type", DataType::Utf8, false),
+            Field::new("address", DataType::Utf8, true),
+            Field::new("path", DataType::Utf8, true),
+            Field::new("table_handle", DataType::Utf8, true),
+            Field::new("table_key", DataType::Binary, true),
+            Field::new("value", DataType::Utf8, true),
+            Field::new("value_bcs", DataType::Binary, true),
+        ],
+    };
+    Arc::new(Schema::new(fields))
+}
+
+#[derive(Default)]
+struct TransactionsBuilder {
+    version: UInt64Builder,
+    hash: StringBuilder,
+    txn_type: StringBuilder,
+    sender: StringBuilder,
+    sequence_number: UInt64Builder,
+    max_gas_amount: UInt64Builder,
+    gas_unit_price: UInt64Builder,
+    expiration_timestamp_secs: UInt64Builder,
+    entry_function: StringBuilder,
+    entry_function_args: StringBuilder,
+    bcs: BinaryBuilder,
+}
+
+impl TransactionsBuilder {
+    fn append(&mut self, version: Version, txn: &Transaction, decoder: &mut Decoder) {
+        self.version.append_value(version);
+        self.hash.append_value(txn.hash().to_hex_literal());
+        self.txn_type.append_value(txn.type_name());
+        self.bcs
+            .append_value(bcs::to_bytes(txn).expect("Transaction serialization should not fail"));
+
+        let signed_txn = match txn.try_as_signed_user_txn() {
+            Some(signed_txn) => signed_txn,
+            None => {
+                self.sender.append_null();
+                self.sequence_number.append_null();
+                self.max_gas_amount.append_null();
+                self.gas_unit_price.append_null();
+                self.expiration_timestamp_secs.append_null();
+                self.entry_function.append_null();
+                self.entry_function_args.append_null();
+                return;
+            },
+        };
+        self.sender
+            .append_value(signed_txn.sender().to_hex_literal());
+        self.sequence_number
+            .append_value(signed_txn.sequence_number());
+        self.max_gas_amount
+            .append_value(signed_txn.max_gas_amount());
+        self.gas_unit_price
+            .append_value(signed_txn.gas_unit_price());
+        self.expiration_timestamp_secs
+            .append_value(signed_txn.expiration_timestamp_secs());
+
+        let entry_function = match signed_txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+            TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+                Some(MultisigTransactionPayload::EntryFunction(entry_function)) => {
+                    Some(entry_function)
+                },
+                None => None,
+            },
+            TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => None,
+        };
+        match entry_function {
+            Some(entry_function) => {
+                self.entry_function.append_value(format!(
+                    "{}::{}",
+                    entry_function.module().short_str_lossless(),
+                    entry_function.function()
+                ));
+                self.entry_function_args
+                    .append_option(decoder.decode_entry_function(entry_function));
+            },
+            None => {
+                self.entry_function.append_null();
+                self.entry_function_args.append_null();
+            },
+        }
+    }
+
+    fn finish(mut self) -> Result<RecordBatch> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.version.finish()),
+            Arc::new(self.hash.finish()),
+            Arc::new(self.txn_type.finish()),
+            Arc::new(self.sender.finish()),
+            Arc::new(self.sequence_number.finish()),
+            Arc::new(self.max_gas_amount.finish()),
+            Arc::new(self.gas_unit_price.finish()),
+            Arc::new(self.expiration_timestamp_secs.finish()),
+            Arc::new(self.entry_function.finish()),
+            Arc::new(self.entry_function_args.finish()),
+            Arc::new(self.bcs.finish()),
+        ];
+        Ok(RecordBatch::try_new(schema(Table::Transactions), columns)?)
+    }
+}
+
+#[derive(Default)]
+struct TransactionInfosBuilder {
+    version: UInt64Builder,
+    transaction_hash: StringBuilder,
+    state_change_hash: StringBuilder,
+    event_root_hash: StringBuilder,
+    state_checkpoint_hash: StringBuilder,
+    gas_used: UInt64Builder,
+    status: StringBuilder,
+}
+
+impl TransactionInfosBuilder {
+    fn append(&mut self, version: Version, txn_info: &TransactionInfo) {
+        self.version.append_value(version);
+        self.transaction_hash
+            .append_value(txn_info.transaction_hash().to_hex_literal());
+        self.state_change_hash
+            .append_value(txn_info.state_change_hash().to_hex_literal());
+        self.event_root_hash
+            .append_value(txn_info.event_root_hash().to_hex_literal());
+        self.state_checkpoint_hash.append_option(
+            txn_info
+                .state_checkpoint_hash()
+                .map(|hash| hash.to_hex_literal()),
+        );
+        self.gas_used.append_value(txn_info.gas_used());
+        self.status.append_value(format!("{:?}", txn_info.status()));
+    }
+
+    fn finish(mut self) -> Result<RecordBatch> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.version.finish()),
+            Arc::new(self.transaction_hash.finish()),
+            Arc::new(self.state_change_hash.finish()),
+            Arc::new(self.event_root_hash.finish()),
+            Arc::new(self.state_checkpoint_hash.finish()),
+            Arc::new(self.gas_used.finish()),
+            Arc::new(self.status.finish()),
+        ];
+        Ok(RecordBatch::try_new(
+            schema(Table::TransactionInfos),
+            columns,
+        )?)
+    }
+}
+
+#[derive(Default)]
+struct EventsBuilder {
+    version: UInt64Builder,
+    event_index: UInt32Builder,
+    event_type: StringBuilder,
+    event_key: StringBuilder,
+    sequence_number: UInt64Builder,
+    data: StringBuilder,
+    data_bcs: BinaryBuilder,
+}
+
+impl EventsBuilder {
+    fn append(
+        &mut self,
+        version: Version,
+        event_index: u32,
+        event: &ContractEvent,
+        decoder: &mut Decoder,
+    ) {
+        self.version.append_value(version);
+        self.event_index.append_value(event_index);
+        self.event_type
+            .append_value(event.type_tag().to_canonical_string());
+        match event {
+            ContractEvent::V1(event_v1) => {
+                self.event_key.append_value(event_v1.key().to_string());
+                self.sequence_number
+                    .append_value(event_v1.sequence_number());
+            },
+            ContractEvent::V2(_) => {
+                self.event_key.append_null();
+                self.sequence_number.append_null();
+            },
+        }
+        self.data.append_option(decoder.decode_event(event));
+        self.data_bcs.append_value(event.event_data());
+    }
+
+    fn finish(mut self) -> Result<RecordBatch> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.version.finish()),
+            Arc::new(self.event_index.finish()),
+            Arc::new(self.event_type.finish()),
+            Arc::new(self.event_key.finish()),
+            Arc::new(self.sequence_number.finish()),
+            Arc::new(self.data.finish()),
+            Arc::new(self.data_bcs.finish()),
+        ];
+        Ok(RecordBatch::try_new(schema(Table::Events), columns)?)
+    }
+}
+
+#[derive(Default)]
+struct WriteSetsBuilder {
+    version: UInt64Builder,
+    write_index: UInt32Builder,
+    write_type: StringBuilder,
+    state_key_type: StringBuilder,
+    address: StringBuilder,
+    path: StringBuilder,
+    table_handle: StringBuilder,
+    table_key: BinaryBuilder,
+    value: StringBuilder,
+    value_bcs: BinaryBuilder,
+}
+
+impl WriteSetsBuilder {
+    fn append(&mut self, version: Version, write_set: &WriteSet, decoder: &mut Decoder) {
+        for (index, (state_key, write_op)) in write_set.iter().enumerate() {
+            self.append_write(version, index as u32, state_key, write_op, decoder);
+        }
+    }
+
+    fn append_write(
+        &mut self,
+        version: Version,
+        write_index: u32,
+        state_key: &StateKey,
+        write_op: &WriteOp,
+        decoder: &mut Decoder,
+    ) {
+        self.version.append_value(version);
+        self.write_index.append_value(write_index);
+        self.write_type
+            .append_value(match write_op.write_op_kind() {
+                WriteOpKind::Creation => "creation",
+                WriteOpKind::Modification => "modification",
+                WriteOpKind::Deletion => "deletion",
+            });
+        self.value_bcs
+            .append_option(write_op.bytes().map(|bytes| bytes.as_ref()));
+
+        match state_key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                let path = access_path.get_path();
+                self.state_key_type.append_value(match &path {
+                    Path::Code(_) => "module",
+                    Path::Resource(_) => "resource",
+                    Path::ResourceGroup(_) => "resource_group",
+                });
+                self.address
+                    .append_value(access_path.address.to_hex_literal());
+                self.path.append_value(match &path {
+                    Path::Code(module_id) => module_id.short_str_lossless(),
+                    Path::Resource(struct_tag) | Path::ResourceGroup(struct_tag) => {
+                        struct_tag.to_canonical_string()
+                    },
+                });
+                self.table_handle.append_null();
+                self.table_key.append_null();
+                self.value.append_option(
+                    write_op
+                        .bytes()
+                        .and_then(|bytes| decoder.decode_state_value(&path, bytes)),
+                );
+            },
+            StateKeyInner::TableItem { handle, key } => {
+                self.state_key_type.append_value("table_item");
+                self.address.append_null();
+                self.path.append_null();
+                self.table_handle.append_value(handle.0.to_hex_literal());
+                self.table_key.append_value(key);
+                // Table items can't be decoded without the table info (i.e., the key and
+                // value types), which is only tracked by the indexer.
+                self.value.append_null();
+            },
+            StateKeyInner::Raw(_) => {
+                self.state_key_type.append_value("raw");
+                self.address.append_null();
+                self.path.append_null();
+                self.table_handle.append_null();
+                self.table_key.append_null();
+                self.value.append_null();
+            },
+        }
+    }
+
+    fn finish(mut self) -> Result<RecordBatch> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.version.finish()),
+            Arc::new(self.write_index.finish()),
+            Arc::new(self.write_type.finish()),
+            Arc::new(self.state_key_type.finish()),
+            Arc::new(self.address.finish()),
+            Arc::new(self.path.finish()),
+            Arc::new(self.table_handle.finish()),
+            Arc::new(self.table_key.finish()),
+            Arc::new(self.value.finish()),
+            Arc::new(self.value_bcs.finish()),
+        ];
+        Ok(RecordBatch::try_new(schema(Table::WriteSets), columns)?)
+    }
+}
@@ -6,11 +6,13 @@ extern crate core;
 mod backup;
 mod backup_maintenance;
 mod bootstrap;
+mod export;
 mod gen_replay_verify_jobs;
 mod replay_verify;
 pub mod restore;
 #[cfg(test)]
 mod tests;
+mod train_compression_dictionary;
 mod utils;
 
 use anyhow::Result;
@@ -32,12 +34,16 @@ pub enum DBTool {
     #[clap(subcommand)]
     Debug(db_debugger::Cmd),
 
+    Export(export::Opt),
+
     ReplayVerify(replay_verify::Opt),
 
     GenReplayVerifyJobs(gen_replay_verify_jobs::Opt),
 
     #[clap(subcommand)]
     Restore(restore::Command),
+
+    TrainCompressionDictionary(train_compression_dictionary::Opt),
 }
 
 impl DBTool {
@@ -47,6 +53,7 @@ impl DBTool {
             DBTool::BackupMaintenance(cmd) => cmd.run().await,
             DBTool::Bootstrap(cmd) => cmd.run(),
             DBTool::Debug(cmd) => Ok(cmd.run()?),
+            DBTool::Export(cmd) => cmd.run(),
             DBTool::ReplayVerify(cmd) => {
                 let ret = cmd.run().await;
                 info!("Replay verify result: {:?}", ret);
@@ -54,6 +61,7 @@ impl DBTool {
             },
             DBTool::GenReplayVerifyJobs(cmd) => cmd.run().await,
             DBTool::Restore(cmd) => cmd.run().await,
+            DBTool::TrainCompressionDictionary(cmd) => cmd.run(),
         }
     }
 }
@@ -46,6 +46,28 @@ fn test_various_cmd_parsing() {
         ".",
     ]);
 
+    run_cmd(&[
+        "aptos-db-tool",
+        "export",
+        "--db-dir",
+        ".",
+        "--output-dir",
+        ".",
+        "--tables",
+        "transactions",
+        "events",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "train-compression-dictionary",
+        "--db-dir",
+        ".",
+        "--output-path",
+        "./dictionary",
+        "--dictionary",
+        "storage-service",
+    ]);
+
     run_cmd(&["aptos-db-tool", "backup", "verify", "--local-fs-dir", "."]);
     run_cmd(&[
         "aptos-db-tool",
@@ -622,6 +644,77 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_export() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let db_dir = TempPath::new();
+        db_dir.create_as_dir().unwrap();
+        let output_dir = TempPath::new();
+        output_dir.create_as_dir().unwrap();
+
+        let db = test_execution_with_storage_impl_inner(false, db_dir.path());
+        let latest_version = db.expect_synced_version();
+        drop(db);
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(
+            DBTool::try_parse_from([
+                "aptos-db-tool",
+                "export",
+                "--db-dir",
+                db_dir.path().to_str().unwrap(),
+                "--output-dir",
+                output_dir.path().to_str().unwrap(),
+                "--versions-per-file",
+                "10",
+                "--versions-per-batch",
+                "3",
+            ])
+            .unwrap()
+            .run(),
+        )
+        .unwrap();
+
+        for table in ["transactions", "transaction_infos", "events", "write_sets"] {
+            // Verify the files cover all versions, in order
+            let mut num_rows = 0;
+            let mut next_version = 0;
+            let mut files: Vec<_> = fs::read_dir(output_dir.path().join(table))
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect();
+            files.sort();
+            assert_eq!(files.len() as u64, latest_version / 10 + 1);
+            for file in files {
+                let reader =
+                    ParquetRecordBatchReaderBuilder::try_new(fs::File::open(file).unwrap())
+                        .unwrap()
+                        .build()
+                        .unwrap();
+                for batch in reader {
+                    let batch = batch.unwrap();
+                    let versions = batch
+                        .column_by_name("version")
+                        .unwrap()
+                        .as_any()
+                        .downcast_ref::<arrow::array::UInt64Array>()
+                        .unwrap();
+                    for version in versions.values() {
+                        assert!(*version >= next_version);
+                        next_version = *version;
+                    }
+                    num_rows += batch.num_rows() as u64;
+                }
+            }
+            assert!(next_version <= latest_version);
+            if table == "transactions" || table == "transaction_infos" {
+                assert_eq!(next_version, latest_version);
+                assert_eq!(num_rows, latest_version + 1);
+            }
+        }
+    }
+
     fn dir_size<P: AsRef<Path>>(path: P) -> u64 {
         let mut size = 0;
 
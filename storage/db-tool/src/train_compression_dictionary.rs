@@ -0,0 +1,163 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use aptos_backup_cli::utils::RocksdbOpt;
+use aptos_compression::dictionary::{
+    train_dictionary, MEMPOOL_DICTIONARY_ID, STORAGE_SERVICE_DICTIONARY_ID,
+};
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
+use aptos_logger::info;
+use aptos_storage_interface::{DbReader, MAX_REQUEST_LIMIT};
+use aptos_types::transaction::Version;
+use clap::{Parser, ValueEnum};
+use std::{fs, path::PathBuf, sync::Arc};
+
+/// Train a zstd compression dictionary for a network protocol, using the ledger data in a
+/// local DB as samples.
+///
+/// The dictionary ID is fixed by the protocol, so the output file can be passed directly to
+/// the node config (e.g., `compression.mempool_dictionary_path`). All peers must use the same
+/// dictionary file, otherwise messages compressed with the dictionary will be rejected.
+#[derive(Parser)]
+pub struct Opt {
+    #[clap(long = "db-dir", value_parser)]
+    db_dir: PathBuf,
+    #[clap(long, value_parser)]
+    output_path: PathBuf,
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+    #[clap(long, value_enum, help = "The protocol dictionary to train.")]
+    dictionary: Dictionary,
+    #[clap(
+        long,
+        help = "The last transaction version to sample (inclusive). \
+        [Defaults to the latest version in the DB]"
+    )]
+    end_version: Option<Version>,
+    #[clap(
+        long,
+        default_value_t = 100_000,
+        help = "The number of versions to sample (ending at end_version)."
+    )]
+    num_versions: u64,
+    #[clap(
+        long,
+        default_value_t = 110 * 1024,
+        help = "The maximum size (in bytes) of the trained dictionary."
+    )]
+    max_dictionary_size: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Dictionary {
+    /// Trained on signed user transactions (as broadcast by mempool)
+    Mempool,
+    /// Trained on transactions, transaction infos, events and write
+    /// sets (as served by the storage service)
+    StorageService,
+}
+
+impl Dictionary {
+    fn id(&self) -> u32 {
+        match self {
+            Dictionary::Mempool => MEMPOOL_DICTIONARY_ID,
+            Dictionary::StorageService => STORAGE_SERVICE_DICTIONARY_ID,
+        }
+    }
+}
+
+impl Opt {
+    pub fn run(self) -> Result<()> {
+        ensure!(self.num_versions > 0, "num_versions must be > 0.");
+
+        let db: Arc<dyn DbReader> = Arc::new(AptosDB::open(
+            StorageDirPaths::from_path(&self.db_dir),
+            true,                        /* read_only */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+            self.rocksdb_opt.into(),
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?);
+
+        let latest_version = db.get_latest_ledger_info_version()?;
+        let end_version = self.end_version.unwrap_or(latest_version);
+        ensure!(
+            end_version <= latest_version,
+            "end_version {} is beyond the latest version in the DB {}.",
+            end_version,
+            latest_version
+        );
+        let start_version = (end_version + 1).saturating_sub(self.num_versions);
+
+        // Collect the BCS encoded samples
+        let mut samples = vec![];
+        let mut batch_start = start_version;
+        while batch_start <= end_version {
+            let limit = std::cmp::min(MAX_REQUEST_LIMIT, end_version - batch_start + 1);
+            self.read_samples(&db, batch_start, limit, &mut samples)?;
+            batch_start += limit;
+        }
+        ensure!(
+            !samples.is_empty(),
+            "No samples found in versions [{}, {}].",
+            start_version,
+            end_version
+        );
+
+        // Train the dictionary and write it to the output file
+        let dictionary =
+            train_dictionary(&samples, self.max_dictionary_size, self.dictionary.id())?;
+        fs::write(&self.output_path, &dictionary)?;
+
+        info!(
+            start_version = start_version,
+            end_version = end_version,
+            num_samples = samples.len(),
+            dictionary_id = self.dictionary.id(),
+            dictionary_size = dictionary.len(),
+            "Compression dictionary trained."
+        );
+        Ok(())
+    }
+
+    /// Reads the BCS encoded samples for the versions in [start_version, start_version + limit)
+    fn read_samples(
+        &self,
+        db: &Arc<dyn DbReader>,
+        start_version: Version,
+        limit: u64,
+        samples: &mut Vec<Vec<u8>>,
+    ) -> Result<()> {
+        match self.dictionary {
+            Dictionary::Mempool => {
+                for txn in db.get_transaction_iterator(start_version, limit)? {
+                    if let Some(signed_txn) = txn?.try_as_signed_user_txn() {
+                        samples.push(bcs::to_bytes(signed_txn)?);
+                    }
+                }
+            },
+            Dictionary::StorageService => {
+                for txn in db.get_transaction_iterator(start_version, limit)? {
+                    samples.push(bcs::to_bytes(&txn?)?);
+                }
+                for txn_info in db.get_transaction_info_iterator(start_version, limit)? {
+                    samples.push(bcs::to_bytes(&txn_info?)?);
+                }
+                for events in db.get_events_iterator(start_version, limit)? {
+                    samples.push(bcs::to_bytes(&events?)?);
+                }
+                for write_set in db.get_write_set_iterator(start_version, limit)? {
+                    samples.push(bcs::to_bytes(&write_set?)?);
+                }
+            },
+        }
+        Ok(())
+    }
+}
@@ -6,9 +6,11 @@ use aptos_config::config::internal_indexer_db_config::InternalIndexerDBConfig;
 use aptos_db_indexer_schemas::{
     metadata::{MetadataKey, MetadataValue, StateSnapshotProgress},
     schema::{
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
-        indexer_metadata::InternalIndexerMetadataSchema, state_keys::StateKeysSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema, indexer_metadata::InternalIndexerMetadataSchema,
+        resource_by_type::ResourceByTypeSchema, state_keys::StateKeysSchema,
         transaction_by_account::TransactionByAccountSchema,
+        transaction_by_function::TransactionByFunctionSchema,
     },
     utils::{
         error_if_too_many_requested, get_first_seq_num_and_limit, AccountTransactionVersionIter,
@@ -20,17 +22,25 @@ use aptos_storage_interface::{
     db_ensure as ensure, db_other_bail as bail, AptosDbError, DbReader, Result,
 };
 use aptos_types::{
+    access_path::Path,
     account_address::AccountAddress,
     contract_event::{ContractEvent, EventWithVersion},
     event::EventKey,
     indexer::indexer_db_reader::Order,
     state_store::{
-        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_key::{inner::StateKeyInner, prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
     },
-    transaction::{AccountTransactionsWithProof, Transaction, Version},
+    transaction::{
+        AccountTransactionsWithProof, EntryFunction, Multisig, MultisigTransactionPayload,
+        Transaction, TransactionPayload, TransactionWithProof, Version,
+    },
     write_set::{TransactionWrite, WriteSet},
 };
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
 use std::{
     cmp::min,
     sync::{
@@ -87,7 +97,14 @@ impl InternalIndexerDB {
         // add state value to internal indexer
         let batch = SchemaBatch::new();
         for state_key in keys {
-            batch.put::<StateKeysSchema>(state_key, &())?;
+            if self.statekeys_enabled() {
+                batch.put::<StateKeysSchema>(state_key, &())?;
+            }
+            if self.resource_by_type_enabled() {
+                if let Some((address, struct_tag)) = get_resource(state_key) {
+                    batch.put::<ResourceByTypeSchema>(&(struct_tag, address), &())?;
+                }
+            }
         }
 
         batch.put::<InternalIndexerMetadataSchema>(
@@ -114,6 +131,18 @@ impl InternalIndexerDB {
         self.get_version(&MetadataKey::TransactionVersion)
     }
 
+    pub fn get_event_by_type_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::EventByTypeVersion)
+    }
+
+    pub fn get_transaction_by_function_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::TransactionByFunctionVersion)
+    }
+
+    pub fn get_resource_by_type_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::ResourceByTypeVersion)
+    }
+
     pub fn event_enabled(&self) -> bool {
         self.config.enable_event
     }
@@ -126,6 +155,23 @@ impl InternalIndexerDB {
         self.config.enable_statekeys
     }
 
+    pub fn event_by_type_enabled(&self) -> bool {
+        self.config.enable_event_by_type
+    }
+
+    pub fn transaction_by_function_enabled(&self) -> bool {
+        self.config.enable_transaction_by_function
+    }
+
+    pub fn resource_by_type_enabled(&self) -> bool {
+        self.config.enable_resource_by_type
+    }
+
+    /// Whether restoring a state snapshot writes to the internal indexer db.
+    pub fn state_snapshot_restore_enabled(&self) -> bool {
+        self.statekeys_enabled() || self.resource_by_type_enabled()
+    }
+
     pub fn get_inner_db_ref(&self) -> &Arc<DB> {
         &self.db
     }
@@ -227,6 +273,88 @@ impl InternalIndexerDB {
         Ok(result)
     }
 
+    /// Given `type_tag`, returns the events of that type identified by transaction version and
+    /// index among all events emitted by the same transaction, in versions
+    /// `[start_version, end_version)`. Lookup stops after `limit` events, but the events of one
+    /// transaction are never split, so more than `limit` events are returned if the last
+    /// transaction emitted more.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let mut iter = self.db.iter::<EventByTypeSchema>()?;
+        iter.seek(&(type_tag.clone(), start_version, 0))?;
+
+        let mut result: Vec<(Version, u64)> = Vec::new();
+        for res in iter {
+            let ((tag, ver, idx), ()) = res?;
+            if tag != *type_tag || ver >= end_version {
+                break;
+            }
+            if result.len() as u64 >= limit && result.last().map(|(v, _)| *v) != Some(ver) {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the versions of the user transactions calling the entry function, starting from
+    /// `start_version`, up to `ledger_version`, in ascending order.
+    pub fn lookup_transactions_by_function(
+        &self,
+        module_id: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let function = function.to_owned();
+        let mut iter = self.db.iter::<TransactionByFunctionSchema>()?;
+        iter.seek(&(module_id.clone(), function.clone(), start_version))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((module, func, ver), ()) = res?;
+            if module != *module_id || func != function || ver > ledger_version {
+                break;
+            }
+            result.push(ver);
+        }
+
+        Ok(result)
+    }
+
+    /// Returns an iterator over the addresses of the accounts that held a resource of
+    /// `struct_tag` as of the latest indexed version, starting from `start_address`, in
+    /// ascending order.
+    pub fn get_resource_by_type_iter(
+        &self,
+        struct_tag: &StructTag,
+        start_address: AccountAddress,
+    ) -> Result<impl Iterator<Item = Result<AccountAddress>> + '_> {
+        let mut iter = self.db.iter::<ResourceByTypeSchema>()?;
+        iter.seek(&(struct_tag.clone(), start_address))?;
+
+        let struct_tag = struct_tag.clone();
+        Ok(iter
+            .map(|res| res.map(|(key, ())| key))
+            .take_while(move |res| match res {
+                Ok((tag, _)) => *tag == struct_tag,
+                Err(_) => true,
+            })
+            .map(|res| res.map(|(_, address)| address)))
+    }
+
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn get_restore_version_and_progress(
         &self,
@@ -376,6 +504,27 @@ impl DBIndexer {
                         &version,
                     )?;
                 }
+                if self.indexer_db.transaction_by_function_enabled() {
+                    if let Some(entry_function) = get_entry_function(txn.payload()) {
+                        batch.put::<TransactionByFunctionSchema>(
+                            &(
+                                entry_function.module().clone(),
+                                entry_function.function().to_owned(),
+                                version,
+                            ),
+                            &(),
+                        )?;
+                    }
+                }
+            }
+
+            if self.indexer_db.event_by_type_enabled() {
+                for (idx, event) in events.iter().enumerate() {
+                    batch.put::<EventByTypeSchema>(
+                        &(event.type_tag().clone(), version, idx as u64),
+                        &(),
+                    )?;
+                }
             }
 
             if self.indexer_db.event_enabled() {
@@ -406,6 +555,19 @@ impl DBIndexer {
                     }
                 });
             }
+
+            if self.indexer_db.resource_by_type_enabled() {
+                for (state_key, write_op) in writeset.iter() {
+                    if let Some((address, struct_tag)) = get_resource(state_key) {
+                        let key = (struct_tag, address);
+                        if write_op.is_deletion() {
+                            batch.delete::<ResourceByTypeSchema>(&key)?;
+                        } else {
+                            batch.put::<ResourceByTypeSchema>(&key, &())?;
+                        }
+                    }
+                }
+            }
             version += 1;
             Ok::<(), AptosDbError>(())
         })?;
@@ -431,6 +593,24 @@ impl DBIndexer {
                 &MetadataValue::Version(version - 1),
             )?;
         }
+        if self.indexer_db.event_by_type_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::EventByTypeVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
+        if self.indexer_db.transaction_by_function_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::TransactionByFunctionVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
+        if self.indexer_db.resource_by_type_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::ResourceByTypeVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
         batch.put::<InternalIndexerMetadataSchema>(
             &MetadataKey::LatestVersion,
             &MetadataValue::Version(version - 1),
@@ -572,4 +752,134 @@ impl DBIndexer {
 
         Ok(events_with_version)
     }
+
+    /// Returns the events of `type_tag` emitted in versions `[start_version, end_version)`, up to
+    /// `ledger_version`, in ascending order. The events of one transaction are never split across
+    /// pages, so the next page starts at the version after the last returned event.
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        let end_version = min(end_version, ledger_version.saturating_add(1));
+        self.indexer_db
+            .lookup_events_by_type(type_tag, start_version, end_version, limit)?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let event = self
+                    .main_db_reader
+                    .get_event_by_version_and_index(ver, idx)?;
+                ensure!(
+                    event.type_tag() == type_tag,
+                    "Index broken, expected type:{}, actual:{}",
+                    type_tag,
+                    event.type_tag()
+                );
+                Ok(EventWithVersion::new(ver, event))
+            })
+            .collect()
+    }
+
+    /// Returns the user transactions calling the entry function, starting from `start_version`,
+    /// up to `ledger_version`, in ascending order.
+    pub fn get_transactions_by_function(
+        &self,
+        module_id: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        self.indexer_db
+            .lookup_transactions_by_function(
+                module_id,
+                function,
+                start_version,
+                limit,
+                ledger_version,
+            )?
+            .into_iter()
+            .map(|txn_version| {
+                self.main_db_reader.get_transaction_by_version(
+                    txn_version,
+                    ledger_version,
+                    include_events,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the resources of `struct_tag` held by accounts at `ledger_version`, starting from
+    /// `start_address`, ordered by address, along with the address to start the next page from,
+    /// if there could be more.
+    ///
+    /// The index only tracks the latest indexed version, so for an older `ledger_version` the
+    /// resources deleted since then are missing from the result.
+    pub fn get_resources_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_address: Option<AccountAddress>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<(Vec<(AccountAddress, StateValue)>, Option<AccountAddress>)> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        let mut resources = Vec::new();
+        let iter = self
+            .indexer_db
+            .get_resource_by_type_iter(struct_tag, start_address.unwrap_or(AccountAddress::ZERO))?;
+        for res in iter {
+            let address = res?;
+            if resources.len() as u64 >= limit {
+                return Ok((resources, Some(address)));
+            }
+            // The index reflects the latest indexed version, the resource might not exist yet, or
+            // anymore, at `ledger_version`. Resources deleted since `ledger_version` are no longer
+            // indexed and thus not returned.
+            let state_key = StateKey::resource(&address, struct_tag)?;
+            if let Some(state_value) = self
+                .main_db_reader
+                .get_state_value_by_version(&state_key, ledger_version)?
+            {
+                resources.push((address, state_value));
+            }
+        }
+
+        Ok((resources, None))
+    }
+}
+
+fn get_entry_function(payload: &TransactionPayload) -> Option<&EntryFunction> {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Multisig(Multisig {
+            transaction_payload: Some(MultisigTransactionPayload::EntryFunction(entry_function)),
+            ..
+        }) => Some(entry_function),
+        _ => None,
+    }
+}
+
+fn get_resource(state_key: &StateKey) -> Option<(AccountAddress, StructTag)> {
+    match state_key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Resource(struct_tag) => Some((access_path.address, struct_tag)),
+            _ => None,
+        },
+        _ => None,
+    }
 }
@@ -13,7 +13,11 @@ use aptos_types::{
         state_value::StateValue,
         table::{TableHandle, TableInfo},
     },
-    transaction::{AccountTransactionsWithProof, Version},
+    transaction::{AccountTransactionsWithProof, TransactionWithProof, Version},
+};
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, StructTag, TypeTag},
 };
 use std::sync::Arc;
 
@@ -157,4 +161,79 @@ impl IndexerReader for IndexerReaders {
         }
         anyhow::bail!("DB indexer reader is not available")
     }
+
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<EventWithVersion>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader.indexer_db.event_by_type_enabled() {
+                return Ok(db_indexer_reader.get_events_by_type(
+                    type_tag,
+                    start_version,
+                    end_version,
+                    limit,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal event by type index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
+    fn get_transactions_by_function(
+        &self,
+        module_id: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<TransactionWithProof>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader
+                .indexer_db
+                .transaction_by_function_enabled()
+            {
+                return Ok(db_indexer_reader.get_transactions_by_function(
+                    module_id,
+                    function,
+                    start_version,
+                    limit,
+                    include_events,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal transaction by function index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
+    fn get_resources_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_address: Option<AccountAddress>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> anyhow::Result<(Vec<(AccountAddress, StateValue)>, Option<AccountAddress>)> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader.indexer_db.resource_by_type_enabled() {
+                return Ok(db_indexer_reader.get_resources_by_type(
+                    struct_tag,
+                    start_address,
+                    limit,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal resource by type index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
 }
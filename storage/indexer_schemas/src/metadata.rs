@@ -38,6 +38,9 @@ pub enum MetadataKey {
     EventVersion,
     StateVersion,
     TransactionVersion,
+    EventByTypeVersion,
+    TransactionByFunctionVersion,
+    ResourceByTypeVersion,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which all events of a Move
+//! type can be found in version order, regardless of the event handle or the module they were
+//! emitted from.
+//!
+//! The BCS encoding of a type tag is never a prefix of the encoding of another type tag, so all
+//! keys of one type are adjacent and ordered by version.
+//!
+//! ```text
+//! |<-----------key------------>|
+//! | type_tag | txn_ver | idx |
+//! ```
+
+use crate::{schema::EVENT_BY_TYPE_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::{ensure, Result};
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::language_storage::TypeTag;
+use std::mem::size_of;
+
+define_pub_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (TypeTag, Version, Index);
+
+const SUFFIX_SIZE: usize = size_of::<Version>() + size_of::<Index>();
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref type_tag, version, index) = *self;
+
+        let mut encoded = bcs::to_bytes(type_tag)?;
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() > SUFFIX_SIZE,
+            "Unexpected data len {}, expected more than {}.",
+            data.len(),
+            SUFFIX_SIZE,
+        );
+
+        let type_tag_len = data.len() - SUFFIX_SIZE;
+        let type_tag = bcs::from_bytes(&data[..type_tag_len])?;
+        let version = (&data[type_tag_len..]).read_u64::<BigEndian>()?;
+        let index = (&data[type_tag_len + size_of::<Version>()..]).read_u64::<BigEndian>()?;
+
+        Ok((type_tag, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -7,11 +7,14 @@
 //! All schemas are `pub(crate)` so not shown in rustdoc, refer to the source code to see details.
 
 pub mod event_by_key;
+pub mod event_by_type;
 pub mod event_by_version;
 pub mod indexer_metadata;
+pub mod resource_by_type;
 pub mod state_keys;
 pub mod table_info;
 pub mod transaction_by_account;
+pub mod transaction_by_function;
 use aptos_schemadb::ColumnFamilyName;
 
 pub const DEFAULT_COLUMN_FAMILY_NAME: ColumnFamilyName = "default";
@@ -22,6 +25,9 @@ pub const EVENT_BY_KEY_CF_NAME: ColumnFamilyName = "event_by_key";
 pub const EVENT_BY_VERSION_CF_NAME: ColumnFamilyName = "event_by_version";
 pub const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
 pub const STATE_KEYS_CF_NAME: ColumnFamilyName = "state_keys";
+pub const EVENT_BY_TYPE_CF_NAME: ColumnFamilyName = "event_by_type";
+pub const TRANSACTION_BY_FUNCTION_CF_NAME: ColumnFamilyName = "transaction_by_function";
+pub const RESOURCE_BY_TYPE_CF_NAME: ColumnFamilyName = "resource_by_type";
 
 pub fn column_families() -> Vec<ColumnFamilyName> {
     vec![
@@ -39,5 +45,8 @@ pub fn internal_indexer_column_families() -> Vec<ColumnFamilyName> {
         EVENT_BY_VERSION_CF_NAME,
         TRANSACTION_BY_ACCOUNT_CF_NAME,
         STATE_KEYS_CF_NAME,
+        EVENT_BY_TYPE_CF_NAME,
+        TRANSACTION_BY_FUNCTION_CF_NAME,
+        RESOURCE_BY_TYPE_CF_NAME,
     ]
 }
@@ -0,0 +1,66 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for a resource index via which all accounts
+//! holding a resource of a struct type can be found, ordered by address.
+//!
+//! Only resources stored directly under the account are indexed, not the members of resource
+//! groups. Keys are removed once the resource gets deleted.
+//!
+//! ```text
+//! |<--------key-------->|
+//! | struct_tag | address |
+//! ```
+
+use crate::{schema::RESOURCE_BY_TYPE_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::{ensure, Result};
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::account_address::AccountAddress;
+use move_core_types::language_storage::StructTag;
+
+define_pub_schema!(ResourceByTypeSchema, Key, (), RESOURCE_BY_TYPE_CF_NAME);
+
+type Key = (StructTag, AccountAddress);
+
+impl KeyCodec<ResourceByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref struct_tag, address) = *self;
+
+        let mut encoded = bcs::to_bytes(struct_tag)?;
+        encoded.extend_from_slice(address.as_ref());
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() > AccountAddress::LENGTH,
+            "Unexpected data len {}, expected more than {}.",
+            data.len(),
+            AccountAddress::LENGTH,
+        );
+
+        let struct_tag_len = data.len() - AccountAddress::LENGTH;
+        let struct_tag = bcs::from_bytes(&data[..struct_tag_len])?;
+        let address = AccountAddress::try_from(&data[struct_tag_len..])?;
+
+        Ok((struct_tag, address))
+    }
+}
+
+impl ValueCodec<ResourceByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for a transaction index via which the versions of
+//! all user transactions calling an entry function can be found in order.
+//!
+//! The BCS encoding of the module id and function name is prefix free, so all keys of one
+//! function are adjacent and ordered by version.
+//!
+//! ```text
+//! |<---------------key--------------->|
+//! | module_id | function_name | txn_ver |
+//! ```
+
+use crate::{schema::TRANSACTION_BY_FUNCTION_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::{ensure, Result};
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use std::mem::size_of;
+
+define_pub_schema!(
+    TransactionByFunctionSchema,
+    Key,
+    (),
+    TRANSACTION_BY_FUNCTION_CF_NAME
+);
+
+type Key = (ModuleId, Identifier, Version);
+
+impl KeyCodec<TransactionByFunctionSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref module_id, ref function, version) = *self;
+
+        let mut encoded = bcs::to_bytes(&(module_id, function))?;
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        const VERSION_SIZE: usize = size_of::<Version>();
+        ensure!(
+            data.len() > VERSION_SIZE,
+            "Unexpected data len {}, expected more than {}.",
+            data.len(),
+            VERSION_SIZE,
+        );
+
+        let function_len = data.len() - VERSION_SIZE;
+        let (module_id, function) = bcs::from_bytes(&data[..function_len])?;
+        let version = (&data[function_len..]).read_u64::<BigEndian>()?;
+
+        Ok((module_id, function, version))
+    }
+}
+
+impl ValueCodec<TransactionByFunctionSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
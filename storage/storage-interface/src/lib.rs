@@ -182,6 +182,11 @@ pub trait DbReader: Send + Sync {
         /// [AptosDB::get_first_write_set_version]: ../aptosdb/struct.AptosDB.html#method.get_first_write_set_version
         fn get_first_write_set_version(&self) -> Result<Option<Version>>;
 
+        /// See [AptosDB::get_first_state_value_version].
+        ///
+        /// [AptosDB::get_first_state_value_version]: ../aptosdb/struct.AptosDB.html#method.get_first_state_value_version
+        fn get_first_state_value_version(&self) -> Result<Option<Version>>;
+
         /// See [AptosDB::get_transaction_outputs].
         ///
         /// [AptosDB::get_transaction_outputs]: ../aptosdb/struct.AptosDB.html#method.get_transaction_outputs
@@ -15,6 +15,7 @@ use aptos_crypto::{
     HashValue, PrivateKey,
 };
 use aptos_forge::{AptosPublicInfo, LocalSwarm, Node, NodeExt, Swarm};
+use aptos_framework::{BuildOptions, BuiltPackage};
 use aptos_gas_schedule::{AptosGasParameters, FromOnChainGasSchedule};
 use aptos_genesis::builder::InitConfigFn;
 use aptos_global_constants::GAS_UNIT_PRICE;
@@ -27,24 +28,27 @@ use aptos_rosetta::{
     common::{native_coin, BlockHash, BLOCKCHAIN, Y2K_MS},
     types::{
         AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, BlockIdentifier,
-        BlockRequest, BlockResponse, NetworkIdentifier, NetworkRequest, Operation,
-        OperationStatusType, OperationType, PartialBlockIdentifier, TransactionIdentifier,
-        TransactionType, STAKING_CONTRACT_MODULE, SWITCH_OPERATOR_WITH_SAME_COMMISSION_FUNCTION,
+        BlockRequest, BlockResponse, Currency, CurrencyMetadata, NetworkIdentifier, NetworkRequest,
+        Operation, OperationStatusType, OperationType, PartialBlockIdentifier,
+        TransactionIdentifier, TransactionType, STAKING_CONTRACT_MODULE,
+        SWITCH_OPERATOR_WITH_SAME_COMMISSION_FUNCTION,
     },
     ROSETTA_VERSION,
 };
 use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_types::{
-    account_address::AccountAddress,
-    account_config::CORE_CODE_ADDRESS,
+    account_address::{create_object_address, AccountAddress},
+    account_config::{aptos_test_root_address, CORE_CODE_ADDRESS},
     chain_id::ChainId,
     on_chain_config::{GasScheduleV2, OnChainRandomnessConfig},
-    transaction::SignedTransaction,
+    transaction::{EntryFunction, SignedTransaction, TransactionPayload},
 };
+use move_core_types::{ident_str, language_storage::ModuleId};
 use serde_json::json;
 use std::{
     collections::{BTreeMap, HashSet},
     future::Future,
+    path::PathBuf,
     str::FromStr,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -66,12 +70,13 @@ async fn setup_simple_test(
     JoinHandle<anyhow::Result<()>>,
     RosettaClient,
 ) {
-    setup_test(num_accounts, Arc::new(|_, _, _| {})).await
+    setup_test(num_accounts, Arc::new(|_, _, _| {}), HashSet::new()).await
 }
 
 async fn setup_test(
     num_accounts: usize,
     config_fn: InitConfigFn,
+    currencies: HashSet<Currency>,
 ) -> (
     LocalSwarm,
     CliTestFramework,
@@ -113,6 +118,7 @@ async fn setup_test(
         Some(aptos_rest_client::Client::new(
             validator.rest_api_endpoint(),
         )),
+        currencies,
     )
     .await
     .unwrap();
@@ -132,6 +138,7 @@ async fn test_block_transactions() {
     let (swarm, cli, _faucet, rosetta_client) = setup_test(
         2,
         Arc::new(|_, config, _| config.api.max_transactions_page_size = NUM_TXNS_PER_PAGE),
+        HashSet::new(),
     )
     .await;
 
@@ -686,6 +693,177 @@ async fn test_transfer() {
     );
 }
 
+/// Seed of the fungible asset object created by the `fa_coin` example
+const FA_COIN_SEED: &[u8] = b"FA";
+
+/// The `fa_coin` example published by the root account, as a Rosetta currency
+fn fa_coin_currency() -> Currency {
+    Currency {
+        symbol: "FA".to_string(),
+        decimals: 8,
+        metadata: Some(CurrencyMetadata {
+            move_type: None,
+            fa_address: Some(
+                create_object_address(aptos_test_root_address(), FA_COIN_SEED).to_hex_literal(),
+            ),
+        }),
+    }
+}
+
+/// Publishes the `fa_coin` example from the root account, and mints `amount` to `receiver`
+async fn create_fa_coin(swarm: &mut LocalSwarm, receiver: AccountAddress, amount: u64) {
+    let mut info = swarm.aptos_public_info();
+    let root_address = info.root_account().address();
+    let package_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../aptos-move/move-examples/fungible_asset/fa_coin");
+    let package = BuiltPackage::build(package_dir, BuildOptions {
+        named_addresses: BTreeMap::from([("FACoin".to_string(), root_address)]),
+        ..BuildOptions::default()
+    })
+    .unwrap();
+    let publish_payload = aptos_stdlib::code_publish_package_txn(
+        bcs::to_bytes(&package.extract_metadata().unwrap()).unwrap(),
+        package.extract_code(),
+    );
+    let mint_payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(root_address, ident_str!("fa_coin").to_owned()),
+        ident_str!("mint").to_owned(),
+        vec![],
+        vec![
+            bcs::to_bytes(&receiver).unwrap(),
+            bcs::to_bytes(&amount).unwrap(),
+        ],
+    ));
+    for payload in [publish_payload, mint_payload] {
+        let txn_builder = info.transaction_factory().payload(payload);
+        let txn = info
+            .root_account()
+            .sign_with_transaction_builder(txn_builder);
+        info.client().submit_and_wait(&txn).await.unwrap();
+    }
+}
+
+async fn currency_balance(
+    rosetta_client: &RosettaClient,
+    chain_id: ChainId,
+    account: AccountAddress,
+    currency: &Currency,
+) -> u64 {
+    let request = AccountBalanceRequest {
+        network_identifier: chain_id.into(),
+        account_identifier: AccountIdentifier::base_account(account),
+        block_identifier: None,
+        currencies: Some(vec![currency.clone()]),
+    };
+    let response = try_until_ok_default(|| rosetta_client.account_balance(&request))
+        .await
+        .unwrap();
+    let amount = response
+        .balances
+        .iter()
+        .find(|amount| &amount.currency == currency)
+        .unwrap_or_else(|| panic!("Missing {:?} balance: {:?}", currency, response));
+    u64::from_str(&amount.value).unwrap()
+}
+
+#[tokio::test]
+async fn test_fungible_asset_transfer() {
+    let fa_currency = fa_coin_currency();
+    let (mut swarm, cli, _faucet, rosetta_client) = setup_test(
+        2,
+        Arc::new(|_, _, _| {}),
+        HashSet::from([fa_currency.clone()]),
+    )
+    .await;
+    let chain_id = swarm.chain_id();
+    let sender = cli.account_id(0);
+    let receiver = cli.account_id(1);
+    create_fa_coin(&mut swarm, sender, 1_000).await;
+    let client = swarm.aptos_public_info().client().clone();
+    let network = NetworkIdentifier::from(chain_id);
+    let node_clients = NodeClients {
+        rosetta_client: &rosetta_client,
+        rest_client: &client,
+        network: &network,
+    };
+
+    // The minted fungible asset shows up in the sender's balance
+    assert_eq!(
+        currency_balance(&rosetta_client, chain_id, sender, &fa_currency).await,
+        1_000
+    );
+
+    // Transfer some of it to another account through the construction API
+    let txn = submit_transaction(&client, DEFAULT_MAX_WAIT_DURATION, |expiry_time| {
+        rosetta_client.transfer_currency(
+            &network,
+            cli.private_key(0),
+            receiver,
+            fa_currency.clone(),
+            DEFAULT_TRANSFER_AMOUNT,
+            expiry_time,
+            None,
+            None,
+            None,
+        )
+    })
+    .await
+    .expect("Fungible asset transfer should succeed");
+    assert_eq!(
+        currency_balance(&rosetta_client, chain_id, sender, &fa_currency).await,
+        1_000 - DEFAULT_TRANSFER_AMOUNT
+    );
+    assert_eq!(
+        currency_balance(&rosetta_client, chain_id, receiver, &fa_currency).await,
+        DEFAULT_TRANSFER_AMOUNT
+    );
+
+    // The transfer is parsed into a withdraw and a deposit of the fungible asset, and gas in APT
+    let block_height = client
+        .get_block_by_version_bcs(txn.info.version.0, false)
+        .await
+        .unwrap()
+        .into_inner()
+        .block_height;
+    wait_for_rosetta_block(&node_clients, block_height).await;
+    let block = rosetta_client
+        .block(&BlockRequest::by_index(chain_id, block_height))
+        .await
+        .unwrap()
+        .block;
+    let transaction = block
+        .transactions
+        .iter()
+        .find(|transaction| {
+            transaction.transaction_identifier.hash == format!("{:x}", txn.info.hash)
+        })
+        .expect("Transfer should be in the block");
+    let operations = &transaction.operations;
+    assert_eq!(3, operations.len(), "{:?}", operations);
+    assert_eq!(&operations[..2], &[
+        Operation::withdraw(
+            0,
+            Some(OperationStatusType::Success),
+            AccountIdentifier::base_account(sender),
+            fa_currency.clone(),
+            DEFAULT_TRANSFER_AMOUNT,
+        ),
+        Operation::deposit(
+            1,
+            Some(OperationStatusType::Success),
+            AccountIdentifier::base_account(receiver),
+            fa_currency,
+            DEFAULT_TRANSFER_AMOUNT,
+        ),
+    ]);
+    assert_gas(
+        &operations[2],
+        txn.info.gas_used.0 * txn.request.gas_unit_price.0,
+        sender,
+        true,
+    );
+}
+
 /// This test tests all of Rosetta's functionality from the read side in one go.  Since
 /// it's block based and it needs time to run, we do all the checks in a single test.
 #[tokio::test]
@@ -2503,6 +2681,7 @@ async fn test_delegation_pool_operations() {
     let (swarm, cli, _, rosetta_client) = setup_test(
         2,
         Arc::new(|_, config, _| config.api.max_transactions_page_size = NUM_TXNS_PER_PAGE),
+        HashSet::new(),
     )
     .await;
 
@@ -10,9 +10,13 @@ use crate::{
         state_value::StateValue,
         table::{TableHandle, TableInfo},
     },
-    transaction::{AccountTransactionsWithProof, Version},
+    transaction::{AccountTransactionsWithProof, TransactionWithProof, Version},
 };
 use anyhow::Result;
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
 
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum Order {
@@ -59,6 +63,33 @@ pub trait IndexerReader: Send + Sync {
         version: Version,
     ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue)>> + '_>>;
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>>;
+
+    fn get_transactions_by_function(
+        &self,
+        module_id: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>>;
+
+    fn get_resources_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_address: Option<AccountAddress>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<(Vec<(AccountAddress, StateValue)>, Option<AccountAddress>)>;
+
     fn get_latest_internal_indexer_ledger_version(&self) -> Result<Option<Version>>;
 
     #[cfg(any(test, feature = "fuzzing"))]